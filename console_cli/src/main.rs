@@ -1,6 +1,5 @@
 use anyhow::Context;
 use std::env;
-use std::time::{Duration, Instant};
 
 fn print_help() {
     println!(
@@ -9,108 +8,337 @@ fn print_help() {
             Commands:
             new <show_name>
             add-fixture <show.json> <fixture_id> <name> <fixture_type> <universe> <address>
+            add-fixture <show.json> <fixture_id> <name> <fixture_type> <2.1|U2:001|513>
             list <show.json>
             save-default <show.json>
             load <show.json>
+            repl <show.json> [full|playback|view]
+            dmx-devices
+            scenario <scenario.json>
+            merge <base.json> <mine.json> <theirs.json> [out.json] [--theirs]
+            version
 
             Examples:
             cargo run -p console_cli -- new "My Show"
             cargo run -p console_cli -- save-default show.json
             cargo run -p console_cli -- add-fixture show.json 1 "PAR 1" rgb_par_3ch 1 1
             cargo run -p console_cli -- list show.json
+            cargo run -p console_cli -- scenario scenario.json
         "#
     );
 }
 
 use std::io::{self, Write};
 
-fn snapshot_fixture_values(
+/// Metadata of a cue (in 'main'), group or palette addressed from the command line.
+/// `cuelist` is the list cue numbers refer to (the active playback's).
+fn meta_mut<'a>(
+    show: &'a mut console_core::Show,
+    cuelist: &str,
+    kind: &str,
+    id: &str,
+) -> Result<&'a mut console_core::Meta, String> {
+    match kind {
+        "cue" => {
+            let num: u32 = id.parse().map_err(|_| format!("Bad cue number '{id}'"))?;
+            show.cue_lists
+                .get_mut(cuelist)
+                .and_then(|cl| cl.cues.get_mut(&num))
+                .map(|c| &mut c.meta)
+                .ok_or_else(|| format!("Cue {num} not found. Type: cues"))
+        }
+        "group" => {
+            if !show.groups.contains_key(id) {
+                return Err(format!("Unknown group '{id}'"));
+            }
+            Ok(show.group_meta.entry(id.to_string()).or_default())
+        }
+        "palette" => show
+            .palettes
+            .get_mut(id)
+            .map(|p| &mut p.meta)
+            .ok_or_else(|| format!("Unknown palette '{id}'")),
+        _ => Err("Expected cue|group|palette".to_string()),
+    }
+}
+
+/// Cues addressed by `<a> [thru <b>]` (in `cuelist`), `all` or a cue list
+/// name, as (list, first, last, words used).
+fn cue_target(
     show: &console_core::Show,
-    playback: &console_core::Playback,
-    programmer: &console_core::Programmer,
-    fixture_id: u32,
-) -> anyhow::Result<console_core::FixtureValues> {
-    let tracked = playback.state_map(show)?;
-    let base = tracked.get(&fixture_id);
-
-    // Start from tracked values (None -> 0)
-    let mut intensity = base.and_then(|v| v.intensity).unwrap_or(0);
-    let mut r = base.and_then(|v| v.r).unwrap_or(0);
-    let mut g = base.and_then(|v| v.g).unwrap_or(0);
-    let mut b = base.and_then(|v| v.b).unwrap_or(0);
-
-    // Apply programmer overlay
-    if let Some(v) = programmer.intensity {
-        intensity = v;
+    cuelist: &str,
+    parts: &[&str],
+) -> Option<(String, u32, u32, usize)> {
+    let first = parts.first()?;
+    if let Ok(a) = first.parse::<u32>() {
+        if parts.get(1).is_some_and(|p| p.eq_ignore_ascii_case("thru")) {
+            let b = parts.get(2)?.parse::<u32>().ok()?;
+            return Some((cuelist.to_string(), a, b, 3));
+        }
+        return Some((cuelist.to_string(), a, a, 1));
     }
-    if let Some(v) = programmer.r {
-        r = v;
+    if first.eq_ignore_ascii_case("all") {
+        return Some((cuelist.to_string(), 0, u32::MAX, 1));
     }
-    if let Some(v) = programmer.g {
-        g = v;
+    show.cue_lists
+        .contains_key(*first)
+        .then(|| (first.to_string(), 0, u32::MAX, 1))
+}
+
+/// "3" or "0.5" seconds -> ms.
+fn parse_seconds(s: &str) -> Option<u32> {
+    let secs: f64 = s.parse().ok()?;
+    (secs >= 0.0).then(|| (secs * 1000.0).round() as u32)
+}
+
+/// sACN listener joined to every input universe the show merges or uses
+/// as a fader wing.
+fn dmx_input_receiver(
+    show: &console_core::Show,
+    bind: Option<std::net::SocketAddr>,
+) -> anyhow::Result<console_core::SacnReceiver> {
+    console_core::SacnReceiver::new(bind, &show.dmx_input_universes())
+}
+
+/// Under the prompt selection policy: tell the operator which values are held.
+fn prompt_release(pending: &[u32]) {
+    if !pending.is_empty() {
+        println!("Fixtures {pending:?} keep their programmer values. Type 'release' to drop them.");
     }
-    if let Some(v) = programmer.b {
-        b = v;
+}
+
+/// "10.0.0.5" or "10.0.0.5:6454" -> socket address, `port` when none is given.
+fn net_target(s: &str, port: u16) -> Option<std::net::SocketAddr> {
+    if s.contains(':') {
+        s.parse().ok()
+    } else {
+        format!("{s}:{port}").parse().ok()
     }
+}
+
+fn artnet_target(s: &str) -> Option<std::net::SocketAddr> {
+    net_target(s, console_core::dmx_output::ARTNET_PORT)
+}
+
+fn sacn_target(s: &str) -> Option<std::net::SocketAddr> {
+    net_target(s, console_core::dmx_output::SACN_PORT)
+}
 
-    Ok(console_core::FixtureValues {
-        intensity: Some(intensity),
-        r: Some(r),
-        g: Some(g),
-        b: Some(b),
-    })
+/// Start (or restart) output over the show's routing matrix.
+fn start_routed(
+    show: &console_core::Show,
+    output: &mut Option<console_core::OutputThread>,
+) -> anyhow::Result<()> {
+    let driver = console_core::RoutedOutput::open(&show.outputs, &show.sacn)?;
+    if let Some(old) = output.take() {
+        old.shutdown();
+    }
+    *output = Some(console_core::OutputThread::spawn(
+        Box::new(driver),
+        console_core::OutputConfig::default(),
+    ));
+    Ok(())
 }
 
-fn repl(show_path: &str) -> anyhow::Result<()> {
-    let show = console_core::Show::load_json_file(show_path)?;
+fn repl(show_path: &str, role: console_core::Role) -> anyhow::Result<()> {
+    let (show, stamp) = console_core::Show::load_json_file_stamped(show_path)?;
+    if let Some(warning) = stamp.and_then(|s| s.compatibility_warning()) {
+        println!("WARNING: {warning}");
+    }
     let mut rt = console_core::Runtime::new(show);
     let mut active_pb: char = 'a';
 
     let mut running = false;
-    let mut last_tick = Instant::now();
-    let mut last_print = Instant::now();
-    let print_every = Duration::from_millis(200); // adjust if you want
+    let mut last_print_ms = rt.now_ms();
+    // Cue warnings while running (CLI timecode only moves on `tc roll`).
+    let mut warner = console_core::CueWarner::default();
+    let print_every_ms: u64 = 200; // adjust if you want
+
+    rt.show.cue_lists.entry("main".to_string()).or_default();
 
-    rt.show
-        .cue_lists
-        .entry("main".to_string())
-        .or_insert_with(console_core::CueList::default);
+    // Show as last synced with the file; base for merging external edits.
+    let mut disk_show = rt.show.clone();
+    let mut watcher = console_core::ShowWatcher::new(show_path);
+    // Writes the show off the command path, per `Show::save_policy`.
+    let mut autosave = console_core::Autosave::new(show_path);
 
     println!("Loaded show: {}", rt.show.name);
     println!("Type 'help' for commands. 'quit' to exit.");
+    if role != console_core::Role::Full {
+        println!("Role: {role:?}");
+    }
 
-    let mut rec_fade_ms: u32 = 1000;
+    let author = console_core::meta::current_author();
+    let mut rec_fade_ms = console_core::commands::DEFAULT_RECORD_FADE_MS;
     let mut rec_delay_ms: u32 = 0;
 
-    fn pb_mut<'a>(
-        rt: &'a mut console_core::Runtime,
-        active: char,
-    ) -> &'a mut console_core::Playback {
+    fn pb_mut(rt: &mut console_core::Runtime, active: char) -> &mut console_core::Playback {
         if active == 'b' {
-            &mut rt.playback_b
+            &mut rt.playbacks["b"]
         } else {
-            &mut rt.playback_a
+            &mut rt.playbacks["a"]
         }
     }
 
-    fn pb_ref<'a>(rt: &'a console_core::Runtime, active: char) -> &'a console_core::Playback {
+    fn pb_ref(rt: &console_core::Runtime, active: char) -> &console_core::Playback {
         if active == 'b' {
-            &rt.playback_b
+            &rt.playbacks["b"]
         } else {
-            &rt.playback_a
+            &rt.playbacks["a"]
+        }
+    }
+
+    // Commands fired by timecode events or MIDI buttons run before the next
+    // prompt, tagged with where they came from.
+    let mut pending: std::collections::VecDeque<(String, &str)> = Default::default();
+    let mut tc_frame: u32 = 0;
+
+    // Network output; fed the rendered frame before every prompt.
+    let mut output: Option<console_core::OutputThread> = None;
+    // Whether `output` runs the show's routing (restarted on routing edits).
+    let mut output_routed = false;
+    // History kept by `framelog on`, reapplied when the output restarts.
+    let mut frame_log_keep: Option<u64> = None;
+
+    // sACN input; drained before every prompt. Starts by itself when the
+    // show has inputs configured.
+    let mut dmx_in: Option<console_core::SacnReceiver> = None;
+    let mut dmx_in_bind: Option<std::net::SocketAddr> = None;
+    if !rt.show.dmx_input_universes().is_empty() {
+        match dmx_input_receiver(&rt.show, None) {
+            Ok(rx) => dmx_in = Some(rx),
+            Err(e) => println!("sACN input not started: {e:#}"),
+        }
+    }
+
+    // MIDI control surface; its map lives next to the show and names the
+    // device to open at startup.
+    let midi_path = console_core::MidiMap::path_for(std::path::Path::new(show_path));
+    let mut midi_map = console_core::MidiMap::load(&midi_path)?;
+    let mut midi: Option<console_core::MidiInput> = None;
+    if let Some(dev) = &midi_map.device {
+        match console_core::MidiInput::open(dev) {
+            Ok(input) => midi = Some(input),
+            Err(e) => println!("MIDI not started: {e:#}"),
+        }
+    }
+    // USB HID jog wheels and keypads, mapped the same way.
+    let hid_path = console_core::HidMap::path_for(std::path::Path::new(show_path));
+    let mut hid_map = console_core::HidMap::load(&hid_path)?;
+    let mut hid: Option<console_core::HidInput> = None;
+    let mut hid_decoder = console_core::HidDecoder::default();
+    if let Some(dev) = &hid_map.device {
+        match console_core::HidInput::open(dev) {
+            Ok(input) => hid = Some(input),
+            Err(e) => println!("HID not started: {e:#}"),
         }
     }
 
     loop {
-        print!("lc> ");
-        io::stdout().flush()?;
+        if let Some(input) = &midi {
+            for msg in input.poll() {
+                let (control, value) = msg.control();
+                let Some(action) = midi_map.action_for(control) else {
+                    continue;
+                };
+                match rt.midi_action(action, value) {
+                    Ok(Some(line)) => pending.push_back((line, "midi")),
+                    Ok(None) => {}
+                    Err(e) => println!("!! midi {action}: {e:#}"),
+                }
+            }
+        }
+        if let Some(input) = &hid {
+            for report in input.poll() {
+                for ev in hid_decoder.decode(&report, &hid_map) {
+                    let Some(action) = hid_map.action_for(ev.control) else {
+                        continue;
+                    };
+                    match rt.midi_action(action, ev.midi_value()) {
+                        Ok(Some(line)) => pending.push_back((line, "hid")),
+                        Ok(None) => {}
+                        Err(e) => println!("!! hid {action}: {e:#}"),
+                    }
+                }
+            }
+        }
+        if let Some(rx) = &mut dmx_in {
+            match rx.receive() {
+                Ok(packets) => rt.feed_dmx_input(&packets),
+                Err(e) => println!("!! {e:#}"),
+            }
+        }
+        if let Some(out) = &output {
+            if let Some(keep) = frame_log_keep
+                && !out.frame_log().is_enabled()
+            {
+                out.frame_log().enable(keep);
+            }
+            out.set_delays(&rt.show.outputs.delays);
+            out.submit(rt.render()?);
+            for ev in out.events() {
+                match ev {
+                    console_core::OutputEvent::Disconnected { driver, error } => {
+                        println!("!! {driver} disconnected: {error} (retrying)")
+                    }
+                    console_core::OutputEvent::Reconnected { driver } => {
+                        println!("{driver} reconnected")
+                    }
+                }
+            }
+        }
+        for done in autosave.poll(&rt.show) {
+            match done.result {
+                Ok(()) => {
+                    // our own write: the new merge base, not an external edit
+                    disk_show = (*done.snapshot.show).clone();
+                    watcher.mark_seen();
+                }
+                Err(e) => println!("!! save failed: {e:#}"),
+            }
+        }
+        // checked before the prompt so an empty Enter fetches new log notes
+        if watcher.poll() {
+            match console_core::Show::load_json_file(show_path) {
+                Ok(on_disk) => {
+                    for entry in console_core::oplog::new_entries(&rt.show.log, &on_disk.log) {
+                        println!("[log] {}", entry.line());
+                    }
+                    rt.show.log = console_core::oplog::merge_log(&rt.show.log, &on_disk.log);
+                    if on_disk == *rt.show {
+                        // our own save, or only new log notes
+                        disk_show = on_disk;
+                    } else {
+                        println!(
+                            "Show file changed on disk. Type 'reload' to merge it in (saving first overwrites it)."
+                        );
+                    }
+                }
+                Err(e) => println!("Show file changed on disk but can't be read: {e:#}"),
+            }
+        }
 
         let mut line = String::new();
-        if io::stdin().read_line(&mut line)? == 0 {
-            // EOF (Ctrl+D)
-            break;
+        if let Some((fired, source)) = pending.pop_front() {
+            println!("lc> {fired}    [{source}]");
+            line = fired;
+        } else {
+            print!("lc> ");
+            io::stdout().flush()?;
+
+            if io::stdin().read_line(&mut line)? == 0 {
+                // EOF (Ctrl+D)
+                break;
+            }
         }
-        let line = line.trim();
+        let line = match console_core::alias::expand(&rt.show.aliases, &line) {
+            Ok(l) => l,
+            Err(e) => {
+                println!("{e}");
+                continue;
+            }
+        };
+        let line = line.as_str();
         if line.is_empty() {
             continue;
         }
@@ -118,76 +346,665 @@ fn repl(show_path: &str) -> anyhow::Result<()> {
         let parts: Vec<&str> = line.split_whitespace().collect();
         let cmd = parts[0].to_lowercase();
 
-        if running {
-            let now = Instant::now();
-            let dt = now.duration_since(last_tick);
-            last_tick = now;
+        // bare keypad lines like `1 thru 5 @ 50` are programming
+        let class_line = if cmd.parse::<u32>().is_ok() || cmd.starts_with('@') {
+            "select"
+        } else {
+            line
+        };
+        if let Err(e) = role.check(class_line) {
+            println!("{e}");
+            continue;
+        }
 
-            let ms = (dt.as_secs_f64() * 1000.0).round() as u32;
-            if ms > 0 {
-                rt.tick(ms.min(100)); // clamp so pauses don't jump too far
+        if running {
+            // clamp so pauses don't jump too far, except while a manual
+            // crossfade is being timed
+            let manual = rt.all_playbacks().any(|(_, pb)| pb.manual_info().is_some());
+            rt.advance(if manual { u32::MAX } else { 100 });
+            for c in warner.poll(&rt, None) {
+                let bell = if c.beep { "\x07" } else { "" };
+                println!("{bell}*** cue {} in {:.1}s", c.cue, c.in_ms as f64 / 1000.0);
             }
 
-            if now.duration_since(last_print) >= print_every {
-                last_print = now;
+            let now = rt.now_ms();
+            if now.saturating_sub(last_print_ms) >= print_every_ms {
+                last_print_ms = now;
 
                 let live = rt.render()?;
                 let nz = live.nonzero();
 
+                let tester = rt.tester.nonzero().len();
                 println!(
-                    "A: {:?} | B: {:?} | nz={}",
-                    rt.playback_a.current,
-                    rt.playback_b.current,
-                    nz.len()
+                    "A: {:?}{} | B: {:?}{} | nz={}{}{}",
+                    rt.playbacks["a"].current,
+                    if rt.playbacks["a"].solo { " SOLO" } else { "" },
+                    rt.playbacks["b"].current,
+                    if rt.playbacks["b"].solo { " SOLO" } else { "" },
+                    nz.len(),
+                    if output.as_ref().is_some_and(|o| o.is_frozen()) {
+                        " | FROZEN"
+                    } else {
+                        ""
+                    },
+                    if tester > 0 {
+                        format!(" | DMX TESTER: {tester}")
+                    } else {
+                        String::new()
+                    }
                 );
             }
         }
 
+        if let Some(parsed) = console_core::Command::parse(line) {
+            let ctx = console_core::CommandContext {
+                playback: active_pb.to_string(),
+                author: author.clone(),
+                record_fade_ms: rec_fade_ms,
+                record_delay_ms: rec_delay_ms,
+            };
+            match parsed.and_then(|c| console_core::commands::execute(c, &mut rt, &ctx)) {
+                Ok(res) => {
+                    if let Some(msg) = res.message {
+                        println!("{msg}");
+                    }
+                    if res.show_changed {
+                        autosave.edited(&rt.show);
+                    }
+                }
+                Err(e) => println!("{e:#}"),
+            }
+            continue;
+        }
+
         match cmd.as_str() {
             "help" => {
                 println!(
                     r#"Commands:
                         select <id>
                         select <a> thru <b>
+                        select <ids|groups joined by +>  (e.g. select area:FOH + 12)
+                        select <ids|groups> - <ids|groups>  (e.g. select group front - 3, g 2 + 7)
+                        <selection> [@ <0..100>|full|out]  (keypad line, e.g. 1 + 3 + 7 thru 9 - 8 @ 50; @ 50 alone sets the selection)
+                        group <name> [+|- <ids|groups>...]  (select a group, e.g. group 1 - 5; terms after +/- are fixture ids unless written g <name> or group <name>)
                         at <0..100>
+                        nudge <+/-n> (level wheel: selected fixtures relative to output)
                         rgb <0..255> <0..255> <0..255>
+                        fan intensity|red|green|blue|pan|tilt <from> thru <to> [center]  (spread over the selection; intensity in %)
+                        focus [<level%>] [time <s>]  (fade the selection up one fixture at a time)
+                        focus next|prev|stop
+                        colors [<n> | record <n> <name...>]  (recent colors: apply one or record it as a palette)
                         show
+                        dmx <universe>/<address> @ <0..255>  (raw tester, bypasses patch; 513 or 2.1 also work)
+                        at-address <address> [select]  (which fixture/channel owns U1:37?)
+                        addrfmt universe|absolute  (show addresses as U2:001 or 513)
+                        dmx clear
+                        tc [list]                        (timecoded event list)
+                        tc add <HH:MM:SS:FF> <command...>
+                        tc del <#> | tc move <#> <HH:MM:SS:FF> | tc fps <24|25|30>
+                        tc locate <tc>  (jump)  |  tc roll <tc>  (play forward, firing events)
+                        fixture <id> <control>  (lamp_on, lamp_off, reset... from the fixture type)
+                        snap fixture <id> on|off        (disable fades for a fixture)
+                        snap type <type_id> <channel> on|off  (snap-only channel)
+                        freeze [fade_ms]  (toggle output hold; fades back to live on release)
                         clear        (clears selection + values)
                         clearvals    (keeps selection, clears values)
+                        clear intensity|color|position  (clears one kind of value from the selection)
+                        selpolicy retain|release|prompt  (values of deselected fixtures)
+                        release [all|<ids...>]  (drop values kept on deselected fixtures)
+                        release <fixtures> [intensity|color|position] from a|b  (take them out of a playback's look until a cue sets them)
+                        release clear a|b  (put a playback's released fixtures back)
                         clearprog    (clears programmer)
-                        list         (lists fixtures from showfile)
+                        list [id|address|type]  (lists fixtures from showfile)
+                        footprint    (per-universe address map: . free, # used, X collision)
+                        stats    (fixtures per type/universe, cues per list, average fade, palette use, largest cues)
+                        output artnet [ip[:port]] | output off  (send DMX over Art-Net; broadcast by default)
+                        output sacn [ip[:port]]  (send DMX over sACN; multicast unless a receiver is given)
+                        output on  (send through the show's output routing)
+                        outputs [add <name> artnet <ip[:port]> | add <name> sacn [ip[:port]] | del <name>]  (routing destinations)
+                        outputs add <name> usb <device> [open|pro]  (USB widget; map the one universe it outputs)
+                        sacn [name <text> | priority [<universe>] <0-200>|clear]  (sACN source settings)
+                        outputs <name> on|off|priority <0-200>|map <u> [dest u]|unmap <u>
+                        outputs delay <u> <ms>|<frames>f|off  (hold a universe back to line up with slower links)
+                        dmxin [<u> [from <sacn_u>] [htp|priority] | <u> off]  (merge an external desk's sACN into universe u)
+                        dmxin listen [ip:port] | dmxin stop  (sACN input receiver)
+                        dmxin wing <u.addr> master <group>|fixtures <sel...>|selection  (input slot as a fader)
+                        dmxin wing <u.addr> off
+                        midi [open <device> | close | learn <action> | unbind <n>]  (MIDI control surface)
+                          actions: go a|b, back a|b, fader a|b, gm, master <group>, encoder intensity|red|green|blue, cmd <command>
+                        hid [open <device> [keyboard] | close | learn <action> | unbind <n>]  (USB jog wheel / keypad, same actions)
                         record palette intensity <name>
                         record palette color <name>
                         palettes
-                        apply palette <name>
+                        apply palette <name...>  (built-in: full, out, white, home)
                         record cue <number> <label...> [track|only]
                         update cue <number> [track|only]
                         delete cue <number>
+                        copy cue <a> [thru <b>] to <n>
+                        move cue <a> [thru <b>] to <n>  (every cue keeps its look)
+                        renumber [<start> [<step>]]  (number cues evenly, e.g. 10 10)
                         pbmode tracking|cueonly
+                        solo         (toggle: mute all other playbacks)
+                        flash [a|b] on|off  (playback intensities at full until off; default active playback)
+                        filter all|intensity|color|position  (parameters the active playback contributes)
+                        masters [add <group>|del <group>|house <group>|none]
+                        master <group>|all <0-100>|full|zero
+                        gm <0-100>   (grand master: scales all intensities, not color)
+                        gm fade <0-100> in <s>  (timed grand master move; runs with 'run')
+                        fader a|b <0-100>  (playback intensity master)
+                        rate [<pct>|off]  (active playback runs its fades, delays and follows at <pct>% speed)
+                        speed [<pct>|off]  (speed master: scales every playback's rate)
+                        blackout [on|off]  (all intensities out, state kept; bare toggles)
+                        blackout time <s>  (fade the grand master out, then black out)
+                        panic [off]  (house lights on, stage out)
                         block <cue_number>
                         unblock <cue_number>
-                        goto <cue_number>
-                        go
+                        assert cue <cue_number> [block]
+                        clean cue <cue_number>|all
+                        time cues <a> [thru <b>]|all|<list> [fade <s>] [delay <s>]  (bulk-set cue times)
+                        scale times <a> [thru <b>]|all|<list> by <factor>  (multiply fade and delay)
+                        balance <fixtures> cues <a> [thru <b>] [+|-<n>%]  (compare intensities across cues, or scale them)
+                        undo  (revert the last balance)
+                        copy cues <a> [thru <b>] from <list> to <list> at <n> [track|flat]
+                        goto <cue_number> [time <s>|manual|blind]  (blind: move the pointer, keep the output)
+                        go           (go! skips the go guard)
+                        goguard <debounce_ms> [confirm]
+                        autoblack on|off  (Go past the last cue releases the playback)
+                        follow <cue_number> <s>|off  (go on automatically after this long)
+                        confirm <cue_number> on|off  (Go into this cue must be given twice; for pyro/motor cues)
+                        link <cue_number> <target> [<times>]|off  (Go jumps back to <target>; without times until a goto)
+                        warn <cue_number> <seconds> [beep]|off  (count down before a follow or timecode event fires the cue)
+                        disarm       (cancel a confirm cue armed by a first Go)
+                        loop on|off  (Go past the last cue wraps to the first)
+                        mib [on|off]  (move in black: preset the next cue's color on dark fixtures; no args lists the marks)
+                        lock [cues <a> [thru <b>]|all|<list>]  (protect cues from record/update/delete; no args lists locks)
+                        unlock cues <a> [thru <b>]|all|<list>
+                        rigcheck [name] [seconds]  (generate a cue list bringing up each fixture in turn)
+                        loop start|stop  (run the active list from the top with follows / release it)
+                        xfade        (start a manual crossfade to the next cue)
+                        xfade <0-100>  (takes over a running fade; starts one to the next cue)
+                        xfade up|down <0-100>  (split: rising / falling intensities)
+                        learn        (store the last manual crossfade time as fade time)
                         state
                         out
-                        pb a|b
+                        pb a|b [use <cuelist>]  (select a playback / point it at a cue list)
+                        pb  (list playbacks with their priority and cue)
+                        pb <name> add [<cuelist>] | pb <name> del  (more playbacks; only A and B have faders)
+                        pb <name> priority <0-255>  (higher priority owns what it sets; equal: latest cue change wins color)
+                        pb <name> go|release|goto <cue>  (run a playback other than A or B)
+                        cuelists [add <name>|rename <old> <new>|del <name>]
                         run
                         stop
-                        save
+                        save  (write the show now)
+                        autosave [record|manual|every <secs>]  (when edits are written: after each edit, only on save, or batched)
+                        reload       (merge external edits to the show file)
+                        alias [<name> = <command...>]  (extra words after an alias are appended)
+                        unalias <name>
+                        locate <fixture_id> <area> [bar] [unit]|clear  (adds automatic area:/bar: groups)
+                        limit <fixture_id> [pan|tilt <min%> <max%> | pan|tilt clear]  (soft limits enforced on output)
+                        place <fixture_id> <x_m> <y_m>|clear  (stage-plot position)
+                        thumbs       (export PNG thumbnails of the active cue list next to the show)
+                        agenda [n]   (upcoming follows and timecode events, soonest first)
+                        framelog [on [seconds]|off]  (log every changed output channel per frame)
+                        dumpframes [<n>s]  (logged frames of the last n seconds, default 5)
+                        say <text>   (post a note to the show log shared by all clients)
+                        log [count]  (last notes from the show log)
+                        versions [restore <YYYY-MM-DD>]  (daily snapshots of the show file)
+                        export cuelist <name> <file.json>
+                        export palettes|groups <file.json> [name...]
+                        import <file.json> [replace]
+                        import groups|colors <file.csv|json> [replace]  (name,ids... or name,r,g,b)
+                        note cue|group|palette <id> <text...>   (no text clears)
+                        tag cue|group|palette <id> <color>|none
+                        info cue|group|palette <id>
+                        search <text...>
+                        preview      (look after the next Go)
+                        lint         (statistics and common problems)
+                        rdmcheck <responders.json>  (compare an RDM discovery dump with the patch; sends no RDM)
+                        about
                         quit
                         "#
                 );
             }
             "quit" | "exit" => break,
 
+            "about" | "version" => println!("{}", console_core::build_info()),
+
+            "preview" => {
+                let pb = pb_ref(&rt, active_pb);
+                let Some(next) = pb.preview_next(&rt.show)? else {
+                    println!("No next cue");
+                    continue;
+                };
+                println!(
+                    "After Go on {} (cue {:?}):",
+                    active_pb.to_ascii_uppercase(),
+                    pb.next_cue(&rt.show)
+                );
+                for (fid, v) in next {
+                    println!(
+                        "  #{fid}: I={:?} R={:?} G={:?} B={:?}",
+                        v.intensity, v.r, v.g, v.b
+                    );
+                }
+            }
+
+            "rdmcheck" => {
+                let Some(path) = parts.get(1) else {
+                    println!("Usage: rdmcheck <responders.json>");
+                    continue;
+                };
+                let responders =
+                    match console_core::rdm::load_responders(std::path::Path::new(path)) {
+                        Ok(r) => r,
+                        Err(e) => {
+                            println!("{e:#}");
+                            continue;
+                        }
+                    };
+                let report = rt.show.patch.channel_check(&responders);
+                for check in &report.fixtures {
+                    println!("{check}");
+                }
+                for r in &report.unpatched {
+                    println!(
+                        "EXTRA {} at {}.{} ({} ch) is not in the patch",
+                        r.uid, r.universe, r.address, r.footprint
+                    );
+                }
+                println!(
+                    "{} of {} fixture(s) passed",
+                    report.fixtures.len() - report.failures(),
+                    report.fixtures.len()
+                );
+            }
+
+            "lint" => {
+                let show = &rt.show;
+                for (name, list) in &show.cue_lists {
+                    let total_ms: u64 = list
+                        .cues
+                        .values()
+                        .map(|c| c.fade_ms as u64 + c.delay_ms as u64)
+                        .sum();
+                    println!(
+                        "Cue list '{name}': {} cue(s), {} blocked, {:.1}s total fade+delay",
+                        list.cues.len(),
+                        list.cues.values().filter(|c| c.block).count(),
+                        total_ms as f64 / 1000.0
+                    );
+                }
+                println!(
+                    "{} fixture(s), {} group(s), {} palette(s)",
+                    show.patch.fixtures.len(),
+                    show.groups.len(),
+                    show.palettes.len()
+                );
+
+                let findings = show.lint();
+                if findings.is_empty() {
+                    println!("No problems found");
+                }
+                for f in findings {
+                    let tag = match f.severity {
+                        console_core::Severity::Warning => "WARN",
+                        console_core::Severity::Info => "info",
+                    };
+                    println!("  [{tag}] {}", f.message);
+                }
+            }
+
             "list" => {
+                let fixtures = match parts.get(1).map(|p| p.to_lowercase()).as_deref() {
+                    None | Some("id") => rt.show.patch.list_fixtures(),
+                    Some("address") | Some("addr") => rt.show.patch.by_address(),
+                    Some("type") => rt.show.patch.by_type(),
+                    Some(_) => {
+                        println!("Usage: list [id|address|type]");
+                        continue;
+                    }
+                };
                 println!("Fixtures:");
-                for f in rt.show.patch.list_fixtures() {
+                for f in fixtures {
+                    println!(
+                        "  #{:>3} | {:<10} | type {:<12} | {}",
+                        f.fixture_id,
+                        f.name,
+                        f.fixture_type,
+                        rt.show.address_format.format(f.universe, f.address)
+                    );
+                }
+            }
+
+            "output" => match &parts[1..] {
+                ["artnet", rest @ ..] => {
+                    let mut config = console_core::ArtNetConfig::default();
+                    if let Some(target) = rest.first() {
+                        config.target = match artnet_target(target) {
+                            Some(t) => t,
+                            None => {
+                                println!("Invalid address '{target}'");
+                                continue;
+                            }
+                        };
+                    }
+                    let target = config.target;
+                    let driver = console_core::ArtNetOutput::new(config)?;
+                    // replacing a running output shuts the old one down first
+                    if let Some(old) = output.take() {
+                        old.shutdown();
+                    }
+                    output = Some(console_core::OutputThread::spawn(
+                        Box::new(driver),
+                        console_core::OutputConfig::default(),
+                    ));
+                    output_routed = false;
+                    println!("Art-Net output to {target}");
+                }
+                ["sacn", rest @ ..] => {
+                    let unicast = match rest.first() {
+                        None => None,
+                        Some(target) => match sacn_target(target) {
+                            Some(t) => Some(t),
+                            None => {
+                                println!("Invalid address '{target}'");
+                                continue;
+                            }
+                        },
+                    };
+                    let driver = console_core::SacnOutput::new(rt.show.sacn.clone(), unicast)?;
+                    if let Some(old) = output.take() {
+                        old.shutdown();
+                    }
+                    output = Some(console_core::OutputThread::spawn(
+                        Box::new(driver),
+                        console_core::OutputConfig::default(),
+                    ));
+                    output_routed = false;
+                    match unicast {
+                        Some(t) => println!("sACN output to {t}"),
+                        None => println!("sACN output (multicast)"),
+                    }
+                }
+                ["on"] => {
+                    if rt.show.outputs.is_empty() {
+                        println!("No outputs configured; see 'outputs add'.");
+                        continue;
+                    }
+                    start_routed(&rt.show, &mut output)?;
+                    output_routed = true;
                     println!(
-                        "  #{:>3} | {:<10} | type {:<12} | U{} @ {}",
-                        f.fixture_id, f.name, f.fixture_type, f.universe, f.address
+                        "Output on ({} destinations)",
+                        rt.show.outputs.destinations.len()
                     );
                 }
+                ["off"] => {
+                    output = None;
+                    output_routed = false;
+                    println!("Output stopped");
+                }
+                _ => println!(
+                    "Usage: output on | output artnet [ip[:port]] | output sacn [ip[:port]] | output off"
+                ),
+            },
+
+            "sacn" => {
+                let sacn = &mut rt.show.sacn;
+                let level = |s: &str| s.parse::<u8>().ok().filter(|p| *p <= 200);
+                match &parts[1..] {
+                    [] => {
+                        println!("Source name: {}", sacn.source_name);
+                        println!("Priority: {}", sacn.priority);
+                        for (u, p) in &sacn.priorities {
+                            println!("  universe {u}: priority {p}");
+                        }
+                        continue;
+                    }
+                    ["name", name @ ..] if !name.is_empty() => sacn.source_name = name.join(" "),
+                    ["priority", p] if level(p).is_some() => sacn.priority = level(p).unwrap(),
+                    ["priority", u, "clear"] if u.parse::<u16>().is_ok() => {
+                        sacn.priorities.remove(&u.parse().unwrap());
+                    }
+                    ["priority", u, p] if u.parse::<u16>().is_ok() && level(p).is_some() => {
+                        sacn.priorities
+                            .insert(u.parse().unwrap(), level(p).unwrap());
+                    }
+                    _ => {
+                        println!(
+                            "Usage: sacn | sacn name <text> | sacn priority [<universe>] <0-200>|clear"
+                        );
+                        continue;
+                    }
+                }
+                autosave.edited(&rt.show);
+                if output_routed {
+                    start_routed(&rt.show, &mut output)?;
+                }
+                println!("sACN settings updated");
+            }
+
+            "outputs" => {
+                let routing = &mut rt.show.outputs;
+                let edited = match &parts[1..] {
+                    [] => {
+                        if routing.is_empty() {
+                            println!("(no outputs)");
+                        }
+                        for d in &routing.destinations {
+                            let map: Vec<String> = d
+                                .universes
+                                .iter()
+                                .map(|(i, e)| {
+                                    if i == e {
+                                        format!("{i}")
+                                    } else {
+                                        format!("{i}->{e}")
+                                    }
+                                })
+                                .collect();
+                            println!(
+                                "  {:<12} {:<26} {} prio {:>3}  universes {}",
+                                d.name,
+                                d.kind.to_string(),
+                                if d.enabled { "on " } else { "off" },
+                                d.priority,
+                                map.join(" ")
+                            );
+                        }
+                        for (u, ms) in &routing.delays {
+                            println!("  universe {u} delayed {ms} ms");
+                        }
+                        false
+                    }
+                    ["delay", u, amount] => {
+                        let Some(u) = u.parse::<u16>().ok().filter(|u| *u >= 1) else {
+                            println!("Universes are numbers from 1");
+                            continue;
+                        };
+                        // frames are output refreshes
+                        let frame_ms = console_core::OutputConfig::default().refresh_ms;
+                        let ms = match amount.strip_suffix('f') {
+                            _ if *amount == "off" => Some(0),
+                            Some(frames) => frames.parse::<u32>().ok().map(|f| f * frame_ms),
+                            None => amount.trim_end_matches("ms").parse::<u32>().ok(),
+                        };
+                        match ms {
+                            Some(0) => {
+                                routing.delays.remove(&u);
+                                println!("Universe {u} not delayed");
+                            }
+                            Some(ms) => {
+                                routing.delays.insert(u, ms);
+                                println!("Universe {u} output delayed {ms} ms");
+                            }
+                            None => {
+                                println!("Usage: outputs delay <universe> <ms>|<frames>f|off");
+                                continue;
+                            }
+                        }
+                        true
+                    }
+                    ["add", name, "artnet", target] => match artnet_target(target) {
+                        Some(target) => {
+                            let dest = console_core::Destination::new(
+                                *name,
+                                console_core::DestinationKind::ArtNet { target },
+                            );
+                            match routing.add(dest) {
+                                Ok(()) => true,
+                                Err(e) => {
+                                    println!("{e}");
+                                    false
+                                }
+                            }
+                        }
+                        None => {
+                            println!("Invalid address '{target}'");
+                            false
+                        }
+                    },
+                    ["add", name, "sacn", rest @ ..] => {
+                        let unicast = match rest.first() {
+                            None => None,
+                            Some(target) => match sacn_target(target) {
+                                Some(t) => Some(t),
+                                None => {
+                                    println!("Invalid address '{target}'");
+                                    continue;
+                                }
+                            },
+                        };
+                        let dest = console_core::Destination::new(
+                            *name,
+                            console_core::DestinationKind::Sacn { unicast },
+                        );
+                        match routing.add(dest) {
+                            Ok(()) => true,
+                            Err(e) => {
+                                println!("{e}");
+                                false
+                            }
+                        }
+                    }
+                    ["add", name, "usb", device, rest @ ..] if rest.len() <= 1 => {
+                        // the device name usually says which widget it is
+                        let widget = match rest.first() {
+                            Some(w) => w.parse(),
+                            None => Ok(console_core::UsbWidget::guess(device)
+                                .unwrap_or(console_core::UsbWidget::UsbPro)),
+                        };
+                        let widget = match widget {
+                            Ok(w) => w,
+                            Err(e) => {
+                                println!("{e}");
+                                continue;
+                            }
+                        };
+                        let dest = console_core::Destination::new(
+                            *name,
+                            console_core::DestinationKind::UsbDmx {
+                                device: device.into(),
+                                widget,
+                            },
+                        );
+                        match routing.add(dest) {
+                            Ok(()) => {
+                                println!(
+                                    "{widget} on {device}; route a universe: outputs {name} map <u>"
+                                );
+                                true
+                            }
+                            Err(e) => {
+                                println!("{e}");
+                                false
+                            }
+                        }
+                    }
+                    ["del", name] => match routing.remove(name) {
+                        Ok(()) => true,
+                        Err(e) => {
+                            println!("{e}");
+                            false
+                        }
+                    },
+                    [name, rest @ ..] if routing.get_mut(name).is_some() => {
+                        let dest = routing.get_mut(name).unwrap();
+                        let num = |s: &str| s.parse::<u16>().ok().filter(|u| *u >= 1);
+                        match rest {
+                            ["on"] => dest.enabled = true,
+                            ["off"] => dest.enabled = false,
+                            ["priority", p] => match p.parse::<u8>() {
+                                Ok(p) if p <= 200 => dest.priority = p,
+                                _ => {
+                                    println!("Priority is 0-200");
+                                    continue;
+                                }
+                            },
+                            ["map", i, rest @ ..] => {
+                                let e = rest.first().map_or(Some(*i), |e| Some(*e));
+                                match (num(i), e.and_then(num)) {
+                                    (Some(i), Some(e)) => {
+                                        dest.universes.insert(i, e);
+                                    }
+                                    _ => {
+                                        println!("Universes are numbers from 1");
+                                        continue;
+                                    }
+                                }
+                            }
+                            ["unmap", i] => {
+                                if num(i).and_then(|i| dest.universes.remove(&i)).is_none() {
+                                    println!("Universe {i} is not routed to {name}");
+                                    continue;
+                                }
+                            }
+                            _ => {
+                                println!(
+                                    "Usage: outputs <name> on|off|priority <0-200>|map <u> [dest u]|unmap <u>"
+                                );
+                                continue;
+                            }
+                        }
+                        true
+                    }
+                    _ => {
+                        println!(
+                            "Usage: outputs | outputs add <name> artnet <ip[:port]> | outputs del <name> | outputs <name> ..."
+                        );
+                        false
+                    }
+                };
+                if edited {
+                    autosave.edited(&rt.show);
+                    if output_routed {
+                        start_routed(&rt.show, &mut output)?;
+                    }
+                    println!("Outputs updated");
+                }
+            }
+
+            "stats" => {
+                for line in rt.show.stats().lines() {
+                    println!("{line}");
+                }
+            }
+
+            "footprint" => {
+                let footprints = rt.show.patch.footprints();
+                if footprints.is_empty() {
+                    println!("(nothing patched)");
+                }
+                for (u, spans) in &footprints {
+                    let used: u32 = spans.iter().map(|s| (s.last - s.first + 1) as u32).sum();
+                    println!("Universe {u}: {used}/512 addresses used");
+                    println!("  |{}|", console_core::footprint::footprint_bar(spans, 64));
+                    for s in spans {
+                        println!("  {:>3}-{:<3} #{}", s.first, s.last, s.fixture_id);
+                    }
+                }
+                for (u, a, b) in rt.show.patch.collisions() {
+                    println!("COLLISION: fixtures {a} and {b} in universe {u}");
+                }
             }
 
             "select" => {
@@ -198,33 +1015,75 @@ fn repl(show_path: &str) -> anyhow::Result<()> {
                     let a: u32 = parts[1].parse()?;
                     let b: u32 = parts[3].parse()?;
                     rt.programmer.select_range(a, b);
+                } else if let Some(ids) = rt.show.parse_selection(&parts[1..]) {
+                    rt.programmer.selected.extend(ids);
                 } else {
-                    println!("Usage: select <id>  OR  select <a> thru <b>");
+                    println!(
+                        "Usage: select <id>  OR  select <a> thru <b>  OR  select <ids|groups joined by + or ->"
+                    );
                 }
             }
 
-            "at" => {
-                if parts.len() != 2 {
-                    println!("Usage: at <0..100>");
+            "nudge" => {
+                let Some(Ok(delta)) = parts.get(1).map(|p| p.parse::<i16>()) else {
+                    println!("Usage: nudge <+/-delta> (0..255 scale, relative to output)");
                     continue;
-                }
-                let pct: u8 = parts[1].parse()?;
-                rt.programmer.set_intensity_percent(pct);
+                };
+                rt.nudge_intensity(delta)?;
             }
 
-            "rgb" | "color" => {
-                if parts.len() != 4 {
-                    println!("Usage: rgb <r> <g> <b> (0..255)");
-                    continue;
+            "colors" => {
+                let history = rt.programmer.color_history().clone();
+                let swatch = |n: &str| {
+                    n.parse::<usize>()
+                        .ok()
+                        .and_then(|n| history.get(n.checked_sub(1)?).copied())
+                };
+                match &parts[1..] {
+                    [] => {
+                        if history.is_empty() {
+                            println!("(no colors used yet)");
+                        }
+                        for (i, [r, g, b]) in history.iter().enumerate() {
+                            println!("  {:>2}  rgb({r},{g},{b})", i + 1);
+                        }
+                    }
+                    [n] => match swatch(n) {
+                        Some([r, g, b]) => rt.programmer.set_rgb(r, g, b),
+                        None => println!("No color {n}; see 'colors'"),
+                    },
+                    ["record", n, name @ ..] if !name.is_empty() => {
+                        let Some(rgb) = swatch(n) else {
+                            println!("No color {n}; see 'colors'");
+                            continue;
+                        };
+                        let name = name.join(" ");
+                        if let Err(e) = rt.show.check_palette_name(&name) {
+                            println!("{e:#}");
+                            continue;
+                        }
+                        if rt.show.palettes.contains_key(&name) {
+                            println!("Palette '{name}' already exists");
+                            continue;
+                        }
+                        let mut pal = console_core::Palette::from_rgb(rgb);
+                        pal.meta = console_core::Meta::created_now(author.as_deref());
+                        rt.show.palettes.insert(name.clone(), pal);
+                        autosave.edited(&rt.show);
+                        println!("Recorded color palette '{name}'.");
+                    }
+                    _ => println!("Usage: colors | colors <n> | colors record <n> <name...>"),
                 }
-                let r: u8 = parts[1].parse()?;
-                let g: u8 = parts[2].parse()?;
-                let b: u8 = parts[3].parse()?;
-                rt.programmer.set_rgb(r, g, b);
             }
 
             "show" => {
                 println!("Selected: {:?}", rt.programmer.selected);
+                if !rt.programmer.retained().is_empty() {
+                    println!(
+                        "Retained (deselected): {:?}",
+                        rt.programmer.retained().keys().collect::<Vec<_>>()
+                    );
+                }
                 println!(
                     "Values: intensity={:?} rgb={:?}",
                     rt.programmer.intensity,
@@ -238,12 +1097,29 @@ fn repl(show_path: &str) -> anyhow::Result<()> {
 
                 println!(
                     "A cue: {:?} mode: {:?} | B cue: {:?} mode: {:?} | Selected: {:?}",
-                    rt.playback_a.current,
-                    rt.playback_a.mode,
-                    rt.playback_b.current,
-                    rt.playback_b.mode,
+                    rt.playbacks["a"].current,
+                    rt.playbacks["a"].mode,
+                    rt.playbacks["b"].current,
+                    rt.playbacks["b"].mode,
                     rt.programmer.selected
                 );
+                if output.as_ref().is_some_and(|o| o.is_frozen()) {
+                    println!("OUTPUT FROZEN (showing the held frame)");
+                }
+                for (fid, name, ms) in rt.active_controls() {
+                    println!("Control: fixture {fid} {name} ({ms}ms left)");
+                }
+
+                let tester = rt.tester.nonzero();
+                if !tester.is_empty() {
+                    println!(
+                        "DMX TESTER active on {} address(es) ('dmx clear' to release):",
+                        tester.len()
+                    );
+                    for (u, addr, v) in tester {
+                        println!("  {} = {}", rt.show.address_format.format(u, addr), v);
+                    }
+                }
 
                 if nz.is_empty() {
                     println!("(all zeros)");
@@ -252,418 +1128,2329 @@ fn repl(show_path: &str) -> anyhow::Result<()> {
 
                 println!("Non-zero DMX output:");
                 for (u, addr, v) in nz {
-                    println!("  U{}:{:03} = {}", u, addr, v);
+                    println!("  {} = {}", rt.show.address_format.format(u, addr), v);
                 }
             }
 
-            "clear" => rt.programmer.clear_all(),
-            "clearvals" => rt.programmer.clear_values(),
-            "clearprog" => rt.programmer.clear_all(),
-            "clearall" => {
-                rt.programmer.clear_all();
-                rt.programmer.selected.clear();
-                println!("Cleared programmer + selection.");
+            "at-address" => {
+                if parts.len() < 2 || parts.len() > 3 {
+                    println!("Usage: at-address <address> [select]   e.g. at-address 1/37");
+                    continue;
+                }
+                let (u, addr) = console_core::parse_address(parts[1])?;
+                let shown = rt.show.address_format.format(u, addr);
+                let Some((f, idx, ch)) = rt.show.patch.fixture_at(u, addr) else {
+                    println!("{shown}: nothing patched");
+                    continue;
+                };
+                println!(
+                    "{shown}: fixture #{} '{}' ({}), channel {} '{}' ({:?})",
+                    f.fixture_id,
+                    f.name,
+                    f.fixture_type,
+                    idx + 1,
+                    ch.name,
+                    ch.kind
+                );
+                if parts
+                    .get(2)
+                    .is_some_and(|p| p.eq_ignore_ascii_case("select"))
+                {
+                    let id = f.fixture_id;
+                    let pending = rt.programmer.set_selection([id].into_iter().collect());
+                    println!("Selected fixture {id}");
+                    prompt_release(&pending);
+                }
             }
 
-            "save" => {
-                rt.show.save_json_file(show_path)?;
-                println!("Saved showfile: {}", show_path);
+            "addrfmt" => {
+                let Some(fmt) = parts.get(1) else {
+                    println!(
+                        "Address format: {:?}. Usage: addrfmt universe|absolute",
+                        rt.show.address_format
+                    );
+                    continue;
+                };
+                rt.show.address_format = fmt.parse()?;
+                autosave.edited(&rt.show);
+                println!(
+                    "Addresses now shown as {}",
+                    rt.show.address_format.format(2, 1)
+                );
             }
 
-            "palettes" => {
-                if rt.show.palettes.is_empty() {
-                    println!("(no palettes yet)");
-                    continue;
-                }
-                println!("Palettes:");
-                for (name, pal) in &rt.show.palettes {
-                    match pal.kind {
-                        console_core::PaletteKind::Intensity => {
-                            let v = pal.values.intensity.unwrap_or(0);
-                            let pct = v as u16 * 100 / 255;
-                            println!("  {name} | Intensity | {v} (~{pct}%)");
-                        }
-                        console_core::PaletteKind::Color => {
-                            let r = pal.values.r.unwrap_or(0);
-                            let g = pal.values.g.unwrap_or(0);
-                            let b = pal.values.b.unwrap_or(0);
-                            println!("  {name} | Color | rgb({r},{g},{b})");
+            "tc" => {
+                let events = &mut rt.show.events;
+                match parts.get(1).copied().unwrap_or("list") {
+                    "list" => {
+                        println!(
+                            "Timecode {} ({} fps)",
+                            events.timecode(tc_frame),
+                            events.fps
+                        );
+                        for (i, e) in events.events.iter().enumerate() {
+                            println!(
+                                "  #{i:<3} {} | {}{}",
+                                events.timecode(e.frame),
+                                e.command,
+                                if e.label.is_empty() {
+                                    String::new()
+                                } else {
+                                    format!("  ({})", e.label)
+                                }
+                            );
                         }
                     }
+                    "add" if parts.len() >= 4 => {
+                        let tc = console_core::Timecode::parse(parts[2], events.fps)?;
+                        let idx = events.add(console_core::TimedEvent {
+                            frame: tc.frame,
+                            command: parts[3..].join(" "),
+                            label: String::new(),
+                        });
+                        autosave.edited(&rt.show);
+                        println!("Added event #{idx} at {tc}");
+                    }
+                    "del" if parts.len() == 3 => {
+                        let ev = events.remove(parts[2].parse()?)?;
+                        autosave.edited(&rt.show);
+                        println!("Deleted event '{}'", ev.command);
+                    }
+                    "move" if parts.len() == 4 => {
+                        let tc = console_core::Timecode::parse(parts[3], events.fps)?;
+                        let idx = events.retime(parts[2].parse()?, tc.frame)?;
+                        autosave.edited(&rt.show);
+                        println!("Event now #{idx} at {tc}");
+                    }
+                    "fps" if parts.len() == 3 => {
+                        let fps: u8 = parts[2].parse()?;
+                        anyhow::ensure!(matches!(fps, 24 | 25 | 30), "fps must be 24, 25 or 30");
+                        events.fps = fps;
+                        autosave.edited(&rt.show);
+                        println!("Event list at {fps} fps");
+                    }
+                    "locate" if parts.len() == 3 => {
+                        tc_frame = console_core::Timecode::parse(parts[2], events.fps)?.frame;
+                        println!("Timecode located to {}", events.timecode(tc_frame));
+                    }
+                    "roll" if parts.len() == 3 => {
+                        let to = console_core::Timecode::parse(parts[2], events.fps)?.frame;
+                        pending.extend(
+                            events
+                                .due(tc_frame, to)
+                                .map(|e| (e.command.clone(), "timecode event")),
+                        );
+                        tc_frame = to;
+                        println!("Timecode rolled to {}", events.timecode(tc_frame));
+                    }
+                    _ => println!(
+                        "Usage: tc [list] | tc add <HH:MM:SS:FF> <command...> | tc del <#> | tc move <#> <HH:MM:SS:FF> | tc fps <24|25|30> | tc locate <tc> | tc roll <tc>"
+                    ),
                 }
             }
 
-            "record" => {
-                // record group <name>
-                if parts.len() == 3 && parts[1].eq_ignore_ascii_case("group") {
-                    let name = parts[2].to_string();
+            "fixture" => {
+                // fixture <id> <control>  e.g. fixture 5 lamp_on
+                if parts.len() != 3 {
+                    println!("Usage: fixture <id> <control>  (e.g. lamp_on, lamp_off, reset)");
+                    continue;
+                }
+                let id: u32 = parts[1].parse()?;
+                rt.fixture_control(id, parts[2])?;
+                println!("Fixture {id}: {} running", parts[2]);
+            }
 
-                    if rt.programmer.selected.is_empty() {
-                        println!("No fixtures selected.");
+            "snap" => {
+                // snap fixture <id> on|off  |  snap type <type_id> <channel> on|off
+                let on = match parts.last().map(|p| p.to_lowercase()) {
+                    Some(p) if p == "on" => true,
+                    Some(p) if p == "off" => false,
+                    _ => {
+                        println!(
+                            "Usage: snap fixture <id> on|off  OR  snap type <type_id> <channel> on|off"
+                        );
+                        continue;
+                    }
+                };
+                match parts.as_slice() {
+                    [_, "fixture", id, _] => {
+                        let id: u32 = id.parse()?;
+                        let Some(f) = rt.show.patch.fixtures.get_mut(&id) else {
+                            println!("No fixture {id}");
+                            continue;
+                        };
+                        f.snap = on;
+                        println!(
+                            "Fixture {id} fades {}",
+                            if on { "disabled" } else { "enabled" }
+                        );
+                    }
+                    [_, "type", type_id, channel, _] => {
+                        let Some(ch) =
+                            rt.show
+                                .patch
+                                .fixture_types
+                                .get_mut(*type_id)
+                                .and_then(|ft| {
+                                    ft.channels
+                                        .iter_mut()
+                                        .find(|c| c.name.eq_ignore_ascii_case(channel))
+                                })
+                        else {
+                            println!("No channel '{channel}' in fixture type '{type_id}'");
+                            continue;
+                        };
+                        ch.snap = on;
+                        println!(
+                            "{type_id} / {}: {}",
+                            ch.name,
+                            if on { "snap-only" } else { "fades" }
+                        );
+                    }
+                    _ => {
+                        println!(
+                            "Usage: snap fixture <id> on|off  OR  snap type <type_id> <channel> on|off"
+                        );
                         continue;
                     }
+                }
+                autosave.edited(&rt.show);
+            }
 
-                    rt.show
-                        .groups
-                        .insert(name.clone(), rt.programmer.selected.clone());
-                    rt.show.save_json_file(show_path)?;
-                    println!("Recorded group '{name}' and saved.");
+            "freeze" => {
+                // The output thread holds the frame and runs the fade back.
+                let Some(out) = &output else {
+                    println!("No output running; start one with 'output' first.");
                     continue;
+                };
+                let fade_ms: u32 = match parts.get(1) {
+                    Some(p) => p.parse()?,
+                    None => 1000,
+                };
+                if out.is_frozen() {
+                    out.unfreeze(fade_ms);
+                    println!("Unfreezing: crossfading back to live over {fade_ms}ms");
+                } else {
+                    out.freeze();
+                    println!(
+                        "Output FROZEN. Programming continues blind; 'freeze' again to release."
+                    );
                 }
-                // record cue <number> <label...> [track|only]
-                if parts.len() >= 3 && parts[1].eq_ignore_ascii_case("cue") {
-                    let num: u32 = parts[2].parse()?;
+            }
 
-                    // Parse optional mode at end
-                    let mut mode = "track";
-                    let mut end = parts.len();
-                    if let Some(last) = parts.last()
-                        && (last.eq_ignore_ascii_case("only") || last.eq_ignore_ascii_case("track"))
-                    {
-                        mode = last;
-                        end -= 1;
-                    }
+            "dmx" => {
+                // dmx <address> @ <value>, address as 1/1, 2.1, U2:001 or 513
+                let args = parts[1..].concat();
+                if args.eq_ignore_ascii_case("clear") {
+                    rt.clear_dmx();
+                    println!("DMX tester cleared");
+                    continue;
+                }
 
-                    let label = if end >= 4 {
-                        parts[3..end].join(" ")
-                    } else {
-                        format!("Cue {num}")
-                    };
+                let parsed = args.split_once('@').and_then(|(target, value)| {
+                    let (u, addr) = console_core::parse_address(target).ok()?;
+                    Some((u, addr, value.parse().ok()?))
+                });
+                let Some((u, addr, v)) = parsed else {
+                    println!("Usage: dmx <universe>/<address> @ <0..255>  OR  dmx clear");
+                    continue;
+                };
 
-                    if rt.programmer.selected.is_empty() {
-                        println!("Nothing selected. Use: select ...");
-                        continue;
-                    }
+                rt.set_dmx(u, addr, v)?;
+                println!(
+                    "DMX tester: {} = {v}",
+                    rt.show.address_format.format(u, addr)
+                );
+            }
 
-                    let mut changes = std::collections::BTreeMap::new();
+            "selpolicy" => {
+                let Some(policy) = parts.get(1) else {
+                    println!(
+                        "Selection policy: {:?}. Usage: selpolicy retain|release|prompt",
+                        rt.programmer.policy
+                    );
+                    continue;
+                };
+                let policy: console_core::SelectionPolicy = policy.parse()?;
+                rt.programmer.policy = policy;
+                rt.show.selection_policy = policy;
+                autosave.edited(&rt.show);
+                println!("Values of deselected fixtures: {policy:?}");
+            }
 
-                    if mode.eq_ignore_ascii_case("track") {
-                        // Track: record programmer deltas only
-                        let delta = console_core::FixtureValues {
-                            intensity: rt.programmer.intensity,
-                            r: rt.programmer.r,
-                            g: rt.programmer.g,
-                            b: rt.programmer.b,
-                        };
+            "release"
+                if parts.iter().any(|p| p.eq_ignore_ascii_case("from"))
+                    || parts
+                        .get(1)
+                        .is_some_and(|p| p.eq_ignore_ascii_case("clear")) =>
+            {
+                const USAGE: &str = "Usage: release <fixtures> [intensity|color|position] from a|b  |  release clear a|b";
+                let (args, pb) = match &parts[1..] {
+                    [args @ .., from, pb] if from.eq_ignore_ascii_case("from") => (args, pb),
+                    [clear, pb] if clear.eq_ignore_ascii_case("clear") => (&[][..], pb),
+                    _ => {
+                        println!("{USAGE}");
+                        continue;
+                    }
+                };
+                let name = match pb.to_lowercase().as_str() {
+                    "a" => 'a',
+                    "b" => 'b',
+                    _ => {
+                        println!("{USAGE}");
+                        continue;
+                    }
+                };
+                if args.is_empty() {
+                    pb_mut(&mut rt, name).clear_released();
+                    println!(
+                        "Playback {} released fixtures restored",
+                        name.to_ascii_uppercase()
+                    );
+                    continue;
+                }
+                let (sel, params) = match args.last().map(|p| p.parse()) {
+                    Some(Ok(params)) => (&args[..args.len() - 1], params),
+                    _ => (args, console_core::ParamFilter::ALL),
+                };
+                let Some(ids) = rt.show.parse_selection(sel) else {
+                    println!("No fixtures in '{}'", sel.join(" "));
+                    continue;
+                };
+                let count = ids.len();
+                pb_mut(&mut rt, name).release_fixtures(ids, params);
+                println!(
+                    "Released {count} fixture(s) from playback {} until a cue sets them",
+                    name.to_ascii_uppercase()
+                );
+                continue;
+            }
 
-                        if delta.is_all_none() {
-                            println!("No values in programmer to record. Use: at / rgb / r/g/b");
+            "release" => {
+                let ids: Vec<u32> = match &parts[1..] {
+                    [] | ["all"] => rt.programmer.retained().keys().copied().collect(),
+                    ids => match ids.iter().map(|p| p.parse()).collect() {
+                        Ok(ids) => ids,
+                        Err(_) => {
+                            println!("Usage: release [all|<fixture ids...>]");
                             continue;
                         }
+                    },
+                };
+                rt.programmer.release(&ids);
+                println!("Released {} fixture(s)", ids.len());
+            }
 
-                        for &fid in &rt.programmer.selected {
-                            changes.insert(fid, delta.clone());
-                        }
-                    } else {
-                        // 1) compute snaps FIRST (immutable borrows only)
-                        let snaps: Vec<(u32, console_core::FixtureValues)> = rt
-                            .programmer
-                            .selected
-                            .iter()
-                            .copied()
-                            .map(|fid| {
-                                let snap = snapshot_fixture_values(
-                                    &rt.show,
-                                    pb_ref(&rt, active_pb),
-                                    &rt.programmer,
-                                    fid,
-                                )?;
-                                Ok((fid, snap))
-                            })
-                            .collect::<anyhow::Result<_>>()?;
+            "clearall" => {
+                rt.programmer.clear_all();
+                rt.programmer.selected.clear();
+                println!("Cleared programmer + selection.");
+            }
 
-                        // 2) fill changes
-                        for (fid, snap) in snaps {
-                            changes.insert(fid, snap);
-                        }
+            "save" => {
+                autosave.save_now(&rt.show);
+                println!("Saving showfile: {}", show_path);
+            }
+
+            "autosave" => {
+                if parts.len() == 1 {
+                    println!("Autosave: {}", rt.show.save_policy);
+                    continue;
+                }
+                match parts[1..].join(" ").parse() {
+                    Ok(policy) => {
+                        rt.show.save_policy = policy;
+                        // the setting itself is written whatever it says
+                        autosave.save_now(&rt.show);
+                        println!("Autosave: {policy}");
                     }
+                    Err(e) => println!("{e:#}"),
+                }
+            }
 
-                    let cue = console_core::Cue {
-                        number: num,
-                        label,
-                        block: false,
-                        fade_ms: rec_fade_ms,
-                        delay_ms: rec_delay_ms,
-                        changes,
+            "versions" => match &parts[1..] {
+                [] => {
+                    let versions =
+                        console_core::archive::list_versions(std::path::Path::new(show_path))?;
+                    if versions.is_empty() {
+                        println!("(no snapshots yet; one is taken on the first save of each day)");
+                    }
+                    for v in versions {
+                        println!("  {}  {}", v.date, v.path.display());
+                    }
+                }
+                ["restore", date] => {
+                    let restored = match console_core::archive::restore_version(
+                        std::path::Path::new(show_path),
+                        date,
+                    ) {
+                        Ok(s) => s,
+                        Err(e) => {
+                            println!("Can't restore: {e:#}");
+                            continue;
+                        }
                     };
+                    watcher.mark_seen();
+                    rt.show.replace(restored.clone());
+                    disk_show = restored;
+                    println!(
+                        "Restored the show from {date}. The replaced file is kept as pre-restore.json."
+                    );
+                }
+                _ => println!("Usage: versions [restore <YYYY-MM-DD>]"),
+            },
 
-                    let cl = rt
-                        .show
-                        .cue_lists
-                        .get_mut("main")
-                        .expect("main cuelist exists");
-                    cl.cues.insert(num, cue);
-
-                    rt.show.save_json_file(show_path)?;
-                    println!("Recorded cue {num} ({mode}) into cuelist 'main' and saved.");
+            "alias" => {
+                if parts.len() == 1 {
+                    if rt.show.aliases.is_empty() {
+                        println!("(no aliases)");
+                    }
+                    for (name, command) in &rt.show.aliases {
+                        println!("  {name} = {command}");
+                    }
                     continue;
                 }
-
-                // ... keep your existing `record palette ...` handling below
-                println!(
-                    "Usage: record cue <number> <label...> [track|only]  OR  record palette ..."
-                );
+                let args = line.split_once(char::is_whitespace).map_or("", |(_, a)| a);
+                match console_core::alias::parse_definition(args) {
+                    Ok((name, command)) => {
+                        println!("Alias {name} = {command}");
+                        rt.show.aliases.insert(name, command);
+                        autosave.edited(&rt.show);
+                    }
+                    Err(e) => println!("{e}. Usage: alias <name> = <command...>"),
+                }
             }
 
-            "update" => {
-                // update cue <number> [track|only]
-                if parts.len() < 3 || !parts[1].eq_ignore_ascii_case("cue") {
-                    println!("Usage: update cue <number> [track|only]");
+            "unalias" => {
+                if parts.len() != 2 {
+                    println!("Usage: unalias <name>");
                     continue;
                 }
-                let num: u32 = parts[2].parse()?;
-                let mode = if parts.len() >= 4 { parts[3] } else { "track" };
-
-                if rt.programmer.selected.is_empty() {
-                    println!("Nothing selected. Use: select ...");
+                let name = parts[1].to_lowercase();
+                if rt.show.aliases.remove(&name).is_none() {
+                    println!("No alias '{name}'");
                     continue;
                 }
+                autosave.edited(&rt.show);
+                println!("Removed alias '{name}'.");
+            }
 
-                // ---- Phase 1: compute what we want to apply (NO mutable borrows of show) ----
-                let snaps: Option<Vec<(u32, console_core::FixtureValues)>> =
-                    if mode.eq_ignore_ascii_case("only") {
-                        Some(
-                            rt.programmer
-                                .selected
-                                .iter()
-                                .copied()
-                                .map(|fid| {
-                                    let snap = snapshot_fixture_values(
-                                        &rt.show,
-                                        pb_ref(&rt, active_pb),
-                                        &rt.programmer,
-                                        fid,
-                                    )?;
-                                    Ok((fid, snap))
-                                })
-                                .collect::<anyhow::Result<_>>()?,
-                        )
-                    } else {
-                        None
-                    };
-
-                let delta: Option<console_core::FixtureValues> =
-                    if mode.eq_ignore_ascii_case("track") {
-                        let d = console_core::FixtureValues {
-                            intensity: rt.programmer.intensity,
-                            r: rt.programmer.r,
-                            g: rt.programmer.g,
-                            b: rt.programmer.b,
-                        };
-                        if d.is_all_none() {
-                            println!("No values in programmer to update. Use: at / rgb / r/g/b");
+            "place" => {
+                // place <fixture> <x_m> <y_m>  OR  place <fixture> clear
+                let usage = "Usage: place <fixture_id> <x_m> <y_m>  OR  place <fixture_id> clear";
+                let Some(Ok(fid)) = parts.get(1).map(|p| p.parse::<u32>()) else {
+                    println!("{usage}");
+                    continue;
+                };
+                let position = match &parts[2..] {
+                    ["clear"] => None,
+                    [x, y] => match (x.parse::<f32>(), y.parse::<f32>()) {
+                        (Ok(x), Ok(y)) => {
+                            Some([(x * 100.0).round() as i32, (y * 100.0).round() as i32])
+                        }
+                        _ => {
+                            println!("{usage}");
                             continue;
                         }
-                        Some(d)
-                    } else {
-                        None
-                    };
-
-                if !(mode.eq_ignore_ascii_case("track") || mode.eq_ignore_ascii_case("only")) {
-                    println!("Unknown mode '{mode}'. Use track|only");
+                    },
+                    _ => {
+                        println!("{usage}");
+                        continue;
+                    }
+                };
+                let Some(f) = rt.show.patch.fixtures.get_mut(&fid) else {
+                    println!("Unknown fixture {fid}");
                     continue;
+                };
+                f.position = position;
+                autosave.edited(&rt.show);
+                match position {
+                    Some([x, y]) => println!(
+                        "Fixture {fid} placed at {:.2} m, {:.2} m",
+                        x as f32 / 100.0,
+                        y as f32 / 100.0
+                    ),
+                    None => println!("Fixture {fid} unplaced"),
                 }
+            }
 
-                // ---- Phase 2: mutate the cue (NOW we can borrow show mutably) ----
-                let cl = rt
-                    .show
-                    .cue_lists
-                    .get_mut("main")
-                    .expect("main cuelist exists");
-
-                let cue = match cl.cues.get_mut(&num) {
-                    Some(c) => c,
-                    None => {
-                        println!("Cue {num} not found. Type: cues");
+            "agenda" => {
+                let limit = match parts.get(1).map(|n| n.parse::<usize>()) {
+                    None => 10,
+                    Some(Ok(n)) if n > 0 => n,
+                    _ => {
+                        println!("Usage: agenda [count]");
+                        continue;
+                    }
+                };
+                let timecode = (!rt.show.events.is_empty()).then_some((tc_frame, false));
+                let items = rt.agenda(timecode, limit);
+                if items.is_empty() {
+                    println!("(nothing coming up)");
+                }
+                for item in &items {
+                    println!("  {item}");
+                }
+                if items.iter().any(|i| !i.running) {
+                    println!(
+                        "  * from timecode {} (stopped)",
+                        rt.show.events.timecode(tc_frame)
+                    );
+                }
+                continue;
+            }
+            "framelog" => {
+                match &parts[1..] {
+                    [] => {}
+                    ["on"] => frame_log_keep = Some(console_core::framelog::DEFAULT_KEEP_MS),
+                    ["on", secs] => match parse_seconds(secs).filter(|&ms| ms > 0) {
+                        Some(ms) => frame_log_keep = Some(ms as u64),
+                        None => {
+                            println!("Invalid seconds '{secs}'");
+                            continue;
+                        }
+                    },
+                    ["off"] => {
+                        frame_log_keep = None;
+                        if let Some(out) = &output {
+                            out.frame_log().disable();
+                        }
+                    }
+                    _ => {
+                        println!("Usage: framelog [on [seconds]|off]");
                         continue;
                     }
+                }
+                if let (Some(out), Some(keep)) = (&output, frame_log_keep) {
+                    out.frame_log().enable(keep);
+                }
+                match (frame_log_keep, &output) {
+                    (None, _) => println!("Frame log off"),
+                    (Some(keep), Some(_)) => {
+                        println!("Frame log on, keeping {:.1}s", keep as f64 / 1000.0)
+                    }
+                    (Some(_), None) => println!("Frame log on; starts with 'output'"),
+                }
+                continue;
+            }
+            "dumpframes" => {
+                let window = match parts.get(1) {
+                    None => Some(5000),
+                    Some(s) => parse_seconds(s.strip_suffix('s').unwrap_or(s)),
+                };
+                let Some(window) = window else {
+                    println!("Usage: dumpframes [<n>s]");
+                    continue;
                 };
+                let Some(out) = output.as_ref().filter(|o| o.frame_log().is_enabled()) else {
+                    println!("Frame log is off; see 'framelog on' (needs a running output).");
+                    continue;
+                };
+                let frames = out.frame_log().dump(window as u64);
+                if frames.is_empty() {
+                    println!("(no output changes)");
+                }
+                for line in console_core::framelog::dump_lines(&frames, rt.show.address_format) {
+                    println!("  {line}");
+                }
+                continue;
+            }
+            "thumbs" => {
+                let list = pb_ref(&rt, active_pb).cuelist.clone();
+                let path = std::path::Path::new(show_path);
+                match console_core::plot::export_thumbnails(&rt.show, path, &list) {
+                    Ok(n) => println!(
+                        "Wrote {n} thumbnail(s) to {}",
+                        console_core::plot::thumbnail_path(path, &list, 0)
+                            .parent()
+                            .map_or_else(String::new, |d| d.display().to_string())
+                    ),
+                    Err(e) => println!("{e:#}"),
+                }
+            }
 
-                if let Some(d) = delta {
-                    for &fid in &rt.programmer.selected {
-                        cue.changes.insert(fid, d.clone());
+            "say" => {
+                if parts.len() < 2 {
+                    println!("Usage: say <text...>");
+                    continue;
+                }
+                let text = line
+                    .split_once(char::is_whitespace)
+                    .map_or("", |(_, t)| t.trim());
+                let entry = console_core::LogEntry::now(author.as_deref(), text);
+                // appended to the file as-is; unsaved edits stay in memory
+                match console_core::oplog::post(show_path, entry) {
+                    Ok(on_disk) => {
+                        rt.show.log = console_core::oplog::merge_log(&rt.show.log, &on_disk.log);
+                        println!("Logged.");
                     }
+                    Err(e) => println!("Can't post to the show log: {e:#}"),
                 }
+            }
 
-                if let Some(list) = snaps {
-                    for (fid, snap) in list {
-                        cue.changes.insert(fid, snap);
+            "log" => {
+                let n = match parts.get(1).map(|n| n.parse::<usize>()) {
+                    None => 20,
+                    Some(Ok(n)) => n,
+                    Some(Err(_)) => {
+                        println!("Usage: log [count]");
+                        continue;
                     }
+                };
+                if rt.show.log.is_empty() {
+                    println!("(log is empty)");
                 }
+                let skip = rt.show.log.len().saturating_sub(n);
+                for entry in &rt.show.log[skip..] {
+                    println!("  {}", entry.line());
+                }
+            }
+
+            "reload" => {
+                let on_disk = match console_core::Show::load_json_file(show_path) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        println!("Can't reload: {e:#}");
+                        continue;
+                    }
+                };
+                watcher.mark_seen();
 
-                rt.show.save_json_file(show_path)?;
-                println!("Updated cue {num} ({mode}) for selected fixtures and saved.");
+                let outcome = console_core::merge_reload(&rt.show, &disk_show, &on_disk);
+                rt.show.replace(outcome.show);
+                disk_show = on_disk;
+
+                if outcome.conflicts.is_empty() {
+                    println!("Reloaded show from {show_path}.");
+                } else {
+                    println!("Reloaded show; kept in-memory version of:");
+                    for c in &outcome.conflicts {
+                        println!("  {c}");
+                    }
+                }
+                if *rt.show != disk_show {
+                    println!("Unsaved in-memory edits kept. Type 'save' to write them.");
+                }
             }
 
-            "delete" => {
-                if parts.len() < 3 {
+            "note" | "tag" => {
+                if parts.len() < 3 || (cmd == "tag" && parts.len() != 4) {
                     println!(
-                        "Usage: delete cue <num> | delete group <name...> | delete palette <name...>"
+                        "Usage: note cue|group|palette <id> <text...>  OR  tag cue|group|palette <id> <color>|none"
                     );
                     continue;
                 }
+                let kind = parts[1].to_lowercase();
+                let list = pb_ref(&rt, active_pb).cuelist.clone();
+                let meta = match meta_mut(&mut rt.show, &list, &kind, parts[2]) {
+                    Ok(m) => m,
+                    Err(e) => {
+                        println!("{e}");
+                        continue;
+                    }
+                };
 
-                match parts[1].to_lowercase().as_str() {
-                    "cue" => {
-                        if parts.len() != 3 {
-                            println!("Usage: delete cue <num>");
-                            continue;
-                        }
-                        let num: u32 = parts[2].parse()?;
+                if cmd == "note" {
+                    meta.notes = parts[3..].join(" ");
+                } else if parts[3].eq_ignore_ascii_case("none") {
+                    meta.color = None;
+                } else {
+                    meta.color = Some(parts[3].to_string());
+                }
+                meta.touch(author.as_deref());
+                let summary = meta.summary();
 
-                        let cl = rt
-                            .show
-                            .cue_lists
-                            .get_mut("main")
-                            .expect("main cuelist exists");
+                autosave.edited(&rt.show);
+                println!("{kind} {}: {summary}", parts[2]);
+            }
 
-                        if cl.cues.remove(&num).is_none() {
-                            println!("Unknown cue {num}");
-                            continue;
-                        }
+            "info" => {
+                if parts.len() != 3 {
+                    println!("Usage: info cue|group|palette <id>");
+                    continue;
+                }
+                let kind = parts[1].to_lowercase();
+                let list = pb_ref(&rt, active_pb).cuelist.clone();
+                let meta = match meta_mut(&mut rt.show, &list, &kind, parts[2]) {
+                    Ok(m) => m.clone(),
+                    Err(e) => {
+                        println!("{e}");
+                        continue;
+                    }
+                };
+                let when = |t: Option<u64>| {
+                    t.map(console_core::meta::format_utc)
+                        .unwrap_or_else(|| "-".to_string())
+                };
+                println!("{kind} {}", parts[2]);
+                println!("  created:  {}", when(meta.created));
+                println!("  modified: {}", when(meta.modified));
+                println!("  author:   {}", meta.author.as_deref().unwrap_or("-"));
+                println!("  color:    {}", meta.color.as_deref().unwrap_or("-"));
+                println!("  notes:    {}", meta.notes);
+            }
 
-                        // Guard rail: if A/B were on this cue, clear them
-                        rt.playback_a.on_cue_deleted(num);
-                        rt.playback_b.on_cue_deleted(num);
+            "search" => {
+                if parts.len() < 2 {
+                    println!("Usage: search <text...>");
+                    continue;
+                }
+                let hits = rt.show.search(&parts[1..].join(" "));
+                if hits.is_empty() {
+                    println!("(no matches)");
+                    continue;
+                }
+                for hit in hits {
+                    println!("  {}", hit.title);
+                }
+            }
 
-                        rt.show.save_json_file(show_path)?;
-                        println!("Deleted cue {num} and saved.");
+            "export" => {
+                const USAGE: &str = "Usage: export cuelist <name> <file.json>  OR  export palettes|groups <file.json> [name...]";
+                if parts.len() < 3 {
+                    println!("{USAGE}");
+                    continue;
+                }
+                let (export, file) = match parts[1].to_lowercase().as_str() {
+                    "cuelist" if parts.len() == 4 => (rt.show.export_cue_list(parts[2]), parts[3]),
+                    "palettes" => (rt.show.export_palettes(&parts[3..]), parts[2]),
+                    "groups" => (rt.show.export_groups(&parts[3..]), parts[2]),
+                    _ => {
+                        println!("{USAGE}");
+                        continue;
+                    }
+                };
+                match export {
+                    Ok(export) => {
+                        export.save_json_file(file)?;
+                        println!("Exported {} to {file}", export.summary());
                     }
+                    Err(e) => println!("{e}"),
+                }
+            }
 
-                    "group" => {
-                        let name = parts[2..].join(" ");
-                        if rt.show.groups.remove(&name).is_none() {
-                            println!("Unknown group '{name}'");
-                            continue;
-                        }
-                        rt.show.save_json_file(show_path)?;
-                        println!("Deleted group '{name}' and saved.");
+            "import" => {
+                // import groups|colors <file.csv|json> [replace]: simple sets
+                let set = parts
+                    .get(1)
+                    .and_then(|p| p.parse::<console_core::SetKind>().ok());
+                let file_at = if set.is_some() { 2 } else { 1 };
+                let replace = parts
+                    .get(file_at + 1)
+                    .is_some_and(|p| p.eq_ignore_ascii_case("replace"));
+                let len = parts.len() - file_at;
+                if !(1..=2).contains(&len) || (len == 2 && !replace) {
+                    println!(
+                        "Usage: import <file.json> [replace]  OR  import groups|colors <file.csv|json> [replace]"
+                    );
+                    continue;
+                }
+                let loaded = match set {
+                    Some(kind) => console_core::ShowExport::load_set_file(parts[file_at], kind),
+                    None => console_core::ShowExport::load_json_file(parts[1]),
+                };
+                let export = match loaded {
+                    Ok(e) => e,
+                    Err(e) => {
+                        println!("{e:#}");
+                        continue;
+                    }
+                };
+                let summary = export.summary();
+                match rt.show.import(export, replace) {
+                    Ok(()) => {
+                        autosave.edited(&rt.show);
+                        println!("Imported {summary}.");
                     }
+                    Err(e) => println!("{e}"),
+                }
+            }
 
-                    "palette" => {
-                        let name = parts[2..].join(" ");
+            "palettes" => {
+                let system: Vec<&str> = console_core::SystemPalette::ALL
+                    .iter()
+                    .map(|p| p.name())
+                    .collect();
+                println!("Built-in: {}", system.join(", "));
+                if rt.show.palettes.is_empty() {
+                    println!("(no palettes yet)");
+                    continue;
+                }
+                println!("Palettes:");
+                for (name, pal) in &rt.show.palettes {
+                    match pal.kind {
+                        console_core::PaletteKind::Intensity => {
+                            let v = pal.values.intensity.unwrap_or(0);
+                            let pct = v as u16 * 100 / 255;
+                            println!("  {name} | Intensity | {v} (~{pct}%)");
+                        }
+                        console_core::PaletteKind::Color => {
+                            let r = pal.values.r.unwrap_or(0);
+                            let g = pal.values.g.unwrap_or(0);
+                            let b = pal.values.b.unwrap_or(0);
+                            println!("  {name} | Color | rgb({r},{g},{b})");
+                        }
+                    }
+                    if !pal.meta.is_empty() {
+                        println!("      {}", pal.meta.summary());
+                    }
+                }
+            }
+
+            // cues and groups are recorded by console_core::commands
+            "record" => println!(
+                "Usage: record cue <number> <label...> [track|only]  OR  record group <name>  OR  record palette intensity|color <name...>"
+            ),
+
+            "delete" => {
+                if parts.len() < 3 {
+                    println!(
+                        "Usage: delete cue <num> | delete group <name...> | delete palette <name...>"
+                    );
+                    continue;
+                }
+
+                match parts[1].to_lowercase().as_str() {
+                    "cue" => {
+                        if parts.len() != 3 {
+                            println!("Usage: delete cue <num>");
+                            continue;
+                        }
+                        let num: u32 = parts[2].parse()?;
+
+                        let list = pb_ref(&rt, active_pb).cuelist.clone();
+                        let Some(cl) = rt.show.cue_lists.get_mut(&list) else {
+                            println!("Unknown cuelist '{list}'");
+                            continue;
+                        };
+                        if let Err(e) = cl.check_unlocked(num, num) {
+                            println!("{e}");
+                            continue;
+                        }
+
+                        if cl.cues.remove(&num).is_none() {
+                            println!("Unknown cue {num}");
+                            continue;
+                        }
+
+                        // Guard rail: if playbacks were on this cue, clear them
+                        for pb in rt.playbacks.values_mut().filter(|pb| pb.cuelist == list) {
+                            pb.on_cue_deleted(num);
+                        }
+
+                        autosave.edited(&rt.show);
+                        println!("Deleted cue {num}.");
+                    }
+
+                    "group" => {
+                        let name = parts[2..].join(" ");
+                        if rt.show.groups.remove(&name).is_none() {
+                            println!("Unknown group '{name}'");
+                            continue;
+                        }
+                        rt.show.group_meta.remove(&name);
+                        autosave.edited(&rt.show);
+                        println!("Deleted group '{name}'.");
+                    }
+
+                    "palette" => {
+                        let name = parts[2..].join(" ");
+
+                        // Most likely your show stores palettes in a map keyed by name.
+                        // If your field name differs, use `rg "palettes"` in console_core to confirm.
+                        if let Some(sys) = console_core::SystemPalette::from_name(&name) {
+                            println!("'{sys}' is a built-in palette and can't be deleted.");
+                            continue;
+                        }
+                        if rt.show.palettes.remove(&name).is_none() {
+                            println!("Unknown palette '{name}'");
+                            continue;
+                        }
+
+                        autosave.edited(&rt.show);
+                        println!("Deleted palette '{name}'.");
+                        let users = rt.show.palette_users(&name);
+                        if !users.is_empty() {
+                            println!(
+                                "{} cue(s) referred to it and keep the values they were recorded with.",
+                                users.len()
+                            );
+                        }
+                    }
+
+                    _ => {
+                        println!(
+                            "Usage: delete cue <num> | delete group <name...> | delete palette <name...>"
+                        );
+                    }
+                }
+            }
+
+            "apply" => {
+                // apply palette <name...>
+                if parts.len() < 3 || !parts[1].eq_ignore_ascii_case("palette") {
+                    println!("Usage: apply palette <name...>");
+                    continue;
+                }
+                let name = &parts[2..].join(" ");
+                if let Some(sys) = console_core::SystemPalette::from_name(name) {
+                    let n = rt.programmer.apply_system_palette(&rt.show, sys);
+                    println!("Applied '{sys}' to {n} fixture(s).");
+                    continue;
+                }
+                let pal = match rt.show.palettes.get(name) {
+                    Some(p) => p,
+                    None => {
+                        println!("Unknown palette '{name}'. Type: palettes");
+                        continue;
+                    }
+                };
+                rt.programmer.apply_named_palette(name, pal);
+                println!("Applied palette '{name}' to programmer.");
+            }
+
+            "cues" => {
+                let list = &pb_ref(&rt, active_pb).cuelist;
+                let Some(cl) = rt.show.cue_lists.get(list) else {
+                    println!("Unknown cuelist '{list}'");
+                    continue;
+                };
+                if cl.cues.is_empty() {
+                    println!("(no cues yet in '{list}')");
+                    continue;
+                }
+                println!(
+                    "Cuelist: {list}{} | A ({}) current: {:?} | B ({}) current: {:?} | active: {}",
+                    match (cl.looping, cl.move_in_black) {
+                        (true, true) => " (loop, mib)",
+                        (true, false) => " (loop)",
+                        (false, true) => " (mib)",
+                        (false, false) => "",
+                    },
+                    rt.playbacks["a"].cuelist,
+                    rt.playbacks["a"].current,
+                    rt.playbacks["b"].cuelist,
+                    rt.playbacks["b"].current,
+                    active_pb.to_ascii_uppercase()
+                );
+                for (&num, cue) in &cl.cues {
+                    let cur = pb_ref(&rt, active_pb).current;
+                    let mark = if Some(num) == cur { " <==" } else { "" };
+                    let mut follow = cue
+                        .follow_ms
+                        .map(|ms| format!(" follow={ms}ms"))
+                        .unwrap_or_default();
+                    match (cue.link, cue.link_loops) {
+                        (Some(to), Some(n)) => follow += &format!(" link={to}x{n}"),
+                        (Some(to), None) => follow += &format!(" link={to}"),
+                        _ => {}
+                    }
+                    if let Some(w) = cue.warn {
+                        let beep = if w.beep { "+beep" } else { "" };
+                        follow += &format!(" warn={}ms{beep}", w.lead_ms);
+                    }
+                    let locked = if cl.is_locked(num) { " locked" } else { "" };
+                    let confirm = match (cue.confirm, pb_ref(&rt, active_pb).armed()) {
+                        (true, Some(n)) if n == num => " confirm (ARMED)",
+                        (true, _) => " confirm",
+                        (false, _) => "",
+                    };
+                    let palettes: std::collections::BTreeSet<&str> =
+                        cue.palette_refs.values().flat_map(|r| r.names()).collect();
+                    let palettes = if palettes.is_empty() {
+                        String::new()
+                    } else {
+                        format!(
+                            " palettes={}",
+                            palettes.into_iter().collect::<Vec<_>>().join(",")
+                        )
+                    };
+                    println!(
+                        "  {} | {} | fade={}ms delay={}ms block={}{}{}{}{}{}",
+                        num,
+                        cue.label,
+                        cue.fade_ms,
+                        cue.delay_ms,
+                        cue.block,
+                        follow,
+                        palettes,
+                        confirm,
+                        locked,
+                        mark
+                    );
+                    if !cue.meta.is_empty() {
+                        println!("      {}", cue.meta.summary());
+                    }
+                }
+            }
+
+            "lock" | "unlock" => {
+                let usage = format!("Usage: {cmd} cues <a> [thru <b>]|all|<list>");
+                let active = pb_ref(&rt, active_pb).cuelist.clone();
+                if parts.len() == 1 && cmd == "lock" {
+                    let mut any = false;
+                    for (name, cl) in &rt.show.cue_lists {
+                        for lock in &cl.locks {
+                            println!("  {name}: {lock}");
+                            any = true;
+                        }
+                    }
+                    if !any {
+                        println!("(nothing locked)");
+                    }
+                    continue;
+                }
+                let target = match parts.get(1) {
+                    Some(p) if p.eq_ignore_ascii_case("cues") => {
+                        cue_target(&rt.show, &active, &parts[2..])
+                    }
+                    Some(_) => cue_target(&rt.show, &active, &parts[1..]),
+                    None => None,
+                };
+                let Some((list, first, last, _)) = target else {
+                    println!("{usage}");
+                    continue;
+                };
+                let Some(cl) = rt.show.cue_lists.get_mut(&list) else {
+                    println!("Unknown cuelist '{list}'");
+                    continue;
+                };
+                let range = console_core::CueLock {
+                    first: first.min(last),
+                    last: first.max(last),
+                };
+                if cmd == "lock" {
+                    cl.lock(first, last);
+                    println!("Locked {range} of '{list}'");
+                } else if cl.unlock(first, last) {
+                    println!("Unlocked {range} of '{list}'");
+                } else {
+                    println!("Nothing locked there");
+                    continue;
+                }
+                autosave.edited(&rt.show);
+                continue;
+            }
+
+            "goto" => {
+                let usage = "Usage: goto <cue_number> [time <seconds>|manual|blind]";
+                let Some(Ok(num)) = parts.get(1).map(|p| p.parse::<u32>()) else {
+                    println!("{usage}");
+                    continue;
+                };
+                let show = &rt.show;
+                let pb = match active_pb {
+                    'b' => &mut rt.playbacks["b"],
+                    _ => &mut rt.playbacks["a"],
+                };
+                match &parts[2..] {
+                    [] => pb.goto(show, num)?,
+                    ["time", secs] => {
+                        let Ok(secs) = secs.parse::<f32>() else {
+                            println!("{usage}");
+                            continue;
+                        };
+                        pb.goto_timed(show, num, Some((secs.max(0.0) * 1000.0) as u32))?;
+                    }
+                    ["manual"] => {
+                        pb.start_manual_to(show, num)?;
+                        println!(
+                            "Manual crossfade to cue {num} started; move it with 'xfade <0-100>'"
+                        );
+                        continue;
+                    }
+                    ["blind"] => {
+                        match pb.goto_blind(show, num) {
+                            Ok(()) => println!(
+                                "Playback {} pointer at cue {num}; output unchanged until the next Go",
+                                active_pb.to_ascii_uppercase()
+                            ),
+                            Err(e) => println!("{e}"),
+                        }
+                        continue;
+                    }
+                    _ => {
+                        println!("{usage}");
+                        continue;
+                    }
+                }
+                let cur = pb.current;
+
+                println!(
+                    "Playback {} now at cue {:?}",
+                    active_pb.to_ascii_uppercase(),
+                    cur
+                );
+            }
+
+            "go" | "go!" => {
+                // The Go guard counts playback time, which only runs in run mode.
+                let show = &rt.show;
+                let pb = match active_pb {
+                    'b' => &mut rt.playbacks["b"],
+                    _ => &mut rt.playbacks["a"],
+                };
+                let outcome = if running && cmd == "go" {
+                    pb.press_go(show)?
+                } else {
+                    pb.go_or_arm(show)?
+                };
+
+                match outcome {
+                    console_core::GoOutcome::Went(None) => println!(
+                        "Playback {} released (end of list)",
+                        active_pb.to_ascii_uppercase()
+                    ),
+                    console_core::GoOutcome::Went(cur) => println!(
+                        "Playback {} now at cue {:?}",
+                        active_pb.to_ascii_uppercase(),
+                        cur
+                    ),
+                    console_core::GoOutcome::Ignored => println!("Go ignored (double press)"),
+                    console_core::GoOutcome::NeedsConfirm => {
+                        println!("Fade just started. Type 'go!' to confirm.")
+                    }
+                    console_core::GoOutcome::CueNeedsConfirm(n) => println!(
+                        "Cue {n} needs confirming. Type 'go' again to fire it, 'disarm' to cancel."
+                    ),
+                }
+            }
+
+            "disarm" => {
+                let pb = pb_mut(&mut rt, active_pb);
+                match pb.armed() {
+                    Some(n) => {
+                        pb.disarm();
+                        println!("Cue {n} disarmed");
+                    }
+                    None => println!("Nothing armed"),
+                }
+            }
+
+            "follow" => {
+                let usage = "Usage: follow <cue_number> <seconds>|off";
+                let (Some(Ok(num)), Some(time)) =
+                    (parts.get(1).map(|p| p.parse::<u32>()), parts.get(2))
+                else {
+                    println!("{usage}");
+                    continue;
+                };
+                let follow_ms = if time.eq_ignore_ascii_case("off") {
+                    None
+                } else if let Some(ms) = parse_seconds(time) {
+                    Some(ms)
+                } else {
+                    println!("{usage}");
+                    continue;
+                };
+                let list = pb_ref(&rt, active_pb).cuelist.clone();
+                if let Some(Err(e)) = rt
+                    .show
+                    .cue_lists
+                    .get(&list)
+                    .map(|l| l.check_unlocked(num, num))
+                {
+                    println!("{e}");
+                    continue;
+                }
+                let Some(cue) = rt
+                    .show
+                    .cue_lists
+                    .get_mut(&list)
+                    .and_then(|l| l.cues.get_mut(&num))
+                else {
+                    println!("Cue {num} not found. Type: cues");
+                    continue;
+                };
+                cue.follow_ms = follow_ms;
+                cue.meta.touch(author.as_deref());
+                autosave.edited(&rt.show);
+                match follow_ms {
+                    Some(ms) => println!("Cue {num} follows on after {ms}ms"),
+                    None => println!("Cue {num} follow off"),
+                }
+            }
+
+            "link" => {
+                let usage = "Usage: link <cue_number> <target> [<times>]|off";
+                let Some(Ok(num)) = parts.get(1).map(|p| p.parse::<u32>()) else {
+                    println!("{usage}");
+                    continue;
+                };
+                // outer None: doesn't parse; inner None: link off
+                let link = match &parts[2..] {
+                    [off] if off.eq_ignore_ascii_case("off") => Some(None),
+                    [target] => target.parse::<u32>().ok().map(|t| Some((t, None))),
+                    [target, times] => match (target.parse::<u32>(), times.parse::<u32>()) {
+                        (Ok(t), Ok(n)) => Some(Some((t, Some(n)))),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                let Some(link) = link else {
+                    println!("{usage}");
+                    continue;
+                };
+                let list = pb_ref(&rt, active_pb).cuelist.clone();
+                if let Some(Err(e)) = rt
+                    .show
+                    .cue_lists
+                    .get(&list)
+                    .map(|l| l.check_unlocked(num, num))
+                {
+                    println!("{e}");
+                    continue;
+                }
+                let Some(cue) = rt
+                    .show
+                    .cue_lists
+                    .get_mut(&list)
+                    .and_then(|l| l.cues.get_mut(&num))
+                else {
+                    println!("Cue {num} not found. Type: cues");
+                    continue;
+                };
+                cue.link = link.map(|(t, _)| t);
+                cue.link_loops = link.and_then(|(_, n)| n);
+                cue.meta.touch(author.as_deref());
+                autosave.edited(&rt.show);
+                match link {
+                    Some((t, Some(n))) => println!("Cue {num} links to cue {t}, {n} time(s)"),
+                    Some((t, None)) => println!("Cue {num} links to cue {t} until a goto"),
+                    None => println!("Cue {num} link off"),
+                }
+            }
+
+            "warn" => {
+                let usage = "Usage: warn <cue_number> <seconds> [beep]|off";
+                let Some(Ok(num)) = parts.get(1).map(|p| p.parse::<u32>()) else {
+                    println!("{usage}");
+                    continue;
+                };
+                // outer None: doesn't parse; inner None: warning off
+                let warn = match &parts[2..] {
+                    [off] if off.eq_ignore_ascii_case("off") => Some(None),
+                    [secs] => parse_seconds(secs).map(|ms| Some((ms, false))),
+                    [secs, beep] if beep.eq_ignore_ascii_case("beep") => {
+                        parse_seconds(secs).map(|ms| Some((ms, true)))
+                    }
+                    _ => None,
+                };
+                let Some(warn) = warn else {
+                    println!("{usage}");
+                    continue;
+                };
+                let list = pb_ref(&rt, active_pb).cuelist.clone();
+                if let Some(Err(e)) = rt
+                    .show
+                    .cue_lists
+                    .get(&list)
+                    .map(|l| l.check_unlocked(num, num))
+                {
+                    println!("{e}");
+                    continue;
+                }
+                let Some(cue) = rt
+                    .show
+                    .cue_lists
+                    .get_mut(&list)
+                    .and_then(|l| l.cues.get_mut(&num))
+                else {
+                    println!("Cue {num} not found. Type: cues");
+                    continue;
+                };
+                cue.warn = warn.map(|(lead_ms, beep)| console_core::CueWarning { lead_ms, beep });
+                cue.meta.touch(author.as_deref());
+                autosave.edited(&rt.show);
+                match warn {
+                    Some((ms, beep)) => println!(
+                        "Cue {num} warns {:.1}s before it fires{}",
+                        ms as f64 / 1000.0,
+                        if beep { ", with a beep" } else { "" }
+                    ),
+                    None => println!("Cue {num} warning off"),
+                }
+            }
+
+            "confirm" => {
+                let on = match parts.get(2).map(|s| s.to_lowercase()).as_deref() {
+                    Some("on") => true,
+                    Some("off") => false,
+                    _ => {
+                        println!("Usage: confirm <cue_number> on|off");
+                        continue;
+                    }
+                };
+                let Some(Ok(num)) = parts.get(1).map(|p| p.parse::<u32>()) else {
+                    println!("Usage: confirm <cue_number> on|off");
+                    continue;
+                };
+                let list = pb_ref(&rt, active_pb).cuelist.clone();
+                if let Some(Err(e)) = rt
+                    .show
+                    .cue_lists
+                    .get(&list)
+                    .map(|l| l.check_unlocked(num, num))
+                {
+                    println!("{e}");
+                    continue;
+                }
+                let Some(cue) = rt
+                    .show
+                    .cue_lists
+                    .get_mut(&list)
+                    .and_then(|l| l.cues.get_mut(&num))
+                else {
+                    println!("Cue {num} not found. Type: cues");
+                    continue;
+                };
+                cue.confirm = on;
+                cue.meta.touch(author.as_deref());
+                autosave.edited(&rt.show);
+                if on {
+                    println!("Cue {num} needs a second Go to fire");
+                } else {
+                    println!("Cue {num} fires on a single Go");
+                }
+            }
+
+            "rigcheck" => {
+                let name = parts.get(1).copied().unwrap_or("rigcheck");
+                let step_ms = match parts.get(2) {
+                    None => console_core::rigcheck::DEFAULT_STEP_MS,
+                    Some(s) => match parse_seconds(s) {
+                        Some(ms) if ms > 0 => ms,
+                        _ => {
+                            println!("Usage: rigcheck [name] [seconds]");
+                            continue;
+                        }
+                    },
+                };
+                match rt.show.add_rig_check(name, step_ms) {
+                    Ok(n) => {
+                        autosave.edited(&rt.show);
+                        println!(
+                            "Cuelist '{name}': {n} fixture(s), {}s each. Run it with 'pb {} use {name}' then 'loop start'",
+                            step_ms as f64 / 1000.0,
+                            active_pb
+                        );
+                    }
+                    Err(e) => println!("{e}"),
+                }
+            }
+
+            "midi" => {
+                const USAGE: &str =
+                    "Usage: midi [open <device> | close | learn <action> | unbind <n>]";
+                match &parts[1..] {
+                    [] => {
+                        println!(
+                            "Device: {}{}",
+                            midi_map
+                                .device
+                                .as_ref()
+                                .map_or("(none)".into(), |d| d.display().to_string()),
+                            if midi.is_some() { " (open)" } else { "" }
+                        );
+                        if midi_map.bindings.is_empty() {
+                            println!("(no bindings; add one with 'midi learn <action>')");
+                        }
+                        for (i, b) in midi_map.bindings.iter().enumerate() {
+                            println!("  {}. {} -> {}", i + 1, b.control, b.action);
+                        }
+                        continue;
+                    }
+                    ["open", dev] => {
+                        let dev = std::path::PathBuf::from(dev);
+                        match console_core::MidiInput::open(&dev) {
+                            Ok(input) => {
+                                println!("MIDI input open: {}", dev.display());
+                                midi = Some(input);
+                                midi_map.device = Some(dev);
+                            }
+                            Err(e) => {
+                                println!("{e:#}");
+                                continue;
+                            }
+                        }
+                    }
+                    ["close"] => {
+                        // the reader thread ends with the next message
+                        midi = None;
+                        midi_map.device = None;
+                        println!("MIDI input closed");
+                    }
+                    ["learn", action @ ..] => {
+                        let action: console_core::MidiAction = match action.join(" ").parse() {
+                            Ok(a) => a,
+                            Err(e) => {
+                                println!("{e}");
+                                continue;
+                            }
+                        };
+                        let Some(input) = &midi else {
+                            println!("No MIDI input open; use 'midi open <device>' first");
+                            continue;
+                        };
+                        input.poll(); // drop anything touched before
+                        println!("Touch the control for '{action}' (10 s)...");
+                        let Some(msg) = input.next(std::time::Duration::from_secs(10)) else {
+                            println!("Nothing received; not bound");
+                            continue;
+                        };
+                        let (control, _) = msg.control();
+                        println!("{control} -> {action}");
+                        midi_map.bind(control, action);
+                    }
+                    ["unbind", n] => match n.parse::<usize>() {
+                        Ok(n) if (1..=midi_map.bindings.len()).contains(&n) => {
+                            let b = midi_map.bindings.remove(n - 1);
+                            println!("Unbound {} ({})", b.control, b.action);
+                        }
+                        _ => {
+                            println!("No binding {n}");
+                            continue;
+                        }
+                    },
+                    _ => {
+                        println!("{USAGE}");
+                        continue;
+                    }
+                }
+                midi_map.save(&midi_path)?;
+                continue;
+            }
+            "hid" => {
+                const USAGE: &str =
+                    "Usage: hid [open <device> [keyboard] | close | learn <action> | unbind <n>]";
+                match &parts[1..] {
+                    [] => {
+                        println!(
+                            "Device: {}{}{}",
+                            hid_map
+                                .device
+                                .as_ref()
+                                .map_or("(none)".into(), |d| d.display().to_string()),
+                            if hid_map.format == console_core::HidFormat::Keyboard {
+                                " keyboard"
+                            } else {
+                                ""
+                            },
+                            if hid.is_some() { " (open)" } else { "" }
+                        );
+                        if hid_map.bindings.is_empty() {
+                            println!("(no bindings; add one with 'hid learn <action>')");
+                        }
+                        for (i, b) in hid_map.bindings.iter().enumerate() {
+                            println!("  {}. {} -> {}", i + 1, b.control, b.action);
+                        }
+                        continue;
+                    }
+                    ["open", dev, format @ ..] => {
+                        let format = match format {
+                            [] => console_core::HidFormat::Raw,
+                            ["keyboard"] => console_core::HidFormat::Keyboard,
+                            _ => {
+                                println!("{USAGE}");
+                                continue;
+                            }
+                        };
+                        let dev = std::path::PathBuf::from(dev);
+                        match console_core::HidInput::open(&dev) {
+                            Ok(input) => {
+                                println!("HID input open: {}", dev.display());
+                                hid = Some(input);
+                                hid_decoder = console_core::HidDecoder::default();
+                                hid_map.device = Some(dev);
+                                hid_map.format = format;
+                            }
+                            Err(e) => {
+                                println!("{e:#}");
+                                continue;
+                            }
+                        }
+                    }
+                    ["close"] => {
+                        hid = None;
+                        hid_map.device = None;
+                        println!("HID input closed");
+                    }
+                    ["learn", action @ ..] => {
+                        let action: console_core::MidiAction = match action.join(" ").parse() {
+                            Ok(a) => a,
+                            Err(e) => {
+                                println!("{e}");
+                                continue;
+                            }
+                        };
+                        let Some(input) = &hid else {
+                            println!("No HID input open; use 'hid open <device>' first");
+                            continue;
+                        };
+                        for report in input.poll() {
+                            hid_decoder.decode(&report, &hid_map); // drop earlier touches
+                        }
+                        println!("Press the key or turn the wheel for '{action}' (10 s)...");
+                        let deadline =
+                            std::time::Instant::now() + std::time::Duration::from_secs(10);
+                        let mut pressed = None;
+                        while pressed.is_none() {
+                            let left =
+                                deadline.saturating_duration_since(std::time::Instant::now());
+                            let Some(report) = input.next(left) else {
+                                break;
+                            };
+                            pressed = hid_decoder
+                                .decode(&report, &hid_map)
+                                .into_iter()
+                                .find(|ev| ev.value != 0);
+                        }
+                        let Some(ev) = pressed else {
+                            println!("Nothing received; not bound");
+                            continue;
+                        };
+                        hid_map.bind(ev.control, action);
+                        let b = hid_map.bindings.last().expect("just bound");
+                        println!("{} -> {}", b.control, b.action);
+                    }
+                    ["unbind", n] => match n.parse::<usize>() {
+                        Ok(n) if (1..=hid_map.bindings.len()).contains(&n) => {
+                            let b = hid_map.bindings.remove(n - 1);
+                            println!("Unbound {} ({})", b.control, b.action);
+                        }
+                        _ => {
+                            println!("No binding {n}");
+                            continue;
+                        }
+                    },
+                    _ => {
+                        println!("{USAGE}");
+                        continue;
+                    }
+                }
+                hid_map.save(&hid_path)?;
+                continue;
+            }
+            "dmxin" => {
+                const USAGE: &str = "Usage: dmxin [<universe> [from <sacn_universe>] [htp|priority] | <universe> off | wing <u.addr> <target>|off | listen [ip:port] | stop]";
+                match &parts[1..] {
+                    [] => {
+                        if rt.show.dmx_input.is_empty() && rt.show.dmx_wing.is_empty() {
+                            println!(
+                                "(no DMX input; add one with 'dmxin <universe> [htp|priority]')"
+                            );
+                        }
+                        let now = rt.now_ms();
+                        let active = rt.dmx_input.active(now);
+                        for (u, m) in &rt.show.dmx_input {
+                            println!(
+                                "  U{u} <- sACN {} {:?}{}",
+                                m.source,
+                                m.mode,
+                                if active.contains(&m.source) {
+                                    " (receiving)"
+                                } else {
+                                    ""
+                                }
+                            );
+                        }
+                        for w in &rt.show.dmx_wing {
+                            println!("  wing {}.{} -> {}", w.universe, w.address, w.target);
+                        }
+                        println!(
+                            "Receiver: {}",
+                            if dmx_in.is_some() { "listening" } else { "off" }
+                        );
+                        continue;
+                    }
+                    ["listen", rest @ ..] => {
+                        let bind = match rest.first() {
+                            None => None,
+                            Some(s) => match s.parse() {
+                                Ok(a) => Some(a),
+                                Err(_) => {
+                                    println!("{USAGE}");
+                                    continue;
+                                }
+                            },
+                        };
+                        dmx_in = None; // free the port before binding again
+                        match dmx_input_receiver(&rt.show, bind) {
+                            Ok(rx) => {
+                                println!("sACN input listening on {}", rx.local_addr()?);
+                                dmx_in = Some(rx);
+                                dmx_in_bind = bind;
+                            }
+                            Err(e) => println!("{e:#}"),
+                        }
+                        continue;
+                    }
+                    ["stop"] => {
+                        dmx_in = None;
+                        println!("sACN input stopped");
+                        continue;
+                    }
+                    ["wing", addr, rest @ ..] => {
+                        let Ok((universe, address)) = console_core::address::parse_address(addr)
+                        else {
+                            println!("{USAGE}");
+                            continue;
+                        };
+                        let target = match rest {
+                            ["off"] => None,
+                            ["master", group] => {
+                                Some(console_core::WingTarget::Master(group.to_string()))
+                            }
+                            ["fixtures", sel @ ..] => match rt.show.parse_selection(sel) {
+                                Some(ids) => Some(console_core::WingTarget::Fixtures(ids)),
+                                None => {
+                                    println!("No fixtures in '{}'", sel.join(" "));
+                                    continue;
+                                }
+                            },
+                            ["selection"] => Some(console_core::WingTarget::Selection),
+                            _ => {
+                                println!("{USAGE}");
+                                continue;
+                            }
+                        };
+                        if let Some(console_core::WingTarget::Master(group)) = &target
+                            && !rt.show.masters.groups.contains(group)
+                        {
+                            println!("'{group}' is not a master (masters add {group})");
+                            continue;
+                        }
+                        let wing = &mut rt.show.dmx_wing;
+                        wing.retain(|w| (w.universe, w.address) != (universe, address));
+                        match target {
+                            Some(target) => {
+                                println!("Wing {universe}.{address} -> {target}");
+                                wing.push(console_core::WingFader {
+                                    universe,
+                                    address,
+                                    target,
+                                });
+                            }
+                            None => println!("Wing {universe}.{address} off"),
+                        }
+                    }
+                    [u, "off"] => {
+                        let Ok(u) = u.parse::<u16>() else {
+                            println!("{USAGE}");
+                            continue;
+                        };
+                        if rt.show.dmx_input.remove(&u).is_none() {
+                            println!("Universe {u} has no DMX input");
+                            continue;
+                        }
+                        println!("Universe {u}: DMX input removed");
+                    }
+                    [u, rest @ ..] => {
+                        let Ok(u) = u.parse::<u16>() else {
+                            println!("{USAGE}");
+                            continue;
+                        };
+                        let mut merge = console_core::InputMerge {
+                            source: u,
+                            mode: console_core::MergeMode::Htp,
+                        };
+                        let mut ok = true;
+                        let mut words = rest.iter();
+                        while let Some(w) = words.next() {
+                            match w.to_lowercase().as_str() {
+                                "from" => match words.next().map(|s| s.parse::<u16>()) {
+                                    Some(Ok(src)) => merge.source = src,
+                                    _ => ok = false,
+                                },
+                                "htp" => merge.mode = console_core::MergeMode::Htp,
+                                "priority" => merge.mode = console_core::MergeMode::Priority,
+                                _ => ok = false,
+                            }
+                        }
+                        if !ok {
+                            println!("{USAGE}");
+                            continue;
+                        }
+                        rt.show.dmx_input.insert(u, merge);
+                        println!(
+                            "Universe {u}: merging sACN universe {} ({:?})",
+                            merge.source, merge.mode
+                        );
+                    }
+                }
+                autosave.edited(&rt.show);
+                // rejoin the multicast groups of the new configuration
+                if dmx_in.is_some() || !rt.show.dmx_input_universes().is_empty() {
+                    dmx_in = None;
+                    match dmx_input_receiver(&rt.show, dmx_in_bind) {
+                        Ok(rx) => dmx_in = Some(rx),
+                        Err(e) => println!("sACN input not started: {e:#}"),
+                    }
+                }
+            }
+
+            "mib" => {
+                let name = pb_ref(&rt, active_pb).cuelist.clone();
+                let Some(list) = rt.show.cue_lists.get(&name) else {
+                    println!("Unknown cuelist '{name}'");
+                    continue;
+                };
+                match parts.get(1).map(|p| p.to_lowercase()).as_deref() {
+                    Some(state @ ("on" | "off")) => {
+                        let Some(list) = rt.show.cue_lists.get_mut(&name) else {
+                            continue;
+                        };
+                        list.move_in_black = state == "on";
+                        autosave.edited(&rt.show);
+                        println!("Cuelist '{name}' move in black {state}");
+                    }
+                    None => {
+                        let state = if list.move_in_black { "on" } else { "off" };
+                        println!("Cuelist '{name}' move in black {state}");
+                        for (fid, v) in pb_ref(&rt, active_pb).marks(&rt.show)? {
+                            println!(
+                                "  fixture {fid} marked: r={} g={} b={}",
+                                v.r.unwrap_or(0),
+                                v.g.unwrap_or(0),
+                                v.b.unwrap_or(0)
+                            );
+                        }
+                    }
+                    _ => println!("Usage: mib [on|off]"),
+                }
+                continue;
+            }
+
+            "loop" => {
+                let name = pb_ref(&rt, active_pb).cuelist.clone();
+                match parts.get(1).map(|p| p.to_lowercase()).as_deref() {
+                    Some(state @ ("on" | "off")) => {
+                        let Some(list) = rt.show.cue_lists.get_mut(&name) else {
+                            println!("Unknown cuelist '{name}'");
+                            continue;
+                        };
+                        list.looping = state == "on";
+                        autosave.edited(&rt.show);
+                        println!("Cuelist '{name}' loop {state}");
+                    }
+                    Some("start") => {
+                        let show = &rt.show;
+                        let pb = match active_pb {
+                            'b' => &mut rt.playbacks["b"],
+                            _ => &mut rt.playbacks["a"],
+                        };
+                        pb.release(show, 0)?;
+                        let first = pb.go(show)?;
+                        // follows need the clock running
+                        running = true;
+                        rt.sync_clock();
+                        last_print_ms = rt.now_ms();
+                        match first {
+                            Some(cue) => {
+                                println!("Cuelist '{name}' started at cue {cue} (run mode on)")
+                            }
+                            None => println!("Cuelist '{name}' has no cues"),
+                        }
+                    }
+                    Some("stop") => {
+                        let show = &rt.show;
+                        let pb = match active_pb {
+                            'b' => &mut rt.playbacks["b"],
+                            _ => &mut rt.playbacks["a"],
+                        };
+                        pb.release(show, 0)?;
+                        println!("Cuelist '{name}' stopped");
+                    }
+                    _ => println!("Usage: loop on|off|start|stop"),
+                }
+            }
+
+            "autoblack" => {
+                let on = match parts.get(1).map(|p| p.to_lowercase()).as_deref() {
+                    Some("on") => true,
+                    Some("off") => false,
+                    _ => {
+                        println!("Usage: autoblack on|off  (Go past the last cue releases)");
+                        continue;
+                    }
+                };
+                let name = pb_ref(&rt, active_pb).cuelist.clone();
+                let Some(list) = rt.show.cue_lists.get_mut(&name) else {
+                    println!("Unknown cuelist '{name}'");
+                    continue;
+                };
+                list.auto_black = on;
+                autosave.edited(&rt.show);
+                println!(
+                    "Cuelist '{name}' auto-black {}",
+                    if on { "on" } else { "off" }
+                );
+            }
+
+            "goguard" => {
+                if parts.len() < 2 || parts.len() > 3 {
+                    println!("Usage: goguard <debounce_ms> [confirm]");
+                    continue;
+                }
+                let guard = console_core::GoGuard {
+                    debounce_ms: parts[1].parse()?,
+                    confirm_early_fade: parts
+                        .get(2)
+                        .is_some_and(|p| p.eq_ignore_ascii_case("confirm")),
+                };
+                pb_mut(&mut rt, active_pb).go_guard = guard;
+                println!(
+                    "Playback {} go guard: debounce={}ms confirm_early_fade={}",
+                    active_pb.to_ascii_uppercase(),
+                    guard.debounce_ms,
+                    guard.confirm_early_fade
+                );
+            }
+
+            "xfade" => {
+                let show = &rt.show;
+                let pb = match active_pb {
+                    'b' => &mut rt.playbacks["b"],
+                    _ => &mut rt.playbacks["a"],
+                };
+                let usage = "Usage: xfade [up|down] [0-100]";
+                let (side, pos) = match &parts[1..] {
+                    [] => (None, None),
+                    [p] => (None, p.parse::<u8>().ok()),
+                    [s, p] if s.eq_ignore_ascii_case("up") || s.eq_ignore_ascii_case("down") => {
+                        (Some(s.to_lowercase()), p.parse::<u8>().ok())
+                    }
+                    _ => {
+                        println!("{usage}");
+                        continue;
+                    }
+                };
+                let Some(pos) = pos else {
+                    if parts.len() > 1 {
+                        println!("{usage}");
+                        continue;
+                    }
+                    match pb.start_manual(show) {
+                        Ok(Some(cue)) => println!(
+                            "Manual crossfade to cue {cue} started; move it with 'xfade <0-100>'"
+                        ),
+                        Ok(None) => println!("No next cue"),
+                        Err(e) => println!("{e:#}"),
+                    }
+                    continue;
+                };
+                // moving the fader with nothing running starts a fade to the next cue
+                if pb.crossfade_position().is_none() {
+                    match pb.start_manual(show) {
+                        Ok(Some(_)) => {}
+                        Ok(None) => {
+                            println!("No next cue");
+                            continue;
+                        }
+                        Err(e) => {
+                            println!("{e:#}");
+                            continue;
+                        }
+                    }
+                }
+                let (up, down) = pb.crossfade_position().unwrap_or_default();
+                let (up, down) = match side.as_deref() {
+                    Some("up") => (pos, down),
+                    Some(_) => (up, pos),
+                    None => (pos, pos),
+                };
+                match pb.set_manual_split(up, down) {
+                    Ok(Some(ms)) => println!(
+                        "Crossfade done in {:.1}s. Type 'learn' to store it as the fade time.",
+                        ms as f32 / 1000.0
+                    ),
+                    Ok(None) if up == down => println!("Crossfade at {up}%"),
+                    Ok(None) => println!("Crossfade up {up}% down {down}%"),
+                    Err(e) => println!("{e:#}"),
+                }
+            }
+
+            "learn" => {
+                let pb = match active_pb {
+                    'b' => &mut rt.playbacks["b"],
+                    _ => &mut rt.playbacks["a"],
+                };
+                let (cue, ms) = pb.learn_fade_time(&mut rt.show)?;
+                if let Some(c) = rt
+                    .show
+                    .cue_lists
+                    .get_mut(&pb.cuelist)
+                    .and_then(|l| l.cues.get_mut(&cue))
+                {
+                    c.meta.touch(author.as_deref());
+                }
+                autosave.edited(&rt.show);
+                println!("Cue {cue} fade time set to {ms}ms");
+            }
+
+            "tick" => {
+                if parts.len() != 2 {
+                    println!("Usage: tick <ms>");
+                    continue;
+                }
+                let ms: u32 = parts[1].parse()?;
+                rt.tick(ms);
+                println!("Ticked {ms}ms");
+            }
+
+            "time" | "scale"
+                if cmd == "scale"
+                    || parts.get(1).is_some_and(|p| p.eq_ignore_ascii_case("cues")) =>
+            {
+                let scale = cmd == "scale";
+                let usage = if scale {
+                    "Usage: scale times <a> [thru <b>]|all|<list> by <factor>"
+                } else {
+                    "Usage: time cues <a> [thru <b>]|all|<list> [fade <s>] [delay <s>]"
+                };
+                if scale
+                    && !parts
+                        .get(1)
+                        .is_some_and(|p| p.eq_ignore_ascii_case("times"))
+                {
+                    println!("{usage}");
+                    continue;
+                }
+
+                let active = pb_ref(&rt, active_pb).cuelist.clone();
+                let Some((list, first, last, used)) = cue_target(&rt.show, &active, &parts[2..])
+                else {
+                    println!("{usage}");
+                    continue;
+                };
+                let edit = match &parts[2 + used..] {
+                    ["by", factor] if scale => match factor.parse::<f64>() {
+                        Ok(f) if f >= 0.0 => console_core::TimeEdit::Scale(f),
+                        _ => {
+                            println!("{usage}");
+                            continue;
+                        }
+                    },
+                    rest if !scale && !rest.is_empty() && rest.len() % 2 == 0 => {
+                        let (mut fade_ms, mut delay_ms) = (None, None);
+                        let mut ok = true;
+                        for pair in rest.chunks(2) {
+                            let ms = parse_seconds(pair[1]);
+                            match pair[0].to_lowercase().as_str() {
+                                "fade" if ms.is_some() => fade_ms = ms,
+                                "delay" if ms.is_some() => delay_ms = ms,
+                                _ => ok = false,
+                            }
+                        }
+                        if !ok {
+                            println!("{usage}");
+                            continue;
+                        }
+                        console_core::TimeEdit::Set { fade_ms, delay_ms }
+                    }
+                    _ => {
+                        println!("{usage}");
+                        continue;
+                    }
+                };
+
+                let Some(cl) = rt.show.cue_lists.get_mut(&list) else {
+                    println!("Unknown cuelist '{list}'");
+                    continue;
+                };
+                if let Err(e) = cl.check_unlocked(first, last) {
+                    println!("{e}");
+                    continue;
+                }
+                let touched = cl.edit_times(first, last, edit);
+                if touched.is_empty() {
+                    println!("No cues in that range");
+                    continue;
+                }
+                for num in &touched {
+                    if let Some(c) = cl.cues.get_mut(num) {
+                        c.meta.touch(author.as_deref());
+                    }
+                }
+                autosave.edited(&rt.show);
+                println!(
+                    "Updated times of {} cue(s) in '{list}': {}",
+                    touched.len(),
+                    touched
+                        .iter()
+                        .map(u32::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                );
+            }
+
+            "balance" => {
+                let cmd = match console_core::BalanceCommand::parse(&rt.show, &parts[1..]) {
+                    Ok(c) => c,
+                    Err(e) => {
+                        println!("{e}");
+                        continue;
+                    }
+                };
+                let list = pb_ref(&rt, active_pb).cuelist.clone();
+                let Some(cl) = rt.show.cue_lists.get(&list) else {
+                    println!("Unknown cuelist '{list}'");
+                    continue;
+                };
+                let Some(factor) = cmd.scale else {
+                    let report = cl.intensity_balance(&cmd.fixtures, cmd.first, cmd.last);
+                    if report.cues.is_empty() {
+                        println!("No cues in that range");
+                        continue;
+                    }
+                    let pct = |v: Option<u8>| {
+                        v.map_or("    -".into(), |v| {
+                            format!("{:>4}%", (v as u32 * 100 + 127) / 255)
+                        })
+                    };
+                    print!("  cue ");
+                    for f in &report.fixtures {
+                        print!("{f:>6}");
+                    }
+                    println!();
+                    for (num, row) in &report.cues {
+                        print!("{num:>5} ");
+                        for &v in row {
+                            print!(" {}", pct(v));
+                        }
+                        println!();
+                    }
+                    for (name, pick) in [("min", 0), ("max", 1), ("avg", 2)] {
+                        print!("{name:>5} ");
+                        for &f in &report.fixtures {
+                            let s = report.stats(f).map(|s| [s.0, s.1, s.2][pick]);
+                            print!(" {}", pct(s));
+                        }
+                        println!();
+                    }
+                    continue;
+                };
+
+                if let Err(e) = cl.check_unlocked(cmd.first, cmd.last) {
+                    println!("{e}");
+                    continue;
+                }
+                rt.show.checkpoint(line.trim());
+                let cl = rt.show.cue_lists.get_mut(&list).expect("checked above");
+                let touched = cl.scale_intensity(&cmd.fixtures, cmd.first, cmd.last, factor);
+                if touched.is_empty() {
+                    rt.show.undo();
+                    println!(
+                        "Those fixtures have no intensity in cues {}-{}",
+                        cmd.first, cmd.last
+                    );
+                    continue;
+                }
+                for num in &touched {
+                    if let Some(c) = cl.cues.get_mut(num) {
+                        c.meta.touch(author.as_deref());
+                    }
+                }
+                autosave.edited(&rt.show);
+                println!(
+                    "Scaled {} fixture(s) by {:+}% in '{list}', cue(s) {} (undo reverts)",
+                    cmd.fixtures.len(),
+                    ((factor - 1.0) * 100.0).round(),
+                    touched
+                        .iter()
+                        .map(u32::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                );
+                continue;
+            }
+
+            "undo" => {
+                match rt.show.undo() {
+                    Some(label) => {
+                        autosave.edited(&rt.show);
+                        println!("Undid '{label}'");
+                    }
+                    None => println!("Nothing to undo"),
+                }
+                continue;
+            }
+
+            "time" => {
+                if parts.len() < 2 || parts.len() > 3 {
+                    println!("Usage: time <fade_ms> [delay_ms]");
+                    continue;
+                }
+                rec_fade_ms = parts[1].parse()?;
+                rec_delay_ms = if parts.len() == 3 {
+                    parts[2].parse()?
+                } else {
+                    0
+                };
+                println!("Record defaults: fade_ms={rec_fade_ms} delay_ms={rec_delay_ms}");
+            }
+
+            "solo" => {
+                let pb = pb_mut(&mut rt, active_pb);
+                pb.solo = !pb.solo;
+                println!(
+                    "Playback {} solo {}",
+                    active_pb.to_ascii_uppercase(),
+                    if pb.solo {
+                        "ON (other playbacks muted)"
+                    } else {
+                        "off"
+                    }
+                );
+            }
+
+            "flash" => {
+                let (pb, on) = match &parts[1..] {
+                    [on] => (active_pb, on.to_lowercase()),
+                    [pb, on] if matches!(pb.to_lowercase().as_str(), "a" | "b") => (
+                        if pb.eq_ignore_ascii_case("b") {
+                            'b'
+                        } else {
+                            'a'
+                        },
+                        on.to_lowercase(),
+                    ),
+                    _ => {
+                        println!("Usage: flash [a|b] on|off");
+                        continue;
+                    }
+                };
+                let on = match on.as_str() {
+                    "on" => true,
+                    "off" => false,
+                    _ => {
+                        println!("Usage: flash [a|b] on|off");
+                        continue;
+                    }
+                };
+                pb_mut(&mut rt, pb).flash = on;
+                println!(
+                    "Playback {} flash {}",
+                    pb.to_ascii_uppercase(),
+                    if on {
+                        "ON (intensities at full)"
+                    } else {
+                        "off"
+                    }
+                );
+            }
+
+            "masters" => match &parts[1..] {
+                [] => {
+                    if rt.show.masters.groups.is_empty() {
+                        println!("(no masters; add one with 'masters add <group>')");
+                    }
+                    for g in &rt.show.masters.groups {
+                        println!("  {g:<16} {:>3}%", rt.master_level(g) as u16 * 100 / 255);
+                    }
+                    match &rt.show.masters.house_group {
+                        Some(g) => println!("House group (panic): {g}"),
+                        None => println!("House group (panic): none"),
+                    }
+                }
+                ["add", group] => {
+                    if !rt.show.groups.contains_key(*group) {
+                        println!("Unknown group '{group}'");
+                        continue;
+                    }
+                    if !rt.show.masters.groups.iter().any(|g| g == group) {
+                        rt.show.masters.groups.push(group.to_string());
+                    }
+                    autosave.edited(&rt.show);
+                    println!("Master for group '{group}' added");
+                }
+                ["del", group] => {
+                    rt.set_master(group, 255).ok();
+                    rt.show.masters.groups.retain(|g| g != group);
+                    autosave.edited(&rt.show);
+                    println!("Master for group '{group}' removed");
+                }
+                ["house", group] => {
+                    rt.show.masters.house_group = if group.eq_ignore_ascii_case("none") {
+                        None
+                    } else if rt.show.groups.contains_key(*group) {
+                        Some(group.to_string())
+                    } else {
+                        println!("Unknown group '{group}'");
+                        continue;
+                    };
+                    autosave.edited(&rt.show);
+                    println!("House group set to {group}");
+                }
+                _ => println!("Usage: masters [add <group>|del <group>|house <group>|none]"),
+            },
 
-                        // Most likely your show stores palettes in a map keyed by name.
-                        // If your field name differs, use `rg "palettes"` in console_core to confirm.
-                        if rt.show.palettes.remove(&name).is_none() {
-                            println!("Unknown palette '{name}'");
+            "master" => {
+                let level = match parts.get(2).map(|p| p.to_lowercase()).as_deref() {
+                    Some("full") => 255,
+                    Some("zero") => 0,
+                    Some(p) => match p.parse::<u8>() {
+                        Ok(pct) => (pct.min(100) as u16 * 255 / 100) as u8,
+                        Err(_) => {
+                            println!("Usage: master <group>|all <0-100>|full|zero");
                             continue;
                         }
-
-                        rt.show.save_json_file(show_path)?;
-                        println!("Deleted palette '{name}' and saved.");
+                    },
+                    None => {
+                        println!("Usage: master <group>|all <0-100>|full|zero");
+                        continue;
                     }
-
-                    _ => {
-                        println!(
-                            "Usage: delete cue <num> | delete group <name...> | delete palette <name...>"
-                        );
+                };
+                match parts[1] {
+                    "all" => rt.set_all_masters(level),
+                    group => {
+                        if let Err(e) = rt.set_master(group, level) {
+                            println!("{e}");
+                            continue;
+                        }
                     }
                 }
+                println!("Master {} at {}%", parts[1], level as u16 * 100 / 255);
             }
 
-            "apply" => {
-                // apply palette <name>
-                if parts.len() != 3 || !parts[1].eq_ignore_ascii_case("palette") {
-                    println!("Usage: apply palette <name>");
+            "gm" | "fader" => {
+                if cmd == "gm" && parts.get(1).is_some_and(|p| p.eq_ignore_ascii_case("fade")) {
+                    let (Some(Ok(pct)), Some(true), Some(Some(ms))) = (
+                        parts.get(2).map(|p| p.parse::<u8>()),
+                        parts.get(3).map(|p| p.eq_ignore_ascii_case("in")),
+                        parts.get(4).map(|s| parse_seconds(s)),
+                    ) else {
+                        println!("Usage: gm fade <0-100> in <seconds>");
+                        continue;
+                    };
+                    let pct = pct.min(100);
+                    rt.fade_grand_master((pct as u16 * 255 / 100) as u8, ms);
+                    println!("Grand master fading to {pct}% in {ms}ms");
                     continue;
                 }
-                let name = parts[2];
-                let pal = match rt.show.palettes.get(name) {
-                    Some(p) => p,
-                    None => {
-                        println!("Unknown palette '{name}'. Type: palettes");
+                // gm <0-100>  OR  fader a|b <0-100>
+                let (target, arg) = match (cmd.as_str(), &parts[1..]) {
+                    ("gm", [p]) => (None, *p),
+                    ("fader", [pb, p]) if matches!(pb.to_lowercase().as_str(), "a" | "b") => {
+                        (Some(pb.to_lowercase()), *p)
+                    }
+                    ("gm", []) => {
+                        println!("Grand master at {}%", rt.grand_master() as u16 * 100 / 255);
+                        continue;
+                    }
+                    _ => {
+                        println!("Usage: gm <0-100>  OR  fader a|b <0-100>");
                         continue;
                     }
                 };
-                rt.programmer.apply_palette(pal);
-                println!("Applied palette '{name}' to programmer.");
+                let Ok(pct) = arg.parse::<u8>() else {
+                    println!("Usage: gm <0-100>  OR  fader a|b <0-100>");
+                    continue;
+                };
+                let level = (pct.min(100) as u16 * 255 / 100) as u8;
+                match target.as_deref() {
+                    None => {
+                        rt.set_grand_master(level);
+                        println!("Grand master at {}%", pct.min(100));
+                    }
+                    Some(pb) => {
+                        pb_mut(&mut rt, if pb == "b" { 'b' } else { 'a' }).master = level;
+                        println!("Playback {} fader at {}%", pb.to_uppercase(), pct.min(100));
+                    }
+                }
             }
 
-            "cues" => {
-                let cl = rt.show.cue_lists.get("main").unwrap();
-                if cl.cues.is_empty() {
-                    println!("(no cues yet)");
+            "rate" | "speed" => {
+                let pct = match parts.get(1).map(|p| p.to_lowercase()).as_deref() {
+                    None => None,
+                    Some("off") => Some(100.0),
+                    Some(p) => match p.trim_end_matches('%').parse::<f32>() {
+                        Ok(pct) => Some(pct),
+                        Err(_) => {
+                            println!("Usage: {cmd} [<pct>|off]");
+                            continue;
+                        }
+                    },
+                };
+                let result = match (cmd.as_str(), pct) {
+                    ("speed", Some(pct)) => rt.set_speed_master(pct / 100.0),
+                    (_, Some(pct)) => pb_mut(&mut rt, active_pb).set_rate(pct / 100.0),
+                    _ => Ok(()),
+                };
+                if let Err(e) = result {
+                    println!("{e}");
                     continue;
                 }
-                println!(
-                    "Cuelist: main | A current: {:?} | B current: {:?} | active: {}",
-                    rt.playback_a.current,
-                    rt.playback_b.current,
-                    active_pb.to_ascii_uppercase()
-                );
-                for (&num, cue) in &cl.cues {
-                    let cur = pb_ref(&rt, active_pb).current;
-                    let mark = if Some(num) == cur { " <==" } else { "" };
+                if cmd == "speed" {
+                    println!("Speed master at {:.0}%", rt.speed_master() * 100.0);
+                } else {
                     println!(
-                        "  {} | {} | fade={}ms delay={}ms block={}{}",
-                        num, cue.label, cue.fade_ms, cue.delay_ms, cue.block, mark
+                        "Playback {} rate at {:.0}%",
+                        active_pb.to_ascii_uppercase(),
+                        pb_ref(&rt, active_pb).rate() * 100.0
                     );
                 }
             }
 
-            "goto" => {
-                if parts.len() != 2 {
-                    println!("Usage: goto <cue_number>");
-                    continue;
-                }
-                let num: u32 = parts[1].parse()?;
-
-                let cur = match active_pb {
-                    'b' => {
-                        rt.playback_b.goto(&rt.show, num)?;
-                        rt.playback_b.current
+            "blackout" => {
+                let on = match parts.get(1).map(|p| p.to_lowercase()).as_deref() {
+                    None => !rt.is_blackout_latched(),
+                    Some("on") => true,
+                    Some("off") => false,
+                    Some("time") => {
+                        let Some(ms) = parts.get(2).and_then(|s| parse_seconds(s)) else {
+                            println!("Usage: blackout time <seconds>");
+                            continue;
+                        };
+                        rt.timed_blackout(ms);
+                        println!("Fading to blackout in {ms}ms ('blackout off' cancels)");
+                        continue;
                     }
                     _ => {
-                        rt.playback_a.goto(&rt.show, num)?;
-                        rt.playback_a.current
+                        println!("Usage: blackout [on|off|time <seconds>]");
+                        continue;
                     }
                 };
+                rt.set_blackout(on);
+                if on {
+                    println!("BLACKOUT: all intensities out ('blackout off' to restore)");
+                } else {
+                    println!("Blackout off");
+                }
+            }
 
-                println!(
-                    "Playback {} now at cue {:?}",
-                    active_pb.to_ascii_uppercase(),
-                    cur
-                );
+            "panic" => {
+                let on = !parts.get(1).is_some_and(|p| p.eq_ignore_ascii_case("off"));
+                rt.set_panic(on);
+                if on {
+                    println!("PANIC: house lights on, stage out ('panic off' to release)");
+                } else {
+                    println!("Panic released");
+                }
             }
 
-            "go" => {
-                let cur = match active_pb {
-                    'b' => rt.playback_b.go(&rt.show)?,
-                    _ => rt.playback_a.go(&rt.show)?,
+            "filter" => {
+                let filter = match parts.get(1).map(|p| p.parse()) {
+                    Some(Ok(f)) => f,
+                    _ => {
+                        println!("Usage: filter all|intensity|color|position");
+                        continue;
+                    }
                 };
-
+                pb_mut(&mut rt, active_pb).filter = filter;
+                rt.show
+                    .playbacks
+                    .entry(active_pb.to_string())
+                    .or_default()
+                    .filter = filter;
+                autosave.edited(&rt.show);
                 println!(
-                    "Playback {} now at cue {:?}",
+                    "Playback {} contributes: {}",
                     active_pb.to_ascii_uppercase(),
-                    cur
+                    parts[1].to_lowercase()
                 );
             }
 
-            "tick" => {
-                if parts.len() != 2 {
-                    println!("Usage: tick <ms>");
-                    continue;
-                }
-                let ms: u32 = parts[1].parse()?;
-                rt.tick(ms);
-                println!("Ticked {ms}ms");
-            }
-
-            "time" => {
-                if parts.len() < 2 || parts.len() > 3 {
-                    println!("Usage: time <fade_ms> [delay_ms]");
-                    continue;
-                }
-                rec_fade_ms = parts[1].parse()?;
-                rec_delay_ms = if parts.len() == 3 {
-                    parts[2].parse()?
-                } else {
-                    0
-                };
-                println!("Record defaults: fade_ms={rec_fade_ms} delay_ms={rec_delay_ms}");
-            }
-
             "pbmode" => {
                 if parts.len() != 2 {
                     println!("Usage: pbmode tracking|cueonly");
@@ -695,14 +3482,19 @@ fn repl(show_path: &str) -> anyhow::Result<()> {
                 // Do the mutation inside a small scope so the mutable borrow ends
                 let new_value = cmd == "block";
                 let result: Option<bool> = {
-                    let cl = rt
-                        .show
-                        .cue_lists
-                        .get_mut("main")
-                        .expect("main cuelist exists");
+                    let list = pb_ref(&rt, active_pb).cuelist.clone();
+                    let Some(cl) = rt.show.cue_lists.get_mut(&list) else {
+                        println!("Unknown cuelist '{list}'");
+                        continue;
+                    };
+                    if let Err(e) = cl.check_unlocked(num, num) {
+                        println!("{e}");
+                        continue;
+                    }
                     match cl.cues.get_mut(&num) {
                         Some(cue) => {
                             cue.block = new_value;
+                            cue.meta.touch(author.as_deref());
                             Some(cue.block)
                         }
                         None => None,
@@ -710,12 +3502,140 @@ fn repl(show_path: &str) -> anyhow::Result<()> {
                 };
                 match result {
                     Some(v) => {
-                        rt.show.save_json_file(show_path)?;
+                        autosave.edited(&rt.show);
                         println!("Cue {num} block = {v}");
                     }
                     None => println!("Cue {num} not found. Type: cues"),
                 }
             }
+
+            "assert" => {
+                // assert cue <number> [block]
+                if parts.len() < 3 || parts.len() > 4 || !parts[1].eq_ignore_ascii_case("cue") {
+                    println!("Usage: assert cue <cue_number> [block]");
+                    continue;
+                }
+                let num: u32 = parts[2].parse()?;
+                let block = match parts.get(3) {
+                    None => false,
+                    Some(p) if p.eq_ignore_ascii_case("block") => true,
+                    Some(_) => {
+                        println!("Usage: assert cue <cue_number> [block]");
+                        continue;
+                    }
+                };
+
+                let list = pb_ref(&rt, active_pb).cuelist.clone();
+                let Some(cl) = rt.show.cue_lists.get_mut(&list) else {
+                    println!("Unknown cuelist '{list}'");
+                    continue;
+                };
+                match cl.assert_cue(num, block) {
+                    Ok(n) => {
+                        if let Some(cue) = cl.cues.get_mut(&num) {
+                            cue.meta.touch(author.as_deref());
+                        }
+                        autosave.edited(&rt.show);
+                        println!(
+                            "Asserted cue {num}: {n} fixture(s) hardened{}.",
+                            if block { ", blocked" } else { "" }
+                        );
+                    }
+                    Err(e) => println!("{e}"),
+                }
+            }
+
+            "clean" => {
+                // clean cue <number>|all
+                if parts.len() != 3 || !parts[1].eq_ignore_ascii_case("cue") {
+                    println!("Usage: clean cue <cue_number>|all");
+                    continue;
+                }
+                let list = pb_ref(&rt, active_pb).cuelist.clone();
+                let Some(cl) = rt.show.cue_lists.get_mut(&list) else {
+                    println!("Unknown cuelist '{list}'");
+                    continue;
+                };
+
+                let removed = if parts[2].eq_ignore_ascii_case("all") {
+                    cl.clean_all()
+                } else {
+                    let num: u32 = parts[2].parse()?;
+                    match cl.clean_cue(num) {
+                        Ok(n) => {
+                            if n > 0
+                                && let Some(cue) = cl.cues.get_mut(&num)
+                            {
+                                cue.meta.touch(author.as_deref());
+                            }
+                            n
+                        }
+                        Err(e) => {
+                            println!("{e}");
+                            continue;
+                        }
+                    }
+                };
+
+                autosave.edited(&rt.show);
+                println!("Removed {removed} redundant value(s).");
+            }
+
+            "copy" => {
+                // copy cues <a> [thru <b>] from <list> to <list> at <n> [track|flat]
+                const USAGE: &str =
+                    "Usage: copy cues <a> [thru <b>] from <list> to <list> at <n> [track|flat]";
+                if parts.len() < 3 || !parts[1].eq_ignore_ascii_case("cues") {
+                    println!("{USAGE}");
+                    continue;
+                }
+
+                let first: u32 = parts[2].parse()?;
+                let mut i = 3;
+                let mut last = first;
+                if parts.get(i).is_some_and(|p| p.eq_ignore_ascii_case("thru")) {
+                    let Some(b) = parts.get(i + 1) else {
+                        println!("{USAGE}");
+                        continue;
+                    };
+                    last = b.parse()?;
+                    i += 2;
+                }
+
+                let rest = &parts[i..];
+                if !(rest.len() == 6 || rest.len() == 7)
+                    || !rest[0].eq_ignore_ascii_case("from")
+                    || !rest[2].eq_ignore_ascii_case("to")
+                    || !rest[4].eq_ignore_ascii_case("at")
+                {
+                    println!("{USAGE}");
+                    continue;
+                }
+                let (from, to) = (rest[1], rest[3]);
+                let at: u32 = rest[5].parse()?;
+                let flatten = match rest.get(6) {
+                    None => false,
+                    Some(m) if m.eq_ignore_ascii_case("track") => false,
+                    Some(m) if m.eq_ignore_ascii_case("flat") => true,
+                    Some(_) => {
+                        println!("{USAGE}");
+                        continue;
+                    }
+                };
+
+                match rt.show.copy_cues(from, first, last, to, at, flatten) {
+                    Ok(nums) => {
+                        autosave.edited(&rt.show);
+                        println!(
+                            "Copied {} cue(s) from '{from}' to '{to}' as {:?}.",
+                            nums.len(),
+                            nums
+                        );
+                    }
+                    Err(e) => println!("{e}"),
+                }
+            }
+
             "state" => {
                 let pb = pb_ref(&rt, active_pb);
                 let st = pb.state_map(&rt.show)?;
@@ -739,19 +3659,159 @@ fn repl(show_path: &str) -> anyhow::Result<()> {
             }
 
             "pb" => {
-                if parts.len() != 2 {
-                    println!("Usage: pb a|b");
+                const USAGE: &str = "Usage: pb [a|b]  OR  pb <name> use <cuelist>|add [<cuelist>]|del|priority <n>|go|release|goto <cue>";
+                let Some(name) = parts.get(1).map(|p| p.to_lowercase()) else {
+                    for (name, pb) in rt.all_playbacks() {
+                        let active = name.chars().eq([active_pb]);
+                        println!(
+                            "  {:<8} prio {:>3}  '{}' cue {:?}{}",
+                            name.to_uppercase(),
+                            pb.priority,
+                            pb.cuelist,
+                            pb.current,
+                            if active { "  (active)" } else { "" }
+                        );
+                    }
                     continue;
+                };
+                let fixed = matches!(name.as_str(), "a" | "b");
+                let is = |w: &str, word: &str| w.eq_ignore_ascii_case(word);
+                // (message, show edited)
+                let result: anyhow::Result<(String, bool)> = match &parts[2..] {
+                    [] if fixed => {
+                        active_pb = if name == "b" { 'b' } else { 'a' };
+                        Ok((
+                            format!(
+                                "Active playback = {} (cuelist '{}')",
+                                name.to_uppercase(),
+                                pb_ref(&rt, active_pb).cuelist
+                            ),
+                            false,
+                        ))
+                    }
+                    [u, list] if is(u, "use") => rt.use_cue_list(&name, list).map(|_| {
+                        let msg = format!(
+                            "Playback {} now runs cuelist '{list}'.",
+                            name.to_uppercase()
+                        );
+                        (msg, true)
+                    }),
+                    [add, rest @ ..] if is(add, "add") && rest.len() <= 1 => {
+                        let list = rest.first().copied().unwrap_or("main");
+                        rt.add_playback(&name, list).map(|_| {
+                            let msg =
+                                format!("Playback {} added, running '{list}'", name.to_uppercase());
+                            (msg, true)
+                        })
+                    }
+                    [del] if is(del, "del") => rt
+                        .remove_playback(&name)
+                        .map(|_| (format!("Playback {} removed", name.to_uppercase()), true)),
+                    [prio, n] if is(prio, "priority") => match n.parse::<u8>() {
+                        Ok(n) => rt.set_playback_priority(&name, n).map(|_| {
+                            (
+                                format!("Playback {} priority {n}", name.to_uppercase()),
+                                true,
+                            )
+                        }),
+                        Err(_) => Err(anyhow::anyhow!("{USAGE}")),
+                    },
+                    // A and B run from go/back/goto on the active playback
+                    [go] if is(go, "go") && !fixed => {
+                        let show = &rt.show;
+                        rt.playbacks
+                            .get_mut(&name)
+                            .ok_or_else(|| anyhow::anyhow!("unknown playback '{name}'"))
+                            .and_then(|pb| pb.go(show))
+                            .map(|cue| {
+                                (
+                                    format!("Playback {} -> {cue:?}", name.to_uppercase()),
+                                    false,
+                                )
+                            })
+                    }
+                    [goto, cue] if is(goto, "goto") && !fixed => {
+                        let show = &rt.show;
+                        match cue.parse::<u32>() {
+                            Ok(cue) => rt
+                                .playbacks
+                                .get_mut(&name)
+                                .ok_or_else(|| anyhow::anyhow!("unknown playback '{name}'"))
+                                .and_then(|pb| pb.goto(show, cue))
+                                .map(|_| {
+                                    (
+                                        format!("Playback {} at cue {cue}", name.to_uppercase()),
+                                        false,
+                                    )
+                                }),
+                            Err(_) => Err(anyhow::anyhow!("{USAGE}")),
+                        }
+                    }
+                    [rel] if is(rel, "release") && !fixed => {
+                        let show = &rt.show;
+                        rt.playbacks
+                            .get_mut(&name)
+                            .ok_or_else(|| anyhow::anyhow!("unknown playback '{name}'"))
+                            .and_then(|pb| pb.release(show, 0))
+                            .map(|_| (format!("Playback {} released", name.to_uppercase()), false))
+                    }
+                    _ => Err(anyhow::anyhow!("{USAGE}")),
+                };
+                match result {
+                    Ok((msg, edited)) => {
+                        if edited {
+                            autosave.edited(&rt.show);
+                        }
+                        println!("{msg}");
+                    }
+                    Err(e) => println!("{e}"),
                 }
-                match parts[1].to_lowercase().as_str() {
-                    "a" => active_pb = 'a',
-                    "b" => active_pb = 'b',
+            }
+
+            "cuelists" => {
+                const USAGE: &str = "Usage: cuelists  OR  cuelists add <name>  OR  cuelists rename <old> <new>  OR  cuelists del <name>";
+                let result = match &parts[1..] {
+                    [] => {
+                        for (name, list) in &rt.show.cue_lists {
+                            let users: Vec<String> = rt
+                                .all_playbacks()
+                                .filter(|(_, pb)| &pb.cuelist == name)
+                                .map(|(l, _)| l.to_uppercase())
+                                .collect();
+                            let used = if users.is_empty() {
+                                String::new()
+                            } else {
+                                format!(" [pb {}]", users.join(", "))
+                            };
+                            println!("  {name}: {} cue(s){used}", list.cues.len());
+                        }
+                        continue;
+                    }
+                    [a, name] if a.eq_ignore_ascii_case("add") => rt.show.add_cue_list(name),
+                    [a, from, to] if a.eq_ignore_ascii_case("rename") => {
+                        rt.rename_cue_list(from, to)
+                    }
+                    [a, name] if a.eq_ignore_ascii_case("del") => rt.delete_cue_list(name),
                     _ => {
-                        println!("Usage: pb a|b");
+                        println!("{USAGE}");
                         continue;
                     }
+                };
+                match result {
+                    Ok(()) => {
+                        autosave.edited(&rt.show);
+                        println!(
+                            "Cue lists: {}",
+                            rt.show
+                                .cue_lists
+                                .keys()
+                                .cloned()
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        );
+                    }
+                    Err(e) => println!("{e}"),
                 }
-                println!("Active playback = {}", active_pb.to_ascii_uppercase());
             }
 
             "trans" => match pb_ref(&rt, active_pb).transition_info() {
@@ -785,18 +3845,18 @@ fn repl(show_path: &str) -> anyhow::Result<()> {
 
             "run" => {
                 running = true;
-                last_tick = Instant::now();
-                last_print = Instant::now();
+                rt.sync_clock();
+                last_print_ms = rt.now_ms();
 
                 let live = rt.render()?;
                 let nz = live.nonzero();
 
                 println!(
                     "A:{:?}({:?}) B:{:?}({:?}) active:{} nz={}",
-                    rt.playback_a.current,
-                    rt.playback_a.mode,
-                    rt.playback_b.current,
-                    rt.playback_b.mode,
+                    rt.playbacks["a"].current,
+                    rt.playbacks["a"].mode,
+                    rt.playbacks["b"].current,
+                    rt.playbacks["b"].mode,
                     active_pb.to_ascii_uppercase(),
                     nz.len()
                 );
@@ -808,7 +3868,8 @@ fn repl(show_path: &str) -> anyhow::Result<()> {
             }
 
             "groups" => {
-                if rt.show.groups.is_empty() {
+                let auto = rt.show.patch.auto_groups();
+                if rt.show.groups.is_empty() && auto.is_empty() {
                     println!("(no groups)");
                     continue;
                 }
@@ -820,29 +3881,168 @@ fn repl(show_path: &str) -> anyhow::Result<()> {
                         .collect::<Vec<_>>()
                         .join(" ");
                     println!("  {name} | {ids}");
+                    if let Some(meta) = rt.show.group_meta.get(name)
+                        && !meta.is_empty()
+                    {
+                        println!("      {}", meta.summary());
+                    }
+                }
+                println!("Automatic (from the patch):");
+                for (name, set) in &auto {
+                    let ids = set.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+                    println!("  {name} | {}", ids.join(" "));
+                }
+            }
+
+            "locate" => {
+                // locate <fixture> <area> [bar] [unit]  OR  locate <fixture> clear
+                let usage =
+                    "Usage: locate <fixture_id> <area> [bar] [unit]  OR  locate <fixture_id> clear";
+                let Some(Ok(fid)) = parts.get(1).map(|p| p.parse::<u32>()) else {
+                    println!("{usage}");
+                    continue;
+                };
+                let location = match &parts[2..] {
+                    ["clear"] => console_core::Location::default(),
+                    [area, rest @ ..] if rest.len() <= 2 => {
+                        let unit = match rest.get(1).map(|u| u.parse::<u32>()) {
+                            None => None,
+                            Some(Ok(u)) => Some(u),
+                            Some(Err(_)) => {
+                                println!("{usage}");
+                                continue;
+                            }
+                        };
+                        console_core::Location {
+                            area: area.to_string(),
+                            bar: rest.first().map(|b| b.to_string()),
+                            unit,
+                        }
+                    }
+                    _ => {
+                        println!("{usage}");
+                        continue;
+                    }
+                };
+                let Some(f) = rt.show.patch.fixtures.get_mut(&fid) else {
+                    println!("Unknown fixture {fid}");
+                    continue;
+                };
+                println!("Fixture {fid}: {location}");
+                f.location = location;
+                autosave.edited(&rt.show);
+            }
+
+            "limit" => {
+                let usage = "Usage: limit <fixture_id> [pan|tilt <min%> <max%> | pan|tilt clear]";
+                let Some(Ok(fid)) = parts.get(1).map(|p| p.parse::<u32>()) else {
+                    println!("{usage}");
+                    continue;
+                };
+                let Some(f) = rt.show.patch.fixtures.get_mut(&fid) else {
+                    println!("Unknown fixture {fid}");
+                    continue;
+                };
+                let show_limit = |axis: &str, l: Option<console_core::Limit>| match l {
+                    Some(l) => {
+                        let (lo, hi) = l.percent();
+                        println!("  {axis}: {lo:.1}% - {hi:.1}%")
+                    }
+                    None => println!("  {axis}: free"),
+                };
+                if parts.len() == 2 {
+                    println!("Fixture {fid} limits:");
+                    show_limit("pan", f.limits.pan);
+                    show_limit("tilt", f.limits.tilt);
+                    continue;
                 }
+                let limit = match &parts[3..] {
+                    ["clear"] => None,
+                    [lo, hi] => match (
+                        lo.trim_end_matches('%').parse(),
+                        hi.trim_end_matches('%').parse(),
+                    ) {
+                        (Ok(lo), Ok(hi)) => Some(console_core::Limit::from_percent(lo, hi)),
+                        _ => {
+                            println!("{usage}");
+                            continue;
+                        }
+                    },
+                    _ => {
+                        println!("{usage}");
+                        continue;
+                    }
+                };
+                match parts[2].to_lowercase().as_str() {
+                    "pan" => f.limits.pan = limit,
+                    "tilt" => f.limits.tilt = limit,
+                    _ => {
+                        println!("{usage}");
+                        continue;
+                    }
+                }
+                println!("Fixture {fid} limits:");
+                show_limit("pan", f.limits.pan);
+                show_limit("tilt", f.limits.tilt);
+                autosave.edited(&rt.show);
             }
 
             "group" => {
-                if parts.len() != 2 {
-                    println!("Usage: group <name>");
+                if parts.len() < 2 {
+                    println!(
+                        "Usage: group <name> [+|- <ids|groups>...]  (+/- terms are fixture ids; use g <name> for groups)"
+                    );
                     continue;
                 }
                 let name = parts[1];
 
-                let Some(sel) = rt.show.groups.get(name) else {
-                    println!("Unknown group '{name}'");
+                // `group 1 - 5`: the rest is a selection expression
+                let sel = if parts.len() == 2 {
+                    rt.show.group_ids(name)
+                } else {
+                    rt.show.parse_selection(&parts)
+                };
+                let Some(sel) = sel else {
+                    println!("Unknown group or no fixtures in '{}'", parts.join(" "));
                     continue;
                 };
 
-                rt.programmer.selected = sel.clone();
-                println!("Selected group '{name}'");
+                let pending = rt.programmer.set_selection(sel);
+                if parts.len() == 2 {
+                    println!("Selected group '{name}'");
+                } else {
+                    println!("Selected {}", parts.join(" "));
+                }
+                prompt_release(&pending);
             }
 
+            _ if class_line == "select" => match rt.show.parse_programmer_line(line) {
+                Ok(console_core::LineStatus::Complete(l)) => {
+                    if let Some(ids) = l.selection {
+                        let pending = rt.programmer.set_selection(ids);
+                        prompt_release(&pending);
+                    }
+                    if let Some(pct) = l.level {
+                        rt.programmer.set_intensity_percent(pct);
+                    }
+                }
+                Ok(console_core::LineStatus::Incomplete(next)) => {
+                    println!("Incomplete: needs {next}")
+                }
+                Err(e) => println!("{e:#}"),
+            },
             _ => println!("Unknown command. Type 'help'."),
         }
     }
 
+    for done in autosave.finish(&rt.show) {
+        if let Err(e) = done.result {
+            println!("!! save failed: {e:#}");
+        }
+    }
+    if autosave.is_unsaved() {
+        println!("Unsaved edits were not written (autosave is manual).");
+    }
     Ok(())
 }
 
@@ -877,10 +4077,53 @@ fn main() -> anyhow::Result<()> {
             show.save_json_file(path)?;
             println!("Saved default showfile to: {}", path);
         }
+        "merge" => {
+            let prefer_theirs = args.iter().any(|a| a == "--theirs");
+            let files: Vec<&String> = args[2..].iter().filter(|a| *a != "--theirs").collect();
+            let [base, mine, theirs, rest @ ..] = files.as_slice() else {
+                anyhow::bail!(
+                    "usage: merge <base.json> <mine.json> <theirs.json> [out.json] [--theirs]"
+                );
+            };
+            let load = |p: &str| {
+                console_core::Show::load_json_file(p).with_context(|| format!("load {p}"))
+            };
+            let (base, mine, theirs) = (load(base)?, load(mine)?, load(theirs)?);
+
+            // merge_reload keeps its first argument on conflict
+            let outcome = if prefer_theirs {
+                console_core::merge_reload(&theirs, &base, &mine)
+            } else {
+                console_core::merge_reload(&mine, &base, &theirs)
+            };
+            let kept = if prefer_theirs { "theirs" } else { "mine" };
+            if outcome.conflicts.is_empty() {
+                println!("Merged without conflicts.");
+            } else {
+                println!("{} conflict(s), kept {kept}:", outcome.conflicts.len());
+                for c in &outcome.conflicts {
+                    println!("  {c}");
+                }
+            }
+            match rest.first() {
+                Some(out) => {
+                    outcome.show.save_json_file(out)?;
+                    println!("Wrote merged show to {out}");
+                }
+                None => println!("Dry run; pass an output file to write the merged show."),
+            }
+        }
         "load" => {
             let path = args.get(2).context("missing <show.json>")?;
-            let show = console_core::Show::load_json_file(path)?;
+            let (show, stamp) = console_core::Show::load_json_file_stamped(path)?;
             println!("Loaded show: {}", show.name);
+            match &stamp {
+                Some(s) => println!("Written by: {} {}", s.app, s.version),
+                None => println!("Written by: (unknown, older file)"),
+            }
+            if let Some(warning) = stamp.and_then(|s| s.compatibility_warning()) {
+                println!("WARNING: {warning}");
+            }
             println!(
                 "Fixture types: {}, fixtures: {}",
                 show.patch.fixture_types.len(),
@@ -896,16 +4139,15 @@ fn main() -> anyhow::Result<()> {
                 .context("fixture_id must be a number")?;
             let name = args.get(4).context("missing <name>")?;
             let fixture_type = args.get(5).context("missing <fixture_type>")?;
-            let universe: u16 = args
-                .get(6)
-                .context("missing <universe>")?
-                .parse()
-                .context("universe must be a number")?;
-            let address: u16 = args
-                .get(7)
-                .context("missing <address>")?
-                .parse()
-                .context("address must be a number")?;
+            // either `<universe> <address>` or one address in any notation (2.1, U2:001, 513)
+            let (universe, address) = match (args.get(6), args.get(7)) {
+                (Some(u), Some(a)) => (
+                    u.parse().context("universe must be a number")?,
+                    a.parse().context("address must be a number")?,
+                ),
+                (Some(addr), None) => console_core::parse_address(addr)?,
+                _ => anyhow::bail!("missing <universe> <address>"),
+            };
 
             let mut show = console_core::Show::load_json_file(path)
                 .with_context(|| format!("failed to load showfile '{path}'"))?;
@@ -929,14 +4171,56 @@ fn main() -> anyhow::Result<()> {
             println!("Fixtures:");
             for f in show.patch.list_fixtures() {
                 println!(
-                    "  #{:>3} | {:<10} | type {:<12} | U{} @ {}",
-                    f.fixture_id, f.name, f.fixture_type, f.universe, f.address
+                    "  #{:>3} | {:<10} | type {:<12} | {}",
+                    f.fixture_id,
+                    f.name,
+                    f.fixture_type,
+                    show.address_format.format(f.universe, f.address)
                 );
             }
         }
+        "version" => println!("{}", console_core::build_info()),
+        "dmx-devices" => {
+            let devices = console_core::usb_dmx_devices();
+            if devices.is_empty() {
+                println!("No USB serial devices found.");
+            }
+            for d in devices {
+                let widget = d.widget.map_or("unknown".to_string(), |w| w.to_string());
+                println!("  {:<60} {widget}", d.path.display());
+            }
+        }
         "repl" => {
             let path = args.get(2).context("missing <show.json>")?;
-            repl(path)?;
+            // role from the command line, else LIGHTCONSOLE_ROLE, else full
+            let role = match args
+                .get(3)
+                .cloned()
+                .or_else(|| env::var("LIGHTCONSOLE_ROLE").ok())
+            {
+                Some(r) => r.parse()?,
+                None => console_core::Role::Full,
+            };
+            repl(path, role)?;
+        }
+        "scenario" => {
+            let path = args.get(2).context("missing <scenario.json>")?;
+            let (scenario, report) = console_core::Scenario::run_file(path)?;
+            println!("Scenario: {}", scenario.name);
+            for m in &report.mismatches {
+                println!(
+                    "  step {}: U{}:{:03} expected {} got {}",
+                    m.step, m.universe, m.address, m.expected, m.actual
+                );
+            }
+            if !report.passed() {
+                anyhow::bail!(
+                    "{} of {} check(s) failed",
+                    report.mismatches.len(),
+                    report.checks
+                );
+            }
+            println!("OK ({} check(s))", report.checks);
         }
 
         _ => print_help(),
@@ -0,0 +1,88 @@
+//! Render time for a 10k-channel rig.
+//!
+//!     cargo bench -p console_core --bench render
+//!     cargo bench -p console_core --bench render --features parallel
+
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+use console_core::{Cue, FixtureInstance, FixtureValues, Runtime, Show, default_fixture_types};
+
+const FIXTURES: u32 = 3340; // 3-channel pars: 10,020 channels
+const PER_UNIVERSE: u32 = 170;
+const ITERATIONS: u32 = 200;
+
+fn big_show() -> anyhow::Result<Show> {
+    let mut show = Show::new("Bench");
+    for ft in default_fixture_types() {
+        show.patch.add_fixture_type(ft);
+    }
+    let mut changes = BTreeMap::new();
+    for id in 1..=FIXTURES {
+        let i = id - 1;
+        let universe = (i / PER_UNIVERSE + 1) as u16;
+        let address = ((i % PER_UNIVERSE) * 3 + 1) as u16;
+        show.patch.add_fixture(FixtureInstance::new(
+            id,
+            format!("Par {id}"),
+            "rgb_par_3ch",
+            universe,
+            address,
+        ))?;
+        changes.insert(
+            id,
+            FixtureValues {
+                intensity: None,
+                r: Some((id % 256) as u8),
+                g: Some(128),
+                b: Some(255),
+                ..Default::default()
+            },
+        );
+    }
+    show.cue_lists.get_mut("main").unwrap().cues.insert(
+        1,
+        Cue {
+            number: 1,
+            label: "Everything".into(),
+            meta: Default::default(),
+            changes,
+            fade_ms: 0,
+            delay_ms: 0,
+            block: false,
+            follow_ms: None,
+            confirm: false,
+            link: None,
+            link_loops: None,
+            warn: None,
+            palette_refs: Default::default(),
+        },
+    );
+    Ok(show)
+}
+
+fn main() -> anyhow::Result<()> {
+    let mut rt = Runtime::new(big_show()?);
+    rt.playbacks["a"].goto(&rt.show, 1)?;
+
+    for _ in 0..10 {
+        rt.render()?; // warm up
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(rt.render()?);
+    }
+    let per_frame = start.elapsed() / ITERATIONS;
+
+    let mode = if cfg!(feature = "parallel") {
+        "parallel"
+    } else {
+        "serial"
+    };
+    println!(
+        "render ({mode}): {} fixtures, {} channels: {per_frame:?} per frame",
+        FIXTURES,
+        FIXTURES * 3
+    );
+    Ok(())
+}
@@ -0,0 +1,46 @@
+//! Build a small show in code and print it as JSON (or save it with
+//! `cargo run --example build_show -- show.json`).
+
+use console_core::{CueBuilder, ShowBuilder};
+
+fn main() -> anyhow::Result<()> {
+    let show = ShowBuilder::new("Example")
+        .default_fixture_types()
+        .fixture(1, "Par 1", "rgb_par_3ch", 1, 1)
+        .fixture(2, "Par 2", "rgb_par_3ch", 1, 4)
+        .fixture(10, "Front", "dimmer_1ch", 1, 10)
+        .group("pars", [1, 2])
+        .cue(
+            CueBuilder::new(1)
+                .label("Preset")
+                .fade_ms(3000)
+                .intensity(10, 180),
+        )
+        .cue(
+            CueBuilder::new(2)
+                .label("Warm")
+                .fade_ms(2000)
+                .intensity(1, 255)
+                .intensity(2, 255)
+                .rgb(1, 255, 140, 40)
+                .rgb(2, 255, 140, 40),
+        )
+        .cue(
+            CueBuilder::new(3)
+                .label("Out")
+                .fade_ms(5000)
+                .intensity(1, 0)
+                .intensity(2, 0)
+                .intensity(10, 0),
+        )
+        .build()?;
+
+    match std::env::args().nth(1) {
+        Some(path) => {
+            show.save_json_file(&path)?;
+            println!("saved '{}' to {path}", show.name);
+        }
+        None => println!("{}", serde_json::to_string_pretty(&show)?),
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+//! Record cues from the programmer, then play them back on playback A and
+//! print the DMX output through the fade.
+
+use console_core::{CueBuilder, Runtime, ShowBuilder};
+
+fn main() -> anyhow::Result<()> {
+    let show = ShowBuilder::new("Example")
+        .default_fixture_types()
+        .fixture(1, "Par 1", "rgb_par_3ch", 1, 1)
+        .fixture(2, "Par 2", "rgb_par_3ch", 1, 4)
+        .build()?;
+    let mut rt = Runtime::new(show);
+
+    // Record cue 1: both pars at full, blue.
+    rt.programmer.select_range(1, 2);
+    rt.programmer.set_intensity_percent(100);
+    rt.programmer.set_rgb(0, 0, 255);
+    let cue = CueBuilder::new(1)
+        .fade_ms(1000)
+        .changes(rt.programmer.contents());
+    rt.show
+        .cue_lists
+        .entry("main".into())
+        .or_default()
+        .cues
+        .insert(1, cue.build());
+
+    // Record cue 2: par 2 goes red, par 1 tracks.
+    rt.programmer.clear_all();
+    rt.programmer.select_one(2);
+    rt.programmer.set_rgb(255, 0, 0);
+    let cue = CueBuilder::new(2)
+        .fade_ms(2000)
+        .changes(rt.programmer.contents());
+    rt.show
+        .cue_lists
+        .entry("main".into())
+        .or_default()
+        .cues
+        .insert(2, cue.build());
+    rt.programmer.clear_all();
+
+    for _ in 0..2 {
+        let cue = rt.playbacks["a"].go(&rt.show)?;
+        println!("go -> cue {cue:?}");
+        for _ in 0..4 {
+            rt.tick(500);
+            println!("  {:?}", rt.render()?.nonzero());
+        }
+    }
+    Ok(())
+}
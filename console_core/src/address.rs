@@ -0,0 +1,101 @@
+//! DMX address notation: "universe.address" (U2:001) vs absolute (513).
+
+use serde::{Deserialize, Serialize};
+
+/// Channels per universe in absolute numbering.
+pub const UNIVERSE_SIZE: u32 = 512;
+
+/// How addresses are shown to the operator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressFormat {
+    /// `U2:001`
+    #[default]
+    UniverseAddress,
+    /// `513` (universe 1 starts at 1)
+    Absolute,
+}
+
+impl AddressFormat {
+    pub fn format(self, universe: u16, address: u16) -> String {
+        match self {
+            AddressFormat::UniverseAddress => format!("U{universe}:{address:03}"),
+            AddressFormat::Absolute => to_absolute(universe, address).to_string(),
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == AddressFormat::default()
+    }
+}
+
+impl std::str::FromStr for AddressFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "universe" | "u.a" | "universe.address" => Ok(AddressFormat::UniverseAddress),
+            "absolute" | "abs" => Ok(AddressFormat::Absolute),
+            _ => anyhow::bail!("unknown address format '{s}' (use universe|absolute)"),
+        }
+    }
+}
+
+pub fn to_absolute(universe: u16, address: u16) -> u32 {
+    (universe.max(1) as u32 - 1) * UNIVERSE_SIZE + address as u32
+}
+
+pub fn from_absolute(abs: u32) -> anyhow::Result<(u16, u16)> {
+    anyhow::ensure!(abs >= 1, "absolute address must be >= 1");
+    let universe = (abs - 1) / UNIVERSE_SIZE + 1;
+    let address = (abs - 1) % UNIVERSE_SIZE + 1;
+    let universe = u16::try_from(universe)
+        .map_err(|_| anyhow::anyhow!("absolute address {abs} is out of range"))?;
+    Ok((universe, address as u16))
+}
+
+/// Parse any accepted notation: `2.1`, `2/1`, `U2:001`, `u2.1` or absolute `513`.
+pub fn parse_address(s: &str) -> anyhow::Result<(u16, u16)> {
+    let t = s.trim();
+    let t = t.strip_prefix(['U', 'u']).unwrap_or(t);
+
+    let Some((u, a)) = t.split_once(['.', '/', ':']) else {
+        let abs: u32 = t
+            .parse()
+            .map_err(|_| anyhow::anyhow!("invalid DMX address '{s}'"))?;
+        return from_absolute(abs);
+    };
+
+    let universe: u16 = u
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid universe in '{s}'"))?;
+    let address: u16 = a
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid address in '{s}'"))?;
+    anyhow::ensure!(universe >= 1, "universe must be >= 1 in '{s}'");
+    anyhow::ensure!(
+        (1..=512).contains(&address),
+        "DMX address must be 1..=512 in '{s}'"
+    );
+    Ok((universe, address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_round_trip() -> anyhow::Result<()> {
+        assert_eq!(parse_address("U2:001")?, (2, 1));
+        assert_eq!(parse_address("2.1")?, (2, 1));
+        assert_eq!(parse_address("2/1")?, (2, 1));
+        assert_eq!(parse_address("513")?, (2, 1));
+        assert_eq!(parse_address("512")?, (1, 512));
+        assert!(parse_address("1.513").is_err());
+        assert!(parse_address("0").is_err());
+
+        assert_eq!(AddressFormat::UniverseAddress.format(2, 1), "U2:001");
+        assert_eq!(AddressFormat::Absolute.format(2, 1), "513");
+        Ok(())
+    }
+}
@@ -0,0 +1,271 @@
+//! What's coming up: follow times running on the playbacks and timecode
+//! events ahead of the playhead, soonest first, each with a countdown.
+//! Cues with a warning (`Cue::warn`) count down before they fire.
+
+use crate::{CueWarning, Playback, Runtime, Show, Timecode};
+
+/// Agenda items looked at for cue warnings.
+const WARN_LOOKAHEAD: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AgendaSource {
+    /// Playback `playback` (by name) follows on from `from` to `to`
+    /// (None = to black).
+    Follow {
+        playback: String,
+        from: u32,
+        to: Option<u32>,
+    },
+    /// Event `index` of the show's timecode event list.
+    Timecode { index: usize, at: Timecode },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgendaItem {
+    /// Countdown from now.
+    pub in_ms: u64,
+    /// False while timecode is stopped: the countdown holds until it rolls.
+    pub running: bool,
+    pub source: AgendaSource,
+    /// What happens: "go" for follows, the command for timecode events.
+    pub command: String,
+}
+
+impl std::fmt::Display for AgendaItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let secs = self.in_ms as f64 / 1000.0;
+        write!(f, "{secs:>7.1}s{} ", if self.running { "" } else { "*" })?;
+        match &self.source {
+            AgendaSource::Follow { playback, from, to } => {
+                write!(f, "follow {}: cue {from} -> ", playback.to_uppercase())?;
+                match to {
+                    Some(to) => write!(f, "cue {to}"),
+                    None => write!(f, "black"),
+                }
+            }
+            AgendaSource::Timecode { index, at } => {
+                write!(f, "tc #{index} {at}: {}", self.command)
+            }
+        }
+    }
+}
+
+/// The follow chain of one playback: the running follow, then every
+/// following cue with a follow time of its own, until `limit` or a cue
+/// without one.
+fn follow_chain(pb: &Playback, name: &str, show: &Show, limit: usize) -> Vec<AgendaItem> {
+    let mut out = Vec::new();
+    let (Some(mut cur), Some(in_ms)) = (pb.current, pb.follow_remaining_ms(show)) else {
+        return out;
+    };
+    let Some(list) = show.cue_lists.get(&pb.cuelist) else {
+        return out;
+    };
+    let mut in_ms = in_ms as u64;
+    while out.len() < limit {
+        let next = list
+            .cues
+            .range(cur.saturating_add(1)..)
+            .next()
+            .or_else(|| list.looping.then(|| list.cues.iter().next()).flatten())
+            .map(|(&n, _)| n);
+        out.push(AgendaItem {
+            in_ms,
+            running: true,
+            source: AgendaSource::Follow {
+                playback: name.to_string(),
+                from: cur,
+                to: next,
+            },
+            command: "go".into(),
+        });
+        let Some(follow_ms) = next.and_then(|n| list.cues[&n].follow_ms) else {
+            break;
+        };
+        cur = next.expect("checked above");
+        in_ms += follow_ms as u64;
+    }
+    out
+}
+
+impl Runtime {
+    /// Upcoming follows and timecode events, soonest first, at most `limit`.
+    /// `timecode` is the playhead frame and whether it's rolling; timecode
+    /// is left out without one.
+    pub fn agenda(&self, timecode: Option<(u32, bool)>, limit: usize) -> Vec<AgendaItem> {
+        let show = &self.show;
+        let mut items: Vec<AgendaItem> = self
+            .all_playbacks()
+            .flat_map(|(name, pb)| follow_chain(pb, name, show, limit))
+            .collect();
+        if let Some((frame, running)) = timecode {
+            let fps = show.events.fps.max(1) as u64;
+            let upcoming = show.events.events.iter().enumerate();
+            items.extend(upcoming.filter(|(_, e)| e.frame > frame).take(limit).map(
+                |(index, e)| AgendaItem {
+                    in_ms: (e.frame - frame) as u64 * 1000 / fps,
+                    running,
+                    source: AgendaSource::Timecode {
+                        index,
+                        at: show.events.timecode(e.frame),
+                    },
+                    command: e.command.clone(),
+                },
+            ));
+        }
+        items.sort_by_key(|i| i.in_ms);
+        items.truncate(limit);
+        items
+    }
+}
+
+/// A running cue warning: `cue` fires on its own in `in_ms`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Countdown {
+    pub cue: u32,
+    pub in_ms: u64,
+    pub beep: bool,
+    pub source: AgendaSource,
+}
+
+/// Turns the agenda into cue warnings. Frontends `poll` it every frame and
+/// flash while `running` isn't empty; each countdown is returned by `poll`
+/// once, when it starts, to beep on.
+#[derive(Debug, Default)]
+pub struct CueWarner {
+    running: Vec<Countdown>,
+}
+
+impl CueWarner {
+    /// Update the countdowns; returns the ones that just started.
+    /// `timecode` as for `Runtime::agenda`.
+    pub fn poll(&mut self, rt: &Runtime, timecode: Option<(u32, bool)>) -> Vec<Countdown> {
+        let due: Vec<Countdown> = rt
+            .agenda(timecode, WARN_LOOKAHEAD)
+            .into_iter()
+            .filter(|item| item.running)
+            .filter_map(|item| {
+                let (cue, warn) = warned_cue(rt, &item)?;
+                (item.in_ms <= warn.lead_ms as u64).then_some(Countdown {
+                    cue,
+                    in_ms: item.in_ms,
+                    beep: warn.beep,
+                    source: item.source,
+                })
+            })
+            .collect();
+        let started = due
+            .iter()
+            .filter(|c| !self.running.iter().any(|r| r.source == c.source))
+            .cloned()
+            .collect();
+        self.running = due;
+        started
+    }
+
+    /// Countdowns as of the last `poll`, soonest first.
+    pub fn running(&self) -> &[Countdown] {
+        &self.running
+    }
+}
+
+/// The cue an agenda item fires, if it has a warning. Timecode `go` and
+/// `goto <n>` events are taken to run on playback A, as the GUI runs them.
+fn warned_cue(rt: &Runtime, item: &AgendaItem) -> Option<(u32, CueWarning)> {
+    let (pb, cue) = match &item.source {
+        AgendaSource::Follow { playback, to, .. } => (rt.playbacks.get(playback)?, (*to)?),
+        AgendaSource::Timecode { .. } => {
+            let words: Vec<&str> = item.command.split_whitespace().collect();
+            let cue = match words.as_slice() {
+                [go] if go.eq_ignore_ascii_case("go") => rt.playbacks["a"].next_cue(&rt.show)?,
+                [goto, n, ..] if goto.eq_ignore_ascii_case("goto") => n.parse().ok()?,
+                _ => return None,
+            };
+            (&rt.playbacks["a"], cue)
+        }
+    };
+    let warn = rt.show.cue_lists.get(&pb.cuelist)?.cues.get(&cue)?.warn?;
+    Some((cue, warn))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CueBuilder, TimedEvent};
+
+    #[test]
+    fn agenda_merges_follows_and_timecode_by_countdown() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        let list = show.cue_lists.get_mut("main").unwrap();
+        list.cues
+            .insert(1, CueBuilder::new(1).follow_ms(3000).build());
+        list.cues
+            .insert(2, CueBuilder::new(2).follow_ms(1000).build());
+        list.cues.insert(3, CueBuilder::new(3).build());
+        show.events.add(TimedEvent {
+            frame: 50, // 2 s at 25 fps
+            command: "goto 3".into(),
+            label: String::new(),
+        });
+
+        let mut rt = Runtime::new(show);
+        assert_eq!(rt.agenda(Some((0, false)), 10).len(), 1);
+        rt.playbacks["a"].goto(&rt.show, 1)?;
+        rt.tick(500);
+
+        let agenda = rt.agenda(Some((0, true)), 10);
+        let summary: Vec<(u64, &str)> = agenda
+            .iter()
+            .map(|i| (i.in_ms, i.command.as_str()))
+            .collect();
+        assert_eq!(summary, [(2000, "goto 3"), (2500, "go"), (3500, "go")]);
+        assert_eq!(
+            agenda[2].source,
+            AgendaSource::Follow {
+                playback: "a".into(),
+                from: 2,
+                to: Some(3)
+            }
+        );
+        assert_eq!(rt.agenda(None, 1).len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn warned_cues_count_down_once_before_firing() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        let list = show.cue_lists.get_mut("main").unwrap();
+        list.cues
+            .insert(1, CueBuilder::new(1).follow_ms(5000).build());
+        list.cues
+            .insert(2, CueBuilder::new(2).warn(3000, true).build());
+        list.cues
+            .insert(3, CueBuilder::new(3).warn(1000, false).build());
+        show.events.add(TimedEvent {
+            frame: 250, // 10 s at 25 fps
+            command: "goto 3".into(),
+            label: String::new(),
+        });
+
+        let mut rt = Runtime::new(show);
+        let mut warner = CueWarner::default();
+        rt.playbacks["a"].goto(&rt.show, 1)?;
+        rt.tick(1000);
+        assert!(warner.poll(&rt, Some((0, true))).is_empty());
+
+        rt.tick(1500);
+        let started = warner.poll(&rt, Some((0, true)));
+        assert_eq!((started.len(), started[0].cue), (1, 2));
+        assert_eq!((started[0].in_ms, started[0].beep), (2500, true));
+        rt.tick(500);
+        assert!(warner.poll(&rt, Some((0, true))).is_empty());
+        assert_eq!(warner.running()[0].in_ms, 2000);
+
+        // stopped timecode doesn't count down
+        assert!(warner.poll(&rt, Some((230, false))).is_empty());
+        let started = warner.poll(&rt, Some((230, true)));
+        assert_eq!(started[0].cue, 3);
+        assert_eq!(warner.running().len(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+//! User command aliases: `alias b1 = group 1 @ full`.
+//!
+//! Aliases are saved with the show and expanded before a command runs, so
+//! they work anywhere a command does (CLI, GUI command line, macro cells,
+//! key bindings). Extra words after an alias are appended to its expansion.
+
+use std::collections::BTreeMap;
+
+/// How many aliases may expand into one another before giving up.
+const MAX_DEPTH: usize = 8;
+
+/// Parse the arguments of `alias <name> = <command...>`; the command may be
+/// quoted.
+pub fn parse_definition(args: &str) -> anyhow::Result<(String, String)> {
+    let (name, command) = args
+        .split_once('=')
+        .ok_or_else(|| anyhow::anyhow!("expected <name> = <command>"))?;
+    let name = name.trim();
+    anyhow::ensure!(
+        !name.is_empty() && !name.contains(char::is_whitespace),
+        "alias name must be a single word"
+    );
+    anyhow::ensure!(
+        name.parse::<u32>().is_err(),
+        "alias name can't be a number (numbers select fixtures)"
+    );
+    let command = command.trim();
+    let command = command
+        .strip_prefix('"')
+        .and_then(|c| c.strip_suffix('"'))
+        .unwrap_or(command)
+        .trim();
+    anyhow::ensure!(!command.is_empty(), "alias '{name}' has no command");
+    Ok((name.to_lowercase(), command.to_string()))
+}
+
+/// Replace a leading alias in `line` with its command, repeatedly.
+/// Lines that don't start with an alias come back unchanged.
+pub fn expand(aliases: &BTreeMap<String, String>, line: &str) -> anyhow::Result<String> {
+    let mut line = line.trim().to_string();
+    for _ in 0..MAX_DEPTH {
+        let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((&line, ""));
+        let Some(command) = aliases.get(&word.to_lowercase()) else {
+            return Ok(line);
+        };
+        line = format!("{command} {}", rest.trim()).trim_end().to_string();
+    }
+    anyhow::bail!("alias loop expanding '{line}'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aliases_expand_with_trailing_words() -> anyhow::Result<()> {
+        let (name, command) = parse_definition(r#"B1 = "group 1 @ full""#)?;
+        assert_eq!((name.as_str(), command.as_str()), ("b1", "group 1 @ full"));
+        assert!(parse_definition("12 = go").is_err());
+        assert!(parse_definition("two words = go").is_err());
+
+        let mut aliases = BTreeMap::from([(name, command)]);
+        aliases.insert("gt".into(), "goto".into());
+        aliases.insert("look".into(), "b1".into());
+        assert_eq!(expand(&aliases, "look")?, "group 1 @ full");
+        assert_eq!(expand(&aliases, "gt 5 time 2")?, "goto 5 time 2");
+        assert_eq!(expand(&aliases, "go")?, "go");
+
+        aliases.insert("ping".into(), "pong".into());
+        aliases.insert("pong".into(), "ping x".into());
+        assert!(expand(&aliases, "ping").is_err());
+        Ok(())
+    }
+}
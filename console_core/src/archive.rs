@@ -0,0 +1,150 @@
+//! Dated show snapshots: the first save of each day copies the file on disk
+//! aside, so a bad editing session can be rolled back to last night's show.
+//!
+//! Snapshots live next to the show in `<stem>.versions/<YYYY-MM-DD>.json`.
+
+use crate::Show;
+use crate::meta::{format_utc, unix_now};
+use anyhow::Context;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Snapshots kept per show unless `$LIGHTCONSOLE_KEEP_VERSIONS` says otherwise.
+pub const DEFAULT_KEEP: usize = 14;
+
+/// One stored snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowVersion {
+    /// "YYYY-MM-DD" (UTC) of the day the snapshot was taken.
+    pub date: String,
+    pub path: PathBuf,
+}
+
+/// Number of snapshots to keep: `$LIGHTCONSOLE_KEEP_VERSIONS` (0 disables).
+pub fn keep_versions() -> usize {
+    std::env::var("LIGHTCONSOLE_KEEP_VERSIONS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_KEEP)
+}
+
+pub fn versions_dir(show_path: &Path) -> PathBuf {
+    let stem = show_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("show");
+    show_path.with_file_name(format!("{stem}.versions"))
+}
+
+fn today() -> String {
+    format_utc(unix_now())[..10].to_string()
+}
+
+/// Snapshot the file on disk if today has no snapshot yet, then prune to
+/// `keep`. Returns the snapshot written, if any.
+pub fn snapshot_daily(show_path: &Path, keep: usize) -> anyhow::Result<Option<PathBuf>> {
+    snapshot_on(show_path, &today(), keep)
+}
+
+fn snapshot_on(show_path: &Path, date: &str, keep: usize) -> anyhow::Result<Option<PathBuf>> {
+    if keep == 0 || !show_path.exists() {
+        return Ok(None);
+    }
+    let dir = versions_dir(show_path);
+    let snapshot = dir.join(format!("{date}.json"));
+    if snapshot.exists() {
+        return Ok(None);
+    }
+
+    fs::create_dir_all(&dir).with_context(|| format!("create {}", dir.display()))?;
+    fs::copy(show_path, &snapshot).with_context(|| format!("write {}", snapshot.display()))?;
+    for old in list_versions(show_path)?.into_iter().skip(keep) {
+        fs::remove_file(&old.path).with_context(|| format!("remove {}", old.path.display()))?;
+    }
+    Ok(Some(snapshot))
+}
+
+/// Snapshots of a show, newest first.
+pub fn list_versions(show_path: &Path) -> anyhow::Result<Vec<ShowVersion>> {
+    let dir = versions_dir(show_path);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut out = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("read {}", dir.display()))? {
+        let path = entry?.path();
+        let Some(date) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let is_date = date.len() == 10 && date.chars().filter(|c| *c == '-').count() == 2;
+        if is_date && path.extension().is_some_and(|e| e == "json") {
+            out.push(ShowVersion {
+                date: date.to_string(),
+                path: path.clone(),
+            });
+        }
+    }
+    out.sort_by(|a, b| b.date.cmp(&a.date));
+    Ok(out)
+}
+
+/// Put the snapshot of `date` back in place of the show file. The replaced
+/// file is kept as `pre-restore.json` in the versions directory.
+pub fn restore_version(show_path: &Path, date: &str) -> anyhow::Result<Show> {
+    let version = list_versions(show_path)?
+        .into_iter()
+        .find(|v| v.date == date)
+        .with_context(|| format!("no snapshot for {date}"))?;
+    let show = Show::load_json_file(&version.path)?;
+
+    if show_path.exists() {
+        let backup = versions_dir(show_path).join("pre-restore.json");
+        fs::copy(show_path, &backup).with_context(|| format!("write {}", backup.display()))?;
+    }
+    fs::copy(&version.path, show_path).with_context(|| format!("write {}", show_path.display()))?;
+    Ok(show)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn daily_snapshots_prune_and_restore() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("lc_archive_{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let path = dir.join("show.json");
+        // plain writes: save_json_file would snapshot with today's date
+        let save = |name: &str| fs::write(&path, serde_json::to_string(&Show::new(name))?);
+
+        assert_eq!(snapshot_on(&path, "2024-04-30", 2)?, None); // nothing on disk yet
+        save("Night 0")?;
+        for (date, name) in [
+            ("2024-05-01", "Night 1"),
+            ("2024-05-02", "Night 2"),
+            ("2024-05-03", "Broken"),
+        ] {
+            assert_eq!(
+                snapshot_on(&path, date, 2)?,
+                Some(versions_dir(&path).join(format!("{date}.json")))
+            );
+            assert_eq!(snapshot_on(&path, date, 2)?, None); // once a day
+            save(name)?;
+        }
+
+        let dates: Vec<_> = list_versions(&path)?.into_iter().map(|v| v.date).collect();
+        assert_eq!(dates, vec!["2024-05-03", "2024-05-02"]);
+
+        // the 05-03 snapshot is the show as it was before that day's session
+        let restored = restore_version(&path, "2024-05-03")?;
+        assert_eq!(restored.name, "Night 2");
+        assert_eq!(Show::load_json_file(&path)?.name, "Night 2");
+        assert_eq!(
+            Show::load_json_file(versions_dir(&path).join("pre-restore.json"))?.name,
+            "Broken"
+        );
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+//! When show edits reach the disk. The policy is stored in the show: write
+//! after every edit (the default), only on an explicit save, or batched on
+//! a timer. Writes go through a background thread from store snapshots, so
+//! recording a cue never waits on the disk.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::{ShowStore, Snapshot};
+
+/// Interval `SavePolicy::Timed` gets when not told otherwise.
+pub const DEFAULT_TIMED_SECS: u32 = 30;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SavePolicy {
+    /// Every record or edit is written right away.
+    #[default]
+    OnRecord,
+    /// Only `save` writes.
+    Manual,
+    /// Edits are written `secs` after the first unsaved one.
+    Timed { secs: u32 },
+}
+
+impl SavePolicy {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::str::FromStr for SavePolicy {
+    type Err = anyhow::Error;
+
+    /// `record`, `manual`, `timed` or `every <secs>`.
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let words: Vec<String> = s.split_whitespace().map(str::to_lowercase).collect();
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        match words.as_slice() {
+            ["record"] | ["edit"] => Ok(Self::OnRecord),
+            ["manual"] => Ok(Self::Manual),
+            ["timed"] => Ok(Self::Timed {
+                secs: DEFAULT_TIMED_SECS,
+            }),
+            ["every", secs] | ["timed", secs] => match secs.trim_end_matches('s').parse() {
+                Ok(secs) if secs > 0 => Ok(Self::Timed { secs }),
+                _ => anyhow::bail!("'{secs}' isn't a number of seconds"),
+            },
+            _ => anyhow::bail!("unknown save policy '{s}' (use record|manual|every <secs>)"),
+        }
+    }
+}
+
+impl std::fmt::Display for SavePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OnRecord => f.write_str("record"),
+            Self::Manual => f.write_str("manual"),
+            Self::Timed { secs } => write!(f, "every {secs}s"),
+        }
+    }
+}
+
+/// A write the background thread finished. Snapshots superseded before
+/// they were written are reported with the result of the write that
+/// replaced them, just before it.
+#[derive(Debug)]
+pub struct SaveDone {
+    /// What was written (or failed to be).
+    pub snapshot: Snapshot,
+    pub result: anyhow::Result<()>,
+}
+
+/// Applies the show's `SavePolicy`: frontends report edits with `edited`
+/// and call `poll` regularly to run timed saves and collect finished ones.
+#[derive(Debug)]
+pub struct Autosave {
+    tx: Option<mpsc::Sender<Snapshot>>,
+    done: mpsc::Receiver<SaveDone>,
+    writer: Option<JoinHandle<()>>,
+    /// When the oldest edit not yet handed to the writer was made.
+    pending_since: Option<Instant>,
+    in_flight: usize,
+}
+
+impl Autosave {
+    /// Start the writer for the show file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let (tx, rx) = mpsc::channel::<Snapshot>();
+        let (done_tx, done) = mpsc::channel();
+        let writer = std::thread::spawn(move || {
+            while let Ok(mut snapshot) = rx.recv() {
+                // a burst of edits only needs its newest state written
+                let mut skipped = Vec::new();
+                while let Ok(newer) = rx.try_recv() {
+                    skipped.push(std::mem::replace(&mut snapshot, newer));
+                }
+                let result = snapshot.save_json_file(&path);
+                for old in skipped {
+                    let result = match &result {
+                        Ok(()) => Ok(()),
+                        Err(e) => Err(anyhow::anyhow!("{e:#}")),
+                    };
+                    if done_tx
+                        .send(SaveDone {
+                            snapshot: old,
+                            result,
+                        })
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+                if done_tx.send(SaveDone { snapshot, result }).is_err() {
+                    return;
+                }
+            }
+        });
+        Self {
+            tx: Some(tx),
+            done,
+            writer: Some(writer),
+            pending_since: None,
+            in_flight: 0,
+        }
+    }
+
+    /// The show changed; saves it now under `SavePolicy::OnRecord`.
+    pub fn edited(&mut self, store: &ShowStore) {
+        self.pending_since.get_or_insert_with(Instant::now);
+        if store.save_policy == SavePolicy::OnRecord {
+            self.save_now(store);
+        }
+    }
+
+    /// Hand the show to the writer whatever the policy (`save`).
+    pub fn save_now(&mut self, store: &ShowStore) {
+        if let Some(tx) = &self.tx
+            && tx.send(store.snapshot()).is_ok()
+        {
+            self.in_flight += 1;
+        }
+        self.pending_since = None;
+    }
+
+    /// Run a due timed save and return the writes finished since last time.
+    pub fn poll(&mut self, store: &ShowStore) -> Vec<SaveDone> {
+        if let SavePolicy::Timed { secs } = store.save_policy
+            && self
+                .pending_since
+                .is_some_and(|t| t.elapsed() >= Duration::from_secs(secs as u64))
+        {
+            self.save_now(store);
+        }
+        let done: Vec<SaveDone> = self.done.try_iter().collect();
+        self.in_flight -= done.len();
+        done
+    }
+
+    /// Edits not yet on disk (waiting for the policy or being written).
+    pub fn is_unsaved(&self) -> bool {
+        self.pending_since.is_some() || self.in_flight > 0
+    }
+
+    /// On exit: write pending timed edits, then wait for the writer. Edits
+    /// under `SavePolicy::Manual` stay unsaved.
+    pub fn finish(&mut self, store: &ShowStore) -> Vec<SaveDone> {
+        if matches!(store.save_policy, SavePolicy::Timed { .. }) && self.pending_since.is_some() {
+            self.save_now(store);
+        }
+        self.tx = None;
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+        let done: Vec<SaveDone> = self.done.try_iter().collect();
+        self.in_flight = self.in_flight.saturating_sub(done.len());
+        done
+    }
+}
+
+impl Drop for Autosave {
+    fn drop(&mut self) {
+        self.tx = None;
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Show;
+
+    #[test]
+    fn policy_decides_when_edits_are_written() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("lc_autosave_{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("show.json");
+        let mut store = ShowStore::new(Show::new("Test"));
+        store.save_policy = SavePolicy::Manual;
+        let mut autosave = Autosave::new(&path);
+
+        store.groups.insert("front".into(), [1].into());
+        autosave.edited(&store);
+        assert!(autosave.is_unsaved() && !path.exists());
+        assert!(autosave.poll(&store).is_empty());
+
+        store.save_policy = SavePolicy::Timed { secs: 0 };
+        let mut done = autosave.poll(&store);
+        done.extend(autosave.finish(&store));
+        assert_eq!(done.len(), 1);
+        assert!(done[0].result.is_ok());
+        assert!(!autosave.is_unsaved());
+        assert_eq!(Show::load_json_file(&path)?.groups.len(), 1);
+
+        assert_eq!(
+            "every 45s".parse::<SavePolicy>()?,
+            SavePolicy::Timed { secs: 45 }
+        );
+        assert_eq!(SavePolicy::Timed { secs: 45 }.to_string(), "every 45s");
+        assert!("every 0".parse::<SavePolicy>().is_err());
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn skipped_snapshots_share_the_failed_write() {
+        let path = std::env::temp_dir()
+            .join(format!("lc_autosave_missing_{}", std::process::id()))
+            .join("show.json");
+        let mut store = ShowStore::new(Show::new("Test"));
+        let mut autosave = Autosave::new(&path);
+        for g in ["front", "back", "side"] {
+            store.groups.insert(g.into(), [1].into());
+            autosave.edited(&store);
+        }
+        let done = autosave.finish(&store);
+        assert_eq!(done.len(), 3);
+        assert!(done.iter().all(|d| d.result.is_err()));
+        assert!(!autosave.is_unsaved());
+    }
+}
@@ -0,0 +1,224 @@
+//! Intensity balance across cues: how a set of fixtures sits in each cue of
+//! a range, and scaling them all at once ("cyc +10% in cues 5 thru 40").
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Cue, CueList, Show};
+
+/// `balance <fixtures> cues <a> [thru <b>] [+|-<n>%]`, parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceCommand {
+    pub fixtures: BTreeSet<u32>,
+    pub first: u32,
+    pub last: u32,
+    /// Relative change as a factor (+10% = 1.1); None only reports.
+    pub scale: Option<f64>,
+}
+
+impl BalanceCommand {
+    pub const USAGE: &str = "Usage: balance <fixtures> cues <a> [thru <b>] [+|-<n>%]";
+
+    /// Parse the words after `balance`.
+    pub fn parse(show: &Show, parts: &[&str]) -> anyhow::Result<Self> {
+        let usage = || anyhow::anyhow!(Self::USAGE);
+        let at = parts
+            .iter()
+            .position(|p| p.eq_ignore_ascii_case("cues"))
+            .ok_or_else(usage)?;
+        let fixtures = show
+            .parse_selection(&parts[..at])
+            .ok_or_else(|| anyhow::anyhow!("no fixtures in '{}'", parts[..at].join(" ")))?;
+        let mut rest = &parts[at + 1..];
+        let first: u32 = rest
+            .first()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(usage)?;
+        let mut last = first;
+        if rest.get(1).is_some_and(|p| p.eq_ignore_ascii_case("thru")) {
+            last = rest.get(2).and_then(|s| s.parse().ok()).ok_or_else(usage)?;
+            rest = &rest[3..];
+        } else {
+            rest = &rest[1..];
+        }
+        let scale = match rest {
+            [] => None,
+            [pct] => {
+                let pct: f64 = pct
+                    .strip_suffix('%')
+                    .and_then(|p| p.parse().ok())
+                    .ok_or_else(usage)?;
+                anyhow::ensure!(pct > -100.0, "can't scale by {pct}%");
+                Some(1.0 + pct / 100.0)
+            }
+            _ => return Err(usage()),
+        };
+        Ok(Self {
+            fixtures,
+            first: first.min(last),
+            last: first.max(last),
+            scale,
+        })
+    }
+}
+
+/// Tracked intensity of each fixture in each cue of a range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntensityBalance {
+    pub fixtures: Vec<u32>,
+    /// Cue number -> one level per fixture (None = never set).
+    pub cues: BTreeMap<u32, Vec<Option<u8>>>,
+}
+
+impl IntensityBalance {
+    /// (min, max, average) of a fixture over the cues where it has a level.
+    pub fn stats(&self, fixture_id: u32) -> Option<(u8, u8, u8)> {
+        let i = self.fixtures.iter().position(|&f| f == fixture_id)?;
+        let levels: Vec<u32> = self
+            .cues
+            .values()
+            .filter_map(|row| row[i])
+            .map(u32::from)
+            .collect();
+        let min = *levels.iter().min()?;
+        let max = *levels.iter().max()?;
+        let avg = levels.iter().sum::<u32>() / levels.len() as u32;
+        Some((min as u8, max as u8, avg as u8))
+    }
+}
+
+impl CueList {
+    pub fn intensity_balance(
+        &self,
+        fixtures: &BTreeSet<u32>,
+        first: u32,
+        last: u32,
+    ) -> IntensityBalance {
+        let cues = self
+            .cues
+            .range(first..=last)
+            .map(|(&num, _)| {
+                let state = self.tracked_state_at(num);
+                let row = fixtures
+                    .iter()
+                    .map(|f| state.get(f).and_then(|v| v.intensity))
+                    .collect();
+                (num, row)
+            })
+            .collect();
+        IntensityBalance {
+            fixtures: fixtures.iter().copied().collect(),
+            cues,
+        }
+    }
+
+    /// Scale the tracked intensity of `fixtures` in cues `first..=last` by
+    /// `factor`. Levels tracking into the range are recorded in its first
+    /// cue, and the cue after the range gets the old levels back so nothing
+    /// past it changes. Returns the cues edited.
+    pub fn scale_intensity(
+        &mut self,
+        fixtures: &BTreeSet<u32>,
+        first: u32,
+        last: u32,
+        factor: f64,
+    ) -> Vec<u32> {
+        let scale = |v: u8| (v as f64 * factor.max(0.0)).round().clamp(0.0, 255.0) as u8;
+        let nums: Vec<u32> = self.cues.range(first..=last).map(|(&n, _)| n).collect();
+        let after = self
+            .cues
+            .range(last.saturating_add(1)..)
+            .next()
+            .map(|(&n, _)| n);
+        let before: BTreeMap<u32, _> = nums
+            .iter()
+            .chain(&after)
+            .map(|&n| (n, self.tracked_state_at(n)))
+            .collect();
+
+        let mut touched = BTreeSet::new();
+        for (i, &num) in nums.iter().enumerate() {
+            let cue = self.cues.get_mut(&num).expect("cue in range");
+            for &fid in fixtures {
+                let own = cue.changes.get(&fid).and_then(|v| v.intensity);
+                let tracked = before[&num].get(&fid).and_then(|v| v.intensity);
+                let Some(level) = own.or(tracked.filter(|_| i == 0)) else {
+                    continue;
+                };
+                cue.changes.entry(fid).or_default().intensity = Some(scale(level));
+                drop_intensity_ref(cue, fid);
+                touched.insert(num);
+            }
+        }
+
+        if let Some(num) = after {
+            let now = self.tracked_state_at(num);
+            let cue = self.cues.get_mut(&num).expect("next cue");
+            for &fid in fixtures {
+                let old = before[&num].get(&fid).and_then(|v| v.intensity);
+                if old.is_some() && now.get(&fid).and_then(|v| v.intensity) != old {
+                    cue.changes.entry(fid).or_default().intensity = old;
+                    drop_intensity_ref(cue, fid);
+                    touched.insert(num);
+                }
+            }
+        }
+        touched.into_iter().collect()
+    }
+}
+
+/// A scaled level is no longer the palette's.
+fn drop_intensity_ref(cue: &mut Cue, fixture_id: u32) {
+    if let Some(refs) = cue.palette_refs.get_mut(&fixture_id) {
+        refs.intensity = None;
+        if refs.is_empty() {
+            cue.palette_refs.remove(&fixture_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CueBuilder;
+
+    fn cue(number: u32, changes: &[(u32, u8)]) -> Cue {
+        changes
+            .iter()
+            .fold(CueBuilder::new(number), |b, &(f, i)| b.intensity(f, i))
+            .build()
+    }
+
+    #[test]
+    fn scaling_a_range_keeps_cues_outside_it() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        show.groups.insert("cyc".into(), BTreeSet::from([1, 2]));
+        let list = show.cue_lists.get_mut("main").unwrap();
+        for c in [
+            cue(1, &[(1, 100), (2, 200)]),
+            cue(5, &[(3, 50)]),
+            cue(6, &[(1, 120)]),
+            cue(7, &[]),
+        ] {
+            list.cues.insert(c.number, c);
+        }
+
+        let cmd = BalanceCommand::parse(&show, &["cyc", "cues", "5", "thru", "6", "+10%"])?;
+        assert_eq!((cmd.first, cmd.last, cmd.scale), (5, 6, Some(1.1)));
+        assert!(BalanceCommand::parse(&show, &["cyc", "5"]).is_err());
+
+        let list = show.cue_lists.get_mut("main").unwrap();
+        let report = list.intensity_balance(&cmd.fixtures, 1, 7);
+        assert_eq!(report.cues[&6], vec![Some(120), Some(200)]);
+        assert_eq!(report.stats(1), Some((100, 120, 110)));
+
+        let touched = list.scale_intensity(&cmd.fixtures, 5, 6, 1.1);
+        assert_eq!(touched, vec![5, 6, 7]);
+        let level = |n, f| list.tracked_state_at(n)[&f].intensity;
+        assert_eq!((level(1, 1), level(1, 2)), (Some(100), Some(200)));
+        assert_eq!((level(5, 1), level(5, 2)), (Some(110), Some(220)));
+        assert_eq!((level(6, 1), level(6, 2)), (Some(132), Some(220)));
+        assert_eq!((level(7, 1), level(7, 2)), (Some(120), Some(200)));
+        assert_eq!(level(5, 3), Some(50));
+        Ok(())
+    }
+}
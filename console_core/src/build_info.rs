@@ -0,0 +1,102 @@
+//! Build information and the "written by" stamp in show files.
+
+use serde::{Deserialize, Serialize};
+
+/// Show file format revision; bump when older cores can't read new files.
+pub const SHOW_FORMAT: u32 = 1;
+
+/// What this build is; shown in about dialogs and sent in handshakes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BuildInfo {
+    pub app: &'static str,
+    pub version: &'static str,
+    pub show_format: u32,
+    pub profile: &'static str,
+    pub target_os: &'static str,
+}
+
+impl BuildInfo {
+    pub fn stamp(&self) -> WrittenBy {
+        WrittenBy {
+            app: self.app.to_string(),
+            version: self.version.to_string(),
+            show_format: self.show_format,
+        }
+    }
+}
+
+impl std::fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} (show format {}, {} build, {})",
+            self.app, self.version, self.show_format, self.profile, self.target_os
+        )
+    }
+}
+
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        app: "lightconsole",
+        version: env!("CARGO_PKG_VERSION"),
+        show_format: SHOW_FORMAT,
+        profile: if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        },
+        target_os: std::env::consts::OS,
+    }
+}
+
+/// Stamp saved in show files (`"written_by"`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WrittenBy {
+    pub app: String,
+    pub version: String,
+    pub show_format: u32,
+}
+
+impl WrittenBy {
+    /// Warning text if this file comes from a newer core than ours.
+    pub fn compatibility_warning(&self) -> Option<String> {
+        let ours = build_info();
+        if self.show_format > ours.show_format {
+            return Some(format!(
+                "Show was written by {} {} (show format {}); this is {} (format {}). \
+                 Some data may be lost if you save.",
+                self.app, self.version, self.show_format, ours.version, ours.show_format
+            ));
+        }
+        if parse_version(&self.version) > parse_version(ours.version) {
+            return Some(format!(
+                "Show was written by newer {} {}; this is {}.",
+                self.app, self.version, ours.version
+            ));
+        }
+        None
+    }
+}
+
+fn parse_version(v: &str) -> Vec<u32> {
+    v.split(['.', '-']).map_while(|p| p.parse().ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newer_files_get_a_warning() {
+        let mut stamp = build_info().stamp();
+        assert_eq!(stamp.compatibility_warning(), None);
+
+        stamp.version = "99.0.0".into();
+        assert!(stamp.compatibility_warning().is_some());
+
+        stamp.version = "0.0.1".into();
+        assert_eq!(stamp.compatibility_warning(), None);
+        stamp.show_format = SHOW_FORMAT + 1;
+        assert!(stamp.compatibility_warning().is_some());
+    }
+}
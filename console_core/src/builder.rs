@@ -0,0 +1,241 @@
+//! Builders for shows and cues made in code: tests, examples and tools that
+//! don't want to spell out every `Cue` field. See `examples/` for shows
+//! built, recorded and played back from library code.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Cue, FixtureInstance, FixtureValues, Show, default_fixture_types};
+
+/// A cue, one setting at a time. Unset times are zero.
+#[derive(Debug, Clone)]
+pub struct CueBuilder {
+    cue: Cue,
+}
+
+impl CueBuilder {
+    pub fn new(number: u32) -> Self {
+        Self {
+            cue: Cue {
+                number,
+                label: String::new(),
+                meta: Default::default(),
+                changes: BTreeMap::new(),
+                fade_ms: 0,
+                delay_ms: 0,
+                block: false,
+                follow_ms: None,
+                confirm: false,
+                link: None,
+                link_loops: None,
+                warn: None,
+                palette_refs: Default::default(),
+            },
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.cue.label = label.into();
+        self
+    }
+
+    pub fn fade_ms(mut self, ms: u32) -> Self {
+        self.cue.fade_ms = ms;
+        self
+    }
+
+    pub fn delay_ms(mut self, ms: u32) -> Self {
+        self.cue.delay_ms = ms;
+        self
+    }
+
+    pub fn follow_ms(mut self, ms: u32) -> Self {
+        self.cue.follow_ms = Some(ms);
+        self
+    }
+
+    pub fn block(mut self) -> Self {
+        self.cue.block = true;
+        self
+    }
+
+    pub fn confirm(mut self) -> Self {
+        self.cue.confirm = true;
+        self
+    }
+
+    /// Go from this cue jumps to `cue`, `loops` times (None: until a goto).
+    pub fn link(mut self, cue: u32, loops: Option<u32>) -> Self {
+        self.cue.link = Some(cue);
+        self.cue.link_loops = loops;
+        self
+    }
+
+    /// Warn `lead_ms` before a follow or timecode event fires this cue.
+    pub fn warn(mut self, lead_ms: u32, beep: bool) -> Self {
+        self.cue.warn = Some(crate::CueWarning { lead_ms, beep });
+        self
+    }
+
+    pub fn intensity(mut self, fixture_id: u32, level: u8) -> Self {
+        self.cue.changes.entry(fixture_id).or_default().intensity = Some(level);
+        self
+    }
+
+    pub fn rgb(mut self, fixture_id: u32, r: u8, g: u8, b: u8) -> Self {
+        let v = self.cue.changes.entry(fixture_id).or_default();
+        (v.r, v.g, v.b) = (Some(r), Some(g), Some(b));
+        self
+    }
+
+    pub fn position(mut self, fixture_id: u32, pan: u8, tilt: u8) -> Self {
+        let v = self.cue.changes.entry(fixture_id).or_default();
+        (v.pan, v.tilt) = (Some(pan), Some(tilt));
+        self
+    }
+
+    /// Merge `values` into the fixture's changes (tracking rules: None
+    /// leaves a parameter alone).
+    pub fn values(mut self, fixture_id: u32, values: &FixtureValues) -> Self {
+        self.cue
+            .changes
+            .entry(fixture_id)
+            .or_default()
+            .apply_delta(values);
+        self
+    }
+
+    /// Record a whole look, e.g. `Programmer::contents()`.
+    pub fn changes(mut self, changes: BTreeMap<u32, FixtureValues>) -> Self {
+        for (fid, v) in &changes {
+            self = self.values(*fid, v);
+        }
+        self
+    }
+
+    pub fn build(self) -> Cue {
+        self.cue
+    }
+}
+
+impl From<CueBuilder> for Cue {
+    fn from(b: CueBuilder) -> Self {
+        b.build()
+    }
+}
+
+/// A show in memory. Patch errors are kept until `build`, so calls chain.
+#[derive(Debug)]
+pub struct ShowBuilder {
+    show: Show,
+    error: Option<anyhow::Error>,
+}
+
+impl ShowBuilder {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            show: Show::new(name),
+            error: None,
+        }
+    }
+
+    /// Add the built-in fixture types (dimmer, RGB par, mover...).
+    pub fn default_fixture_types(mut self) -> Self {
+        for ft in default_fixture_types() {
+            self.show.patch.add_fixture_type(ft);
+        }
+        self
+    }
+
+    pub fn fixture(
+        self,
+        fixture_id: u32,
+        name: impl Into<String>,
+        fixture_type: impl Into<String>,
+        universe: u16,
+        address: u16,
+    ) -> Self {
+        self.fixture_instance(FixtureInstance::new(
+            fixture_id,
+            name,
+            fixture_type,
+            universe,
+            address,
+        ))
+    }
+
+    /// Patch a fixture set up beyond `fixture` (location, limits...).
+    pub fn fixture_instance(mut self, fixture: FixtureInstance) -> Self {
+        if self.error.is_none()
+            && let Err(e) = self.show.patch.add_fixture(fixture)
+        {
+            self.error = Some(e);
+        }
+        self
+    }
+
+    pub fn group(mut self, name: impl Into<String>, ids: impl IntoIterator<Item = u32>) -> Self {
+        let ids: BTreeSet<u32> = ids.into_iter().collect();
+        self.show.groups.insert(name.into(), ids);
+        self
+    }
+
+    /// Add a cue to "main".
+    pub fn cue(self, cue: impl Into<Cue>) -> Self {
+        self.cue_in("main", cue)
+    }
+
+    /// Add a cue to `list`, creating the list if needed.
+    pub fn cue_in(mut self, list: &str, cue: impl Into<Cue>) -> Self {
+        let cue = cue.into();
+        self.show
+            .cue_lists
+            .entry(list.to_string())
+            .or_default()
+            .cues
+            .insert(cue.number, cue);
+        self
+    }
+
+    pub fn build(self) -> anyhow::Result<Show> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.show),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builders_fill_in_defaults_and_keep_patch_errors() -> anyhow::Result<()> {
+        let show = ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "Par", "rgb_par_3ch", 1, 1)
+            .cue(CueBuilder::new(1).intensity(1, 200).rgb(1, 1, 2, 3))
+            .cue_in("fx", CueBuilder::new(5).follow_ms(500).block())
+            .build()?;
+        let cue = &show.cue_lists["main"].cues[&1];
+        assert_eq!(
+            cue.changes[&1],
+            FixtureValues {
+                intensity: Some(200),
+                r: Some(1),
+                g: Some(2),
+                b: Some(3),
+                ..Default::default()
+            }
+        );
+        assert_eq!((cue.fade_ms, cue.follow_ms), (0, None));
+        assert!(show.cue_lists["fx"].cues[&5].block);
+
+        let clash = ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "A", "dimmer_1ch", 1, 1)
+            .fixture(1, "B", "dimmer_1ch", 1, 2)
+            .build();
+        assert!(clash.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+//! Built-in fixture types covering a small real rig out of the box.
+
+use crate::{ChannelDef, ChannelKind, FixtureType};
+
+fn ch(name: &str, kind: ChannelKind) -> ChannelDef {
+    ChannelDef {
+        name: name.to_string(),
+        kind,
+        snap: false,
+        default: None,
+    }
+}
+
+fn with_default(name: &str, kind: ChannelKind, default: u8) -> ChannelDef {
+    ChannelDef {
+        default: Some(default),
+        ..ch(name, kind)
+    }
+}
+
+fn fixture_type(type_id: &str, model: &str, channels: Vec<ChannelDef>) -> FixtureType {
+    FixtureType {
+        type_id: type_id.to_string(),
+        manufacturer: "Generic".to_string(),
+        model: model.to_string(),
+        channels,
+        controls: Vec::new(),
+    }
+}
+
+/// The built-in mini-library. Only channels the console can drive
+/// (intensity, RGB, pan/tilt and their fine bytes); types that need white,
+/// amber, UV, strobe, wheels or per-cell color come from the user library.
+pub fn default_fixture_types() -> Vec<FixtureType> {
+    use ChannelKind::*;
+
+    vec![
+        fixture_type(
+            "rgb_par_3ch",
+            "RGB PAR (3ch)",
+            vec![ch("Red", ColorR), ch("Green", ColorG), ch("Blue", ColorB)],
+        ),
+        fixture_type(
+            "dimmer_1ch",
+            "Dimmer (1ch)",
+            vec![ch("Intensity", Intensity)],
+        ),
+        fixture_type(
+            "dimmer_16bit_2ch",
+            "Dimmer 16-bit (2ch)",
+            vec![
+                ch("Intensity", Intensity),
+                ch("Intensity Fine", IntensityFine),
+            ],
+        ),
+        fixture_type(
+            "moving_head_5ch",
+            "Moving Head (5ch)",
+            vec![
+                with_default("Pan", Pan, 128),
+                with_default("Tilt", Tilt, 128),
+                with_default("Pan Fine", PanFine, 0),
+                with_default("Tilt Fine", TiltFine, 0),
+                ch("Dimmer", Intensity),
+            ],
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_types_are_unique_and_sane() {
+        use ChannelKind::*;
+        let types = default_fixture_types();
+        let mut ids: Vec<_> = types.iter().map(|t| t.type_id.as_str()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), types.len());
+
+        let mh = types
+            .iter()
+            .find(|t| t.type_id == "moving_head_5ch")
+            .unwrap();
+        assert_eq!(mh.channels.len(), 5);
+        // every channel is one the programmer and cues can set
+        let drivable = |k: &ChannelKind| {
+            matches!(k, Intensity | ColorR | ColorG | ColorB | Pan | Tilt) || k.fine_of().is_some()
+        };
+        assert!(
+            types
+                .iter()
+                .flat_map(|t| &t.channels)
+                .all(|c| drivable(&c.kind))
+        );
+    }
+}
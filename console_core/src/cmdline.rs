@@ -0,0 +1,124 @@
+//! The programmer command line as typed on the keypad: a selection
+//! expression with an optional level, e.g. `1 + 3 + 7 thru 9 - 8 @ 50`.
+//!
+//! `thru` binds tighter than `+` and `-`, which apply left to right. A line
+//! that is fine so far but unfinished (`1 thru`, `5 @`) parses as
+//! `Incomplete`, so the keypad can say what it's waiting for.
+
+use anyhow::{anyhow, bail};
+use std::collections::BTreeSet;
+
+use crate::Show;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgrammerLine {
+    /// None keeps the current selection (`@ 50`).
+    pub selection: Option<BTreeSet<u32>>,
+    /// Intensity in percent.
+    pub level: Option<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineStatus {
+    Complete(ProgrammerLine),
+    /// Valid so far; names what has to come next.
+    Incomplete(&'static str),
+}
+
+impl Show {
+    pub fn parse_programmer_line(&self, line: &str) -> anyhow::Result<LineStatus> {
+        let spaced = line.replace('@', " @ ");
+        let tokens: Vec<&str> = spaced.split_whitespace().collect();
+        let is_level =
+            |t: &&str| *t == "@" || t.eq_ignore_ascii_case("full") || t.eq_ignore_ascii_case("out");
+        let split = tokens.iter().position(is_level).unwrap_or(tokens.len());
+        let (sel, rest) = tokens.split_at(split);
+
+        let waiting = match sel.last() {
+            None if rest.is_empty() => Some("fixtures or @"),
+            Some(&"+") | Some(&"-") => Some("a fixture or group"),
+            Some(t) if t.eq_ignore_ascii_case("thru") => Some("the end of the range"),
+            Some(t) if t.eq_ignore_ascii_case("group") || t.eq_ignore_ascii_case("g") => {
+                Some("a group name")
+            }
+            _ => None,
+        };
+        if let Some(next) = waiting {
+            if rest.is_empty() {
+                return Ok(LineStatus::Incomplete(next));
+            }
+            bail!("'{}' needs {next} before the level", sel.join(" "));
+        }
+        let selection = if sel.is_empty() {
+            None
+        } else {
+            let ids = self
+                .parse_selection(sel)
+                .ok_or_else(|| anyhow!("No fixtures in '{}'", sel.join(" ")))?;
+            Some(ids)
+        };
+
+        let level = match rest {
+            [] => None,
+            ["@"] => return Ok(LineStatus::Incomplete("a level")),
+            [l] | ["@", l] if l.eq_ignore_ascii_case("full") => Some(100),
+            [l] | ["@", l] if l.eq_ignore_ascii_case("out") => Some(0),
+            ["@", l] => match l.parse::<u8>() {
+                Ok(pct) if pct <= 100 => Some(pct),
+                _ => bail!("Level '{l}' isn't 0-100, full or out"),
+            },
+            _ => bail!(
+                "Expected '@ <level>' after the selection, got '{}'",
+                rest.join(" ")
+            ),
+        };
+        Ok(LineStatus::Complete(ProgrammerLine { selection, level }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(show: &Show, text: &str) -> ProgrammerLine {
+        match show.parse_programmer_line(text) {
+            Ok(LineStatus::Complete(l)) => l,
+            other => panic!("{text}: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn ranges_combine_left_to_right_with_levels() {
+        let mut show = Show::new("Test");
+        show.groups.insert("front".into(), BTreeSet::from([1, 2]));
+
+        let l = line(&show, "1 + 3 + 7 thru 9 - 8 @ 50");
+        assert_eq!(l.selection, Some(BTreeSet::from([1, 3, 7, 9])));
+        assert_eq!(l.level, Some(50));
+        // no precedence between + and -: order matters
+        let sel = |t| line(&show, t).selection.unwrap();
+        assert_eq!(sel("1 thru 5 - 3 + 3"), BTreeSet::from([1, 2, 3, 4, 5]));
+        assert_eq!(sel("1 thru 5 + 3 - 3"), BTreeSet::from([1, 2, 4, 5]));
+        assert_eq!(sel("9 thru 7"), BTreeSet::from([7, 8, 9]));
+        assert_eq!(sel("group front + 4@full"), BTreeSet::from([1, 2, 4]));
+        assert_eq!(line(&show, "@ 20").selection, None);
+        assert_eq!(line(&show, "2 out").level, Some(0));
+
+        for (text, next) in [
+            ("", "fixtures or @"),
+            ("1 +", "a fixture or group"),
+            ("1 thru", "the end of the range"),
+            ("2 + group", "a group name"),
+            ("1 thru 5 @", "a level"),
+        ] {
+            assert_eq!(
+                show.parse_programmer_line(text).ok(),
+                Some(LineStatus::Incomplete(next)),
+                "{text}"
+            );
+        }
+        for bad in ["1 @ 101", "1 + @ 50", "1 - 1", "- 3", "1 @ 50 60", "3 nope"] {
+            assert!(show.parse_programmer_line(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,900 @@
+//! Command-line commands shared by the CLI and the GUI command console:
+//! `Command::parse` reads a line, `execute` runs it against a `Runtime`.
+//! Frontends keep their own handling for what only they can do (windows,
+//! files, prompts) and hand everything else to this module.
+
+use anyhow::{Context as _, anyhow, bail, ensure};
+use std::collections::BTreeMap;
+
+use crate::{
+    ChannelKind, Cue, FanMode, FixtureValues, Palette, PaletteKind, PaletteValues, ParamFilter,
+    Playback, Programmer, Runtime, Show,
+};
+
+/// Fade time new cues get until the operator changes it (`time`).
+pub const DEFAULT_RECORD_FADE_MS: u32 = 1000;
+
+/// What `record`/`update` store for the selected fixtures.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecordMode {
+    /// Only what the programmer holds (tracking deltas).
+    #[default]
+    Track,
+    /// Full looks: the playback's state with the programmer on top.
+    Only,
+}
+
+impl std::str::FromStr for RecordMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "track" => Ok(Self::Track),
+            "only" => Ok(Self::Only),
+            _ => bail!("Unknown mode '{s}'. Use track|only"),
+        }
+    }
+}
+
+impl std::fmt::Display for RecordMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Track => "track",
+            Self::Only => "only",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// `record cue <n> [label...] [track|only]`
+    RecordCue {
+        number: u32,
+        label: Option<String>,
+        mode: RecordMode,
+    },
+    /// `update cue <n> [track|only]`
+    UpdateCue { number: u32, mode: RecordMode },
+    /// `copy cue <a> [thru <b>] to <target>`
+    CopyCues { first: u32, last: u32, target: u32 },
+    /// `move cue <a> [thru <b>] to <target>`
+    MoveCues { first: u32, last: u32, target: u32 },
+    /// `renumber [<start> [<step>]]`, both default 1.
+    Renumber { start: u32, step: u32 },
+    /// `record group <name>`
+    RecordGroup(String),
+    /// `record palette intensity|color <name...>`
+    RecordPalette { kind: PaletteKind, name: String },
+    /// `at <0..100>`
+    At(u8),
+    /// `rgb <r> <g> <b>` or `color <r> <g> <b>`
+    Rgb(u8, u8, u8),
+    /// `fan intensity|red|green|blue|pan|tilt <from> thru <to> [center]`, DMX values.
+    Fan {
+        kind: ChannelKind,
+        from: u8,
+        to: u8,
+        mode: FanMode,
+    },
+    /// `focus [<level%>] [time <seconds>]`: step through the selection.
+    FocusStart { level: u8, fade_ms: u32 },
+    /// `focus next|prev`
+    FocusStep { forward: bool },
+    /// `focus stop`
+    FocusStop,
+    /// `clear`: selection and values.
+    Clear,
+    /// `clear intensity|color|position|all`: one kind of value from the selection.
+    ClearParams(ParamFilter),
+    /// `clearvals`: values only, the selection stays.
+    ClearValues,
+}
+
+impl Command {
+    /// None if `line` isn't one of the shared commands, so the frontend
+    /// can try its own; Some(Err) with a usage message if it is but
+    /// doesn't parse.
+    pub fn parse(line: &str) -> Option<anyhow::Result<Self>> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let word = parts.first()?.to_lowercase();
+        let args = &parts[1..];
+        let is = |s: &&str, kw: &str| s.eq_ignore_ascii_case(kw);
+        let parsed = match word.as_str() {
+            "record" => match args {
+                [kind, name] if is(kind, "group") => Ok(Self::RecordGroup(name.to_string())),
+                [kind, number, rest @ ..] if is(kind, "cue") => {
+                    let (label, mode) = match rest.split_last() {
+                        Some((last, label)) => match last.parse() {
+                            Ok(mode) => (label, mode),
+                            Err(_) => (rest, RecordMode::Track),
+                        },
+                        None => (rest, RecordMode::Track),
+                    };
+                    number
+                        .parse()
+                        .map(|number| Self::RecordCue {
+                            number,
+                            label: (!label.is_empty()).then(|| label.join(" ")),
+                            mode,
+                        })
+                        .map_err(|_| anyhow!("Cue numbers are whole numbers, got '{number}'"))
+                }
+                [kind, what, name @ ..] if is(kind, "palette") && !name.is_empty() => {
+                    let name = name.join(" ");
+                    match what.to_lowercase().as_str() {
+                        "intensity" => Ok(Self::RecordPalette {
+                            kind: PaletteKind::Intensity,
+                            name,
+                        }),
+                        "color" => Ok(Self::RecordPalette {
+                            kind: PaletteKind::Color,
+                            name,
+                        }),
+                        _ => Err(anyhow!("Palettes are intensity or color, not '{what}'")),
+                    }
+                }
+                _ => Err(anyhow!(
+                    "Usage: record cue <number> <label...> [track|only]  OR  record group <name>  OR  record palette intensity|color <name...>"
+                )),
+            },
+            "update" => match args {
+                [kind, number, mode @ ..] if is(kind, "cue") && mode.len() <= 1 => {
+                    let mode = mode.first().map_or(Ok(RecordMode::Track), |m| m.parse());
+                    mode.and_then(|mode| {
+                        let number = number
+                            .parse()
+                            .with_context(|| format!("No cue number '{number}'"))?;
+                        Ok(Self::UpdateCue { number, mode })
+                    })
+                }
+                _ => Err(anyhow!("Usage: update cue <number> [track|only]")),
+            },
+            "at" => match args {
+                [pct] => pct
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|p| *p <= 100)
+                    .map(Self::At)
+                    .ok_or_else(|| anyhow!("Usage: at <0..100>")),
+                _ => Err(anyhow!("Usage: at <0..100>")),
+            },
+            "rgb" | "color" => match args {
+                [r, g, b] => match (r.parse(), g.parse(), b.parse()) {
+                    (Ok(r), Ok(g), Ok(b)) => Ok(Self::Rgb(r, g, b)),
+                    _ => Err(anyhow!("Usage: rgb <r> <g> <b> (0..255)")),
+                },
+                _ => Err(anyhow!("Usage: rgb <r> <g> <b> (0..255)")),
+            },
+            "fan" => parse_fan(args),
+            "focus" => parse_focus(args),
+            // `copy cues ... from <list>` copies between lists in the CLI
+            "copy" | "move" if args.first().is_some_and(|a| is(a, "cue")) => {
+                parse_relocate(&word, &args[1..])
+            }
+            "renumber" => {
+                let usage = || anyhow!("Usage: renumber [<start> [<step>]]");
+                let num = |s: &&str| s.parse::<u32>().map_err(|_| usage());
+                match args {
+                    [] => Ok(Self::Renumber { start: 1, step: 1 }),
+                    [start] => num(start).map(|start| Self::Renumber { start, step: 1 }),
+                    [start, step] => num(start).and_then(|start| {
+                        Ok(Self::Renumber {
+                            start,
+                            step: num(step)?,
+                        })
+                    }),
+                    _ => Err(usage()),
+                }
+            }
+            "clear" | "clearprog" if args.is_empty() => Ok(Self::Clear),
+            "clear" => match args {
+                [what] => what.parse().map(Self::ClearParams),
+                _ => Err(anyhow!("Usage: clear [intensity|color|position]")),
+            },
+            "clearvals" => Ok(Self::ClearValues),
+            _ => return None,
+        };
+        Some(parsed)
+    }
+}
+
+fn parse_relocate(word: &str, args: &[&str]) -> anyhow::Result<Command> {
+    let usage = || anyhow!("Usage: {word} cue <a> [thru <b>] to <target>");
+    let (first, last, target) = match args {
+        [a, to, t] if to.eq_ignore_ascii_case("to") => (a, a, t),
+        [a, thru, b, to, t]
+            if thru.eq_ignore_ascii_case("thru") && to.eq_ignore_ascii_case("to") =>
+        {
+            (a, b, t)
+        }
+        _ => return Err(usage()),
+    };
+    let num = |s: &str| s.parse::<u32>().map_err(|_| usage());
+    let (first, last, target) = (num(first)?, num(last)?, num(target)?);
+    Ok(if word == "copy" {
+        Command::CopyCues {
+            first,
+            last,
+            target,
+        }
+    } else {
+        Command::MoveCues {
+            first,
+            last,
+            target,
+        }
+    })
+}
+
+fn parse_fan(args: &[&str]) -> anyhow::Result<Command> {
+    let usage = "Usage: fan intensity|red|green|blue|pan|tilt <from> thru <to> [center]";
+    let (param, from, to, mode) = match args {
+        [param, from, thru, to] if thru.eq_ignore_ascii_case("thru") => {
+            (param, from, to, FanMode::Linear)
+        }
+        [param, from, thru, to, mode] if thru.eq_ignore_ascii_case("thru") => {
+            (param, from, to, mode.parse()?)
+        }
+        _ => bail!(usage),
+    };
+    let (kind, max) = match param.to_lowercase().as_str() {
+        "intensity" | "i" => (ChannelKind::Intensity, 100),
+        "red" | "r" => (ChannelKind::ColorR, 255),
+        "green" | "g" => (ChannelKind::ColorG, 255),
+        "blue" | "b" => (ChannelKind::ColorB, 255),
+        "pan" => (ChannelKind::Pan, 255),
+        "tilt" => (ChannelKind::Tilt, 255),
+        _ => bail!(usage),
+    };
+    let value = |s: &str| -> anyhow::Result<u8> {
+        let v = s
+            .parse::<u8>()
+            .ok()
+            .filter(|v| *v <= max)
+            .ok_or_else(|| anyhow!("'{s}' isn't 0-{max}"))?;
+        // intensity is typed in percent like `at`
+        Ok(if max == 100 {
+            (v as u16 * 255 / 100) as u8
+        } else {
+            v
+        })
+    };
+    Ok(Command::Fan {
+        kind,
+        from: value(from)?,
+        to: value(to)?,
+        mode,
+    })
+}
+
+fn parse_focus(args: &[&str]) -> anyhow::Result<Command> {
+    let usage = "Usage: focus [<level%>] [time <seconds>]  OR  focus next|prev|stop";
+    let words: Vec<String> = args.iter().map(|a| a.to_lowercase()).collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let (level, time) = match words.as_slice() {
+        ["next"] | ["n"] => return Ok(Command::FocusStep { forward: true }),
+        ["prev"] | ["back"] => return Ok(Command::FocusStep { forward: false }),
+        ["stop"] | ["off"] => return Ok(Command::FocusStop),
+        [] => (None, None),
+        [level] => (Some(*level), None),
+        ["time", secs] => (None, Some(*secs)),
+        [level, "time", secs] => (Some(*level), Some(*secs)),
+        _ => bail!(usage),
+    };
+    let level = match level.map(|l| l.trim_end_matches('%').parse::<u8>()) {
+        None => crate::focus::DEFAULT_FOCUS_LEVEL,
+        Some(Ok(pct)) if pct <= 100 => ((pct as u16 * 255) / 100) as u8,
+        Some(_) => bail!(usage),
+    };
+    let fade_ms = match time.map(|s| s.trim_end_matches('s').parse::<f32>()) {
+        None => crate::focus::DEFAULT_FOCUS_FADE_MS,
+        Some(Ok(secs)) if secs >= 0.0 => (secs * 1000.0) as u32,
+        Some(_) => bail!(usage),
+    };
+    Ok(Command::FocusStart { level, fade_ms })
+}
+
+/// Frontend state the commands need.
+#[derive(Debug, Clone)]
+pub struct CommandContext {
+    /// Playback whose cue list `record`/`update` write to ("a" or "b").
+    pub playback: String,
+    pub author: Option<String>,
+    pub record_fade_ms: u32,
+    pub record_delay_ms: u32,
+}
+
+impl Default for CommandContext {
+    fn default() -> Self {
+        Self {
+            playback: "a".into(),
+            author: crate::meta::current_author(),
+            record_fade_ms: DEFAULT_RECORD_FADE_MS,
+            record_delay_ms: 0,
+        }
+    }
+}
+
+/// What a command did, for the frontend to report.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandResult {
+    pub message: Option<String>,
+    /// The show changed and should be saved.
+    pub show_changed: bool,
+}
+
+impl CommandResult {
+    fn said(message: impl Into<String>) -> Self {
+        Self {
+            message: Some(message.into()),
+            show_changed: false,
+        }
+    }
+
+    fn edited(message: impl Into<String>) -> Self {
+        Self {
+            message: Some(message.into()),
+            show_changed: true,
+        }
+    }
+}
+
+pub fn execute(
+    command: Command,
+    rt: &mut Runtime,
+    ctx: &CommandContext,
+) -> anyhow::Result<CommandResult> {
+    let author = ctx.author.as_deref();
+    Ok(match command {
+        Command::RecordGroup(name) => {
+            ensure!(!rt.programmer.selected.is_empty(), "No fixtures selected.");
+            let show = &mut rt.show;
+            show.groups
+                .insert(name.clone(), rt.programmer.selected.clone());
+            show.group_meta
+                .entry(name.clone())
+                .or_default()
+                .touch(author);
+            CommandResult::edited(format!("Recorded group '{name}'"))
+        }
+        Command::RecordPalette { kind, name } => {
+            rt.show.check_palette_name(&name)?;
+            // palettes hold one look, taken from the first fixture that has it
+            let contents = rt.programmer.contents();
+            let mut values = contents.values();
+            let (what, values) = match kind {
+                PaletteKind::Intensity => (
+                    "intensity",
+                    values.find_map(|v| v.intensity).map(|i| PaletteValues {
+                        intensity: Some(i),
+                        ..Default::default()
+                    }),
+                ),
+                PaletteKind::Color => (
+                    "color",
+                    values
+                        .find(|v| v.r.is_some() || v.g.is_some() || v.b.is_some())
+                        .map(|v| PaletteValues {
+                            intensity: None,
+                            r: v.r,
+                            g: v.g,
+                            b: v.b,
+                        }),
+                ),
+            };
+            let values =
+                values.ok_or_else(|| anyhow!("No {what} values in the programmer to record"))?;
+            // re-recording keeps the original creation info
+            let mut meta = rt
+                .show
+                .palettes
+                .get(&name)
+                .map(|p| p.meta.clone())
+                .unwrap_or_default();
+            meta.touch(author);
+            let mut pal = Palette::new(kind, values);
+            pal.meta = meta;
+            rt.show.palettes.insert(name.clone(), pal);
+            CommandResult::edited(format!("Recorded palette '{name}'"))
+        }
+        Command::RecordCue {
+            number,
+            label,
+            mode,
+        } => {
+            ensure!(
+                !rt.programmer.selected.is_empty(),
+                "Nothing selected. Use: select ..."
+            );
+            let pb = rt.playback(&ctx.playback)?;
+            let changes = match mode {
+                RecordMode::Track => {
+                    let changes = rt.programmer.contents();
+                    ensure!(
+                        !changes.is_empty(),
+                        "No values in programmer to record. Use: at / rgb / r/g/b"
+                    );
+                    changes
+                }
+                RecordMode::Only => snapshots(&rt.show, pb, &rt.programmer)?,
+            };
+            let mut palette_refs = rt.programmer.palette_refs(&rt.show.palettes);
+            palette_refs.retain(|fid, _| changes.contains_key(fid));
+
+            let list = pb.cuelist.clone();
+            let cl = rt
+                .show
+                .cue_lists
+                .get_mut(&list)
+                .ok_or_else(|| anyhow!("Unknown cuelist '{list}'"))?;
+            cl.check_unlocked(number, number)?;
+            // re-recording keeps the original creation info
+            let mut meta = cl
+                .cues
+                .get(&number)
+                .map(|c| c.meta.clone())
+                .unwrap_or_default();
+            meta.touch(author);
+            let cue = Cue {
+                number,
+                label: label.unwrap_or_else(|| format!("Cue {number}")),
+                meta,
+                block: false,
+                follow_ms: None,
+                confirm: false,
+                link: None,
+                link_loops: None,
+                warn: None,
+                palette_refs,
+                fade_ms: ctx.record_fade_ms,
+                delay_ms: ctx.record_delay_ms,
+                changes,
+            };
+            cl.cues.insert(number, cue);
+            CommandResult::edited(format!(
+                "Recorded cue {number} ({mode}) into cuelist '{list}'"
+            ))
+        }
+        Command::UpdateCue { number, mode } => {
+            ensure!(
+                !rt.programmer.selected.is_empty(),
+                "Nothing selected. Use: select ..."
+            );
+            let pb = rt.playback(&ctx.playback)?;
+            let changes = match mode {
+                RecordMode::Track => {
+                    let delta = FixtureValues {
+                        intensity: rt.programmer.intensity,
+                        r: rt.programmer.r,
+                        g: rt.programmer.g,
+                        b: rt.programmer.b,
+                        ..Default::default()
+                    };
+                    ensure!(
+                        !delta.is_all_none(),
+                        "No values in programmer to update. Use: at / rgb / r/g/b"
+                    );
+                    rt.programmer
+                        .selected
+                        .iter()
+                        .map(|&fid| (fid, delta.clone()))
+                        .collect()
+                }
+                RecordMode::Only => snapshots(&rt.show, pb, &rt.programmer)?,
+            };
+            let refs = rt.programmer.palette_refs(&rt.show.palettes);
+
+            let list = pb.cuelist.clone();
+            let cl = rt
+                .show
+                .cue_lists
+                .get_mut(&list)
+                .ok_or_else(|| anyhow!("Unknown cuelist '{list}'"))?;
+            cl.check_unlocked(number, number)?;
+            let cue = cl
+                .cues
+                .get_mut(&number)
+                .ok_or_else(|| anyhow!("Cue {number} not found. Type: cues"))?;
+            cue.changes.extend(changes);
+            // updated fixtures refer to what the programmer applied now
+            for &fid in &rt.programmer.selected {
+                match refs.get(&fid) {
+                    Some(r) => cue.palette_refs.insert(fid, r.clone()),
+                    None => cue.palette_refs.remove(&fid),
+                };
+            }
+            cue.meta.touch(author);
+            CommandResult::edited(format!(
+                "Updated cue {number} ({mode}) for selected fixtures"
+            ))
+        }
+        Command::CopyCues {
+            first,
+            last,
+            target,
+        } => {
+            let list = rt.playback(&ctx.playback)?.cuelist.clone();
+            let cl = rt
+                .show
+                .cue_lists
+                .get_mut(&list)
+                .ok_or_else(|| anyhow!("Unknown cuelist '{list}'"))?;
+            let nums = cl.copy_cues_to(first, last, target)?;
+            CommandResult::edited(format!("Copied {} cue(s) to {nums:?}", nums.len()))
+        }
+        Command::MoveCues {
+            first,
+            last,
+            target,
+        } => {
+            let list = rt.playback(&ctx.playback)?.cuelist.clone();
+            let cl = rt
+                .show
+                .cue_lists
+                .get_mut(&list)
+                .ok_or_else(|| anyhow!("Unknown cuelist '{list}'"))?;
+            let moves = cl.move_cues_to(first, last, target)?;
+            for pb in rt.playbacks.values_mut() {
+                if pb.cuelist == list {
+                    pb.on_cues_moved(&moves);
+                }
+            }
+            let nums: Vec<u32> = moves.iter().map(|&(_, to)| to).collect();
+            CommandResult::edited(format!("Moved {} cue(s) to {nums:?}", nums.len()))
+        }
+        Command::Renumber { start, step } => {
+            let list = rt.playback(&ctx.playback)?.cuelist.clone();
+            let cl = rt
+                .show
+                .cue_lists
+                .get_mut(&list)
+                .ok_or_else(|| anyhow!("Unknown cuelist '{list}'"))?;
+            let moves = cl.renumber(start, step)?;
+            let total = cl.cues.len();
+            // timecode gotos run on whichever of A and B is active
+            let gotos_are_ours = ["a", "b"].iter().all(|n| rt.playbacks[*n].cuelist == list);
+            for pb in rt.playbacks.values_mut() {
+                if pb.cuelist == list {
+                    pb.on_cues_moved(&moves);
+                }
+            }
+            let mut message =
+                format!("Renumbered {total} cue(s) in '{list}' from {start} by {step}");
+            if gotos_are_ours {
+                let events = rt.show.events.renumber_gotos(&moves);
+                if events > 0 {
+                    message += &format!(", {events} timecode event(s) updated");
+                }
+            }
+            CommandResult::edited(message)
+        }
+        Command::At(pct) => {
+            rt.programmer.set_intensity_percent(pct);
+            CommandResult::default()
+        }
+        Command::Rgb(r, g, b) => {
+            rt.programmer.set_rgb(r, g, b);
+            CommandResult::default()
+        }
+        Command::FocusStart { level, fade_ms } => {
+            let focus = crate::Focus::new(rt.programmer.selected.iter().copied(), level, fade_ms)?;
+            let (_, count) = focus.position();
+            let first = focus.current();
+            rt.focus = Some(focus);
+            CommandResult::said(format!(
+                "Focusing {count} fixture(s), at {first}; focus next|prev to step"
+            ))
+        }
+        Command::FocusStep { forward } => {
+            let focus = rt
+                .focus
+                .as_mut()
+                .ok_or_else(|| anyhow!("No focus session; select fixtures and type focus"))?;
+            let fid = if forward {
+                focus.step_next()
+            } else {
+                focus.step_prev()
+            };
+            let (at, count) = focus.position();
+            CommandResult::said(format!("Focus fixture {fid} ({at}/{count})"))
+        }
+        Command::FocusStop => match rt.focus.take() {
+            Some(_) => CommandResult::said("Focus session ended"),
+            None => CommandResult::default(),
+        },
+        Command::Fan {
+            kind,
+            from,
+            to,
+            mode,
+        } => {
+            ensure!(!rt.programmer.selected.is_empty(), "No fixtures selected.");
+            rt.programmer.fan(kind, from, to, mode)?;
+            CommandResult::said(format!(
+                "Fanned over {} fixtures",
+                rt.programmer.selected.len()
+            ))
+        }
+        Command::Clear => {
+            rt.programmer.clear_all();
+            CommandResult::said("Selection cleared")
+        }
+        Command::ClearParams(params) => {
+            rt.programmer.clear_params(params);
+            let what = match params {
+                ParamFilter::INTENSITY => "intensity",
+                ParamFilter::COLOR => "color",
+                ParamFilter::POSITION => "position",
+                _ => "all values",
+            };
+            CommandResult::said(format!("Cleared {what} from the selection"))
+        }
+        Command::ClearValues => {
+            rt.programmer.clear_values();
+            CommandResult::said("Programmer values cleared")
+        }
+    })
+}
+
+/// Pools a frontend can quick-record the programmer into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickPool {
+    Cues,
+    Palettes,
+}
+
+/// Recording the programmer into slot `n` of a pool: cue `n` in the
+/// playback's list, or palette "Palette n" (color if the programmer holds
+/// any color).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuickRecord {
+    /// Shared command that records it, e.g. "record palette color Palette 5".
+    pub line: String,
+    /// Label of the cue or palette, e.g. "Cue 5".
+    pub name: String,
+    /// The cue or palette exists already and gets overwritten.
+    pub replaces: bool,
+}
+
+impl QuickRecord {
+    pub fn new(
+        rt: &Runtime,
+        ctx: &CommandContext,
+        pool: QuickPool,
+        n: u32,
+    ) -> anyhow::Result<Self> {
+        let contents = rt.programmer.contents();
+        ensure!(!contents.is_empty(), "Nothing in the programmer to record");
+        Ok(match pool {
+            QuickPool::Cues => {
+                let list = &rt.playback(&ctx.playback)?.cuelist;
+                let replaces = rt
+                    .show
+                    .cue_lists
+                    .get(list)
+                    .is_some_and(|cl| cl.cues.contains_key(&n));
+                Self {
+                    line: format!("record cue {n}"),
+                    name: format!("Cue {n}"),
+                    replaces,
+                }
+            }
+            QuickPool::Palettes => {
+                let color = contents
+                    .values()
+                    .any(|v| v.r.is_some() || v.g.is_some() || v.b.is_some());
+                let kind = if color { "color" } else { "intensity" };
+                let name = format!("Palette {n}");
+                Self {
+                    line: format!("record palette {kind} {name}"),
+                    replaces: rt.show.palettes.contains_key(&name),
+                    name,
+                }
+            }
+        })
+    }
+
+    /// Record it. Overwriting needs the operator's `confirmed`.
+    pub fn run(
+        &self,
+        rt: &mut Runtime,
+        ctx: &CommandContext,
+        confirmed: bool,
+    ) -> anyhow::Result<CommandResult> {
+        ensure!(
+            !self.replaces || confirmed,
+            "'{}' exists; confirm to overwrite it",
+            self.name
+        );
+        let command = Command::parse(&self.line)
+            .unwrap_or_else(|| Err(anyhow!("'{}' isn't a command", self.line)))?;
+        execute(command, rt, ctx)
+    }
+}
+
+/// Full values for each selected fixture: the playback's tracked state
+/// (unset = 0) with the programmer's values on top.
+fn snapshots(
+    show: &Show,
+    playback: &Playback,
+    programmer: &Programmer,
+) -> anyhow::Result<BTreeMap<u32, FixtureValues>> {
+    let tracked = playback.state_map(show)?;
+    Ok(programmer
+        .selected
+        .iter()
+        .map(|&fid| {
+            let base = tracked.get(&fid);
+            let pick = |prog: Option<u8>, field: fn(&FixtureValues) -> Option<u8>| {
+                Some(prog.or(base.and_then(field)).unwrap_or(0))
+            };
+            let values = FixtureValues {
+                intensity: pick(programmer.intensity, |v| v.intensity),
+                r: pick(programmer.r, |v| v.r),
+                g: pick(programmer.g, |v| v.g),
+                b: pick(programmer.b, |v| v.b),
+                pan: base.and_then(|v| v.pan),
+                tilt: base.and_then(|v| v.tilt),
+            };
+            (fid, values)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ShowBuilder;
+
+    fn run(rt: &mut Runtime, line: &str) -> anyhow::Result<CommandResult> {
+        let ctx = CommandContext {
+            author: None,
+            ..Default::default()
+        };
+        execute(Command::parse(line).expect("shared command")?, rt, &ctx)
+    }
+
+    #[test]
+    fn focus_lights_one_selected_fixture_at_a_time() -> anyhow::Result<()> {
+        let show = ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "D1", "dimmer_1ch", 1, 1)
+            .fixture(2, "D2", "dimmer_1ch", 1, 2)
+            .build()?;
+        let mut rt = Runtime::new(show);
+        assert!(run(&mut rt, "focus").is_err()); // nothing selected
+        assert!(Command::parse("focus 120").unwrap().is_err());
+
+        rt.programmer.selected.extend([1, 2]);
+        run(&mut rt, "at 100")?;
+        run(&mut rt, "focus 50 time 0")?;
+        let live = rt.render()?;
+        assert_eq!((live.get(1, 1), live.get(1, 2)), (127, 0));
+        run(&mut rt, "focus next")?;
+        let live = rt.render()?;
+        assert_eq!((live.get(1, 1), live.get(1, 2)), (0, 127));
+
+        run(&mut rt, "focus stop")?;
+        assert_eq!(rt.render()?.get(1, 1), 255);
+        assert!(run(&mut rt, "focus prev").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn record_and_update_cues_from_the_command_line() -> anyhow::Result<()> {
+        let show = ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "Par", "rgb_par_3ch", 1, 1)
+            .fixture(2, "Par", "rgb_par_3ch", 1, 4)
+            .build()?;
+        let mut rt = Runtime::new(show);
+        assert!(Command::parse("go").is_none());
+        assert!(Command::parse("at 150").unwrap().is_err());
+        assert!(run(&mut rt, "record cue 1").is_err()); // nothing selected
+
+        rt.programmer.selected.extend([1, 2]);
+        run(&mut rt, "at 100")?;
+        let res = run(&mut rt, "record cue 1 Opening look")?;
+        assert!(res.show_changed);
+        let cue = &rt.show.cue_lists["main"].cues[&1];
+        assert_eq!(cue.label, "Opening look");
+        assert_eq!(cue.fade_ms, DEFAULT_RECORD_FADE_MS);
+        assert_eq!(cue.changes[&2].intensity, Some(255));
+        assert_eq!(cue.changes[&2].r, None);
+
+        run(&mut rt, "clear intensity")?;
+        run(&mut rt, "rgb 0 0 200")?;
+        run(&mut rt, "update cue 1")?;
+        let cue = &rt.show.cue_lists["main"].cues[&1];
+        assert_eq!(cue.changes[&1].b, Some(200));
+        assert!(run(&mut rt, "update cue 9").is_err());
+
+        run(&mut rt, "record cue 2 only")?;
+        let cue = &rt.show.cue_lists["main"].cues[&2];
+        assert_eq!(cue.label, "Cue 2");
+        assert_eq!(cue.changes[&1].intensity, Some(0));
+
+        rt.playbacks["a"].current = Some(2);
+        run(&mut rt, "move cue 2 to 5")?;
+        assert_eq!(rt.playbacks["a"].current, Some(5));
+        assert!(run(&mut rt, "copy cue 1 thru 5 to 5").is_err());
+        run(&mut rt, "renumber 10 10")?;
+        assert_eq!(rt.playbacks["a"].current, Some(20));
+        assert!(Command::parse("copy cues 1 from main to fx at 1").is_none());
+
+        run(&mut rt, "record group pars")?;
+        assert_eq!(rt.show.groups["pars"].len(), 2);
+
+        run(&mut rt, "record palette color Deep blue")?;
+        let pal = &rt.show.palettes["Deep blue"];
+        assert_eq!((pal.values.b, pal.values.intensity), (Some(200), None));
+        run(&mut rt, "clear color")?;
+        assert!(run(&mut rt, "record palette color Empty").is_err());
+        assert!(run(&mut rt, "record palette position Wide").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn clear_position_keeps_intensity() -> anyhow::Result<()> {
+        let show = ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "MH", "moving_head_5ch", 1, 1)
+            .build()?;
+        let mut rt = Runtime::new(show);
+        rt.programmer.selected.insert(1);
+        run(&mut rt, "at 50")?;
+        let position = FixtureValues {
+            pan: Some(10),
+            tilt: Some(20),
+            ..Default::default()
+        };
+        rt.programmer.edit_fixture(1, &position);
+        assert_eq!(rt.programmer.contents()[&1].pan, Some(10));
+
+        let res = run(&mut rt, "clear position")?;
+        assert_eq!(
+            res.message.as_deref(),
+            Some("Cleared position from the selection")
+        );
+        let v = &rt.programmer.contents()[&1];
+        assert_eq!((v.pan, v.tilt, v.intensity), (None, None, Some(127)));
+        Ok(())
+    }
+
+    #[test]
+    fn quick_record_fills_empty_slots_and_asks_before_overwriting() -> anyhow::Result<()> {
+        let show = ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "Par", "rgb_par_3ch", 1, 1)
+            .build()?;
+        let mut rt = Runtime::new(show);
+        let ctx = CommandContext {
+            author: None,
+            ..Default::default()
+        };
+        assert!(QuickRecord::new(&rt, &ctx, QuickPool::Cues, 3).is_err()); // empty programmer
+
+        rt.programmer.selected.insert(1);
+        run(&mut rt, "rgb 10 20 30")?;
+        let q = QuickRecord::new(&rt, &ctx, QuickPool::Palettes, 3)?;
+        assert_eq!(q.line, "record palette color Palette 3");
+        assert!(!q.replaces);
+        assert!(q.run(&mut rt, &ctx, false)?.show_changed);
+        assert_eq!(rt.show.palettes["Palette 3"].values.g, Some(20));
+
+        let q = QuickRecord::new(&rt, &ctx, QuickPool::Cues, 4)?;
+        q.run(&mut rt, &ctx, false)?;
+        assert!(rt.show.cue_lists["main"].cues.contains_key(&4));
+
+        // occupied: refused until confirmed
+        run(&mut rt, "rgb 0 0 255")?;
+        let q = QuickRecord::new(&rt, &ctx, QuickPool::Palettes, 3)?;
+        assert!(q.replaces);
+        assert!(q.run(&mut rt, &ctx, false).is_err());
+        assert_eq!(rt.show.palettes["Palette 3"].values.g, Some(20));
+        q.run(&mut rt, &ctx, true)?;
+        assert_eq!(rt.show.palettes["Palette 3"].values.b, Some(255));
+        assert!(QuickRecord::new(&rt, &ctx, QuickPool::Cues, 4)?.replaces);
+        Ok(())
+    }
+}
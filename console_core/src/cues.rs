@@ -1,12 +1,20 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+use crate::lock::CueLock;
+use crate::{Meta, Palette, PaletteRefs};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FixtureValues {
     pub intensity: Option<u8>,
     pub r: Option<u8>,
     pub g: Option<u8>,
     pub b: Option<u8>,
+    /// Position, written to the coarse and fine Pan/Tilt channels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pan: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tilt: Option<u8>,
 }
 
 impl FixtureValues {
@@ -24,14 +32,38 @@ impl FixtureValues {
         if let Some(v) = delta.b {
             self.b = Some(v);
         }
+        if let Some(v) = delta.pan {
+            self.pan = Some(v);
+        }
+        if let Some(v) = delta.tilt {
+            self.tilt = Some(v);
+        }
     }
 
     pub fn is_all_none(&self) -> bool {
-        self.intensity.is_none() && self.r.is_none() && self.g.is_none() && self.b.is_none()
+        self.intensity.is_none()
+            && self.r.is_none()
+            && self.g.is_none()
+            && self.b.is_none()
+            && self.pan.is_none()
+            && self.tilt.is_none()
+    }
+
+    /// Drop fields that already have the same value in `base`.
+    fn without_matching(&self, base: &FixtureValues) -> FixtureValues {
+        let keep = |v: Option<u8>, b: Option<u8>| if v == b { None } else { v };
+        FixtureValues {
+            intensity: keep(self.intensity, base.intensity),
+            r: keep(self.r, base.r),
+            g: keep(self.g, base.g),
+            b: keep(self.b, base.b),
+            pan: keep(self.pan, base.pan),
+            tilt: keep(self.tilt, base.tilt),
+        }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Cue {
     pub number: u32,
     pub label: String,
@@ -45,17 +77,623 @@ pub struct Cue {
     #[serde(default)]
     pub delay_ms: u32,
 
+    /// Go to the next cue automatically this long after this cue's Go.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follow_ms: Option<u32>,
+
+    /// Go into this cue must be confirmed (pyro, motors): the first Go only
+    /// arms it, a second Go fires it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub confirm: bool,
+
+    /// Go from this cue jumps to cue `link` instead of the next one
+    /// (chases, pre-show loops). A missing link target is ignored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<u32>,
+
+    /// How many times the link is taken before Go carries on to the next
+    /// cue; None loops until a `goto` leaves.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_loops: Option<u32>,
+
+    /// Count down to this cue when a follow or timecode event is about to
+    /// fire it (see `CueWarner`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warn: Option<CueWarning>,
+
+    /// Per fixture, palettes whose current values replace the recorded ones.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub palette_refs: BTreeMap<u32, PaletteRefs>,
+
     /// Changes recorded in this cue (tracking style).
     pub changes: BTreeMap<u32, FixtureValues>, // fixture_id -> delta values
+
+    #[serde(default, skip_serializing_if = "Meta::is_empty")]
+    pub meta: Meta,
+}
+
+/// Countdown before a cue fires on its own, for operators running a
+/// sequence alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CueWarning {
+    /// How long before the cue fires the warning starts.
+    pub lead_ms: u32,
+    /// Beep as well as flash.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub beep: bool,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+/// Bulk change of cue times (`time cues ...`, `scale times ...`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeEdit {
+    /// Set fade and/or delay; None leaves that time alone.
+    Set {
+        fade_ms: Option<u32>,
+        delay_ms: Option<u32>,
+    },
+    /// Multiply fade and delay by a factor.
+    Scale(f64),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CueList {
     pub cues: BTreeMap<u32, Cue>,
+
+    /// Go past the last cue releases the playback (fade to black) instead
+    /// of staying on the last cue.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto_black: bool,
+
+    /// Go past the last cue wraps to the first (installations). Takes
+    /// precedence over `auto_black`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub looping: bool,
+
+    /// Move in black: fixtures dark in a cue take the color of the next cue
+    /// they come up in while still dark, so it doesn't fade in visibly.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub move_in_black: bool,
+
+    /// Cue ranges protected from editing (see `lock`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locks: Vec<CueLock>,
 }
 
 impl CueList {
     pub fn ensure() -> Self {
         Self::default()
     }
+
+    /// Tracked fixture values after playing every cue up to and including `cue_num`.
+    pub fn tracked_state_at(&self, cue_num: u32) -> BTreeMap<u32, FixtureValues> {
+        self.tracked_state_in(..=cue_num)
+    }
+
+    /// Tracked fixture values coming into `cue_num` (everything before it).
+    pub fn tracked_state_before(&self, cue_num: u32) -> BTreeMap<u32, FixtureValues> {
+        self.tracked_state_in(..cue_num)
+    }
+
+    /// Like `tracked_state_at`, with palette references resolved against
+    /// `palettes`: what the cue looks like when played.
+    pub fn resolved_state_at(
+        &self,
+        cue_num: u32,
+        palettes: &BTreeMap<String, Palette>,
+    ) -> BTreeMap<u32, FixtureValues> {
+        self.tracked_state_with(..=cue_num, Some(palettes))
+    }
+
+    fn tracked_state_in(
+        &self,
+        range: impl std::ops::RangeBounds<u32>,
+    ) -> BTreeMap<u32, FixtureValues> {
+        self.tracked_state_with(range, None)
+    }
+
+    fn tracked_state_with(
+        &self,
+        range: impl std::ops::RangeBounds<u32>,
+        palettes: Option<&BTreeMap<String, Palette>>,
+    ) -> BTreeMap<u32, FixtureValues> {
+        let mut tracked: BTreeMap<u32, FixtureValues> = BTreeMap::new();
+
+        for cue in self.cues.range(range).map(|(_, c)| c) {
+            // BLOCKING: reset fixtures touched by this cue so nothing tracks through
+            if cue.block {
+                for &fid in cue.changes.keys() {
+                    tracked.insert(fid, FixtureValues::default());
+                }
+            }
+
+            for (&fid, delta) in &cue.changes {
+                tracked.entry(fid).or_default().apply_delta(delta);
+            }
+            if let Some(palettes) = palettes {
+                for (&fid, refs) in &cue.palette_refs {
+                    refs.resolve(palettes, tracked.entry(fid).or_default());
+                }
+            }
+        }
+
+        tracked
+    }
+
+    /// Palette references in effect after `cue_num`: a reference tracks like
+    /// a value, until a later cue sets that parameter without one.
+    fn tracked_refs_at(&self, cue_num: u32) -> BTreeMap<u32, PaletteRefs> {
+        let mut tracked: BTreeMap<u32, PaletteRefs> = BTreeMap::new();
+        for cue in self.cues.range(..=cue_num).map(|(_, c)| c) {
+            for (&fid, delta) in &cue.changes {
+                let refs = tracked.entry(fid).or_default();
+                let own = cue.palette_refs.get(&fid);
+                if cue.block
+                    || (delta.intensity.is_some() && own.is_none_or(|r| r.intensity.is_none()))
+                {
+                    refs.intensity = None;
+                }
+                let color = delta.r.is_some() || delta.g.is_some() || delta.b.is_some();
+                if cue.block || (color && own.is_none_or(|r| r.color.is_none())) {
+                    refs.color = None;
+                }
+            }
+            for (&fid, own) in &cue.palette_refs {
+                let refs = tracked.entry(fid).or_default();
+                if own.intensity.is_some() {
+                    refs.intensity = own.intensity.clone();
+                }
+                if own.color.is_some() {
+                    refs.color = own.color.clone();
+                }
+            }
+        }
+        tracked.retain(|_, r| !r.is_empty());
+        tracked
+    }
+
+    /// Assert a cue: every value that tracks into the cue becomes a hard value
+    /// recorded in the cue itself. With `block` the cue is also marked as a block,
+    /// so edits to earlier cues stop tracking through it.
+    ///
+    /// Returns the number of fixtures whose changes were touched.
+    pub fn assert_cue(&mut self, cue_num: u32, block: bool) -> anyhow::Result<usize> {
+        if !self.cues.contains_key(&cue_num) {
+            anyhow::bail!("unknown cue {cue_num}");
+        }
+        self.check_unlocked(cue_num, cue_num)?;
+
+        let state = self.tracked_state_at(cue_num);
+        let cue = self.cues.get_mut(&cue_num).expect("checked above");
+
+        let mut touched = 0;
+        for (fid, vals) in state {
+            if vals.is_all_none() {
+                continue;
+            }
+            if cue.changes.get(&fid) != Some(&vals) {
+                cue.changes.insert(fid, vals);
+                touched += 1;
+            }
+        }
+
+        if block {
+            cue.block = true;
+        }
+
+        Ok(touched)
+    }
+
+    /// Smart block/unblock: remove hard values in a cue that match what already
+    /// tracks into it, and drop the block flag when it no longer changes anything.
+    /// Playback output is unchanged.
+    ///
+    /// Returns the number of values removed.
+    pub fn clean_cue(&mut self, cue_num: u32) -> anyhow::Result<usize> {
+        self.check_unlocked(cue_num, cue_num)?;
+        let before = self.tracked_state_before(cue_num);
+        let cue = self
+            .cues
+            .get_mut(&cue_num)
+            .ok_or_else(|| anyhow::anyhow!("unknown cue {cue_num}"))?;
+
+        if cue.block {
+            // The block is redundant only if playing the cue without it gives the same look.
+            let unblocked_same = cue.changes.iter().all(|(fid, delta)| {
+                let base = before.get(fid).cloned().unwrap_or_default();
+                let mut tracked = base.clone();
+                tracked.apply_delta(delta);
+                let mut blocked = FixtureValues::default();
+                blocked.apply_delta(delta);
+                tracked == blocked
+            });
+            if !unblocked_same {
+                return Ok(0);
+            }
+            cue.block = false;
+        }
+
+        let mut removed = 0;
+        cue.changes.retain(|fid, delta| {
+            let base = before.get(fid).cloned().unwrap_or_default();
+            let kept = delta.without_matching(&base);
+            removed += count_some(delta) - count_some(&kept);
+            *delta = kept;
+            !delta.is_all_none()
+        });
+
+        Ok(removed)
+    }
+
+    /// Clone the cues numbered `first..=last`. With `flatten`, every cue carries the
+    /// full tracked look at that point instead of only its own changes, and
+    /// the palette references tracking into it.
+    pub fn cues_in_range(&self, first: u32, last: u32, flatten: bool) -> Vec<Cue> {
+        let (first, last) = if first <= last {
+            (first, last)
+        } else {
+            (last, first)
+        };
+
+        self.cues
+            .range(first..=last)
+            .map(|(&num, cue)| {
+                let mut cue = cue.clone();
+                if flatten {
+                    cue.changes = self
+                        .tracked_state_at(num)
+                        .into_iter()
+                        .filter(|(_, v)| !v.is_all_none())
+                        .collect();
+                    cue.palette_refs = self.tracked_refs_at(num);
+                    cue.block = false;
+                }
+                cue
+            })
+            .collect()
+    }
+
+    /// Apply `edit` to the cues numbered `first..=last`; returns their numbers.
+    pub fn edit_times(&mut self, first: u32, last: u32, edit: TimeEdit) -> Vec<u32> {
+        let scale = |ms: u32, factor: f64| (ms as f64 * factor.max(0.0)).round() as u32;
+        let mut touched = Vec::new();
+        for (&num, cue) in self.cues.range_mut(first.min(last)..=first.max(last)) {
+            match edit {
+                TimeEdit::Set { fade_ms, delay_ms } => {
+                    cue.fade_ms = fade_ms.unwrap_or(cue.fade_ms);
+                    cue.delay_ms = delay_ms.unwrap_or(cue.delay_ms);
+                }
+                TimeEdit::Scale(factor) => {
+                    cue.fade_ms = scale(cue.fade_ms, factor);
+                    cue.delay_ms = scale(cue.delay_ms, factor);
+                }
+            }
+            touched.push(num);
+        }
+        touched
+    }
+
+    /// Copy the cues numbered `first..=last` so the first lands on `target`,
+    /// keeping their spacing. The copies play the look they had at the
+    /// source, and every other cue keeps its look. Returns the new numbers.
+    pub fn copy_cues_to(&mut self, first: u32, last: u32, target: u32) -> anyhow::Result<Vec<u32>> {
+        let moved = self.relocate(first, last, target, false)?;
+        Ok(moved.into_iter().map(|(_, to)| to).collect())
+    }
+
+    /// Like `copy_cues_to`, removing the originals. Returns (old, new)
+    /// numbers for the playback guard rails (`Playback::on_cues_moved`).
+    pub fn move_cues_to(
+        &mut self,
+        first: u32,
+        last: u32,
+        target: u32,
+    ) -> anyhow::Result<Vec<(u32, u32)>> {
+        self.relocate(first, last, target, true)
+    }
+
+    /// Number the cues `start`, `start + step`, ... in their current order,
+    /// so every look stays as it was. Returns (old, new) numbers of the cues
+    /// that changed; those must not be locked.
+    pub fn renumber(&mut self, start: u32, step: u32) -> anyhow::Result<Vec<(u32, u32)>> {
+        anyhow::ensure!(step > 0, "renumber step must be at least 1");
+        let mut new_number = BTreeMap::new();
+        for (i, &n) in self.cues.keys().enumerate() {
+            let to = (i as u32)
+                .checked_mul(step)
+                .and_then(|x| x.checked_add(start))
+                .ok_or_else(|| anyhow::anyhow!("cue number overflow"))?;
+            new_number.insert(n, to);
+        }
+        let relink = |l: u32| new_number.get(&l).copied().unwrap_or(l);
+        for (n, cue) in &self.cues {
+            if new_number[n] != *n || cue.link.is_some_and(|l| relink(l) != l) {
+                self.check_unlocked(*n, *n)?;
+            }
+        }
+        let cues = std::mem::take(&mut self.cues);
+        self.cues = cues
+            .into_values()
+            .map(|mut cue| {
+                cue.number = new_number[&cue.number];
+                cue.link = cue.link.map(relink);
+                (cue.number, cue)
+            })
+            .collect();
+        Ok(new_number.into_iter().filter(|(n, to)| n != to).collect())
+    }
+
+    fn relocate(
+        &mut self,
+        first: u32,
+        last: u32,
+        target: u32,
+        remove: bool,
+    ) -> anyhow::Result<Vec<(u32, u32)>> {
+        let (first, last) = (first.min(last), first.max(last));
+        let sources: Vec<u32> = self.cues.range(first..=last).map(|(&n, _)| n).collect();
+        let Some(&base) = sources.first() else {
+            anyhow::bail!("no cues in range {first} thru {last}");
+        };
+        let mut moves = Vec::with_capacity(sources.len());
+        for &n in &sources {
+            let to = (n - base)
+                .checked_add(target)
+                .ok_or_else(|| anyhow::anyhow!("cue number overflow"))?;
+            let vacated = remove && sources.contains(&to);
+            if self.cues.contains_key(&to) && !vacated {
+                anyhow::bail!("cue {to} already exists");
+            }
+            moves.push((n, to));
+        }
+
+        // the look every cue should keep, by its number afterwards
+        let mut looks: BTreeMap<u32, BTreeMap<u32, FixtureValues>> = self
+            .cues
+            .keys()
+            .filter(|n| !(remove && sources.contains(n)))
+            .map(|&n| (n, self.tracked_state_at(n)))
+            .collect();
+        let mut next = self.clone();
+        for &(from, to) in &moves {
+            looks.insert(to, self.tracked_state_at(from));
+            let mut cue = self.cues[&from].clone();
+            cue.number = to;
+            if remove {
+                next.cues.remove(&from);
+            }
+            next.cues.insert(to, cue);
+        }
+        if remove {
+            next.cues.retain(|n, _| looks.contains_key(n));
+        }
+        // links follow their target: every link on a move, only links
+        // within the copied range on a copy
+        let mut relinked = Vec::new();
+        for (&num, cue) in next.cues.iter_mut() {
+            let copy = moves.iter().any(|&(_, to)| to == num);
+            let target = cue
+                .link
+                .and_then(|l| moves.iter().find(|&&(from, _)| from == l));
+            if let Some(&(_, to)) = target
+                && (remove || copy)
+            {
+                cue.link = Some(to);
+                relinked.push(num);
+            }
+        }
+
+        // re-add whatever no longer tracks in at the new positions
+        let mut tracked: BTreeMap<u32, FixtureValues> = BTreeMap::new();
+        let mut touched = Vec::new();
+        for (&num, cue) in next.cues.iter_mut() {
+            if cue.block {
+                for &fid in cue.changes.keys() {
+                    tracked.insert(fid, FixtureValues::default());
+                }
+            }
+            for (&fid, delta) in &cue.changes {
+                tracked.entry(fid).or_default().apply_delta(delta);
+            }
+            let want = &looks[&num];
+            let mut corrected = false;
+            for fid in want
+                .keys()
+                .chain(tracked.keys())
+                .copied()
+                .collect::<Vec<_>>()
+            {
+                let have = tracked.get(&fid).cloned().unwrap_or_default();
+                let want = want.get(&fid).cloned().unwrap_or_default();
+                let fix = |w: Option<u8>, h: Option<u8>| match (w, h) {
+                    _ if w == h => None,
+                    (Some(w), _) => Some(w),
+                    // a value that didn't exist here before goes back to zero
+                    (None, Some(0)) => None,
+                    (None, _) => Some(0),
+                };
+                let delta = FixtureValues {
+                    intensity: fix(want.intensity, have.intensity),
+                    r: fix(want.r, have.r),
+                    g: fix(want.g, have.g),
+                    b: fix(want.b, have.b),
+                    pan: fix(want.pan, have.pan),
+                    tilt: fix(want.tilt, have.tilt),
+                };
+                if delta.is_all_none() {
+                    continue;
+                }
+                cue.changes.entry(fid).or_default().apply_delta(&delta);
+                tracked.entry(fid).or_default().apply_delta(&delta);
+                corrected = true;
+            }
+            if corrected || moves.iter().any(|&(_, to)| to == num) {
+                touched.push(num);
+            }
+        }
+        if remove {
+            touched.extend(moves.iter().map(|&(from, _)| from));
+        }
+        touched.extend(relinked);
+        for n in touched {
+            self.check_unlocked(n, n)?;
+        }
+        *self = next;
+        Ok(moves)
+    }
+
+    /// Run [`CueList::clean_cue`] over every cue, first to last.
+    pub fn clean_all(&mut self) -> usize {
+        let nums: Vec<u32> = self.cues.keys().copied().collect();
+        nums.into_iter()
+            .map(|n| self.clean_cue(n).unwrap_or(0))
+            .sum()
+    }
+}
+
+fn count_some(v: &FixtureValues) -> usize {
+    [v.intensity, v.r, v.g, v.b, v.pan, v.tilt]
+        .iter()
+        .filter(|x| x.is_some())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(number: u32, changes: &[(u32, FixtureValues)]) -> Cue {
+        Cue {
+            number,
+            label: format!("Cue {number}"),
+            meta: Default::default(),
+            block: false,
+            fade_ms: 0,
+            delay_ms: 0,
+            follow_ms: None,
+            confirm: false,
+            link: None,
+            link_loops: None,
+            warn: None,
+            palette_refs: Default::default(),
+            changes: changes.iter().cloned().collect(),
+        }
+    }
+
+    fn red(v: u8) -> FixtureValues {
+        FixtureValues {
+            r: Some(v),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn assert_cue_hardens_tracked_values() -> anyhow::Result<()> {
+        let mut cl = CueList::default();
+        cl.cues.insert(1, cue(1, &[(1, red(200))]));
+        cl.cues.insert(2, cue(2, &[(2, red(50))]));
+
+        let touched = cl.assert_cue(2, true)?;
+        assert_eq!(touched, 1);
+
+        let c2 = &cl.cues[&2];
+        assert!(c2.block);
+        assert_eq!(c2.changes.get(&1), Some(&red(200)));
+
+        // editing cue 1 no longer tracks into cue 2
+        cl.cues.get_mut(&1).unwrap().changes.insert(1, red(10));
+        assert_eq!(cl.tracked_state_at(2).get(&1), Some(&red(200)));
+        Ok(())
+    }
+
+    #[test]
+    fn clean_cue_removes_redundant_values_and_block() -> anyhow::Result<()> {
+        let mut cl = CueList::default();
+        cl.cues.insert(1, cue(1, &[(1, red(200))]));
+        cl.cues.insert(2, cue(2, &[(1, red(200)), (2, red(50))]));
+        cl.assert_cue(2, true)?;
+
+        let before = cl.tracked_state_at(2);
+        let removed = cl.clean_cue(2)?;
+        assert_eq!(removed, 1);
+
+        let c2 = &cl.cues[&2];
+        assert!(!c2.block);
+        assert!(!c2.changes.contains_key(&1));
+        assert_eq!(cl.tracked_state_at(2), before);
+        Ok(())
+    }
+
+    #[test]
+    fn moved_and_copied_cues_keep_every_look() -> anyhow::Result<()> {
+        let mut cl = CueList::default();
+        cl.cues.insert(1, cue(1, &[(1, red(100))]));
+        cl.cues.insert(2, cue(2, &[(1, red(50))]));
+        cl.cues.insert(3, cue(3, &[(2, red(255))]));
+        let look_2 = cl.tracked_state_at(2);
+
+        assert_eq!(cl.move_cues_to(2, 2, 10)?, vec![(2, 10)]);
+        assert!(!cl.cues.contains_key(&2));
+        // cue 3 no longer gets red 50 from cue 2, so it records it
+        assert_eq!(cl.cues[&3].changes[&1], red(50));
+        assert_eq!(cl.cues[&10].number, 10);
+        assert_eq!(cl.tracked_state_at(10)[&1], look_2[&1]);
+        assert_eq!(cl.cues[&10].changes[&2], red(0));
+
+        assert_eq!(cl.copy_cues_to(1, 1, 20)?, vec![20]);
+        assert_eq!(cl.cues[&20].changes, cl.cues[&1].changes);
+        assert!(cl.copy_cues_to(3, 3, 10).is_err());
+
+        // cue 20 would need a correction
+        cl.lock(20, 20);
+        let before = cl.clone();
+        assert!(cl.move_cues_to(3, 3, 15).is_err());
+        assert_eq!(cl, before);
+        Ok(())
+    }
+
+    #[test]
+    fn renumber_spaces_cues_evenly_in_order() -> anyhow::Result<()> {
+        let mut cl = CueList::default();
+        for n in [3, 7, 8] {
+            cl.cues.insert(n, cue(n, &[(1, red(n as u8))]));
+        }
+        cl.cues.get_mut(&8).unwrap().link = Some(3);
+        let look = cl.tracked_state_at(8);
+
+        assert_eq!(cl.renumber(10, 10)?, vec![(3, 10), (7, 20), (8, 30)]);
+        assert_eq!(cl.cues.keys().copied().collect::<Vec<_>>(), [10, 20, 30]);
+        assert_eq!(cl.cues[&20].number, 20);
+        assert_eq!(cl.cues[&30].link, Some(10));
+        assert_eq!(cl.tracked_state_at(30), look);
+
+        cl.lock(20, 20);
+        assert!(cl.renumber(1, 1).is_err());
+        assert!(cl.renumber(10, 10)?.is_empty());
+        assert!(cl.renumber(u32::MAX, 1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn edit_times_sets_and_scales_a_range() {
+        let mut cl = CueList::default();
+        for n in [5, 10, 15, 20, 25] {
+            let mut c = cue(n, &[]);
+            c.fade_ms = 2000;
+            cl.cues.insert(n, c);
+        }
+
+        let set = TimeEdit::Set {
+            fade_ms: Some(3000),
+            delay_ms: Some(500),
+        };
+        assert_eq!(cl.edit_times(20, 10, set), vec![10, 15, 20]);
+        assert_eq!(cl.cues[&15].fade_ms, 3000);
+        assert_eq!(cl.cues[&15].delay_ms, 500);
+        assert_eq!(cl.cues[&5].fade_ms, 2000);
+
+        assert_eq!(cl.edit_times(0, u32::MAX, TimeEdit::Scale(1.5)).len(), 5);
+        assert_eq!(cl.cues[&15].fade_ms, 4500);
+        assert_eq!(cl.cues[&15].delay_ms, 750);
+        assert_eq!(cl.cues[&25].fade_ms, 3000);
+    }
 }
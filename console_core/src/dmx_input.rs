@@ -0,0 +1,372 @@
+//! DMX input: receive sACN from an external desk and merge it with our own
+//! output, so the console can run as a merger or backup node.
+//!
+//! `SacnReceiver` owns the socket and parses E1.31 packets; `DmxInput` keeps
+//! the latest data per source and picks the winning source per universe the
+//! way E1.31 receivers do (highest priority, HTP between equal priorities,
+//! sources dropped after 2.5 s of silence). Which input feeds which output
+//! universe, and how, is configured per universe in the show.
+//!
+//! Input slots can also be used as a fader wing (`Show::dmx_wing`): each
+//! drives a group master or programmer intensities when its value moves.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+
+use crate::dmx_output::{E131_STREAM_TERMINATED, SACN_PORT, sacn_multicast};
+use crate::{LiveState, Show};
+
+/// A source that sent nothing for this long is gone (E1.31 data loss).
+pub const SOURCE_TIMEOUT_MS: u64 = 2500;
+
+/// Framing-layer option bit: preview data, not meant for live output.
+const E131_PREVIEW_DATA: u8 = 0x80;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeMode {
+    /// Highest value per slot wins.
+    #[default]
+    Htp,
+    /// Whoever has the higher sACN priority owns the universe; equal
+    /// priorities merge HTP.
+    Priority,
+}
+
+/// Input merged into one output universe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputMerge {
+    /// sACN universe to listen on.
+    pub source: u16,
+    #[serde(default)]
+    pub mode: MergeMode,
+}
+
+/// What a wing fader drives.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WingTarget {
+    /// A group master of the master page.
+    Master(String),
+    /// Programmer intensity of these fixtures, selected or not.
+    Fixtures(BTreeSet<u32>),
+    /// Programmer intensity of whatever is selected, like the level wheel.
+    Selection,
+}
+
+/// An input slot used as a fader.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WingFader {
+    /// sACN universe and slot (1-512).
+    pub universe: u16,
+    pub address: u16,
+    pub target: WingTarget,
+}
+
+impl std::fmt::Display for WingTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Master(group) => write!(f, "master {group}"),
+            Self::Fixtures(ids) => {
+                let ids: Vec<String> = ids.iter().map(u32::to_string).collect();
+                write!(f, "fixtures {}", ids.join(" + "))
+            }
+            Self::Selection => f.write_str("selection"),
+        }
+    }
+}
+
+impl Show {
+    /// sACN universes to listen on: merged inputs and wing faders.
+    pub fn dmx_input_universes(&self) -> Vec<u16> {
+        let merged = self.dmx_input.values().map(|m| m.source);
+        let wing = self.dmx_wing.iter().map(|w| w.universe);
+        let set: BTreeSet<u16> = merged.chain(wing).collect();
+        set.into_iter().collect()
+    }
+}
+
+/// One received E1.31 data packet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct E131Data {
+    pub cid: [u8; 16],
+    pub source_name: String,
+    pub priority: u8,
+    pub sequence: u8,
+    pub options: u8,
+    pub universe: u16,
+    pub data: [u8; 512],
+}
+
+/// Parse an E1.31 data packet (the inverse of `e131_packet`). None for
+/// anything else, including non-zero start codes.
+pub fn parse_e131(p: &[u8]) -> Option<E131Data> {
+    let u16_at = |i: usize| u16::from_be_bytes([p[i], p[i + 1]]);
+    let u32_at = |i: usize| u32::from_be_bytes([p[i], p[i + 1], p[i + 2], p[i + 3]]);
+    if p.len() < 126
+        || &p[4..16] != b"ASC-E1.17\0\0\0"
+        || u32_at(18) != 4
+        || u32_at(40) != 2
+        || p[117] != 0x02
+        || p[125] != 0
+    {
+        return None;
+    }
+    let slots = (u16_at(123) as usize).checked_sub(1)?.min(512);
+    let values = p.get(126..126 + slots)?;
+    let mut data = [0u8; 512];
+    data[..slots].copy_from_slice(values);
+
+    let name = &p[44..108];
+    let name_len = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+    Some(E131Data {
+        cid: p[22..38].try_into().ok()?,
+        source_name: String::from_utf8_lossy(&name[..name_len]).into_owned(),
+        priority: p[108],
+        sequence: p[111],
+        options: p[112],
+        universe: u16_at(113),
+        data,
+    })
+}
+
+#[derive(Debug, Clone)]
+struct Source {
+    priority: u8,
+    data: [u8; 512],
+    received_ms: u64,
+}
+
+/// Latest data of every sACN source, per universe.
+#[derive(Debug, Clone, Default)]
+pub struct DmxInput {
+    universes: BTreeMap<u16, BTreeMap<[u8; 16], Source>>,
+}
+
+impl DmxInput {
+    pub fn feed(&mut self, packet: &E131Data, now_ms: u64) {
+        if packet.options & E131_PREVIEW_DATA != 0 {
+            return;
+        }
+        let sources = self.universes.entry(packet.universe).or_default();
+        if packet.options & E131_STREAM_TERMINATED != 0 {
+            sources.remove(&packet.cid);
+            return;
+        }
+        sources.insert(
+            packet.cid,
+            Source {
+                priority: packet.priority,
+                data: packet.data,
+                received_ms: now_ms,
+            },
+        );
+    }
+
+    /// Winning (priority, slots) of a universe: the highest priority among
+    /// live sources, HTP between sources sharing it.
+    pub fn universe(&self, universe: u16, now_ms: u64) -> Option<(u8, [u8; 512])> {
+        let live: Vec<&Source> = self
+            .universes
+            .get(&universe)?
+            .values()
+            .filter(|s| now_ms.saturating_sub(s.received_ms) < SOURCE_TIMEOUT_MS)
+            .collect();
+        let top = live.iter().map(|s| s.priority).max()?;
+        let mut out = [0u8; 512];
+        for s in live.iter().filter(|s| s.priority == top) {
+            for (o, &v) in out.iter_mut().zip(&s.data) {
+                *o = (*o).max(v);
+            }
+        }
+        Some((top, out))
+    }
+
+    /// Universes with at least one live source.
+    pub fn active(&self, now_ms: u64) -> Vec<u16> {
+        self.universes
+            .keys()
+            .copied()
+            .filter(|&u| self.universe(u, now_ms).is_some())
+            .collect()
+    }
+
+    /// Merge the configured inputs into `live` (our rendered output).
+    pub fn merge_into(&self, show: &Show, live: &mut LiveState, now_ms: u64) {
+        for (&universe, merge) in &show.dmx_input {
+            let Some((priority, data)) = self.universe(merge.source, now_ms) else {
+                continue;
+            };
+            let ours = show.sacn.priority_of(universe);
+            let take_all = merge.mode == MergeMode::Priority && priority > ours;
+            if merge.mode == MergeMode::Priority && priority < ours {
+                continue;
+            }
+            for (i, &v) in data.iter().enumerate() {
+                let addr = i as u16 + 1;
+                if take_all || v > live.get(universe, addr) {
+                    live.set(universe, addr, v);
+                }
+            }
+        }
+    }
+}
+
+/// Non-blocking sACN listener on the standard port.
+#[derive(Debug)]
+pub struct SacnReceiver {
+    socket: UdpSocket,
+}
+
+impl SacnReceiver {
+    /// Listen on `bind` (default 0.0.0.0:5568) and join the multicast groups
+    /// of `universes`.
+    pub fn new(bind: Option<SocketAddr>, universes: &[u16]) -> anyhow::Result<Self> {
+        let bind = bind.unwrap_or(SocketAddr::from(([0, 0, 0, 0], SACN_PORT)));
+        let socket = UdpSocket::bind(bind).with_context(|| format!("bind sACN input {bind}"))?;
+        socket
+            .set_nonblocking(true)
+            .context("set sACN input non-blocking")?;
+        for &u in universes {
+            if let SocketAddr::V4(group) = sacn_multicast(u) {
+                socket
+                    .join_multicast_v4(group.ip(), &Ipv4Addr::UNSPECIFIED)
+                    .with_context(|| format!("join sACN universe {u}"))?;
+            }
+        }
+        Ok(Self { socket })
+    }
+
+    pub fn local_addr(&self) -> anyhow::Result<SocketAddr> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    /// Everything that arrived since the last call.
+    pub fn receive(&mut self) -> anyhow::Result<Vec<E131Data>> {
+        let mut out = Vec::new();
+        let mut buf = [0u8; 1144];
+        loop {
+            match self.socket.recv_from(&mut buf) {
+                Ok((n, _)) => out.extend(parse_e131(&buf[..n])),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok(out),
+                Err(e) => return Err(e).context("receive sACN"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dmx_output::{E131Header, e131_packet};
+
+    fn packet(cid: u8, priority: u8, slots: &[(usize, u8)]) -> Vec<u8> {
+        let mut data = [0u8; 512];
+        for &(i, v) in slots {
+            data[i] = v;
+        }
+        e131_packet(
+            &E131Header {
+                cid: [cid; 16],
+                source_name: "Desk",
+                priority,
+                sequence: 1,
+                options: 0,
+                universe: 7,
+            },
+            &data,
+        )
+    }
+
+    #[test]
+    fn sacn_input_merges_by_priority_and_htp() -> anyhow::Result<()> {
+        let mut rx = SacnReceiver::new(Some("127.0.0.1:0".parse()?), &[])?;
+        let tx = UdpSocket::bind("127.0.0.1:0")?;
+        tx.send_to(&packet(1, 100, &[(0, 200), (1, 10)]), rx.local_addr()?)?;
+        let mut got = Vec::new();
+        for _ in 0..100 {
+            got.extend(rx.receive()?);
+            if !got.is_empty() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(got.len(), 1);
+        assert_eq!((got[0].universe, got[0].source_name.as_str()), (7, "Desk"));
+
+        let mut input = DmxInput::default();
+        input.feed(&got[0], 0);
+        input.feed(&parse_e131(&packet(2, 100, &[(1, 50)])).unwrap(), 0);
+        input.feed(&parse_e131(&packet(3, 50, &[(2, 255)])).unwrap(), 0);
+        let (priority, data) = input.universe(7, 1000).unwrap();
+        assert_eq!((priority, data[0], data[1], data[2]), (100, 200, 50, 0));
+        assert_eq!(input.universe(7, SOURCE_TIMEOUT_MS), None);
+
+        let mut show = Show::new("Test");
+        show.dmx_input.insert(
+            1,
+            InputMerge {
+                source: 7,
+                mode: MergeMode::Htp,
+            },
+        );
+        let mut live = LiveState::new();
+        live.set(1, 1, 100);
+        live.set(1, 2, 80);
+        input.merge_into(&show, &mut live, 0);
+        assert_eq!(live.nonzero(), vec![(1, 1, 200), (1, 2, 80)]);
+
+        show.dmx_input.get_mut(&1).unwrap().mode = MergeMode::Priority;
+        show.sacn.priority = 150; // we outrank the desk: input ignored
+        let mut live = LiveState::new();
+        live.set(1, 2, 80);
+        input.merge_into(&show, &mut live, 0);
+        assert_eq!(live.nonzero(), vec![(1, 2, 80)]);
+        show.sacn.priority = 90; // the desk outranks us: it owns the universe
+        input.merge_into(&show, &mut live, 0);
+        assert_eq!(live.nonzero(), vec![(1, 1, 200), (1, 2, 50)]);
+        Ok(())
+    }
+
+    #[test]
+    fn wing_faders_take_over_when_they_move() -> anyhow::Result<()> {
+        let mut show = crate::ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "D1", "dimmer_1ch", 1, 1)
+            .fixture(2, "D2", "dimmer_1ch", 1, 2)
+            .build()?;
+        show.groups.insert("stage".into(), BTreeSet::from([1, 2]));
+        show.masters.groups = vec!["stage".into()];
+        for (address, target) in [
+            (1, WingTarget::Master("stage".into())),
+            (2, WingTarget::Fixtures(BTreeSet::from([2]))),
+            (3, WingTarget::Selection),
+        ] {
+            show.dmx_wing.push(WingFader {
+                universe: 7,
+                address,
+                target,
+            });
+        }
+        assert_eq!(show.dmx_input_universes(), [7]);
+
+        let mut rt = crate::Runtime::new(show);
+        rt.programmer.selected.insert(1);
+        let faders = parse_e131(&packet(1, 100, &[(0, 128), (1, 200), (2, 50)])).unwrap();
+        rt.feed_dmx_input(std::slice::from_ref(&faders));
+        assert_eq!(rt.master_level("stage"), 128);
+        let contents = rt.programmer.contents();
+        assert_eq!(
+            (contents[&1].intensity, contents[&2].intensity),
+            (Some(50), Some(200))
+        );
+
+        // unmoved faders leave edits made since alone
+        rt.set_master("stage", 255)?;
+        rt.feed_dmx_input(std::slice::from_ref(&faders));
+        assert_eq!(rt.master_level("stage"), 255);
+        Ok(())
+    }
+}
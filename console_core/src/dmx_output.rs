@@ -0,0 +1,409 @@
+//! Network DMX drivers. Art-Net: one ArtDMX packet per universe per frame,
+//! broadcast or unicast. sACN (E1.31): one data packet per universe per
+//! frame to the universe's multicast group (or one unicast receiver), with
+//! per-universe priority. Drivers implement `OutputDriver`; run them on an
+//! `OutputThread`, whose `OutputConfig::refresh_ms` sets the refresh rate.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::{SocketAddr, UdpSocket};
+
+use crate::{LiveState, OutputDriver};
+
+pub const ARTNET_PORT: u16 = 6454;
+
+/// Where Art-Net packets go.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtNetConfig {
+    /// Default destination: a broadcast address (e.g. 2.255.255.255) or one node.
+    pub target: SocketAddr,
+    /// Universes sent to a specific node instead of `target`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub unicast: BTreeMap<u16, SocketAddr>,
+}
+
+impl Default for ArtNetConfig {
+    fn default() -> Self {
+        Self {
+            target: SocketAddr::from(([255, 255, 255, 255], ARTNET_PORT)),
+            unicast: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ArtNetOutput {
+    socket: UdpSocket,
+    config: ArtNetConfig,
+    /// Per-universe ArtDMX sequence (1..=255; 0 would disable reordering).
+    sequence: BTreeMap<u16, u8>,
+}
+
+impl ArtNetOutput {
+    pub fn new(config: ArtNetConfig) -> anyhow::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", 0)).context("bind Art-Net socket")?;
+        socket
+            .set_broadcast(true)
+            .context("enable broadcast on Art-Net socket")?;
+        Ok(Self {
+            socket,
+            config,
+            sequence: BTreeMap::new(),
+        })
+    }
+}
+
+impl OutputDriver for ArtNetOutput {
+    fn name(&self) -> String {
+        format!("Art-Net {}", self.config.target)
+    }
+
+    /// A changed network interface can leave the old socket unusable.
+    fn reconnect(&mut self) -> anyhow::Result<()> {
+        *self = Self {
+            sequence: std::mem::take(&mut self.sequence),
+            ..Self::new(self.config.clone())?
+        };
+        Ok(())
+    }
+
+    fn send(&mut self, frame: &LiveState) -> anyhow::Result<()> {
+        for (&universe, addrs) in &frame.universes {
+            let data = slots(addrs);
+            let seq = self.sequence.entry(universe).or_insert(0);
+            *seq = if *seq == 255 { 1 } else { *seq + 1 };
+
+            let packet = artdmx_packet(universe, *seq, &data);
+            let dest = self
+                .config
+                .unicast
+                .get(&universe)
+                .unwrap_or(&self.config.target);
+            self.socket
+                .send_to(&packet, dest)
+                .with_context(|| format!("send Art-Net universe {universe} to {dest}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// One universe's addresses as 512 DMX slots.
+pub(crate) fn slots(addrs: &BTreeMap<u16, u8>) -> [u8; 512] {
+    let mut data = [0u8; 512];
+    for (&a, &v) in addrs {
+        if (1..=512).contains(&a) {
+            data[a as usize - 1] = v;
+        }
+    }
+    data
+}
+
+/// Build an ArtDMX packet. Console universes are 1-based; Art-Net port
+/// addresses start at 0, so universe 1 goes out as port address 0.
+pub fn artdmx_packet(universe: u16, sequence: u8, data: &[u8; 512]) -> Vec<u8> {
+    let port = universe.saturating_sub(1) & 0x7fff;
+    let mut p = Vec::with_capacity(18 + 512);
+    p.extend_from_slice(b"Art-Net\0");
+    p.extend_from_slice(&0x5000u16.to_le_bytes()); // OpDmx
+    p.extend_from_slice(&14u16.to_be_bytes()); // protocol version
+    p.push(sequence);
+    p.push(0); // physical input port
+    p.push((port & 0xff) as u8); // SubUni
+    p.push((port >> 8) as u8); // Net
+    p.extend_from_slice(&512u16.to_be_bytes());
+    p.extend_from_slice(data);
+    p
+}
+
+pub const SACN_PORT: u16 = 5568;
+
+fn default_source_name() -> String {
+    "LightConsole".to_string()
+}
+
+fn default_priority() -> u8 {
+    100
+}
+
+/// sACN settings saved with the show.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SacnConfig {
+    /// Shown by receivers and sACN viewers (max 63 bytes are sent).
+    #[serde(default = "default_source_name")]
+    pub source_name: String,
+    /// Priority of universes without an entry in `priorities` (0-200).
+    #[serde(default = "default_priority")]
+    pub priority: u8,
+    /// Per-universe priority overrides.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub priorities: BTreeMap<u16, u8>,
+}
+
+impl Default for SacnConfig {
+    fn default() -> Self {
+        Self {
+            source_name: default_source_name(),
+            priority: default_priority(),
+            priorities: BTreeMap::new(),
+        }
+    }
+}
+
+impl SacnConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn priority_of(&self, universe: u16) -> u8 {
+        self.priorities
+            .get(&universe)
+            .copied()
+            .unwrap_or(self.priority)
+            .min(200)
+    }
+}
+
+/// Multicast group of an sACN universe: 239.255.<hi>.<lo>.
+pub fn sacn_multicast(universe: u16) -> SocketAddr {
+    let [hi, lo] = universe.to_be_bytes();
+    SocketAddr::from(([239, 255, hi, lo], SACN_PORT))
+}
+
+#[derive(Debug)]
+pub struct SacnOutput {
+    socket: UdpSocket,
+    config: SacnConfig,
+    /// Send everything to this receiver instead of multicast.
+    unicast: Option<SocketAddr>,
+    /// Component id identifying this source to receivers.
+    cid: [u8; 16],
+    sequence: BTreeMap<u16, u8>,
+}
+
+impl SacnOutput {
+    pub fn new(config: SacnConfig, unicast: Option<SocketAddr>) -> anyhow::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", 0)).context("bind sACN socket")?;
+        socket
+            .set_multicast_ttl_v4(8)
+            .context("set multicast TTL on sACN socket")?;
+        Ok(Self {
+            socket,
+            cid: new_cid(&config.source_name),
+            config,
+            unicast,
+            sequence: BTreeMap::new(),
+        })
+    }
+
+    fn dest(&self, universe: u16) -> SocketAddr {
+        self.unicast.unwrap_or_else(|| sacn_multicast(universe))
+    }
+
+    fn send_universe(
+        &mut self,
+        universe: u16,
+        data: &[u8; 512],
+        options: u8,
+    ) -> anyhow::Result<()> {
+        let seq = self.sequence.entry(universe).or_insert(0);
+        *seq = seq.wrapping_add(1);
+        let packet = e131_packet(
+            &E131Header {
+                cid: self.cid,
+                source_name: &self.config.source_name,
+                priority: self.config.priority_of(universe),
+                sequence: *seq,
+                options,
+                universe,
+            },
+            data,
+        );
+        let dest = self.dest(universe);
+        self.socket
+            .send_to(&packet, dest)
+            .with_context(|| format!("send sACN universe {universe} to {dest}"))?;
+        Ok(())
+    }
+}
+
+/// A per-run CID; receivers only need it to stay stable while we send.
+fn new_cid(source_name: &str) -> [u8; 16] {
+    use std::hash::BuildHasher;
+    let mut cid = [0u8; 16];
+    for half in cid.chunks_mut(8) {
+        let h = std::collections::hash_map::RandomState::new()
+            .hash_one((source_name, std::process::id()));
+        half.copy_from_slice(&h.to_be_bytes());
+    }
+    cid[6] = (cid[6] & 0x0f) | 0x40; // UUID version 4
+    cid[8] = (cid[8] & 0x3f) | 0x80;
+    cid
+}
+
+impl OutputDriver for SacnOutput {
+    fn name(&self) -> String {
+        match self.unicast {
+            Some(addr) => format!("sACN {addr}"),
+            None => "sACN multicast".to_string(),
+        }
+    }
+
+    fn reconnect(&mut self) -> anyhow::Result<()> {
+        let cid = self.cid;
+        *self = Self {
+            sequence: std::mem::take(&mut self.sequence),
+            cid,
+            ..Self::new(self.config.clone(), self.unicast)?
+        };
+        Ok(())
+    }
+
+    fn send(&mut self, frame: &LiveState) -> anyhow::Result<()> {
+        for (&universe, addrs) in &frame.universes {
+            if (1..=63999).contains(&universe) {
+                self.send_universe(universe, &slots(addrs), 0)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Receivers drop the source at once instead of holding for 2.5 s.
+    fn terminate(&mut self) -> anyhow::Result<()> {
+        let universes: Vec<u16> = self.sequence.keys().copied().collect();
+        for universe in universes {
+            for _ in 0..3 {
+                self.send_universe(universe, &[0; 512], E131_STREAM_TERMINATED)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Framing-layer option bit: the source stops sending this universe.
+pub const E131_STREAM_TERMINATED: u8 = 0x40;
+
+/// Per-packet fields of an E1.31 data packet.
+#[derive(Debug, Clone, Copy)]
+pub struct E131Header<'a> {
+    pub cid: [u8; 16],
+    pub source_name: &'a str,
+    pub priority: u8,
+    pub sequence: u8,
+    pub options: u8,
+    pub universe: u16,
+}
+
+/// Build an E1.31 data packet carrying a full 512-slot universe (638 bytes).
+pub fn e131_packet(h: &E131Header, data: &[u8; 512]) -> Vec<u8> {
+    const LEN: u16 = 638;
+    let flags_len = |from: u16| (0x7000 | (LEN - from)).to_be_bytes();
+
+    let mut p = Vec::with_capacity(LEN as usize);
+    // root layer
+    p.extend_from_slice(&0x0010u16.to_be_bytes()); // preamble size
+    p.extend_from_slice(&0u16.to_be_bytes()); // postamble size
+    p.extend_from_slice(b"ASC-E1.17\0\0\0");
+    p.extend_from_slice(&flags_len(16));
+    p.extend_from_slice(&4u32.to_be_bytes()); // VECTOR_ROOT_E131_DATA
+    p.extend_from_slice(&h.cid);
+    // framing layer
+    p.extend_from_slice(&flags_len(38));
+    p.extend_from_slice(&2u32.to_be_bytes()); // VECTOR_E131_DATA_PACKET
+    let mut name = [0u8; 64];
+    let bytes = h.source_name.as_bytes();
+    let n = bytes.len().min(63);
+    name[..n].copy_from_slice(&bytes[..n]);
+    p.extend_from_slice(&name);
+    p.push(h.priority.min(200));
+    p.extend_from_slice(&0u16.to_be_bytes()); // synchronization address
+    p.push(h.sequence);
+    p.push(h.options);
+    p.extend_from_slice(&h.universe.to_be_bytes());
+    // DMP layer
+    p.extend_from_slice(&flags_len(115));
+    p.push(0x02); // VECTOR_DMP_SET_PROPERTY
+    p.push(0xa1); // address & data type
+    p.extend_from_slice(&0u16.to_be_bytes()); // first property address
+    p.extend_from_slice(&1u16.to_be_bytes()); // address increment
+    p.extend_from_slice(&513u16.to_be_bytes()); // start code + 512 slots
+    p.push(0); // DMX start code
+    p.extend_from_slice(data);
+    p
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn artnet_sends_artdmx_per_universe() -> anyhow::Result<()> {
+        let node = UdpSocket::bind("127.0.0.1:0")?;
+        node.set_read_timeout(Some(std::time::Duration::from_secs(5)))?;
+        let mut out = ArtNetOutput::new(ArtNetConfig {
+            target: node.local_addr()?,
+            unicast: BTreeMap::new(),
+        })?;
+
+        let mut frame = LiveState::new();
+        frame.set(1, 1, 255);
+        frame.set(1, 512, 7);
+        out.send(&frame)?;
+        out.send(&frame)?;
+
+        let mut buf = [0u8; 1024];
+        let (n, _) = node.recv_from(&mut buf)?;
+        assert_eq!(n, 18 + 512);
+        assert_eq!(&buf[..8], b"Art-Net\0");
+        assert_eq!(&buf[8..12], &[0x00, 0x50, 0x00, 14]);
+        assert_eq!(buf[12], 1); // sequence
+        assert_eq!(&buf[14..18], &[0, 0, 0x02, 0x00]); // port 0, length 512
+        assert_eq!((buf[18], buf[18 + 511]), (255, 7));
+
+        let (_, _) = node.recv_from(&mut buf)?;
+        assert_eq!(buf[12], 2);
+
+        let packet = artdmx_packet(0x123 + 1, 9, &[0; 512]);
+        assert_eq!((packet[14], packet[15]), (0x23, 0x01));
+        Ok(())
+    }
+
+    #[test]
+    fn sacn_sends_e131_with_priority_and_terminates() -> anyhow::Result<()> {
+        let receiver = UdpSocket::bind("127.0.0.1:0")?;
+        receiver.set_read_timeout(Some(std::time::Duration::from_secs(5)))?;
+        let config = SacnConfig {
+            source_name: "FOH desk".into(),
+            priority: 100,
+            priorities: BTreeMap::from([(2, 150)]),
+        };
+        let mut out = SacnOutput::new(config, Some(receiver.local_addr()?))?;
+
+        let mut frame = LiveState::new();
+        frame.set(2, 1, 255);
+        frame.set(2, 512, 9);
+        out.send(&frame)?;
+
+        let mut buf = [0u8; 1024];
+        let (n, _) = receiver.recv_from(&mut buf)?;
+        assert_eq!(n, 638);
+        assert_eq!(&buf[4..16], b"ASC-E1.17\0\0\0");
+        assert_eq!(&buf[16..18], &[0x72, 0x6e]); // root flags & length 622
+        assert_eq!(&buf[44..52], b"FOH desk");
+        assert_eq!(buf[108], 150); // per-universe priority
+        assert_eq!((buf[111], buf[112]), (1, 0)); // sequence, options
+        assert_eq!(&buf[113..115], &[0, 2]);
+        assert_eq!(&buf[123..126], &[0x02, 0x01, 0x00]); // 513 values, start code 0
+        assert_eq!((buf[126], buf[637]), (255, 9));
+
+        out.terminate()?;
+        let (_, _) = receiver.recv_from(&mut buf)?;
+        assert_eq!(buf[112], E131_STREAM_TERMINATED);
+
+        assert_eq!(
+            sacn_multicast(0x0102),
+            SocketAddr::from(([239, 255, 1, 2], SACN_PORT))
+        );
+        Ok(())
+    }
+}
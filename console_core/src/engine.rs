@@ -1,12 +1,86 @@
-use anyhow::Context;
-use std::collections::{BTreeMap, BTreeSet};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::playback::render_fixture_values;
+use crate::{ChannelKind, FixtureValues, ParamFilter, Show};
+use crate::{Palette, PaletteKind, PaletteRefs, PaletteValues, SystemPalette};
+
+/// What happens to programmer values of fixtures that leave the selection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionPolicy {
+    /// Deselected fixtures keep their values in the programmer.
+    Retain,
+    /// Deselected fixtures drop out of the programmer.
+    #[default]
+    Release,
+    /// Keep the values until the operator answers (`release` or keep).
+    Prompt,
+}
+
+impl SelectionPolicy {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::str::FromStr for SelectionPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "retain" | "keep" => Ok(Self::Retain),
+            "release" => Ok(Self::Release),
+            "prompt" | "ask" => Ok(Self::Prompt),
+            _ => anyhow::bail!("unknown selection policy '{s}' (use retain|release|prompt)"),
+        }
+    }
+}
+
+/// How `Programmer::fan` spreads a range over the selected fixtures.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FanMode {
+    /// First fixture gets the start value, last the end value.
+    #[default]
+    Linear,
+    /// The middle gets the start value, both ends the end value.
+    Center,
+}
+
+impl FanMode {
+    /// Value for fixture `i` of `n`.
+    fn value(self, i: usize, n: usize, from: u8, to: u8) -> u8 {
+        if n < 2 {
+            return from;
+        }
+        let last = (n - 1) as f32;
+        let t = match self {
+            FanMode::Linear => i as f32 / last,
+            FanMode::Center => (i as f32 - last / 2.0).abs() / (last / 2.0),
+        };
+        (from as f32 + (to as f32 - from as f32) * t).round() as u8
+    }
+}
+
+impl std::str::FromStr for FanMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "linear" => Ok(Self::Linear),
+            "center" | "centre" | "mirror" => Ok(Self::Center),
+            _ => anyhow::bail!("unknown fan mode '{s}' (use linear|center)"),
+        }
+    }
+}
 
-use crate::{ChannelKind, Show};
-use crate::{Palette, PaletteKind, PaletteValues};
+/// Swatches kept in the programmer's color history.
+pub const COLOR_HISTORY_LEN: usize = 12;
 
 /// The Programmer is the live edit buffer:
 /// - selection
 /// - temporary values (intensity, rgb)
+/// - values kept on fixtures that left the selection (see `SelectionPolicy`)
 #[derive(Debug, Default, Clone)]
 pub struct Programmer {
     pub selected: BTreeSet<u32>,
@@ -14,6 +88,21 @@ pub struct Programmer {
     pub r: Option<u8>,
     pub g: Option<u8>,
     pub b: Option<u8>,
+    pub policy: SelectionPolicy,
+    retained: BTreeMap<u32, FixtureValues>,
+    /// Per-fixture intensity from the level wheel; wins over `intensity`.
+    levels: BTreeMap<u32, u8>,
+    /// Per-fixture color typed into the contents table; wins over `r`/`g`/`b`
+    /// until the next color is set for the whole selection.
+    colors: BTreeMap<u32, FixtureValues>,
+    /// Per-fixture pan/tilt (from `home` or the contents table); setting a
+    /// color for the selection leaves it alone.
+    positions: BTreeMap<u32, FixtureValues>,
+    /// Recently used colors, newest first, no repeats. Survives clearing.
+    color_history: VecDeque<[u8; 3]>,
+    /// Palettes last applied by name, for recording references.
+    intensity_palette: Option<String>,
+    color_palette: Option<String>,
 }
 
 impl Programmer {
@@ -23,10 +112,7 @@ impl Programmer {
 
     pub fn clear_all(&mut self) {
         self.selected.clear();
-        self.intensity = None;
-        self.r = None;
-        self.g = None;
-        self.b = None;
+        self.clear_values();
     }
 
     pub fn clear_values(&mut self) {
@@ -34,6 +120,81 @@ impl Programmer {
         self.r = None;
         self.g = None;
         self.b = None;
+        self.retained.clear();
+        self.levels.clear();
+        self.colors.clear();
+        self.positions.clear();
+        self.intensity_palette = None;
+        self.color_palette = None;
+    }
+
+    /// Knock the given parameters out of the selected fixtures, keeping the
+    /// rest (`clear color` leaves intensities alone).
+    pub fn clear_params(&mut self, params: ParamFilter) {
+        if params.intensity {
+            self.intensity = None;
+            self.intensity_palette = None;
+            self.levels.retain(|fid, _| !self.selected.contains(fid));
+        }
+        if params.color {
+            self.r = None;
+            self.g = None;
+            self.b = None;
+            self.color_palette = None;
+            self.colors.retain(|fid, _| !self.selected.contains(fid));
+        }
+        if params.position {
+            self.positions.retain(|fid, _| !self.selected.contains(fid));
+        }
+    }
+
+    /// Replace the selection, applying `policy` to fixtures that leave it.
+    /// Returns the fixtures awaiting an answer under `SelectionPolicy::Prompt`.
+    pub fn set_selection(&mut self, ids: BTreeSet<u32>) -> Vec<u32> {
+        let left: Vec<u32> = self.selected.difference(&ids).copied().collect();
+        let has_values = left
+            .iter()
+            .any(|fid| self.values_for(*fid) != FixtureValues::default());
+        let left_values: Vec<_> = left
+            .iter()
+            .map(|&fid| (fid, self.values_for(fid)))
+            .collect();
+        for fid in &left {
+            self.levels.remove(fid);
+            self.colors.remove(fid);
+            self.positions.remove(fid);
+        }
+        self.selected = ids;
+
+        match self.policy {
+            SelectionPolicy::Release => {
+                self.release(&left);
+                Vec::new()
+            }
+            SelectionPolicy::Retain | SelectionPolicy::Prompt if has_values => {
+                for (fid, values) in left_values {
+                    self.retained.entry(fid).or_default().apply_delta(&values);
+                }
+                if self.policy == SelectionPolicy::Prompt {
+                    left
+                } else {
+                    Vec::new()
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Drop values kept on deselected fixtures.
+    pub fn release(&mut self, ids: &[u32]) {
+        for fid in ids {
+            self.retained.remove(fid);
+        }
+    }
+
+    /// Values kept on fixtures outside the selection.
+    pub fn retained(&self) -> &BTreeMap<u32, FixtureValues> {
+        &self.retained
     }
 
     pub fn select_one(&mut self, id: u32) {
@@ -52,12 +213,182 @@ impl Programmer {
         // scale 0..100 -> 0..255
         let value = ((pct as u16 * 255) / 100) as u8;
         self.intensity = Some(value);
+        self.levels.clear();
+    }
+
+    /// Level wheel: move the intensity of every selected fixture by `delta`
+    /// relative to its level in `output` (the current rendered frame).
+    pub fn nudge_intensity(&mut self, show: &Show, output: &LiveState, delta: i16) {
+        for &fid in &self.selected {
+            let current = self
+                .levels
+                .get(&fid)
+                .copied()
+                .or(self.intensity)
+                .or_else(|| {
+                    show.patch
+                        .channel_addresses(fid, ChannelKind::Intensity)
+                        .first()
+                        .map(|&(u, a)| output.get(u, a))
+                });
+            let Some(current) = current else {
+                continue; // no intensity channel
+            };
+            let level = (current as i16 + delta).clamp(0, 255) as u8;
+            self.levels.insert(fid, level);
+        }
+    }
+
+    /// Set one fixture's intensity (DMX) without touching the selection:
+    /// per fixture like the level wheel when selected, kept otherwise.
+    pub fn set_fixture_intensity(&mut self, fixture_id: u32, level: u8) {
+        if self.selected.contains(&fixture_id) {
+            self.levels.insert(fixture_id, level);
+        } else {
+            self.retained.entry(fixture_id).or_default().intensity = Some(level);
+        }
+    }
+
+    /// Spread `from`..`to` (DMX values) over the selected fixtures in id
+    /// order, per fixture like the level wheel and contents table.
+    pub fn fan(
+        &mut self,
+        kind: ChannelKind,
+        from: u8,
+        to: u8,
+        mode: FanMode,
+    ) -> anyhow::Result<()> {
+        let n = self.selected.len();
+        for (i, fid) in self.selected.clone().into_iter().enumerate() {
+            let value = mode.value(i, n, from, to);
+            let v = Some(value);
+            let values = match kind {
+                ChannelKind::Intensity => FixtureValues {
+                    intensity: v,
+                    ..Default::default()
+                },
+                ChannelKind::ColorR => FixtureValues {
+                    r: v,
+                    ..Default::default()
+                },
+                ChannelKind::ColorG => FixtureValues {
+                    g: v,
+                    ..Default::default()
+                },
+                ChannelKind::ColorB => FixtureValues {
+                    b: v,
+                    ..Default::default()
+                },
+                ChannelKind::Pan => FixtureValues {
+                    pan: v,
+                    ..Default::default()
+                },
+                ChannelKind::Tilt => FixtureValues {
+                    tilt: v,
+                    ..Default::default()
+                },
+                other => anyhow::bail!("The programmer can't fan {other:?}"),
+            };
+            self.edit_fixture(fid, &values);
+        }
+        Ok(())
+    }
+
+    /// Values the programmer puts on a fixture (selected or retained).
+    fn values_for(&self, fixture_id: u32) -> FixtureValues {
+        if !self.selected.contains(&fixture_id) {
+            return self.retained.get(&fixture_id).cloned().unwrap_or_default();
+        }
+        let mut values = FixtureValues {
+            intensity: self.levels.get(&fixture_id).copied().or(self.intensity),
+            r: self.r,
+            g: self.g,
+            b: self.b,
+            ..Default::default()
+        };
+        if let Some(color) = self.colors.get(&fixture_id) {
+            values.apply_delta(color);
+        }
+        if let Some(position) = self.positions.get(&fixture_id) {
+            values.apply_delta(position);
+        }
+        values
+    }
+
+    /// Everything the programmer holds, per fixture: what a tracking
+    /// record stores. Fixtures without values are left out.
+    pub fn contents(&self) -> BTreeMap<u32, FixtureValues> {
+        self.retained
+            .keys()
+            .chain(&self.selected)
+            .map(|&fid| (fid, self.values_for(fid)))
+            .filter(|(_, v)| !v.is_all_none())
+            .collect()
+    }
+
+    /// Set the given parameters on one fixture already in the programmer.
+    pub fn edit_fixture(&mut self, fixture_id: u32, values: &FixtureValues) {
+        if !self.selected.contains(&fixture_id) {
+            if let Some(kept) = self.retained.get_mut(&fixture_id) {
+                kept.apply_delta(values);
+            }
+            return;
+        }
+        if let Some(i) = values.intensity {
+            self.levels.insert(fixture_id, i);
+        }
+        let color = FixtureValues {
+            r: values.r,
+            g: values.g,
+            b: values.b,
+            ..Default::default()
+        };
+        if !color.is_all_none() {
+            self.colors
+                .entry(fixture_id)
+                .or_default()
+                .apply_delta(&color);
+        }
+        let position = FixtureValues {
+            pan: values.pan,
+            tilt: values.tilt,
+            ..Default::default()
+        };
+        if !position.is_all_none() {
+            self.positions
+                .entry(fixture_id)
+                .or_default()
+                .apply_delta(&position);
+        }
+    }
+
+    /// Take a fixture out of the programmer entirely (deselect and drop its values).
+    pub fn knockout(&mut self, fixture_id: u32) {
+        self.selected.remove(&fixture_id);
+        self.retained.remove(&fixture_id);
+        self.levels.remove(&fixture_id);
+        self.colors.remove(&fixture_id);
+        self.positions.remove(&fixture_id);
     }
 
     pub fn set_rgb(&mut self, r: u8, g: u8, b: u8) {
         self.r = Some(r);
         self.g = Some(g);
         self.b = Some(b);
+        self.colors.clear();
+        self.remember_color([r, g, b]);
+    }
+
+    /// Put a color at the front of the history.
+    pub fn remember_color(&mut self, rgb: [u8; 3]) {
+        self.color_history.retain(|c| *c != rgb);
+        self.color_history.push_front(rgb);
+        self.color_history.truncate(COLOR_HISTORY_LEN);
+    }
+
+    /// Recently used colors, newest first.
+    pub fn color_history(&self) -> &VecDeque<[u8; 3]> {
+        &self.color_history
     }
 
     /// Render ONLY the programmer into a fresh LiveState.
@@ -65,44 +396,13 @@ impl Programmer {
     pub fn render(&self, show: &Show) -> anyhow::Result<LiveState> {
         let mut live = LiveState::new();
 
-        for fixture_id in &self.selected {
-            let f = show
-                .patch
-                .fixtures
-                .get(fixture_id)
-                .with_context(|| format!("unknown fixture id {}", fixture_id))?;
-
-            let ft = show
-                .patch
-                .fixture_types
-                .get(&f.fixture_type)
-                .with_context(|| format!("unknown fixture type '{}'", f.fixture_type))?;
-
-            for (i, ch) in ft.channels.iter().enumerate() {
-                let addr = f.address + i as u16; // 1-based DMX
-                if !(1..=512).contains(&addr) {
-                    anyhow::bail!(
-                        "fixture {} '{}' maps outside DMX range: U{} @ {} (channel index {})",
-                        f.fixture_id,
-                        f.name,
-                        f.universe,
-                        f.address,
-                        i
-                    );
-                }
-
-                let value_opt = match ch.kind {
-                    ChannelKind::Intensity => self.intensity,
-                    ChannelKind::ColorR => self.r,
-                    ChannelKind::ColorG => self.g,
-                    ChannelKind::ColorB => self.b,
-                    _ => None,
-                };
-
-                if let Some(value) = value_opt {
-                    live.set(f.universe, addr, value);
-                }
-            }
+        let fixtures = self
+            .retained
+            .iter()
+            .map(|(fid, vals)| (fid, vals.clone()))
+            .chain(self.selected.iter().map(|fid| (fid, self.values_for(*fid))));
+        for (&fixture_id, vals) in fixtures {
+            render_fixture_values(show, fixture_id, &vals, None, &mut live)?;
         }
 
         Ok(live)
@@ -117,14 +417,80 @@ impl Programmer {
         }
     }
 
+    /// `apply_palette`, remembering the name so a record references the
+    /// palette instead of only copying its values.
+    pub fn apply_named_palette(&mut self, name: &str, pal: &Palette) {
+        self.apply_palette(pal);
+        match pal.kind {
+            PaletteKind::Intensity => self.intensity_palette = Some(name.to_string()),
+            PaletteKind::Color => self.color_palette = Some(name.to_string()),
+        }
+    }
+
+    /// Set a system palette on every selected fixture, with the values of
+    /// its own type. Returns how many fixtures it applied to.
+    pub fn apply_system_palette(&mut self, show: &Show, pal: SystemPalette) -> usize {
+        let selected: Vec<u32> = self.selected.iter().copied().collect();
+        let mut applied = 0;
+        for fid in selected {
+            let values = show
+                .patch
+                .fixtures
+                .get(&fid)
+                .and_then(|f| show.patch.fixture_types.get(&f.fixture_type))
+                .and_then(|ft| pal.values_for(ft));
+            if let Some(values) = values {
+                self.edit_fixture(fid, &values);
+                applied += 1;
+            }
+        }
+        applied
+    }
+
+    /// References to record with `contents()`: a fixture refers to a named
+    /// palette while its values still are the palette's.
+    pub fn palette_refs(&self, palettes: &BTreeMap<String, Palette>) -> BTreeMap<u32, PaletteRefs> {
+        let named = |name: &Option<String>| {
+            name.as_ref()
+                .and_then(|n| palettes.get(n).map(|p| (n.clone(), &p.values)))
+        };
+        let intensity = named(&self.intensity_palette);
+        let color = named(&self.color_palette);
+        self.contents()
+            .into_iter()
+            .filter_map(|(fid, v)| {
+                let refs = PaletteRefs {
+                    intensity: intensity
+                        .as_ref()
+                        .filter(|(_, p)| p.intensity.is_some() && p.intensity == v.intensity)
+                        .map(|(n, _)| n.clone()),
+                    color: color
+                        .as_ref()
+                        .filter(|(_, p)| {
+                            let same =
+                                |want: Option<u8>, have| want.is_none_or(|w| Some(w) == have);
+                            [p.r, p.g, p.b].iter().any(Option::is_some)
+                                && same(p.r, v.r)
+                                && same(p.g, v.g)
+                                && same(p.b, v.b)
+                        })
+                        .map(|(n, _)| n.clone()),
+                };
+                (!refs.is_empty()).then_some((fid, refs))
+            })
+            .collect()
+    }
+
     pub fn apply_palette(&mut self, pal: &Palette) {
         match pal.kind {
             PaletteKind::Intensity => {
                 if let Some(v) = pal.values.intensity {
                     self.intensity = Some(v);
+                    self.levels.clear();
                 }
             }
             PaletteKind::Color => {
+                self.colors.clear();
                 if let Some(v) = pal.values.r {
                     self.r = Some(v);
                 }
@@ -134,6 +500,9 @@ impl Programmer {
                 if let Some(v) = pal.values.b {
                     self.b = Some(v);
                 }
+                if let (Some(r), Some(g), Some(b)) = (self.r, self.g, self.b) {
+                    self.remember_color([r, g, b]);
+                }
             }
         }
     }
@@ -168,6 +537,15 @@ impl LiveState {
         }
     }
 
+    /// Value at an address (0 when unset).
+    pub fn get(&self, universe: u16, address: u16) -> u8 {
+        self.universes
+            .get(&universe)
+            .and_then(|m| m.get(&address))
+            .copied()
+            .unwrap_or(0)
+    }
+
     pub fn nonzero(&self) -> Vec<(u16, u16, u8)> {
         let mut out = Vec::new();
         for (&u, addrs) in &self.universes {
@@ -209,6 +587,138 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn color_history_keeps_recent_unique_colors() {
+        let mut p = Programmer::new();
+        p.set_rgb(255, 0, 0);
+        p.set_rgb(0, 0, 255);
+        p.set_rgb(255, 0, 0);
+        assert_eq!(
+            p.color_history().iter().collect::<Vec<_>>(),
+            vec![&[255, 0, 0], &[0, 0, 255]]
+        );
+
+        p.apply_palette(&Palette::from_rgb([1, 2, 3]));
+        assert_eq!(p.color_history()[0], [1, 2, 3]);
+
+        for v in 0..20 {
+            p.set_rgb(v, v, v);
+        }
+        p.clear_all();
+        assert_eq!(p.color_history().len(), COLOR_HISTORY_LEN);
+        assert_eq!(p.color_history()[0], [19, 19, 19]);
+    }
+
+    #[test]
+    fn contents_are_editable_per_fixture() {
+        let mut p = Programmer::new();
+        p.policy = SelectionPolicy::Retain;
+        p.selected.extend([1, 2, 3]);
+        p.intensity = Some(200);
+        p.set_rgb(255, 0, 0);
+        let red = |i| FixtureValues {
+            intensity: Some(i),
+            r: Some(255),
+            g: Some(0),
+            b: Some(0),
+            ..Default::default()
+        };
+
+        p.edit_fixture(
+            2,
+            &FixtureValues {
+                intensity: Some(50),
+                b: Some(90),
+                ..Default::default()
+            },
+        );
+        p.knockout(3);
+        p.set_selection([2].into_iter().collect()); // 1 is retained
+        p.edit_fixture(
+            1,
+            &FixtureValues {
+                g: Some(7),
+                ..Default::default()
+            },
+        );
+        p.edit_fixture(9, &red(1)); // not in the programmer: ignored
+
+        let contents = p.contents();
+        assert_eq!(contents.keys().copied().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(
+            contents[&1],
+            FixtureValues {
+                g: Some(7),
+                ..red(200)
+            }
+        );
+        assert_eq!(
+            contents[&2],
+            FixtureValues {
+                b: Some(90),
+                ..red(50)
+            }
+        );
+
+        // a new color for the selection replaces typed-in colors
+        p.set_rgb(255, 0, 0);
+        assert_eq!(p.contents()[&2], red(50));
+    }
+
+    #[test]
+    fn clear_params_keeps_the_other_category() {
+        let mut p = Programmer::new();
+        p.selected.extend([1, 2]);
+        p.intensity = Some(200);
+        p.set_rgb(255, 0, 0);
+        p.edit_fixture(
+            2,
+            &FixtureValues {
+                intensity: Some(50),
+                b: Some(90),
+                ..Default::default()
+            },
+        );
+
+        p.clear_params(ParamFilter::COLOR);
+        let contents = p.contents();
+        assert_eq!(
+            contents[&1],
+            FixtureValues {
+                intensity: Some(200),
+                ..Default::default()
+            }
+        );
+        assert_eq!(contents[&2].intensity, Some(50));
+        assert_eq!(contents[&2].b, None);
+
+        p.clear_params(ParamFilter::INTENSITY);
+        assert!(p.contents().is_empty());
+    }
+
+    #[test]
+    fn fan_spreads_values_over_the_selection() -> anyhow::Result<()> {
+        let mut p = Programmer::new();
+        p.selected.extend([1, 2, 3, 4, 5]);
+        p.set_rgb(0, 0, 255);
+        p.fan(ChannelKind::Intensity, 0, 200, FanMode::Linear)?;
+        p.fan(ChannelKind::ColorR, 100, 0, FanMode::Center)?;
+        let c = p.contents();
+        let levels: Vec<_> = c.values().map(|v| v.intensity.unwrap()).collect();
+        assert_eq!(levels, [0, 50, 100, 150, 200]);
+        let reds: Vec<_> = c.values().map(|v| v.r.unwrap()).collect();
+        assert_eq!(reds, [0, 50, 100, 50, 0]);
+        assert_eq!(c[&3].b, Some(255));
+
+        p.fan(ChannelKind::Pan, 0, 200, FanMode::Linear)?;
+        assert_eq!(p.contents()[&5].pan, Some(200));
+        assert!(p.fan(ChannelKind::Gobo, 0, 255, FanMode::Linear).is_err());
+        p.selected = BTreeSet::from([9]);
+        p.fan(ChannelKind::Intensity, 30, 90, FanMode::Linear)?;
+        assert_eq!(p.contents()[&9].intensity, Some(30));
+        Ok(())
+    }
+
     #[test]
     fn render_dimmer_intensity() -> anyhow::Result<()> {
         let mut show = Show::new("Test");
@@ -230,6 +740,69 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn selection_policy_retains_or_releases_deselected_values() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        for ft in default_fixture_types() {
+            show.patch.add_fixture_type(ft);
+        }
+        show.patch
+            .add_fixture(FixtureInstance::new(1, "DIM 1", "dimmer_1ch", 1, 1))?;
+        show.patch
+            .add_fixture(FixtureInstance::new(2, "DIM 2", "dimmer_1ch", 1, 2))?;
+
+        for (policy, kept) in [
+            (SelectionPolicy::Release, false),
+            (SelectionPolicy::Retain, true),
+            (SelectionPolicy::Prompt, true),
+        ] {
+            let mut p = Programmer::new();
+            p.policy = policy;
+            p.set_selection([1].into());
+            p.intensity = Some(200);
+            let pending = p.set_selection([2].into());
+            assert_eq!(pending.is_empty(), policy != SelectionPolicy::Prompt);
+
+            let nz = p.render(&show)?.nonzero();
+            assert!(nz.contains(&(1, 2, 200)));
+            assert_eq!(nz.contains(&(1, 1, 200)), kept, "{policy:?}");
+
+            p.release(&pending);
+            if policy == SelectionPolicy::Prompt {
+                assert!(!p.render(&show)?.nonzero().contains(&(1, 1, 200)));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn nudge_intensity_is_relative_per_fixture() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        for ft in default_fixture_types() {
+            show.patch.add_fixture_type(ft);
+        }
+        show.patch
+            .add_fixture(FixtureInstance::new(1, "DIM 1", "dimmer_1ch", 1, 1))?;
+        show.patch
+            .add_fixture(FixtureInstance::new(2, "DIM 2", "dimmer_1ch", 1, 2))?;
+
+        let mut output = LiveState::new();
+        output.set(1, 1, 100);
+        output.set(1, 2, 250);
+
+        let mut p = Programmer::new();
+        p.select_range(1, 2);
+        p.nudge_intensity(&show, &output, 10);
+        assert_eq!(p.render(&show)?.nonzero(), vec![(1, 1, 110), (1, 2, 255)]);
+
+        p.nudge_intensity(&show, &output, -20); // continues from its own levels
+        assert_eq!(p.render(&show)?.nonzero(), vec![(1, 1, 90), (1, 2, 235)]);
+
+        p.set_intensity_percent(100);
+        assert_eq!(p.render(&show)?.nonzero(), vec![(1, 1, 255), (1, 2, 255)]);
+        Ok(())
+    }
+
     #[test]
     fn apply_color_palette_sets_rgb() {
         let mut p = Programmer::new();
@@ -249,4 +822,59 @@ mod tests {
         assert_eq!(p.g, Some(2));
         assert_eq!(p.b, Some(3));
     }
+
+    #[test]
+    fn system_palettes_use_each_fixture_type() -> anyhow::Result<()> {
+        let show = crate::ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "D1", "dimmer_1ch", 1, 1)
+            .fixture(2, "PAR", "rgb_par_3ch", 1, 2)
+            .fixture(3, "MH", "moving_head_5ch", 1, 11)
+            .build()?;
+        let mut p = Programmer::new();
+        p.select_range(1, 2);
+
+        assert_eq!(p.apply_system_palette(&show, SystemPalette::Full), 1);
+        assert_eq!(p.apply_system_palette(&show, SystemPalette::White), 1);
+        assert_eq!(
+            p.render(&show)?.nonzero(),
+            vec![(1, 1, 255), (1, 2, 255), (1, 3, 255), (1, 4, 255)]
+        );
+        p.set_rgb(10, 20, 30);
+        assert_eq!(p.apply_system_palette(&show, SystemPalette::Home), 2);
+        assert_eq!(p.render(&show)?.get(1, 1), 0);
+        assert_eq!(p.render(&show)?.get(1, 3), 255);
+
+        // pan and tilt go to the type's defaults, fine bytes following
+        p.select_one(3);
+        p.edit_fixture(
+            3,
+            &crate::FixtureValues {
+                pan: Some(10),
+                tilt: Some(20),
+                ..Default::default()
+            },
+        );
+        assert_eq!(p.apply_system_palette(&show, SystemPalette::Home), 3);
+        let out = p.render(&show)?;
+        assert_eq!(
+            (11..=14).map(|a| out.get(1, a)).collect::<Vec<_>>(),
+            vec![128, 128, 128, 128]
+        );
+        // a new color, or clearing color, keeps the position
+        p.set_rgb(255, 0, 0);
+        p.clear_params(ParamFilter::COLOR);
+        assert_eq!(
+            (p.contents()[&3].pan, p.contents()[&3].tilt),
+            (Some(128), Some(128))
+        );
+
+        assert_eq!(
+            SystemPalette::from_name("Open White"),
+            Some(SystemPalette::White)
+        );
+        assert!(show.check_palette_name("OUT").is_err());
+        assert!(show.check_palette_name("Warm").is_ok());
+        Ok(())
+    }
 }
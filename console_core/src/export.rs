@@ -0,0 +1,328 @@
+//! Standalone object files: share a cue list, palettes or groups without the whole show.
+//!
+//! Groups and color palettes can also come from simple sets prepared outside
+//! the console: CSV rows of `name, ids...` or `name, r, g, b`, or a JSON
+//! object of name to ids or `[r, g, b]`.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use crate::hookup::parse_csv;
+use crate::{CueList, Meta, Palette, Show};
+
+/// What a simple set file holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetKind {
+    Groups,
+    Colors,
+}
+
+impl std::str::FromStr for SetKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "groups" => Ok(Self::Groups),
+            "colors" | "colours" => Ok(Self::Colors),
+            _ => anyhow::bail!("sets are groups or colors, not '{s}'"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShowExport {
+    CueList {
+        name: String,
+        cue_list: CueList,
+    },
+    Palettes {
+        palettes: BTreeMap<String, Palette>,
+    },
+    Groups {
+        groups: BTreeMap<String, BTreeSet<u32>>,
+
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        group_meta: BTreeMap<String, Meta>,
+    },
+}
+
+impl ShowExport {
+    pub fn save_json_file(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let json = serde_json::to_string_pretty(self).context("serialize export to json")?;
+        fs::write(path.as_ref(), json).context("write export json file")?;
+        Ok(())
+    }
+
+    pub fn load_json_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let text = fs::read_to_string(path.as_ref()).context("read export json file")?;
+        let export = serde_json::from_str::<ShowExport>(&text).context("parse export json")?;
+        Ok(export)
+    }
+
+    /// A set file: JSON if it ends in `.json`, CSV otherwise.
+    pub fn load_set_file(path: impl AsRef<Path>, kind: SetKind) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+        let is_json = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+        if is_json {
+            Self::parse_set_json(&text, kind)
+        } else {
+            Self::parse_set_csv(&text, kind)
+        }
+    }
+
+    /// CSV rows of `name, ids...` (a cell may hold several ids or a `5-8`
+    /// range) or `name, r, g, b`. A header row is skipped.
+    pub fn parse_set_csv(text: &str, kind: SetKind) -> anyhow::Result<Self> {
+        let mut groups = BTreeMap::new();
+        let mut colors = BTreeMap::new();
+        for (i, row) in parse_csv(text).into_iter().enumerate() {
+            let Some((name, values)) = row.split_first() else {
+                continue;
+            };
+            let parsed = match kind {
+                SetKind::Groups => parse_ids(values).map(|ids| {
+                    groups.insert(name.clone(), ids);
+                }),
+                SetKind::Colors => parse_rgb(values).map(|rgb| {
+                    colors.insert(name.clone(), Palette::from_rgb(rgb));
+                }),
+            };
+            match parsed {
+                Ok(()) if name.is_empty() => anyhow::bail!("row {}: no name", i + 1),
+                Ok(()) => {}
+                Err(_) if i == 0 => {} // header
+                Err(e) => return Err(e.context(format!("row {} ('{name}')", i + 1))),
+            }
+        }
+        Ok(match kind {
+            SetKind::Groups => ShowExport::Groups {
+                groups,
+                group_meta: BTreeMap::new(),
+            },
+            SetKind::Colors => ShowExport::Palettes { palettes: colors },
+        })
+    }
+
+    /// A JSON object of name to member ids or to `[r, g, b]`.
+    pub fn parse_set_json(text: &str, kind: SetKind) -> anyhow::Result<Self> {
+        Ok(match kind {
+            SetKind::Groups => ShowExport::Groups {
+                groups: serde_json::from_str(text).context("parse groups json")?,
+                group_meta: BTreeMap::new(),
+            },
+            SetKind::Colors => {
+                let colors: BTreeMap<String, [u8; 3]> =
+                    serde_json::from_str(text).context("parse colors json")?;
+                ShowExport::Palettes {
+                    palettes: colors
+                        .into_iter()
+                        .map(|(name, rgb)| (name, Palette::from_rgb(rgb)))
+                        .collect(),
+                }
+            }
+        })
+    }
+
+    /// Short description for status lines, e.g. "3 palette(s)".
+    pub fn summary(&self) -> String {
+        match self {
+            ShowExport::CueList { name, cue_list } => {
+                format!("cuelist '{name}' ({} cue(s))", cue_list.cues.len())
+            }
+            ShowExport::Palettes { palettes } => format!("{} palette(s)", palettes.len()),
+            ShowExport::Groups { groups, .. } => format!("{} group(s)", groups.len()),
+        }
+    }
+}
+
+impl Show {
+    pub fn export_cue_list(&self, name: &str) -> anyhow::Result<ShowExport> {
+        let cue_list = self
+            .cue_lists
+            .get(name)
+            .with_context(|| format!("unknown cuelist '{name}'"))?;
+        Ok(ShowExport::CueList {
+            name: name.to_string(),
+            cue_list: cue_list.clone(),
+        })
+    }
+
+    /// Export the named palettes, or all of them when `names` is empty.
+    pub fn export_palettes(&self, names: &[&str]) -> anyhow::Result<ShowExport> {
+        Ok(ShowExport::Palettes {
+            palettes: pick(&self.palettes, names, "palette")?,
+        })
+    }
+
+    /// Export the named groups, or all of them when `names` is empty.
+    pub fn export_groups(&self, names: &[&str]) -> anyhow::Result<ShowExport> {
+        let groups = pick(&self.groups, names, "group")?;
+        let group_meta = self
+            .group_meta
+            .iter()
+            .filter(|(k, _)| groups.contains_key(*k))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        Ok(ShowExport::Groups { groups, group_meta })
+    }
+
+    /// Merge an exported object into this show. Existing objects with the same
+    /// name are only overwritten with `replace`; otherwise nothing is imported.
+    pub fn import(&mut self, export: ShowExport, replace: bool) -> anyhow::Result<()> {
+        match export {
+            ShowExport::CueList { name, cue_list } => {
+                if !replace
+                    && self
+                        .cue_lists
+                        .get(&name)
+                        .is_some_and(|l| !l.cues.is_empty())
+                {
+                    anyhow::bail!("cuelist '{name}' already exists (use replace)");
+                }
+                self.cue_lists.insert(name, cue_list);
+            }
+            ShowExport::Palettes { palettes } => {
+                check_clashes(&self.palettes, &palettes, "palette", replace)?;
+                self.palettes.extend(palettes);
+            }
+            ShowExport::Groups { groups, group_meta } => {
+                check_clashes(&self.groups, &groups, "group", replace)?;
+                for name in groups.keys() {
+                    self.group_meta.remove(name);
+                }
+                self.groups.extend(groups);
+                self.group_meta.extend(group_meta);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_ids(cells: &[String]) -> anyhow::Result<BTreeSet<u32>> {
+    let mut ids = BTreeSet::new();
+    for word in cells.iter().flat_map(|c| c.split_whitespace()) {
+        let num = |s: &str| {
+            s.parse::<u32>()
+                .with_context(|| format!("'{word}' isn't a fixture id or range"))
+        };
+        match word.split_once('-') {
+            Some((a, b)) => {
+                let (a, b) = (num(a)?, num(b)?);
+                ids.extend(a.min(b)..=a.max(b));
+            }
+            None => {
+                ids.insert(num(word)?);
+            }
+        }
+    }
+    anyhow::ensure!(!ids.is_empty(), "no fixture ids");
+    Ok(ids)
+}
+
+fn parse_rgb(cells: &[String]) -> anyhow::Result<[u8; 3]> {
+    let [r, g, b] = cells else {
+        anyhow::bail!("expected r, g, b");
+    };
+    let value = |s: &String| {
+        s.parse::<u8>()
+            .with_context(|| format!("'{s}' isn't a 0-255 value"))
+    };
+    Ok([value(r)?, value(g)?, value(b)?])
+}
+
+fn pick<V: Clone>(
+    all: &BTreeMap<String, V>,
+    names: &[&str],
+    what: &str,
+) -> anyhow::Result<BTreeMap<String, V>> {
+    if names.is_empty() {
+        return Ok(all.clone());
+    }
+    names
+        .iter()
+        .map(|&n| {
+            let v = all
+                .get(n)
+                .with_context(|| format!("unknown {what} '{n}'"))?;
+            Ok((n.to_string(), v.clone()))
+        })
+        .collect()
+}
+
+fn check_clashes<V>(
+    existing: &BTreeMap<String, V>,
+    incoming: &BTreeMap<String, V>,
+    what: &str,
+    replace: bool,
+) -> anyhow::Result<()> {
+    if replace {
+        return Ok(());
+    }
+    let clashes: Vec<&str> = incoming
+        .keys()
+        .filter(|k| existing.contains_key(*k))
+        .map(String::as_str)
+        .collect();
+    if !clashes.is_empty() {
+        anyhow::bail!(
+            "{what}(s) already exist: {} (use replace)",
+            clashes.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_export_import_round_trip() -> anyhow::Result<()> {
+        let mut a = Show::new("A");
+        a.groups
+            .insert("front".into(), [1, 2, 3].into_iter().collect());
+        a.groups.insert("back".into(), [4].into_iter().collect());
+
+        let export = a.export_groups(&["front"])?;
+        let json = serde_json::to_string(&export)?;
+        let export: ShowExport = serde_json::from_str(&json)?;
+
+        let mut b = Show::new("B");
+        b.import(export.clone(), false)?;
+        assert_eq!(b.groups["front"], a.groups["front"]);
+        assert!(!b.groups.contains_key("back"));
+
+        assert!(b.import(export.clone(), false).is_err());
+        b.import(export, true)?;
+        Ok(())
+    }
+
+    #[test]
+    fn sets_import_from_csv_and_json() -> anyhow::Result<()> {
+        let csv = "Name,Members\nfront,1 2,5-7\n\"wash, all\",10\n";
+        let ShowExport::Groups { groups, .. } = ShowExport::parse_set_csv(csv, SetKind::Groups)?
+        else {
+            panic!("not groups");
+        };
+        assert_eq!(groups["front"], BTreeSet::from([1, 2, 5, 6, 7]));
+        assert_eq!(groups["wash, all"], BTreeSet::from([10]));
+        assert!(ShowExport::parse_set_csv("a,1\nb,x", SetKind::Groups).is_err());
+
+        let colors = ShowExport::parse_set_csv("Amber,255,191,0", SetKind::Colors)?;
+        let json = ShowExport::parse_set_json(r#"{"Amber": [255, 191, 0]}"#, SetKind::Colors)?;
+        assert_eq!(colors, json);
+        assert!(ShowExport::parse_set_csv("name,r,g,b\nRed,255,0", SetKind::Colors).is_err());
+
+        let mut show = Show::new("Test");
+        show.import(colors, false)?;
+        assert_eq!(show.palettes["Amber"].values.g, Some(191));
+        Ok(())
+    }
+}
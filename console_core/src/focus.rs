@@ -0,0 +1,143 @@
+//! Focus sessions: step through a set of fixtures one at a time. The
+//! fixture being focused fades up to the focus level while the one before
+//! fades down, so only one unit of the set is lit while it's pointed.
+
+use std::collections::BTreeMap;
+
+/// Focus level when not told otherwise (DMX).
+pub const DEFAULT_FOCUS_LEVEL: u8 = 255;
+/// Fade between fixtures when not told otherwise.
+pub const DEFAULT_FOCUS_FADE_MS: u32 = 1500;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Focus {
+    fixtures: Vec<u32>,
+    index: usize,
+    /// Intensity the focused fixture fades up to (DMX).
+    pub level: u8,
+    pub fade_ms: u32,
+    /// Fixture fading down and the level it started from.
+    previous: Option<(u32, u8)>,
+    /// Level the focused fixture started its fade from.
+    up_from: u8,
+    elapsed_ms: u32,
+}
+
+impl Focus {
+    /// Start on the first of `fixtures` (in order, duplicates dropped).
+    pub fn new(
+        fixtures: impl IntoIterator<Item = u32>,
+        level: u8,
+        fade_ms: u32,
+    ) -> anyhow::Result<Self> {
+        let mut list = Vec::new();
+        for fid in fixtures {
+            if !list.contains(&fid) {
+                list.push(fid);
+            }
+        }
+        anyhow::ensure!(!list.is_empty(), "nothing selected to focus");
+        Ok(Self {
+            fixtures: list,
+            index: 0,
+            level,
+            fade_ms,
+            previous: None,
+            up_from: 0,
+            elapsed_ms: 0,
+        })
+    }
+
+    /// The fixture being focused.
+    pub fn current(&self) -> u32 {
+        self.fixtures[self.index]
+    }
+
+    /// (1-based position, number of fixtures), e.g. for "3/12".
+    pub fn position(&self) -> (usize, usize) {
+        (self.index + 1, self.fixtures.len())
+    }
+
+    /// Step to the next fixture, wrapping at the end. Returns it.
+    pub fn step_next(&mut self) -> u32 {
+        self.step_to((self.index + 1) % self.fixtures.len())
+    }
+
+    /// Step to the previous fixture, wrapping at the start. Returns it.
+    pub fn step_prev(&mut self) -> u32 {
+        let len = self.fixtures.len();
+        self.step_to((self.index + len - 1) % len)
+    }
+
+    fn step_to(&mut self, index: usize) -> u32 {
+        let levels = self.levels();
+        let level_of = |fid| levels.get(&fid).copied().unwrap_or(0);
+        let leaving = self.current();
+        self.index = index;
+        let arriving = self.current();
+        if leaving != arriving {
+            // a quick step back picks the fixture up where its fade-down is
+            self.up_from = level_of(arriving);
+            self.previous = Some((leaving, level_of(leaving)));
+            self.elapsed_ms = 0;
+        }
+        arriving
+    }
+
+    pub fn tick(&mut self, dt_ms: u32) {
+        self.elapsed_ms = self.elapsed_ms.saturating_add(dt_ms);
+    }
+
+    /// True while the last step's fades are running.
+    pub fn is_fading(&self) -> bool {
+        self.elapsed_ms < self.fade_ms
+    }
+
+    /// Intensity for every fixture of the set: the focused one on its way
+    /// up, the one before on its way down, the rest out.
+    pub fn levels(&self) -> BTreeMap<u32, u8> {
+        let fade = |from: u8, to: u8| {
+            if !self.is_fading() {
+                return to;
+            }
+            let t = self.elapsed_ms as f32 / self.fade_ms as f32;
+            (from as f32 + (to as f32 - from as f32) * t).round() as u8
+        };
+        let mut levels: BTreeMap<u32, u8> = self.fixtures.iter().map(|&f| (f, 0)).collect();
+        if let Some((fid, from)) = self.previous {
+            levels.insert(fid, fade(from, 0));
+        }
+        levels.insert(self.current(), fade(self.up_from, self.level));
+        levels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stepping_fades_one_fixture_up_and_the_last_down() -> anyhow::Result<()> {
+        let mut focus = Focus::new([4, 2, 4, 9], 200, 1000)?;
+        assert_eq!(focus.position(), (1, 3));
+        focus.tick(500);
+        assert_eq!(focus.levels()[&4], 100);
+        focus.tick(500);
+        assert_eq!(focus.levels(), BTreeMap::from([(2, 0), (4, 200), (9, 0)]));
+
+        assert_eq!(focus.step_next(), 2);
+        focus.tick(250);
+        assert_eq!(focus.levels()[&4], 150);
+        assert_eq!(focus.levels()[&2], 50);
+
+        // straight back: 4 comes up from where it had got to
+        assert_eq!(focus.step_prev(), 4);
+        focus.tick(500);
+        assert_eq!(focus.levels()[&4], 175);
+        assert_eq!(focus.levels()[&2], 25);
+
+        assert_eq!(focus.step_prev(), 9);
+        assert!(Focus::new([], 255, 0).is_err());
+        Ok(())
+    }
+}
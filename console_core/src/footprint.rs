@@ -0,0 +1,123 @@
+//! Patch views: fixtures sorted by address or type, and per-universe
+//! footprints (occupied address ranges) that make gaps and collisions obvious.
+
+use std::collections::BTreeMap;
+
+use crate::{FixtureInstance, Patch};
+
+/// Addresses a fixture occupies in one universe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub fixture_id: u32,
+    pub first: u16,
+    pub last: u16,
+}
+
+impl Patch {
+    /// Fixtures sorted by universe, then address.
+    pub fn by_address(&self) -> Vec<&FixtureInstance> {
+        let mut out: Vec<_> = self.fixtures.values().collect();
+        out.sort_by_key(|f| (f.universe, f.address, f.fixture_id));
+        out
+    }
+
+    /// Fixtures sorted by fixture type, then id.
+    pub fn by_type(&self) -> Vec<&FixtureInstance> {
+        let mut out: Vec<_> = self.fixtures.values().collect();
+        out.sort_by(|a, b| (&a.fixture_type, a.fixture_id).cmp(&(&b.fixture_type, b.fixture_id)));
+        out
+    }
+
+    /// Occupied spans per universe, sorted by address.
+    pub fn footprints(&self) -> BTreeMap<u16, Vec<Span>> {
+        let mut out: BTreeMap<u16, Vec<Span>> = BTreeMap::new();
+        for f in self.by_address() {
+            if let Some((u, first, last)) = self.footprint(f) {
+                out.entry(u).or_default().push(Span {
+                    fixture_id: f.fixture_id,
+                    first,
+                    last,
+                });
+            }
+        }
+        out
+    }
+
+    /// Pairs of fixtures sharing at least one address, as (universe, a, b).
+    pub fn collisions(&self) -> Vec<(u16, u32, u32)> {
+        let mut out = Vec::new();
+        for (&u, spans) in &self.footprints() {
+            for (i, a) in spans.iter().enumerate() {
+                for b in &spans[i + 1..] {
+                    if b.first > a.last {
+                        break; // sorted by first address
+                    }
+                    out.push((u, a.fixture_id, b.fixture_id));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// One-line text bar of a universe, `width` cells for 512 addresses:
+/// `.` free, `#` occupied, `X` collision.
+pub fn footprint_bar(spans: &[Span], width: usize) -> String {
+    let width = width.max(1);
+    let mut use_count = vec![0u8; 513];
+    for s in spans {
+        for a in s.first..=s.last.min(512) {
+            use_count[a as usize] = use_count[a as usize].saturating_add(1);
+        }
+    }
+    (0..width)
+        .map(|cell| {
+            let from = 1 + cell * 512 / width;
+            let to = ((cell + 1) * 512 / width).max(from);
+            let max = use_count[from..=to].iter().copied().max().unwrap_or(0);
+            match max {
+                0 => '.',
+                1 => '#',
+                _ => 'X',
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Show, default_fixture_types};
+
+    #[test]
+    fn footprints_sort_and_find_collisions() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        for ft in default_fixture_types() {
+            show.patch.add_fixture_type(ft);
+        }
+        let patch = &mut show.patch;
+        patch.add_fixture(FixtureInstance::new(1, "PAR", "rgb_par_3ch", 1, 10))?;
+        patch.add_fixture(FixtureInstance::new(2, "DIM", "dimmer_1ch", 1, 1))?;
+        patch.add_fixture(FixtureInstance::new(3, "DIM", "dimmer_1ch", 1, 12))?;
+        patch.add_fixture(FixtureInstance::new(4, "DIM", "dimmer_1ch", 2, 12))?;
+
+        let ids = |v: Vec<&FixtureInstance>| v.iter().map(|f| f.fixture_id).collect::<Vec<_>>();
+        assert_eq!(ids(patch.by_address()), vec![2, 1, 3, 4]);
+        assert_eq!(ids(patch.by_type()), vec![2, 3, 4, 1]);
+
+        let fp = patch.footprints();
+        assert_eq!(
+            fp[&1][1],
+            Span {
+                fixture_id: 1,
+                first: 10,
+                last: 12
+            }
+        );
+        assert_eq!(patch.collisions(), vec![(1, 1, 3)]);
+
+        let bar = footprint_bar(&fp[&1], 512);
+        assert_eq!(&bar[..13], "#........##X.");
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+//! Frame-accurate output log for debugging fades: while enabled, every frame
+//! the output thread sends is compared with the one before and its changed
+//! channels are kept, timestamped, in a ring covering the last few seconds.
+//! Dumping a window shows exactly where a fade stepped or jumped.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::{AddressFormat, LiveState};
+
+/// How much history `enable` keeps when not told otherwise.
+pub const DEFAULT_KEEP_MS: u64 = 30_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelChange {
+    pub universe: u16,
+    pub address: u16,
+    pub from: u8,
+    pub to: u8,
+}
+
+/// One sent frame that changed something.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggedFrame {
+    /// Output clock time the frame was sent.
+    pub at_ms: u64,
+    pub changes: Vec<ChannelChange>,
+}
+
+#[derive(Debug, Default)]
+struct Ring {
+    /// None while disabled.
+    keep_ms: Option<u64>,
+    frames: VecDeque<LoggedFrame>,
+    last: LiveState,
+    /// Time of the newest frame sent, changed or not.
+    now_ms: u64,
+}
+
+/// Shared handle: the output thread records, frontends enable and dump.
+#[derive(Debug, Clone, Default)]
+pub struct FrameLog {
+    ring: Arc<Mutex<Ring>>,
+}
+
+impl FrameLog {
+    /// Start logging, keeping `keep_ms` of history. History so far is kept.
+    pub fn enable(&self, keep_ms: u64) {
+        self.lock().keep_ms = Some(keep_ms.max(1));
+    }
+
+    /// Stop logging and drop the history.
+    pub fn disable(&self) {
+        *self.lock() = Ring::default();
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.lock().keep_ms.is_some()
+    }
+
+    /// Compare `frame` with the previous one and log what changed.
+    pub fn record(&self, at_ms: u64, frame: &LiveState) {
+        let mut ring = self.lock();
+        let Some(keep_ms) = ring.keep_ms else {
+            return;
+        };
+        let changes = diff(&ring.last, frame);
+        if !changes.is_empty() {
+            ring.frames.push_back(LoggedFrame { at_ms, changes });
+        }
+        while ring
+            .frames
+            .front()
+            .is_some_and(|f| at_ms.saturating_sub(f.at_ms) > keep_ms)
+        {
+            ring.frames.pop_front();
+        }
+        ring.last = frame.clone();
+        ring.now_ms = at_ms;
+    }
+
+    /// Logged frames from the last `window_ms`, oldest first.
+    pub fn dump(&self, window_ms: u64) -> Vec<LoggedFrame> {
+        let ring = self.lock();
+        let from = ring.now_ms.saturating_sub(window_ms);
+        ring.frames
+            .iter()
+            .filter(|f| f.at_ms >= from)
+            .cloned()
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Ring> {
+        // a panic elsewhere mustn't take the diagnostics with it
+        self.ring.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// One line per frame: time from the first frame, gap since the previous
+/// one (a long gap before a big change is a step) and the changed channels.
+pub fn dump_lines(frames: &[LoggedFrame], format: AddressFormat) -> Vec<String> {
+    let start = frames.first().map_or(0, |f| f.at_ms);
+    let mut prev = start;
+    frames
+        .iter()
+        .map(|f| {
+            let changes: Vec<String> = f
+                .changes
+                .iter()
+                .map(|c| {
+                    let addr = format.format(c.universe, c.address);
+                    format!("{addr} {}->{}", c.from, c.to)
+                })
+                .collect();
+            let line = format!(
+                "{:>8.3}s (+{:>4}ms) {}",
+                (f.at_ms - start) as f64 / 1000.0,
+                f.at_ms - prev,
+                changes.join(", ")
+            );
+            prev = f.at_ms;
+            line
+        })
+        .collect()
+}
+
+fn diff(old: &LiveState, new: &LiveState) -> Vec<ChannelChange> {
+    let mut out = Vec::new();
+    for (&universe, addrs) in &new.universes {
+        for (&address, &to) in addrs {
+            let from = old.get(universe, address);
+            if from != to {
+                out.push(ChannelChange {
+                    universe,
+                    address,
+                    from,
+                    to,
+                });
+            }
+        }
+    }
+    // channels that dropped out of the frame count as going to zero
+    for (&universe, addrs) in &old.universes {
+        for (&address, &from) in addrs {
+            let gone = new
+                .universes
+                .get(&universe)
+                .is_none_or(|a| !a.contains_key(&address));
+            if gone && from != 0 {
+                out.push(ChannelChange {
+                    universe,
+                    address,
+                    from,
+                    to: 0,
+                });
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logs_changed_channels_within_the_window() {
+        let log = FrameLog::default();
+        let mut frame = LiveState::new();
+        frame.set(1, 1, 10);
+        log.record(0, &frame);
+        assert!(log.dump(u64::MAX).is_empty());
+
+        log.enable(1000);
+        for (t, v) in [(0, 10), (25, 20), (50, 20), (75, 60)] {
+            frame.set(1, 1, v);
+            log.record(t, &frame);
+        }
+        let frames = log.dump(5000);
+        let times: Vec<u64> = frames.iter().map(|f| f.at_ms).collect();
+        assert_eq!(times, [0, 25, 75]);
+        assert_eq!(
+            frames[2].changes,
+            [ChannelChange {
+                universe: 1,
+                address: 1,
+                from: 20,
+                to: 60
+            }]
+        );
+        assert_eq!(log.dump(30).len(), 1);
+        assert_eq!(
+            dump_lines(&frames, AddressFormat::UniverseAddress)[2],
+            "   0.075s (+  50ms) U1:001 20->60"
+        );
+
+        // older than keep_ms falls out of the ring
+        frame.set(1, 1, 0);
+        log.record(1050, &frame);
+        assert_eq!(log.dump(u64::MAX).len(), 2);
+
+        log.disable();
+        assert!(!log.is_enabled() && log.dump(u64::MAX).is_empty());
+    }
+}
@@ -0,0 +1,285 @@
+//! USB HID jog wheels, encoders and keypads (Shuttle, X-keys...): buttons
+//! and keys take the same actions as MIDI controls (go, back, cmd...) and
+//! relative encoders drive the programmer wheels.
+//!
+//! Reports are read from a hidraw device (`/dev/hidraw2`) on a background
+//! thread. Raw devices report buttons as bits and encoders as signed step
+//! counts; keypads in keyboard mode send boot keyboard reports. The map is
+//! saved next to the show as `<show>.hid.json`.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use crate::MidiAction;
+
+/// How the device's reports are laid out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HidFormat {
+    /// Vendor reports: buttons are bits, encoders whole bytes.
+    #[default]
+    Raw,
+    /// Boot keyboard reports: up to six key usage codes from byte 2.
+    Keyboard,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HidControl {
+    /// Bit `bit` (0-7) of report byte `byte`: a button.
+    Bit { byte: usize, bit: u8 },
+    /// Report byte `byte` as a signed step count: a jog wheel or encoder.
+    Relative { byte: usize },
+    /// Keyboard usage code, e.g. 0x1e for "1".
+    Key { usage: u8 },
+}
+
+impl std::fmt::Display for HidControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bit { byte, bit } => write!(f, "byte {byte} bit {bit}"),
+            Self::Relative { byte } => write!(f, "byte {byte} (relative)"),
+            Self::Key { usage } => write!(f, "key {usage:#04x}"),
+        }
+    }
+}
+
+/// A control that changed: 1/0 for press/release, steps for encoders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HidEvent {
+    pub control: HidControl,
+    pub value: i16,
+}
+
+impl HidEvent {
+    /// The value as `Runtime::midi_action` takes it: buttons 127/0,
+    /// encoder steps 1-63 up and 65-127 down.
+    pub fn midi_value(&self) -> u8 {
+        match self.control {
+            HidControl::Relative { .. } => (self.value.clamp(-63, 63) as u8) & 0x7f,
+            _ if self.value > 0 => 127,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HidBinding {
+    pub control: HidControl,
+    pub action: MidiAction,
+}
+
+/// The device map saved next to the show.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HidMap {
+    /// Device opened automatically, e.g. "/dev/hidraw2".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<PathBuf>,
+    #[serde(default)]
+    pub format: HidFormat,
+    #[serde(default)]
+    pub bindings: Vec<HidBinding>,
+}
+
+impl HidMap {
+    /// `<show>.hid.json` next to the show file.
+    pub fn path_for(show_path: &Path) -> PathBuf {
+        let mut p = show_path.as_os_str().to_owned();
+        p.push(".hid.json");
+        PathBuf::from(p)
+    }
+
+    /// The map at `path`, or an empty one if there's no file yet.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => {
+                serde_json::from_str(&text).with_context(|| format!("parse {}", path.display()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("read {}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let text = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, text).with_context(|| format!("write {}", path.display()))
+    }
+
+    /// Bind `control`, replacing what it did before. An encoder action
+    /// learned from a button bit binds the whole byte as relative.
+    pub fn bind(&mut self, control: HidControl, action: MidiAction) {
+        let control = match (control, &action) {
+            (HidControl::Bit { byte, .. }, MidiAction::Encoder(_)) => HidControl::Relative { byte },
+            _ => control,
+        };
+        self.bindings.retain(|b| b.control != control);
+        self.bindings.push(HidBinding { control, action });
+    }
+
+    pub fn action_for(&self, control: HidControl) -> Option<&MidiAction> {
+        self.bindings
+            .iter()
+            .find(|b| b.control == control)
+            .map(|b| &b.action)
+    }
+
+    fn relative_bytes(&self) -> BTreeSet<usize> {
+        self.bindings
+            .iter()
+            .filter_map(|b| match b.control {
+                HidControl::Relative { byte } => Some(byte),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Turns successive reports into control changes.
+#[derive(Debug, Clone, Default)]
+pub struct HidDecoder {
+    last: Vec<u8>,
+}
+
+impl HidDecoder {
+    pub fn decode(&mut self, report: &[u8], map: &HidMap) -> Vec<HidEvent> {
+        let mut events = Vec::new();
+        match map.format {
+            HidFormat::Keyboard => {
+                // 0 = no key, 1 = too many keys down
+                let keys = |r: &[u8]| -> BTreeSet<u8> {
+                    r.iter().skip(2).copied().filter(|&k| k > 1).collect()
+                };
+                let (old, new) = (keys(&self.last), keys(report));
+                for &usage in new.difference(&old) {
+                    events.push(HidEvent {
+                        control: HidControl::Key { usage },
+                        value: 1,
+                    });
+                }
+                for &usage in old.difference(&new) {
+                    events.push(HidEvent {
+                        control: HidControl::Key { usage },
+                        value: 0,
+                    });
+                }
+            }
+            HidFormat::Raw => {
+                let relative = map.relative_bytes();
+                for (byte, &new) in report.iter().enumerate() {
+                    if relative.contains(&byte) {
+                        if new != 0 {
+                            events.push(HidEvent {
+                                control: HidControl::Relative { byte },
+                                value: new as i8 as i16,
+                            });
+                        }
+                        continue;
+                    }
+                    let old = self.last.get(byte).copied().unwrap_or(0);
+                    for bit in 0..8 {
+                        if (old ^ new) & (1 << bit) != 0 {
+                            events.push(HidEvent {
+                                control: HidControl::Bit { byte, bit },
+                                value: ((new >> bit) & 1) as i16,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        self.last = report.to_vec();
+        events
+    }
+}
+
+/// Reads a hidraw device on a background thread, one report per read.
+#[derive(Debug)]
+pub struct HidInput {
+    rx: mpsc::Receiver<Vec<u8>>,
+}
+
+impl HidInput {
+    pub fn open(device: &Path) -> anyhow::Result<Self> {
+        let mut file =
+            std::fs::File::open(device).with_context(|| format!("open {}", device.display()))?;
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 64];
+            // ends when the device goes away or the input is dropped
+            while let Ok(n @ 1..) = file.read(&mut buf) {
+                if tx.send(buf[..n].to_vec()).is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(Self { rx })
+    }
+
+    /// Reports received since the last call.
+    pub fn poll(&self) -> Vec<Vec<u8>> {
+        self.rx.try_iter().collect()
+    }
+
+    /// Wait up to `timeout` for the next report (learn mode).
+    pub fn next(&self, timeout: std::time::Duration) -> Option<Vec<u8>> {
+        self.rx.recv_timeout(timeout).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::midi::EncoderParam;
+    use crate::{Runtime, Show};
+
+    #[test]
+    fn reports_decode_to_bound_actions() -> anyhow::Result<()> {
+        let mut map = HidMap::default();
+        let mut dec = HidDecoder::default();
+        let events = dec.decode(&[0, 0b0000_0100, 0], &map);
+        assert_eq!(
+            events,
+            [HidEvent {
+                control: HidControl::Bit { byte: 1, bit: 2 },
+                value: 1
+            }]
+        );
+        map.bind(events[0].control, "go a".parse()?);
+        // learned from a bit, bound as a relative byte
+        map.bind(
+            HidControl::Bit { byte: 2, bit: 0 },
+            MidiAction::Encoder(EncoderParam::Red),
+        );
+
+        let events = dec.decode(&[0, 0, 0xfe], &map); // release, jog -2
+        assert_eq!(events[0].value, 0);
+        assert_eq!(events[1].control, HidControl::Relative { byte: 2 });
+        assert_eq!(events[1].midi_value(), 126);
+
+        let mut rt = Runtime::new(Show::new("Test"));
+        rt.programmer.r = Some(10);
+        let action = map.action_for(events[1].control).unwrap();
+        rt.midi_action(action, events[1].midi_value())?;
+        assert_eq!(rt.programmer.r, Some(8));
+
+        map.format = HidFormat::Keyboard;
+        let mut dec = HidDecoder::default();
+        assert_eq!(dec.decode(&[0, 0, 0x1e, 0x1f, 0, 0, 0, 0], &map).len(), 2);
+        let up = dec.decode(&[0, 0, 0x1f, 0, 0, 0, 0, 0], &map);
+        assert_eq!(
+            up,
+            [HidEvent {
+                control: HidControl::Key { usage: 0x1e },
+                value: 0
+            }]
+        );
+
+        let json = serde_json::to_string(&map)?;
+        assert_eq!(serde_json::from_str::<HidMap>(&json)?, map);
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+//! Channel hookup import: turn a designer's CSV (channel, fixture type,
+//! universe, address, position) into patch entries, with a validation
+//! preview before anything is patched.
+
+use crate::{FixtureInstance, Show, parse_address};
+
+/// Split CSV text into rows of trimmed cells. Handles quoted cells with
+/// embedded commas and `""` escapes; blank lines are skipped.
+pub fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut cells = Vec::new();
+        let mut cell = String::new();
+        let mut quoted = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    cell.push('"');
+                    chars.next();
+                }
+                '"' => quoted = !quoted,
+                ',' if !quoted => cells.push(std::mem::take(&mut cell).trim().to_string()),
+                _ => cell.push(c),
+            }
+        }
+        cells.push(cell.trim().to_string());
+        rows.push(cells);
+    }
+    rows
+}
+
+/// Which CSV column holds what (0-based). Without a universe column the
+/// address column may hold a full address (`2.1`, `U2:001`, `513`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ColumnMap {
+    pub channel: Option<usize>,
+    pub fixture_type: Option<usize>,
+    pub universe: Option<usize>,
+    pub address: Option<usize>,
+    pub position: Option<usize>,
+}
+
+impl ColumnMap {
+    /// Guess the mapping from header names ("Channel", "Type", "Univ", "DMX"...).
+    pub fn guess(header: &[String]) -> Self {
+        let find = |names: &[&str]| {
+            header.iter().position(|h| {
+                let h = h.to_lowercase();
+                names.iter().any(|n| h.contains(n))
+            })
+        };
+        Self {
+            channel: find(&["chan", "fixture id", "id"]),
+            fixture_type: find(&["type", "model", "instrument"]),
+            universe: find(&["univ"]),
+            address: find(&["addr", "dmx"]),
+            position: find(&["pos", "location"]),
+        }
+    }
+}
+
+/// One data row after validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookupRow {
+    /// 1-based line in the CSV.
+    pub line: usize,
+    /// The fixture to patch, when the row could be read.
+    pub fixture: Option<FixtureInstance>,
+    /// Why the row can't be patched; empty = ok.
+    pub problems: Vec<String>,
+}
+
+impl HookupRow {
+    pub fn is_ok(&self) -> bool {
+        self.fixture.is_some() && self.problems.is_empty()
+    }
+}
+
+/// Validate the rows against the show's patch (and each other) without
+/// changing anything.
+pub fn preview(
+    show: &Show,
+    rows: &[Vec<String>],
+    map: &ColumnMap,
+    has_header: bool,
+) -> Vec<HookupRow> {
+    let skip = usize::from(has_header);
+    let mut taken: Vec<(u32, u16, u16, u16)> = show
+        .patch
+        .fixtures
+        .values()
+        .filter_map(|f| {
+            let (u, first, last) = show.patch.footprint(f)?;
+            Some((f.fixture_id, u, first, last))
+        })
+        .collect();
+
+    let mut out = Vec::new();
+    for (i, row) in rows.iter().enumerate().skip(skip) {
+        let cell = |col: Option<usize>| col.and_then(|c| row.get(c)).map(String::as_str);
+        let mut problems = Vec::new();
+
+        let channel = match cell(map.channel).map(str::parse::<u32>) {
+            Some(Ok(id)) => Some(id),
+            Some(Err(_)) => {
+                problems.push(format!(
+                    "channel '{}' is not a number",
+                    cell(map.channel).unwrap()
+                ));
+                None
+            }
+            None => {
+                problems.push("no channel".to_string());
+                None
+            }
+        };
+
+        let fixture_type = cell(map.fixture_type).and_then(|t| {
+            show.patch
+                .fixture_types
+                .keys()
+                .find(|k| k.eq_ignore_ascii_case(t))
+                .cloned()
+        });
+        if fixture_type.is_none() {
+            problems.push(format!(
+                "unknown fixture type '{}'",
+                cell(map.fixture_type).unwrap_or("")
+            ));
+        }
+
+        let address = match (cell(map.universe), cell(map.address)) {
+            (Some(u), Some(a)) => parse_address(&format!("{u}.{a}")),
+            (None, Some(a)) => parse_address(a),
+            _ => Err(anyhow::anyhow!("no address")),
+        };
+        let address = address.map_err(|e| problems.push(e.to_string())).ok();
+
+        let fixture = match (channel, fixture_type, address) {
+            (Some(id), Some(ft), Some((u, a))) => {
+                let position = cell(map.position).filter(|p| !p.is_empty());
+                let name = match position {
+                    Some(pos) => format!("{pos} {id}"),
+                    None => format!("{ft} {id}"),
+                };
+                let mut f = FixtureInstance::new(id, name, ft, u, a);
+                // the position column doubles as the area for automatic groups
+                f.location.area = position.unwrap_or_default().to_string();
+                Some(f)
+            }
+            _ => None,
+        };
+
+        if let Some(f) = &fixture {
+            if taken.iter().any(|t| t.0 == f.fixture_id) {
+                problems.push(format!("channel {} is already patched", f.fixture_id));
+            }
+            if let Some((u, first, last)) = show.patch.footprint(f) {
+                if last > 512 {
+                    problems.push(format!("runs past address 512 (ends at {last})"));
+                }
+                for t in taken
+                    .iter()
+                    .filter(|t| t.1 == u && t.2 <= last && first <= t.3)
+                {
+                    problems.push(format!("overlaps channel {} at {u}.{}", t.0, t.2));
+                }
+                if problems.is_empty() {
+                    taken.push((f.fixture_id, u, first, last));
+                }
+            }
+        }
+
+        out.push(HookupRow {
+            line: i + 1,
+            fixture,
+            problems,
+        });
+    }
+    out
+}
+
+/// Patch every row that passed validation. Returns how many were added.
+pub fn apply(show: &mut Show, rows: &[HookupRow]) -> anyhow::Result<usize> {
+    let mut added = 0;
+    for f in rows
+        .iter()
+        .filter(|r| r.is_ok())
+        .filter_map(|r| r.fixture.clone())
+    {
+        show.patch.add_fixture(f)?;
+        added += 1;
+    }
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::default_fixture_types;
+
+    #[test]
+    fn hookup_preview_flags_problems_and_applies_good_rows() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        for ft in default_fixture_types() {
+            show.patch.add_fixture_type(ft);
+        }
+        show.patch
+            .add_fixture(FixtureInstance::new(1, "PAR 1", "rgb_par_3ch", 1, 1))?;
+
+        let csv = "Channel,Type,Universe,Address,Position\n\
+                   10,RGB_PAR_3CH,1,10,\"FOH, left\"\n\
+                   11,dimmer_1ch,1,2,LX1\n\
+                   12,mystery,1,40,LX1\n\
+                   13,dimmer_1ch,1,11,LX1\n";
+        let rows = parse_csv(csv);
+        let map = ColumnMap::guess(&rows[0]);
+        assert_eq!(map.position, Some(4));
+
+        let preview = preview(&show, &rows, &map, true);
+        assert!(preview[0].is_ok());
+        assert_eq!(preview[0].fixture.as_ref().unwrap().name, "FOH, left 10");
+        assert!(preview[1].problems[0].contains("overlaps channel 1"));
+        assert!(preview[2].problems[0].contains("unknown fixture type"));
+        assert!(preview[3].problems[0].contains("overlaps channel 10"));
+
+        assert_eq!(apply(&mut show, &preview)?, 1);
+        assert_eq!(show.patch.fixtures[&10].address, 10);
+        Ok(())
+    }
+}
@@ -5,25 +5,109 @@ use std::collections::BTreeSet;
 use std::fs;
 use std::path::Path;
 
+pub mod address;
+pub mod agenda;
+pub mod alias;
+pub mod archive;
+pub mod autosave;
+pub mod balance;
+pub mod build_info;
+pub mod builder;
+pub mod builtin;
+pub mod clock;
+pub mod cmdline;
+pub mod commands;
 pub mod cues;
+pub mod dmx_input;
+pub mod dmx_output;
 pub mod engine;
+pub mod export;
+pub mod focus;
+pub mod footprint;
+pub mod framelog;
+pub mod hid;
+pub mod hookup;
+pub mod library;
+pub mod limits;
+pub mod lint;
+pub mod lock;
+pub mod masters;
+pub mod meta;
+pub mod midi;
+pub mod modifier;
+pub mod oplog;
+pub mod output;
 pub mod palette;
 pub mod playback;
+pub mod plot;
+pub mod rdm;
+pub mod rigcheck;
+pub mod routing;
 mod runtime;
+pub mod scenario;
+pub mod search;
+pub mod selection;
+pub mod session;
+pub mod stats;
+pub mod store;
+pub mod timecode;
+pub mod usb_dmx;
+pub mod watch;
 
-pub use cues::{Cue, CueList, FixtureValues};
-pub use engine::{LiveState, Programmer};
-pub use palette::{Palette, PaletteKind, PaletteValues};
-pub use playback::{Playback, PlaybackMode};
-pub use runtime::Runtime;
+pub use address::{AddressFormat, parse_address};
+pub use agenda::{AgendaItem, AgendaSource, Countdown, CueWarner};
+pub use archive::ShowVersion;
+pub use autosave::{Autosave, SaveDone, SavePolicy};
+pub use balance::{BalanceCommand, IntensityBalance};
+pub use build_info::{BuildInfo, WrittenBy, build_info};
+pub use builder::{CueBuilder, ShowBuilder};
+pub use builtin::default_fixture_types;
+pub use clock::{Clock, MonotonicClock, SimClock};
+pub use cmdline::{LineStatus, ProgrammerLine};
+pub use commands::{Command, CommandContext, CommandResult, QuickPool, QuickRecord, RecordMode};
+pub use cues::{Cue, CueList, CueWarning, FixtureValues, TimeEdit};
+pub use dmx_input::{DmxInput, InputMerge, MergeMode, SacnReceiver, WingFader, WingTarget};
+pub use dmx_output::{ArtNetConfig, ArtNetOutput, SacnConfig, SacnOutput};
+pub use engine::{FanMode, LiveState, Programmer, SelectionPolicy};
+pub use export::{SetKind, ShowExport};
+pub use focus::Focus;
+pub use framelog::{ChannelChange, FrameLog, LoggedFrame};
+pub use hid::{HidControl, HidDecoder, HidFormat, HidInput, HidMap};
+pub use library::FixtureTypeRef;
+pub use limits::{Limit, MotionLimits};
+pub use lint::{Finding, Severity};
+pub use lock::CueLock;
+pub use masters::MasterPage;
+pub use meta::Meta;
+pub use midi::{MidiAction, MidiControl, MidiInput, MidiMap};
+pub use modifier::{Modifiers, OutputModifier};
+pub use oplog::LogEntry;
+pub use output::{OutputConfig, OutputDriver, OutputEvent, OutputThread, ShutdownLook};
+pub use palette::{Palette, PaletteKind, PaletteRefs, PaletteValues, SystemPalette};
+pub use playback::{
+    CueSummary, GoGuard, GoOutcome, MAX_RATE, MIN_RATE, ParamFilter, Playback, PlaybackConfig,
+    PlaybackMode, PlaybackStatus,
+};
+pub use rdm::{ChannelCheckReport, CheckStatus, FixtureCheck, RdmResponder};
+pub use routing::{Destination, DestinationKind, OutputRouting, RoutedOutput};
+pub use runtime::{Playbacks, Runtime};
+pub use scenario::{Scenario, ScenarioReport};
+pub use search::{ObjectRef, SearchHit};
+pub use selection::Location;
+pub use session::{CommandClass, Role};
+pub use stats::ShowStats;
+pub use store::{ShowStore, Snapshot};
+pub use timecode::{EventList, Timecode, TimedEvent};
+pub use usb_dmx::{UsbDmxDevice, UsbDmxOutput, UsbWidget, usb_dmx_devices};
+pub use watch::{MergeOutcome, ShowWatcher, merge_reload};
 
 pub fn version() -> &'static str {
-    "0.1.0"
+    build_info().version
 }
 
 /// A show is the top-level document we save/load.
 /// For now it only contains a Patch.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Show {
     pub name: String,
     pub patch: Patch,
@@ -36,6 +120,58 @@ pub struct Show {
 
     #[serde(default)]
     pub groups: BTreeMap<String, BTreeSet<u32>>,
+
+    /// Metadata for groups, keyed like `groups`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub group_meta: BTreeMap<String, Meta>,
+
+    /// How DMX addresses are displayed (U2:001 vs 513).
+    #[serde(default, skip_serializing_if = "AddressFormat::is_default")]
+    pub address_format: AddressFormat,
+
+    /// Commands fired at timecode frames, independent of cue lists.
+    #[serde(default, skip_serializing_if = "EventList::is_empty")]
+    pub events: EventList,
+
+    /// User command aliases (name -> command), see `alias`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+
+    /// Saved playback settings, keyed by playback name ("a", "b").
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub playbacks: BTreeMap<String, PlaybackConfig>,
+
+    /// What happens to programmer values of fixtures leaving the selection.
+    #[serde(default, skip_serializing_if = "SelectionPolicy::is_default")]
+    pub selection_policy: SelectionPolicy,
+
+    /// When edits are written to disk, see `autosave`.
+    #[serde(default, skip_serializing_if = "SavePolicy::is_default")]
+    pub save_policy: SavePolicy,
+
+    /// Group master page and the panic house-light group.
+    #[serde(default, skip_serializing_if = "MasterPage::is_empty")]
+    pub masters: MasterPage,
+
+    /// Which universes go to which output destinations.
+    #[serde(default, skip_serializing_if = "OutputRouting::is_default")]
+    pub outputs: OutputRouting,
+
+    /// Operator log shared by all clients of the show, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub log: Vec<LogEntry>,
+
+    /// sACN source name and per-universe priorities.
+    #[serde(default, skip_serializing_if = "SacnConfig::is_default")]
+    pub sacn: SacnConfig,
+
+    /// sACN input merged into output universes, keyed by output universe.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dmx_input: BTreeMap<u16, InputMerge>,
+
+    /// Input slots used as faders (see `WingFader`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dmx_wing: Vec<WingFader>,
 }
 
 impl Show {
@@ -48,37 +184,194 @@ impl Show {
             patch: Patch::default(),
             palettes: BTreeMap::new(),
             groups: BTreeMap::new(),
+            group_meta: BTreeMap::new(),
+            address_format: AddressFormat::default(),
+            events: EventList::default(),
+            aliases: BTreeMap::new(),
+            playbacks: BTreeMap::new(),
+            selection_policy: SelectionPolicy::default(),
+            save_policy: SavePolicy::default(),
+            masters: MasterPage::default(),
+            outputs: OutputRouting::default(),
+            sacn: SacnConfig::default(),
+            dmx_input: BTreeMap::new(),
+            dmx_wing: Vec::new(),
+            log: Vec::new(),
             cue_lists,
         }
     }
 
+    /// Add an empty cue list.
+    pub fn add_cue_list(&mut self, name: &str) -> anyhow::Result<()> {
+        anyhow::ensure!(!name.is_empty(), "cuelist name is empty");
+        anyhow::ensure!(
+            !self.cue_lists.contains_key(name),
+            "cuelist '{name}' already exists"
+        );
+        self.cue_lists.insert(name.to_string(), CueList::ensure());
+        Ok(())
+    }
+
+    /// Rename a cue list, updating playback settings that point at it.
+    /// "main" can't be renamed: it's the list playbacks fall back to.
+    pub fn rename_cue_list(&mut self, from: &str, to: &str) -> anyhow::Result<()> {
+        anyhow::ensure!(from != "main", "cuelist 'main' can't be renamed");
+        anyhow::ensure!(!to.is_empty(), "cuelist name is empty");
+        anyhow::ensure!(
+            !self.cue_lists.contains_key(to),
+            "cuelist '{to}' already exists"
+        );
+        let list = self
+            .cue_lists
+            .remove(from)
+            .with_context(|| format!("unknown cuelist '{from}'"))?;
+        self.cue_lists.insert(to.to_string(), list);
+        for cfg in self.playbacks.values_mut() {
+            if cfg.cuelist.as_deref() == Some(from) {
+                cfg.cuelist = Some(to.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete a cue list. "main" and lists a playback is set to run are kept.
+    pub fn delete_cue_list(&mut self, name: &str) -> anyhow::Result<()> {
+        anyhow::ensure!(name != "main", "cuelist 'main' can't be deleted");
+        if let Some((pb, _)) = self
+            .playbacks
+            .iter()
+            .find(|(_, c)| c.cuelist.as_deref() == Some(name))
+        {
+            anyhow::bail!("cuelist '{name}' is in use by playback {pb}");
+        }
+        self.cue_lists
+            .remove(name)
+            .with_context(|| format!("unknown cuelist '{name}'"))?;
+        Ok(())
+    }
+
+    /// Copy cues `first..=last` from one cue list into another (created if missing).
+    /// Copied cues are renumbered so `first` lands on `at`, keeping the gaps between them.
+    /// With `flatten`, each copy holds the full tracked look of its source cue.
+    ///
+    /// Returns the new cue numbers.
+    pub fn copy_cues(
+        &mut self,
+        from: &str,
+        first: u32,
+        last: u32,
+        to: &str,
+        at: u32,
+        flatten: bool,
+    ) -> anyhow::Result<Vec<u32>> {
+        let src = self
+            .cue_lists
+            .get(from)
+            .with_context(|| format!("unknown cuelist '{from}'"))?;
+
+        let cues = src.cues_in_range(first, last, flatten);
+        if cues.is_empty() {
+            anyhow::bail!("no cues in range {first} thru {last} in '{from}'");
+        }
+
+        let base = cues[0].number;
+        let mut renumbered = Vec::with_capacity(cues.len());
+        for mut cue in cues {
+            let num = (cue.number - base)
+                .checked_add(at)
+                .context("cue number overflow")?;
+            cue.number = num;
+            renumbered.push(cue);
+        }
+
+        // a new destination list is only created once the copy can go ahead
+        if let Some(dst) = self.cue_lists.get(to) {
+            if let Some(c) = renumbered.iter().find(|c| dst.cues.contains_key(&c.number)) {
+                anyhow::bail!("cue {} already exists in '{to}'", c.number);
+            }
+            if let (Some(a), Some(b)) = (renumbered.first(), renumbered.last()) {
+                dst.check_unlocked(a.number, b.number)?;
+            }
+        }
+
+        let dst = self.cue_lists.entry(to.to_string()).or_default();
+        let nums = renumbered.iter().map(|c| c.number).collect();
+        for cue in renumbered {
+            dst.cues.insert(cue.number, cue);
+        }
+        Ok(nums)
+    }
+
     /// Save the show to JSON.
+    /// Fixture types loaded from external files are written back as references.
+    /// The first save of the day snapshots the previous file (see `archive`).
     pub fn save_json_file(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
-        let json = serde_json::to_string_pretty(self).context("serialize show to json")?;
-        fs::write(path.as_ref(), json).context("write show json file")?;
+        archive::snapshot_daily(path.as_ref(), archive::keep_versions())?;
+        let mut value = serde_json::to_value(self).context("serialize show to json")?;
+        library::restore_refs(&mut value, &self.patch.fixture_type_refs)?;
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert(
+                "written_by".into(),
+                serde_json::to_value(build_info().stamp()).context("serialize stamp")?,
+            );
+        }
+        let json = serde_json::to_string_pretty(&value).context("serialize show to json")?;
+        // write then rename, so a reader (watcher, other client) never sees half a file
+        let mut tmp = path.as_ref().as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, json).context("write show json file")?;
+        fs::rename(&tmp, path.as_ref()).context("replace show json file")?;
         Ok(())
     }
 
-    /// Load the show from JSON.
+    /// Load the show from JSON, resolving fixture type references.
     pub fn load_json_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
-        let text = fs::read_to_string(path.as_ref()).context("read show json file")?;
-        let show = serde_json::from_str::<Show>(&text).context("parse show json")?;
-        Ok(show)
+        Ok(Self::load_json_file_stamped(path)?.0)
+    }
+
+    /// Like `load_json_file`, also returning who wrote the file (None for
+    /// files from before stamping). Check it with `WrittenBy::compatibility_warning`.
+    pub fn load_json_file_stamped(
+        path: impl AsRef<Path>,
+    ) -> anyhow::Result<(Self, Option<WrittenBy>)> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).context("read show json file")?;
+        let mut value =
+            serde_json::from_str::<serde_json::Value>(&text).context("parse show json")?;
+
+        let stamp = value
+            .as_object_mut()
+            .and_then(|obj| obj.remove("written_by"))
+            .map(serde_json::from_value::<WrittenBy>)
+            .transpose()
+            .context("parse written_by stamp")?;
+
+        let show_dir = path.parent().unwrap_or(Path::new("."));
+        let refs = library::resolve_refs(&mut value, show_dir)?;
+
+        let mut show = serde_json::from_value::<Show>(value).context("parse show json")?;
+        show.patch.fixture_type_refs = refs;
+        Ok((show, stamp))
     }
 }
 
 /// Patch holds fixture instances + fixture types.
 /// In a pro console, the type library is huge; here we start tiny.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Patch {
     /// Fixture types known in this show (like a small fixture library).
     pub fixture_types: BTreeMap<String, FixtureType>,
     /// Actual patched fixtures.
     pub fixtures: BTreeMap<u32, FixtureInstance>,
+    /// Fixture types that came from external files (see [`library`]).
+    #[serde(skip)]
+    pub fixture_type_refs: BTreeMap<String, FixtureTypeRef>,
 }
 
 impl Patch {
     pub fn add_fixture_type(&mut self, fixture_type: FixtureType) {
+        // an inline definition replaces any external reference
+        self.fixture_type_refs.remove(&fixture_type.type_id);
         self.fixture_types
             .insert(fixture_type.type_id.clone(), fixture_type);
     }
@@ -100,26 +393,108 @@ impl Patch {
     pub fn list_fixtures(&self) -> Vec<&FixtureInstance> {
         self.fixtures.values().collect()
     }
+
+    /// Reverse lookup: the fixture occupying a DMX address, with the index
+    /// and definition of the channel there.
+    pub fn fixture_at(
+        &self,
+        universe: u16,
+        address: u16,
+    ) -> Option<(&FixtureInstance, usize, &ChannelDef)> {
+        self.fixtures.values().find_map(|f| {
+            if f.universe != universe || address < f.address {
+                return None;
+            }
+            let ft = self.fixture_types.get(&f.fixture_type)?;
+            let idx = (address - f.address) as usize;
+            ft.channels.get(idx).map(|ch| (f, idx, ch))
+        })
+    }
+
+    /// Addresses a fixture occupies as (universe, first, last); None if its
+    /// type is unknown.
+    pub fn footprint(&self, fixture: &FixtureInstance) -> Option<(u16, u16, u16)> {
+        let ft = self.fixture_types.get(&fixture.fixture_type)?;
+        let len = ft.channels.len().max(1) as u16;
+        Some((
+            fixture.universe,
+            fixture.address,
+            fixture.address.saturating_add(len - 1),
+        ))
+    }
+
+    /// (universe, address) of every channel of `kind` on a fixture.
+    pub fn channel_addresses(&self, fixture_id: u32, kind: ChannelKind) -> Vec<(u16, u16)> {
+        let Some(f) = self.fixtures.get(&fixture_id) else {
+            return Vec::new();
+        };
+        let Some(ft) = self.fixture_types.get(&f.fixture_type) else {
+            return Vec::new();
+        };
+        ft.channels
+            .iter()
+            .enumerate()
+            .filter(|(_, ch)| ch.kind == kind)
+            .map(|(i, _)| (f.universe, f.address + i as u16))
+            .collect()
+    }
+
+    /// Channel kinds of a fixture that must snap instead of fading: every
+    /// snap channel of its type, or all channels if the fixture itself is
+    /// marked `snap`.
+    pub fn snap_kinds(&self, fixture_id: u32) -> Vec<ChannelKind> {
+        let Some(f) = self.fixtures.get(&fixture_id) else {
+            return Vec::new();
+        };
+        let Some(ft) = self.fixture_types.get(&f.fixture_type) else {
+            return Vec::new();
+        };
+        ft.channels
+            .iter()
+            .filter(|ch| f.snap || ch.snap)
+            .map(|ch| ch.kind.clone())
+            .collect()
+    }
 }
 
 /// Describes a fixture model/mode in a simplified way.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FixtureType {
     pub type_id: String,
     pub manufacturer: String,
     pub model: String,
     pub channels: Vec<ChannelDef>,
+    /// Control macros such as lamp on/off or reset.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub controls: Vec<ControlMacro>,
+}
+
+/// A fixture control: hold `value` on a channel for `hold_ms`, then release.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ControlMacro {
+    pub name: String,
+    /// Channel name as in `channels`.
+    pub channel: String,
+    pub value: u8,
+    pub hold_ms: u32,
 }
 
 /// One channel definition in a fixture type.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ChannelDef {
     pub name: String,
     pub kind: ChannelKind,
+    /// Snap-only: jumps to its target when a fade starts, never interpolated
+    /// (strobe speed, gobo rotation, lamp control...).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub snap: bool,
+    /// Value output when nothing else drives the channel (e.g. shutter open).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<u8>,
 }
 
 /// Very simplified categories.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ChannelKind {
     Intensity,
     Pan,
@@ -127,17 +502,51 @@ pub enum ChannelKind {
     ColorR,
     ColorG,
     ColorB,
+    ColorW,
+    ColorA,
+    ColorUv,
+    Strobe,
+    ColorWheel,
+    Gobo,
     Other,
+    /// Low byte of a 16-bit intensity (the `Intensity` channel is the high byte).
+    IntensityFine,
+    PanFine,
+    TiltFine,
+}
+
+impl ChannelKind {
+    /// For the fine byte of a 16-bit parameter, the coarse kind it refines.
+    pub fn fine_of(&self) -> Option<ChannelKind> {
+        match self {
+            ChannelKind::IntensityFine => Some(ChannelKind::Intensity),
+            ChannelKind::PanFine => Some(ChannelKind::Pan),
+            ChannelKind::TiltFine => Some(ChannelKind::Tilt),
+            _ => None,
+        }
+    }
 }
 
 /// A single fixture as patched into a universe/address.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FixtureInstance {
     pub fixture_id: u32,
     pub name: String,
     pub fixture_type: String,
     pub universe: u16,
     pub address: u16,
+    /// Disable fades for this fixture entirely (all channels snap).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub snap: bool,
+    /// Stage-plot position in cm (x across, y up- to downstage); see `plot`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<[i32; 2]>,
+    /// Area / bar / unit the fixture hangs in; drives the automatic groups.
+    #[serde(default, skip_serializing_if = "Location::is_empty")]
+    pub location: Location,
+    /// Soft pan/tilt limits enforced on output; see `limits`.
+    #[serde(default, skip_serializing_if = "MotionLimits::is_empty")]
+    pub limits: MotionLimits,
 }
 
 impl FixtureInstance {
@@ -154,40 +563,91 @@ impl FixtureInstance {
             fixture_type: fixture_type.into(),
             universe,
             address,
+            snap: false,
+            position: None,
+            location: Location::default(),
+            limits: MotionLimits::default(),
         }
     }
 }
 
-/// A helper: returns a default tiny “library” we can start with.
-pub fn default_fixture_types() -> Vec<FixtureType> {
-    vec![
-        FixtureType {
-            type_id: "rgb_par_3ch".to_string(),
-            manufacturer: "Generic".to_string(),
-            model: "RGB PAR (3ch)".to_string(),
-            channels: vec![
-                ChannelDef {
-                    name: "Red".to_string(),
-                    kind: ChannelKind::ColorR,
-                },
-                ChannelDef {
-                    name: "Green".to_string(),
-                    kind: ChannelKind::ColorG,
-                },
-                ChannelDef {
-                    name: "Blue".to_string(),
-                    kind: ChannelKind::ColorB,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixture_at_finds_owner_and_channel() -> anyhow::Result<()> {
+        let mut patch = Patch::default();
+        for ft in default_fixture_types() {
+            patch.add_fixture_type(ft);
+        }
+        patch.add_fixture(FixtureInstance::new(1, "PAR 1", "rgb_par_3ch", 1, 35))?;
+        patch.add_fixture(FixtureInstance::new(2, "Dim 1", "dimmer_1ch", 2, 37))?;
+
+        let (f, idx, ch) = patch.fixture_at(1, 37).unwrap();
+        assert_eq!((f.fixture_id, idx, ch.name.as_str()), (1, 2, "Blue"));
+        assert_eq!(patch.fixture_at(2, 37).unwrap().0.fixture_id, 2);
+        assert!(patch.fixture_at(1, 38).is_none());
+        assert!(patch.fixture_at(1, 34).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn copy_cues_renumbers_and_flattens() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        let main = show.cue_lists.get_mut("main").unwrap();
+        for (num, fid) in [(10, 1), (15, 2), (30, 3)] {
+            main.cues.insert(
+                num,
+                Cue {
+                    number: num,
+                    label: format!("Cue {num}"),
+                    meta: Default::default(),
+                    block: false,
+                    follow_ms: None,
+                    confirm: false,
+                    link: None,
+                    link_loops: None,
+                    warn: None,
+                    palette_refs: Default::default(),
+                    fade_ms: 0,
+                    delay_ms: 0,
+                    changes: [(
+                        fid,
+                        FixtureValues {
+                            intensity: Some(255),
+                            ..Default::default()
+                        },
+                    )]
+                    .into_iter()
+                    .collect(),
                 },
-            ],
-        },
-        FixtureType {
-            type_id: "dimmer_1ch".to_string(),
-            manufacturer: "Generic".to_string(),
-            model: "Dimmer (1ch)".to_string(),
-            channels: vec![ChannelDef {
-                name: "Intensity".to_string(),
-                kind: ChannelKind::Intensity,
-            }],
-        },
-    ]
+            );
+        }
+
+        let warm = crate::PaletteRefs {
+            intensity: Some("Warm".into()),
+            color: None,
+        };
+        main.cues
+            .get_mut(&10)
+            .unwrap()
+            .palette_refs
+            .insert(1, warm.clone());
+
+        let nums = show.copy_cues("main", 10, 20, "act2", 1, true)?;
+        assert_eq!(nums, vec![1, 6]);
+
+        let act2 = &show.cue_lists["act2"];
+        // flattened: cue 6 carries fixture 1 tracked from source cue 10,
+        // still linked to its palette
+        assert_eq!(act2.cues[&6].changes.len(), 2);
+        assert_eq!(act2.cues[&6].palette_refs.get(&1), Some(&warm));
+
+        // copying onto existing numbers is refused
+        assert!(show.copy_cues("main", 10, 10, "act2", 6, false).is_err());
+        assert!(show.copy_cues("main", 40, 50, "act3", 1, false).is_err());
+        assert!(!show.cue_lists.contains_key("act3"));
+        Ok(())
+    }
 }
@@ -0,0 +1,154 @@
+//! External fixture type files referenced from a show.
+//!
+//! In a show file an entry of `patch.fixture_types` can be a reference instead of
+//! a full definition:
+//!
+//! ```json
+//! "fixture_types": {
+//!   "mover_16ch": { "path": "fixtures/mover_16ch.json" },
+//!   "dimmer_1ch": { "library": "generic/dimmer_1ch" }
+//! }
+//! ```
+//!
+//! `path` is relative to the show file. `library` ids resolve to
+//! `<library dir>/<id>.json`, where the library dir is `$LIGHTCONSOLE_FIXTURE_LIB`
+//! or `fixtures/` next to the show file. References are resolved on load and
+//! written back as references on save.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::FixtureType;
+
+pub const LIBRARY_ENV: &str = "LIGHTCONSOLE_FIXTURE_LIB";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixtureTypeRef {
+    Path(PathBuf),
+    Library(String),
+}
+
+impl FixtureTypeRef {
+    fn file(&self, show_dir: &Path) -> PathBuf {
+        match self {
+            FixtureTypeRef::Path(p) => show_dir.join(p),
+            FixtureTypeRef::Library(id) => {
+                let lib = std::env::var_os(LIBRARY_ENV)
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| show_dir.join("fixtures"));
+                lib.join(format!("{id}.json"))
+            }
+        }
+    }
+
+    /// Load the referenced fixture type.
+    pub fn resolve(&self, show_dir: &Path) -> anyhow::Result<FixtureType> {
+        let file = self.file(show_dir);
+        let text = fs::read_to_string(&file)
+            .with_context(|| format!("read fixture type file '{}'", file.display()))?;
+        serde_json::from_str::<FixtureType>(&text)
+            .with_context(|| format!("parse fixture type file '{}'", file.display()))
+    }
+}
+
+/// Replace reference entries in a show's JSON with the definitions they point to.
+/// Returns the references so they can be restored on save.
+pub(crate) fn resolve_refs(
+    show_json: &mut Value,
+    show_dir: &Path,
+) -> anyhow::Result<BTreeMap<String, FixtureTypeRef>> {
+    let mut refs = BTreeMap::new();
+
+    let Some(types) = show_json
+        .pointer_mut("/patch/fixture_types")
+        .and_then(Value::as_object_mut)
+    else {
+        return Ok(refs);
+    };
+
+    for (type_id, entry) in types.iter_mut() {
+        if entry.get("type_id").is_some() {
+            continue; // inline definition
+        }
+        let r: FixtureTypeRef = serde_json::from_value(entry.clone()).with_context(|| {
+            format!("fixture type '{type_id}' is neither a definition nor a reference")
+        })?;
+
+        let ft = r.resolve(show_dir)?;
+        if ft.type_id != *type_id {
+            anyhow::bail!(
+                "fixture type '{type_id}' references a file defining '{}'",
+                ft.type_id
+            );
+        }
+
+        *entry = serde_json::to_value(&ft)?;
+        refs.insert(type_id.clone(), r);
+    }
+
+    Ok(refs)
+}
+
+/// Swap resolved definitions back to their references before writing a show.
+pub(crate) fn restore_refs(
+    show_json: &mut Value,
+    refs: &BTreeMap<String, FixtureTypeRef>,
+) -> anyhow::Result<()> {
+    let Some(types) = show_json
+        .pointer_mut("/patch/fixture_types")
+        .and_then(Value::as_object_mut)
+    else {
+        return Ok(());
+    };
+
+    for (type_id, r) in refs {
+        if let Some(entry) = types.get_mut(type_id) {
+            *entry = serde_json::to_value(r)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FixtureInstance, Show, default_fixture_types};
+
+    #[test]
+    fn show_round_trips_fixture_type_refs() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("lc_library_{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+
+        let dimmer = default_fixture_types()
+            .into_iter()
+            .find(|ft| ft.type_id == "dimmer_1ch")
+            .unwrap();
+        std::fs::write(dir.join("dimmer.json"), serde_json::to_string(&dimmer)?)?;
+        std::fs::write(
+            dir.join("show.json"),
+            r#"{
+                "name": "Refs",
+                "patch": {
+                    "fixture_types": { "dimmer_1ch": { "path": "dimmer.json" } },
+                    "fixtures": {}
+                }
+            }"#,
+        )?;
+
+        let mut show = Show::load_json_file(dir.join("show.json"))?;
+        assert_eq!(show.patch.fixture_types["dimmer_1ch"], dimmer);
+        show.patch
+            .add_fixture(FixtureInstance::new(1, "DIM 1", "dimmer_1ch", 1, 1))?;
+
+        show.save_json_file(dir.join("show.json"))?;
+        let text = std::fs::read_to_string(dir.join("show.json"))?;
+        assert!(text.contains(r#""path": "dimmer.json""#));
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
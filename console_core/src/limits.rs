@@ -0,0 +1,126 @@
+//! Per-fixture soft limits for pan and tilt, enforced on the rendered frame
+//! so a mover near a set piece can't be pointed into it, whatever the cue,
+//! tester or DMX input says.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ChannelKind, LiveState, Show};
+
+/// Allowed range of a 16-bit parameter (coarse byte high, fine byte low).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Limit {
+    pub min: u16,
+    pub max: u16,
+}
+
+impl Limit {
+    /// From percentages of the full range, in either order.
+    pub fn from_percent(a: f32, b: f32) -> Self {
+        let to16 = |p: f32| (p.clamp(0.0, 100.0) / 100.0 * 65535.0).round() as u16;
+        let (a, b) = (to16(a), to16(b));
+        Self {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    pub fn percent(&self) -> (f32, f32) {
+        let pct = |v: u16| v as f32 * 100.0 / 65535.0;
+        (pct(self.min), pct(self.max))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MotionLimits {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pan: Option<Limit>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tilt: Option<Limit>,
+}
+
+impl MotionLimits {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Clamp pan and tilt of every limited fixture in `live`. Without a fine
+/// channel the coarse byte is kept inside the limit.
+pub(crate) fn apply_limits(show: &Show, live: &mut LiveState) {
+    let patch = &show.patch;
+    for f in patch.fixtures.values().filter(|f| !f.limits.is_empty()) {
+        let axes = [
+            (f.limits.pan, ChannelKind::Pan, ChannelKind::PanFine),
+            (f.limits.tilt, ChannelKind::Tilt, ChannelKind::TiltFine),
+        ];
+        for (limit, coarse_kind, fine_kind) in axes {
+            let Some(limit) = limit else {
+                continue;
+            };
+            let fine = patch
+                .channel_addresses(f.fixture_id, fine_kind)
+                .first()
+                .copied();
+            for (u, a) in patch.channel_addresses(f.fixture_id, coarse_kind) {
+                match fine {
+                    Some((fu, fa)) => {
+                        let v = u16::from_be_bytes([live.get(u, a), live.get(fu, fa)]);
+                        let [hi, lo] = v.clamp(limit.min, limit.max).to_be_bytes();
+                        live.set(u, a, hi);
+                        live.set(fu, fa, lo);
+                    }
+                    None => {
+                        let lo = limit.min.div_ceil(256).min(255) as u8;
+                        let hi = ((limit.max >> 8) as u8).max(lo);
+                        live.set(u, a, live.get(u, a).clamp(lo, hi));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FixtureInstance, Runtime, default_fixture_types};
+
+    #[test]
+    fn tilt_limits_clamp_the_output_frame() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        for ft in default_fixture_types() {
+            show.patch.add_fixture_type(ft);
+        }
+        let mover = show
+            .patch
+            .fixture_types
+            .values()
+            .find(|t| t.channels.iter().any(|c| c.kind == ChannelKind::TiltFine))
+            .map(|t| t.type_id.clone())
+            .expect("a built-in mover");
+        let mut f = FixtureInstance::new(1, "Mover", mover, 1, 1);
+        f.limits.tilt = Some(Limit::from_percent(20.0, 60.0));
+        show.patch.add_fixture(f)?;
+        let (tilt, tilt_fine) = (
+            show.patch.channel_addresses(1, ChannelKind::Tilt)[0],
+            show.patch.channel_addresses(1, ChannelKind::TiltFine)[0],
+        );
+
+        let mut rt = Runtime::new(show);
+        rt.set_dmx(tilt.0, tilt.1, 255)?;
+        rt.set_dmx(tilt_fine.0, tilt_fine.1, 255)?;
+        let out = rt.render()?;
+        let max = Limit::from_percent(20.0, 60.0).max.to_be_bytes();
+        assert_eq!(out.get(tilt.0, tilt.1), max[0]);
+        assert_eq!(out.get(tilt_fine.0, tilt_fine.1), max[1]);
+
+        rt.set_dmx(tilt.0, tilt.1, 0)?;
+        rt.set_dmx(tilt_fine.0, tilt_fine.1, 0)?;
+        let out = rt.render()?;
+        assert_eq!(out.get(tilt.0, tilt.1), 51); // 20% of 65535 = 0x3333
+        // pan is unlimited: its 128 default passes through
+        let pan = rt.show.patch.channel_addresses(1, ChannelKind::Pan)[0];
+        assert_eq!(out.get(pan.0, pan.1), 128);
+        Ok(())
+    }
+}
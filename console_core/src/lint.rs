@@ -0,0 +1,238 @@
+//! Show sanity checks (`lint`): structured findings for CLI and GUI.
+
+use std::collections::BTreeSet;
+
+use crate::{ObjectRef, PaletteKind, Show};
+
+/// Intensity change treated as a "big jump" when it happens with no fade.
+pub const BIG_JUMP: u8 = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Info,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub object: ObjectRef,
+    pub message: String,
+}
+
+impl Show {
+    /// Look for common problems. Warnings first, then infos.
+    pub fn lint(&self) -> Vec<Finding> {
+        let mut out = Vec::new();
+        let mut used = BTreeSet::new();
+
+        for (list_name, list) in &self.cue_lists {
+            let cue_ref = |number| ObjectRef::Cue {
+                list: list_name.clone(),
+                number,
+            };
+
+            for (&num, cue) in &list.cues {
+                used.extend(cue.changes.keys().copied());
+
+                if cue.changes.values().all(|v| v.is_all_none()) {
+                    out.push(Finding {
+                        severity: Severity::Warning,
+                        object: cue_ref(num),
+                        message: format!("cue {num} ({list_name}) has no changes"),
+                    });
+                }
+
+                if let Some(link) = cue.link.filter(|l| !list.cues.contains_key(l)) {
+                    out.push(Finding {
+                        severity: Severity::Warning,
+                        object: cue_ref(num),
+                        message: format!(
+                            "cue {num} ({list_name}) links to missing cue {link}; Go carries on instead"
+                        ),
+                    });
+                }
+
+                if cue.fade_ms == 0 {
+                    let before = list.tracked_state_before(num);
+                    let after = list.tracked_state_at(num);
+                    let jumps: Vec<String> = after
+                        .iter()
+                        .filter(|(fid, v)| {
+                            let from = before.get(fid).and_then(|b| b.intensity).unwrap_or(0);
+                            let to = v.intensity.unwrap_or(0);
+                            from.abs_diff(to) >= BIG_JUMP
+                        })
+                        .map(|(fid, _)| fid.to_string())
+                        .collect();
+                    if !jumps.is_empty() {
+                        out.push(Finding {
+                            severity: Severity::Warning,
+                            object: cue_ref(num),
+                            message: format!(
+                                "cue {num} ({list_name}) snaps intensity on fixture(s) {} with a 0s fade",
+                                jumps.join(" ")
+                            ),
+                        });
+                    }
+                }
+
+                // tracking anomalies: what `clean cue` would change
+                let mut probe = list.clone();
+                let redundant = probe.clean_cue(num).unwrap_or(0);
+                if redundant > 0 {
+                    out.push(Finding {
+                        severity: Severity::Info,
+                        object: cue_ref(num),
+                        message: format!(
+                            "cue {num} ({list_name}) repeats {redundant} value(s) that already track in (clean cue {num})"
+                        ),
+                    });
+                }
+                if cue.block && !probe.cues[&num].block {
+                    out.push(Finding {
+                        severity: Severity::Info,
+                        object: cue_ref(num),
+                        message: format!("block on cue {num} ({list_name}) has no effect"),
+                    });
+                }
+            }
+        }
+
+        for ids in self.groups.values() {
+            used.extend(ids.iter().copied());
+        }
+        for (u, a, b) in self.patch.collisions() {
+            out.push(Finding {
+                severity: Severity::Warning,
+                object: ObjectRef::Fixture(b),
+                message: format!("fixtures {a} and {b} share addresses in universe {u}"),
+            });
+        }
+        for f in self.patch.fixtures.values() {
+            if !used.contains(&f.fixture_id) {
+                out.push(Finding {
+                    severity: Severity::Info,
+                    object: ObjectRef::Fixture(f.fixture_id),
+                    message: format!(
+                        "fixture {} '{}' is not used in any cue or group",
+                        f.fixture_id, f.name
+                    ),
+                });
+            }
+        }
+
+        // Palettes are applied by value, so "referenced" means some cue holds its values.
+        for (name, pal) in &self.palettes {
+            let v = &pal.values;
+            let referenced = self
+                .cue_lists
+                .values()
+                .flat_map(|l| l.cues.values())
+                .any(|c| {
+                    c.changes.values().any(|cv| match pal.kind {
+                        PaletteKind::Intensity => {
+                            v.intensity.is_some() && cv.intensity == v.intensity
+                        }
+                        PaletteKind::Color => {
+                            (v.r, v.g, v.b) != (None, None, None)
+                                && (cv.r, cv.g, cv.b) == (v.r, v.g, v.b)
+                        }
+                    })
+                });
+            if !referenced {
+                out.push(Finding {
+                    severity: Severity::Info,
+                    object: ObjectRef::Palette(name.clone()),
+                    message: format!("palette '{name}' is not used by any cue"),
+                });
+            }
+        }
+
+        out.sort_by_key(|f| f.severity);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cue, FixtureInstance, FixtureValues, Palette, default_fixture_types};
+
+    #[test]
+    fn lint_reports_common_problems() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        for ft in default_fixture_types() {
+            show.patch.add_fixture_type(ft);
+        }
+        for id in 1..=2 {
+            show.patch.add_fixture(FixtureInstance::new(
+                id,
+                format!("Dim {id}"),
+                "dimmer_1ch",
+                1,
+                id as u16,
+            ))?;
+        }
+        show.palettes.insert(
+            "Full".into(),
+            Palette::new(
+                PaletteKind::Intensity,
+                crate::PaletteValues {
+                    intensity: Some(255),
+                    ..Default::default()
+                },
+            ),
+        );
+
+        let full = FixtureValues {
+            intensity: Some(255),
+            ..Default::default()
+        };
+        let main = show.cue_lists.get_mut("main").unwrap();
+        for (num, changes) in [
+            (1, vec![(1, full.clone())]),
+            (2, vec![(1, full)]),
+            (3, vec![]),
+        ] {
+            main.cues.insert(
+                num,
+                Cue {
+                    number: num,
+                    label: String::new(),
+                    meta: Default::default(),
+                    block: false,
+                    follow_ms: None,
+                    confirm: false,
+                    link: None,
+                    link_loops: None,
+                    warn: None,
+                    palette_refs: Default::default(),
+                    fade_ms: 0,
+                    delay_ms: 0,
+                    changes: changes.into_iter().collect(),
+                },
+            );
+        }
+
+        let msgs: Vec<String> = show.lint().into_iter().map(|f| f.message).collect();
+        assert!(
+            msgs.iter()
+                .any(|m| m.contains("cue 3 (main) has no changes"))
+        );
+        assert!(
+            msgs.iter()
+                .any(|m| m.contains("cue 1 (main) snaps intensity on fixture(s) 1"))
+        );
+        assert!(
+            msgs.iter()
+                .any(|m| m.contains("cue 2 (main) repeats 1 value"))
+        );
+        assert!(
+            msgs.iter()
+                .any(|m| m.contains("fixture 2 'Dim 2' is not used"))
+        );
+        assert!(!msgs.iter().any(|m| m.contains("palette 'Full'")));
+        Ok(())
+    }
+}
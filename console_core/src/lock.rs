@@ -0,0 +1,140 @@
+//! Locked cue ranges: a finished act can be protected while another is
+//! still being teched. Record, update, delete and the other cue edits refuse
+//! to touch a locked cue until it's unlocked.
+//!
+//! Locks protect the cues' own data. Track-mode edits made in cues before a
+//! locked range still track into it; block the first locked cue to keep its
+//! look as well.
+
+use serde::{Deserialize, Serialize};
+
+use crate::CueList;
+
+/// Cues `first..=last` (by number) can't be edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CueLock {
+    pub first: u32,
+    pub last: u32,
+}
+
+impl CueLock {
+    /// The whole list, including cues added later.
+    pub const ALL: Self = Self {
+        first: 0,
+        last: u32::MAX,
+    };
+
+    pub fn contains(&self, cue: u32) -> bool {
+        (self.first..=self.last).contains(&cue)
+    }
+}
+
+impl std::fmt::Display for CueLock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.first, self.last) {
+            (0, u32::MAX) => write!(f, "all cues"),
+            (a, b) if a == b => write!(f, "cue {a}"),
+            (a, u32::MAX) => write!(f, "cues {a} onwards"),
+            (a, b) => write!(f, "cues {a} thru {b}"),
+        }
+    }
+}
+
+impl CueList {
+    pub fn is_locked(&self, cue: u32) -> bool {
+        self.locks.iter().any(|l| l.contains(cue))
+    }
+
+    /// Error naming the lock if any cue number in `first..=last` is locked.
+    pub fn check_unlocked(&self, first: u32, last: u32) -> anyhow::Result<()> {
+        let (first, last) = (first.min(last), first.max(last));
+        match self
+            .locks
+            .iter()
+            .find(|l| l.first <= last && first <= l.last)
+        {
+            Some(lock) => anyhow::bail!("{lock} locked (unlock to edit)"),
+            None => Ok(()),
+        }
+    }
+
+    /// Lock `first..=last`, merging with overlapping or adjacent locks.
+    pub fn lock(&mut self, first: u32, last: u32) {
+        let mut new = CueLock {
+            first: first.min(last),
+            last: first.max(last),
+        };
+        self.locks.retain(|l| {
+            let touches =
+                l.first <= new.last.saturating_add(1) && new.first <= l.last.saturating_add(1);
+            if touches {
+                new.first = new.first.min(l.first);
+                new.last = new.last.max(l.last);
+            }
+            !touches
+        });
+        self.locks.push(new);
+        self.locks.sort_by_key(|l| l.first);
+    }
+
+    /// Unlock `first..=last`, splitting locks that extend past it. Returns
+    /// whether anything was locked there.
+    pub fn unlock(&mut self, first: u32, last: u32) -> bool {
+        let (first, last) = (first.min(last), first.max(last));
+        let before = self.locks.clone();
+        self.locks = before
+            .iter()
+            .flat_map(|l| {
+                if l.last < first || last < l.first {
+                    return vec![*l];
+                }
+                let mut keep = Vec::new();
+                if l.first < first {
+                    keep.push(CueLock {
+                        first: l.first,
+                        last: first - 1,
+                    });
+                }
+                if last < l.last {
+                    keep.push(CueLock {
+                        first: last + 1,
+                        last: l.last,
+                    });
+                }
+                keep
+            })
+            .collect();
+        self.locks != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locks_merge_split_and_refuse_edits() {
+        let mut list = CueList::ensure();
+        list.lock(10, 20);
+        list.lock(21, 30);
+        assert_eq!(
+            list.locks,
+            [CueLock {
+                first: 10,
+                last: 30
+            }]
+        );
+        assert!(list.check_unlocked(5, 9).is_ok());
+        let err = list.check_unlocked(25, 40).unwrap_err();
+        assert_eq!(err.to_string(), "cues 10 thru 30 locked (unlock to edit)");
+
+        assert!(list.unlock(15, 15));
+        assert!(!list.is_locked(15));
+        assert!(list.is_locked(14) && list.is_locked(16));
+        assert!(!list.unlock(1, 5));
+
+        list.lock(0, u32::MAX);
+        assert_eq!(list.locks, [CueLock::ALL]);
+        assert_eq!(CueLock::ALL.to_string(), "all cues");
+    }
+}
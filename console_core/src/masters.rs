@@ -0,0 +1,137 @@
+//! Group masters: a page of inhibitive faders that cap the intensity of a
+//! group, plus the emergency "house lights on, stage out" panic.
+//! The grand master and the per-playback masters scale intensity the same way.
+//!
+//! The page layout is saved in the show; fader levels and the panic state
+//! are live runtime state (see `Runtime::set_master` / `Runtime::set_panic`).
+
+use crate::playback::StateMap;
+use crate::{ChannelKind, LiveState, Show};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MasterPage {
+    /// Groups with a master on the page, in fader order.
+    #[serde(default)]
+    pub groups: Vec<String>,
+    /// Group brought to full by the panic macro (everything else goes out).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub house_group: Option<String>,
+}
+
+impl MasterPage {
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty() && self.house_group.is_none()
+    }
+}
+
+/// Scale intensity channels by the group masters, then by the grand master.
+/// A fixture in several mastered groups follows the lowest master.
+pub(crate) fn apply_masters(
+    show: &Show,
+    levels: &BTreeMap<String, u8>,
+    grand: u8,
+    live: &mut LiveState,
+) {
+    let mut caps: BTreeMap<u32, u8> = BTreeMap::new();
+    for (group, &level) in levels {
+        if level == 255 || !show.masters.groups.contains(group) {
+            continue;
+        }
+        for &fid in show.groups.get(group).into_iter().flatten() {
+            let cap = caps.entry(fid).or_insert(255);
+            *cap = (*cap).min(level);
+        }
+    }
+    if grand < 255 {
+        for &fid in show.patch.fixtures.keys() {
+            let cap = caps.entry(fid).or_insert(255);
+            *cap = (*cap as u32 * grand as u32 / 255) as u8;
+        }
+    }
+    for (fid, cap) in caps {
+        let fine = show
+            .patch
+            .channel_addresses(fid, ChannelKind::IntensityFine);
+        for (i, (u, a)) in show
+            .patch
+            .channel_addresses(fid, ChannelKind::Intensity)
+            .into_iter()
+            .enumerate()
+        {
+            let Some(&coarse) = live.universes.get(&u).and_then(|m| m.get(&a)) else {
+                continue;
+            };
+            // scale 16-bit intensities as a whole
+            let fine = fine.get(i).copied();
+            let low = fine.map_or(coarse, |(fu, fa)| live.get(fu, fa));
+            let v = (((coarse as u32) << 8 | low as u32) * cap as u32 / 255) as u16;
+            live.set(u, a, (v >> 8) as u8);
+            if let Some((fu, fa)) = fine {
+                live.set(fu, fa, v as u8);
+            }
+        }
+    }
+}
+
+/// Scale the intensities of a playback's output (coarse and fine together)
+/// by its master.
+pub(crate) fn scale_levels(coarse: &mut StateMap, fine: &mut StateMap, level: u8) {
+    if level == 255 {
+        return;
+    }
+    for (fid, v) in coarse.iter_mut() {
+        let Some(hi) = v.intensity else {
+            continue;
+        };
+        let f = fine.entry(*fid).or_default();
+        let lo = f.intensity.unwrap_or(hi);
+        let scaled = (((hi as u32) << 8 | lo as u32) * level as u32 / 255) as u16;
+        v.intensity = Some((scaled >> 8) as u8);
+        f.intensity = Some(scaled as u8);
+    }
+}
+
+/// Flash: every intensity a playback outputs at full, whatever its master.
+pub(crate) fn flash_levels(coarse: &mut StateMap, fine: &mut StateMap) {
+    for (fid, v) in coarse.iter_mut() {
+        if v.intensity.is_some() {
+            v.intensity = Some(255);
+            fine.entry(*fid).or_default().intensity = Some(255);
+        }
+    }
+}
+
+/// Panic look: every intensity channel out, the house group at full.
+/// Without a house group this is a plain blackout.
+pub(crate) fn apply_panic(show: &Show, live: &mut LiveState) {
+    let house = show
+        .masters
+        .house_group
+        .as_ref()
+        .and_then(|g| show.groups.get(g));
+    apply_intensity(show, live, |fid| {
+        if house.is_some_and(|h| h.contains(&fid)) {
+            255
+        } else {
+            0
+        }
+    });
+}
+
+/// Blackout: every intensity channel out; nothing else changes.
+pub(crate) fn apply_blackout(show: &Show, live: &mut LiveState) {
+    apply_intensity(show, live, |_| 0);
+}
+
+fn apply_intensity(show: &Show, live: &mut LiveState, level: impl Fn(u32) -> u8) {
+    for &fid in show.patch.fixtures.keys() {
+        let value = level(fid);
+        for kind in [ChannelKind::Intensity, ChannelKind::IntensityFine] {
+            for (u, a) in show.patch.channel_addresses(fid, kind) {
+                live.set(u, a, value);
+            }
+        }
+    }
+}
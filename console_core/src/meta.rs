@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Bookkeeping attached to cues, groups and palettes.
+/// Timestamps are Unix seconds (UTC).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Meta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+
+    /// Free-form color tag, e.g. "red" or "#ff8800".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+impl Meta {
+    /// Meta for a freshly recorded object.
+    pub fn created_now(author: Option<&str>) -> Self {
+        let mut meta = Self::default();
+        meta.touch(author);
+        meta
+    }
+
+    /// Mark as modified now; sets `created` the first time and updates the author if known.
+    pub fn touch(&mut self, author: Option<&str>) {
+        let now = unix_now();
+        self.created.get_or_insert(now);
+        self.modified = Some(now);
+        if let Some(a) = author {
+            self.author = Some(a.to_string());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// One-line summary for listings, e.g. "jane, modified 2024-05-01 20:13 UTC [red]".
+    pub fn summary(&self) -> String {
+        let mut out = Vec::new();
+        if let Some(a) = &self.author {
+            out.push(a.clone());
+        }
+        if let Some(m) = self.modified.or(self.created) {
+            out.push(format!("modified {}", format_utc(m)));
+        }
+        if let Some(c) = &self.color {
+            out.push(format!("[{c}]"));
+        }
+        if !self.notes.is_empty() {
+            out.push(format!("\"{}\"", self.notes));
+        }
+        out.join(", ")
+    }
+}
+
+/// Author for new edits: `$LIGHTCONSOLE_AUTHOR`, falling back to `$USER`.
+pub fn current_author() -> Option<String> {
+    std::env::var("LIGHTCONSOLE_AUTHOR")
+        .or_else(|_| std::env::var("USER"))
+        .ok()
+        .filter(|s| !s.is_empty())
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Format Unix seconds as "YYYY-MM-DD HH:MM UTC".
+pub fn format_utc(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // civil-from-days (Howard Hinnant)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        rem / 3600,
+        (rem % 3600) / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_utc_dates() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_utc(951_782_400), "2000-02-29 00:00 UTC");
+        assert_eq!(format_utc(1_714_594_380), "2024-05-01 20:13 UTC");
+    }
+}
@@ -0,0 +1,418 @@
+//! MIDI control surfaces (APC, LaunchControl...): notes and CCs mapped to
+//! Go/Back buttons, playback and group masters, programmer encoders or any
+//! command line.
+//!
+//! Input is read from a raw MIDI device file (`/dev/snd/midiC1D0`,
+//! `/dev/midi1`) on a background thread. The mapping is saved next to the
+//! show as `<show>.midi.json`. Learn mode binds the next control touched.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use crate::Runtime;
+
+/// A channel voice message we care about. Channels are 1-16.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MidiMessage {
+    NoteOn {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    NoteOff {
+        channel: u8,
+        note: u8,
+    },
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+    },
+}
+
+impl MidiMessage {
+    /// The physical control that sent this, and its value (0-127).
+    pub fn control(&self) -> (MidiControl, u8) {
+        match *self {
+            Self::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => (MidiControl::Note { channel, note }, velocity),
+            Self::NoteOff { channel, note } => (MidiControl::Note { channel, note }, 0),
+            Self::ControlChange {
+                channel,
+                controller,
+                value,
+            } => (
+                MidiControl::Cc {
+                    channel,
+                    controller,
+                },
+                value,
+            ),
+        }
+    }
+}
+
+/// Byte-stream parser with running status; system messages are skipped.
+#[derive(Debug, Clone, Default)]
+pub struct MidiParser {
+    status: Option<u8>,
+    data: Vec<u8>,
+}
+
+impl MidiParser {
+    pub fn push(&mut self, byte: u8) -> Option<MidiMessage> {
+        if byte >= 0xf8 {
+            return None; // real-time (clock, active sensing) may come anywhere
+        }
+        if byte & 0x80 != 0 {
+            self.status = (byte < 0xf0).then_some(byte);
+            self.data.clear();
+            return None;
+        }
+        let status = self.status?;
+        self.data.push(byte);
+        let needed = if matches!(status & 0xf0, 0xc0 | 0xd0) {
+            1
+        } else {
+            2
+        };
+        if self.data.len() < needed {
+            return None;
+        }
+        let (a, b) = (self.data[0], self.data.get(1).copied().unwrap_or(0));
+        self.data.clear();
+        let channel = (status & 0x0f) + 1;
+        match status & 0xf0 {
+            0x90 if b > 0 => Some(MidiMessage::NoteOn {
+                channel,
+                note: a,
+                velocity: b,
+            }),
+            0x80 | 0x90 => Some(MidiMessage::NoteOff { channel, note: a }),
+            0xb0 => Some(MidiMessage::ControlChange {
+                channel,
+                controller: a,
+                value: b,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MidiControl {
+    Note { channel: u8, note: u8 },
+    Cc { channel: u8, controller: u8 },
+}
+
+impl std::fmt::Display for MidiControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Note { channel, note } => write!(f, "ch{channel} note {note}"),
+            Self::Cc {
+                channel,
+                controller,
+            } => write!(f, "ch{channel} cc {controller}"),
+        }
+    }
+}
+
+/// Programmer parameter driven by a relative encoder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EncoderParam {
+    Intensity,
+    Red,
+    Green,
+    Blue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MidiAction {
+    /// Go on a playback ("a" or "b"), go guard included.
+    Go(String),
+    Back(String),
+    /// Playback fader.
+    Fader(String),
+    GrandMaster,
+    /// Group master of the master page.
+    Master(String),
+    /// Relative encoder: 1-63 up, 65-127 down (two's complement style).
+    Encoder(EncoderParam),
+    /// Any command line, run by the frontend when the button is pressed.
+    Command(String),
+}
+
+impl std::str::FromStr for MidiAction {
+    type Err = anyhow::Error;
+
+    /// `go a`, `back b`, `fader a`, `gm`, `master <group>`,
+    /// `encoder intensity|red|green|blue`, `cmd <command...>`.
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        let pb = |p: &str| -> anyhow::Result<String> {
+            let p = p.to_lowercase();
+            anyhow::ensure!(p == "a" || p == "b", "playback must be a or b");
+            Ok(p)
+        };
+        Ok(match parts.as_slice() {
+            ["go", p] => Self::Go(pb(p)?),
+            ["back", p] => Self::Back(pb(p)?),
+            ["fader", p] => Self::Fader(pb(p)?),
+            ["gm"] => Self::GrandMaster,
+            ["master", group] => Self::Master(group.to_string()),
+            ["encoder", param] => Self::Encoder(match param.to_lowercase().as_str() {
+                "intensity" => EncoderParam::Intensity,
+                "red" => EncoderParam::Red,
+                "green" => EncoderParam::Green,
+                "blue" => EncoderParam::Blue,
+                _ => anyhow::bail!("encoder is intensity, red, green or blue"),
+            }),
+            ["cmd", rest @ ..] if !rest.is_empty() => Self::Command(rest.join(" ")),
+            _ => anyhow::bail!(
+                "expected go a|b, back a|b, fader a|b, gm, master <group>, encoder <param> or cmd <command>"
+            ),
+        })
+    }
+}
+
+impl std::fmt::Display for MidiAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Go(p) => write!(f, "go {p}"),
+            Self::Back(p) => write!(f, "back {p}"),
+            Self::Fader(p) => write!(f, "fader {p}"),
+            Self::GrandMaster => write!(f, "gm"),
+            Self::Master(g) => write!(f, "master {g}"),
+            Self::Encoder(p) => write!(f, "encoder {}", format!("{p:?}").to_lowercase()),
+            Self::Command(c) => write!(f, "cmd {c}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MidiBinding {
+    pub control: MidiControl,
+    pub action: MidiAction,
+}
+
+/// The mapping file saved next to the show.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MidiMap {
+    /// Device opened automatically, e.g. "/dev/snd/midiC1D0".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<PathBuf>,
+    #[serde(default)]
+    pub bindings: Vec<MidiBinding>,
+}
+
+impl MidiMap {
+    /// `<show>.midi.json` next to the show file.
+    pub fn path_for(show_path: &Path) -> PathBuf {
+        let mut p = show_path.as_os_str().to_owned();
+        p.push(".midi.json");
+        PathBuf::from(p)
+    }
+
+    /// The map at `path`, or an empty one if there's no file yet.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => {
+                serde_json::from_str(&text).with_context(|| format!("parse {}", path.display()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("read {}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let text = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, text).with_context(|| format!("write {}", path.display()))
+    }
+
+    /// Bind `control`, replacing what it did before.
+    pub fn bind(&mut self, control: MidiControl, action: MidiAction) {
+        self.bindings.retain(|b| b.control != control);
+        self.bindings.push(MidiBinding { control, action });
+    }
+
+    pub fn action_for(&self, control: MidiControl) -> Option<&MidiAction> {
+        self.bindings
+            .iter()
+            .find(|b| b.control == control)
+            .map(|b| &b.action)
+    }
+}
+
+/// Reads a raw MIDI device on a background thread.
+#[derive(Debug)]
+pub struct MidiInput {
+    rx: mpsc::Receiver<MidiMessage>,
+}
+
+impl MidiInput {
+    pub fn open(device: &Path) -> anyhow::Result<Self> {
+        let mut file =
+            std::fs::File::open(device).with_context(|| format!("open {}", device.display()))?;
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut parser = MidiParser::default();
+            let mut buf = [0u8; 64];
+            // ends when the device goes away or the input is dropped
+            while let Ok(n @ 1..) = file.read(&mut buf) {
+                for &byte in &buf[..n] {
+                    if let Some(msg) = parser.push(byte)
+                        && tx.send(msg).is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(Self { rx })
+    }
+
+    /// Messages received since the last call.
+    pub fn poll(&self) -> Vec<MidiMessage> {
+        self.rx.try_iter().collect()
+    }
+
+    /// Wait up to `timeout` for the next message (learn mode).
+    pub fn next(&self, timeout: std::time::Duration) -> Option<MidiMessage> {
+        self.rx.recv_timeout(timeout).ok()
+    }
+}
+
+/// CC value 0-127 as a 0-255 level.
+fn level(value: u8) -> u8 {
+    (value.min(127) as u16 * 255 / 127) as u8
+}
+
+impl Runtime {
+    /// Perform a mapped action for a control at `value` (0-127). Buttons act
+    /// on press only. A `Command` comes back for the frontend to run.
+    pub fn midi_action(
+        &mut self,
+        action: &MidiAction,
+        value: u8,
+    ) -> anyhow::Result<Option<String>> {
+        let pressed = value > 0;
+        match action {
+            MidiAction::Go(pb) if pressed => {
+                let pb = if pb == "b" {
+                    &mut self.playbacks["b"]
+                } else {
+                    &mut self.playbacks["a"]
+                };
+                pb.press_go(&self.show)?;
+            }
+            MidiAction::Back(pb) if pressed => {
+                let pb = if pb == "b" {
+                    &mut self.playbacks["b"]
+                } else {
+                    &mut self.playbacks["a"]
+                };
+                pb.back(&self.show)?;
+            }
+            MidiAction::Go(_) | MidiAction::Back(_) => {}
+            MidiAction::Fader(pb) => {
+                let pb = if pb == "b" {
+                    &mut self.playbacks["b"]
+                } else {
+                    &mut self.playbacks["a"]
+                };
+                pb.master = level(value);
+            }
+            MidiAction::GrandMaster => self.set_grand_master(level(value)),
+            MidiAction::Master(group) => self.set_master(group, level(value))?,
+            MidiAction::Encoder(param) => {
+                let delta = if value < 64 {
+                    value as i16
+                } else {
+                    value as i16 - 128
+                };
+                let p = &mut self.programmer;
+                let field = match param {
+                    EncoderParam::Intensity => return self.nudge_intensity(delta).map(|_| None),
+                    EncoderParam::Red => &mut p.r,
+                    EncoderParam::Green => &mut p.g,
+                    EncoderParam::Blue => &mut p.b,
+                };
+                *field = Some((field.unwrap_or(0) as i16 + delta).clamp(0, 255) as u8);
+            }
+            MidiAction::Command(cmd) if pressed => return Ok(Some(cmd.clone())),
+            MidiAction::Command(_) => {}
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Show;
+
+    #[test]
+    fn midi_bytes_map_to_actions() -> anyhow::Result<()> {
+        let mut parser = MidiParser::default();
+        // note on ch1, running-status note off (velocity 0), clock, CC ch2
+        let bytes = [0x90, 60, 100, 60, 0, 0xf8, 0xb1, 7, 127];
+        let msgs: Vec<_> = bytes.iter().filter_map(|&b| parser.push(b)).collect();
+        assert_eq!(
+            msgs,
+            vec![
+                MidiMessage::NoteOn {
+                    channel: 1,
+                    note: 60,
+                    velocity: 100
+                },
+                MidiMessage::NoteOff {
+                    channel: 1,
+                    note: 60
+                },
+                MidiMessage::ControlChange {
+                    channel: 2,
+                    controller: 7,
+                    value: 127
+                },
+            ]
+        );
+
+        let mut map = MidiMap::default();
+        let (fader, _) = msgs[2].control();
+        map.bind(fader, "fader b".parse()?);
+        map.bind(fader, "gm".parse()?); // rebinding replaces
+        let (button, _) = msgs[0].control();
+        map.bind(button, "cmd goto 5".parse()?);
+        assert_eq!(map.bindings.len(), 2);
+        assert_eq!(
+            map.action_for(fader).map(|a| a.to_string()),
+            Some("gm".into())
+        );
+        assert!("encoder hue".parse::<MidiAction>().is_err());
+
+        let mut rt = Runtime::new(Show::new("Test"));
+        rt.midi_action(&MidiAction::GrandMaster, 64)?;
+        assert_eq!(rt.grand_master(), 128);
+        let (_, velocity) = msgs[0].control();
+        let cmd = map.action_for(button).unwrap().clone();
+        assert_eq!(rt.midi_action(&cmd, velocity)?, Some("goto 5".into()));
+        assert_eq!(rt.midi_action(&cmd, 0)?, None);
+
+        rt.midi_action(&MidiAction::Encoder(EncoderParam::Red), 10)?;
+        rt.midi_action(&MidiAction::Encoder(EncoderParam::Red), 125)?;
+        assert_eq!(rt.programmer.r, Some(7));
+
+        let json = serde_json::to_string(&map)?;
+        assert_eq!(serde_json::from_str::<MidiMap>(&json)?, map);
+        Ok(())
+    }
+}
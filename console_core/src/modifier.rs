@@ -0,0 +1,143 @@
+//! Output modifiers: code outside the engine (scripting, a pixel mapper,
+//! plugins) changing the merged playback values each frame, before they're
+//! rendered to DMX. Modifiers run in the order they were added; switching
+//! one off keeps its place.
+
+use std::fmt::Debug;
+
+use crate::Show;
+use crate::playback::StateMap;
+
+pub trait OutputModifier: Debug + Send {
+    /// Unique within a runtime; used to enable, disable and remove it.
+    fn name(&self) -> &str;
+
+    /// Time passed, from `Runtime::tick`.
+    fn tick(&mut self, _dt_ms: u32) {}
+
+    /// Change the merged playback values (fixture id to values, every
+    /// parameter a playback drives set). Fixtures may be added or removed.
+    /// The programmer and the layers above it are merged afterwards.
+    fn modify(&self, show: &Show, values: &mut StateMap);
+}
+
+#[derive(Debug)]
+struct Registered {
+    modifier: Box<dyn OutputModifier>,
+    enabled: bool,
+}
+
+/// The runtime's modifiers, in running order.
+#[derive(Debug, Default)]
+pub struct Modifiers {
+    list: Vec<Registered>,
+}
+
+impl Modifiers {
+    /// Add a modifier, enabled, after the ones already there.
+    pub fn add(&mut self, modifier: Box<dyn OutputModifier>) -> anyhow::Result<()> {
+        let name = modifier.name();
+        anyhow::ensure!(
+            self.position(name).is_none(),
+            "output modifier '{name}' already registered"
+        );
+        self.list.push(Registered {
+            modifier,
+            enabled: true,
+        });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Box<dyn OutputModifier>> {
+        let idx = self.position(name)?;
+        Some(self.list.remove(idx).modifier)
+    }
+
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> anyhow::Result<()> {
+        let idx = self
+            .position(name)
+            .ok_or_else(|| anyhow::anyhow!("no output modifier '{name}'"))?;
+        self.list[idx].enabled = enabled;
+        Ok(())
+    }
+
+    /// (name, enabled) in running order.
+    pub fn list(&self) -> Vec<(&str, bool)> {
+        self.list
+            .iter()
+            .map(|r| (r.modifier.name(), r.enabled))
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.list.iter().position(|r| r.modifier.name() == name)
+    }
+
+    /// Disabled modifiers keep time too, so they resume in step.
+    pub(crate) fn tick(&mut self, dt_ms: u32) {
+        for r in &mut self.list {
+            r.modifier.tick(dt_ms);
+        }
+    }
+
+    pub(crate) fn apply(&self, show: &Show, values: &mut StateMap) {
+        for r in self.list.iter().filter(|r| r.enabled) {
+            r.modifier.modify(show, values);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CueBuilder, Runtime, ShowBuilder};
+
+    /// Halves intensities, or sets them to a fixed level.
+    #[derive(Debug)]
+    struct Level {
+        name: &'static str,
+        set: Option<u8>,
+    }
+
+    impl OutputModifier for Level {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn modify(&self, _show: &Show, values: &mut StateMap) {
+            for v in values.values_mut() {
+                v.intensity = self.set.or(v.intensity.map(|i| i / 2));
+            }
+        }
+    }
+
+    #[test]
+    fn modifiers_run_in_order_and_can_be_switched_off() -> anyhow::Result<()> {
+        let show = ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "D1", "dimmer_1ch", 1, 1)
+            .cue(CueBuilder::new(1).intensity(1, 200).fade_ms(0))
+            .build()?;
+        let mut rt = Runtime::new(show);
+        rt.playbacks["a"].go(&rt.show)?;
+
+        let set = |name, set| Box::new(Level { name, set });
+        rt.modifiers.add(set("fixed", Some(100)))?;
+        rt.modifiers.add(set("half", None))?;
+        assert!(rt.modifiers.add(set("half", None)).is_err());
+        assert_eq!(rt.render()?.get(1, 1), 50);
+
+        rt.modifiers.set_enabled("fixed", false)?;
+        assert_eq!(rt.render()?.get(1, 1), 100);
+        assert_eq!(rt.modifiers.list(), [("fixed", false), ("half", true)]);
+
+        assert!(rt.modifiers.remove("half").is_some());
+        assert_eq!(rt.render()?.get(1, 1), 200);
+        assert!(rt.modifiers.set_enabled("half", true).is_err());
+        Ok(())
+    }
+}
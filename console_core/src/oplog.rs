@@ -0,0 +1,102 @@
+//! Operator log: timestamped notes ("replaced lamp in 14") kept in the show.
+//!
+//! Clients share a show through its file, so a note is posted by appending
+//! it to the file on disk; other clients pick it up when their
+//! `ShowWatcher` fires. Logs only ever grow, so merging is a union.
+
+use crate::Show;
+use crate::meta::{format_utc, unix_now};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// Unix seconds (UTC).
+    pub time: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub text: String,
+}
+
+impl LogEntry {
+    pub fn now(author: Option<&str>, text: impl Into<String>) -> Self {
+        Self {
+            time: unix_now(),
+            author: author.map(str::to_string),
+            text: text.into(),
+        }
+    }
+
+    /// "2024-05-01 20:13 UTC jane: replaced lamp in 14"
+    pub fn line(&self) -> String {
+        format!(
+            "{} {}: {}",
+            format_utc(self.time),
+            self.author.as_deref().unwrap_or("-"),
+            self.text
+        )
+    }
+}
+
+/// Union of two logs in time order, without duplicates.
+pub fn merge_log(a: &[LogEntry], b: &[LogEntry]) -> Vec<LogEntry> {
+    let mut out: Vec<LogEntry> = a.iter().chain(b).cloned().collect();
+    out.sort();
+    out.dedup();
+    out
+}
+
+/// Entries of `other` that `known` doesn't have yet.
+pub fn new_entries<'a>(known: &[LogEntry], other: &'a [LogEntry]) -> Vec<&'a LogEntry> {
+    other.iter().filter(|e| !known.contains(e)).collect()
+}
+
+/// Append `entry` to the show file on disk, leaving everything else in the
+/// file as it is. Returns the show now on disk.
+pub fn post(show_path: impl AsRef<Path>, entry: LogEntry) -> anyhow::Result<Show> {
+    let path = show_path.as_ref();
+    let mut on_disk = Show::load_json_file(path)?;
+    on_disk.log = merge_log(&on_disk.log, &[entry]);
+    on_disk.save_json_file(path)?;
+    Ok(on_disk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notes_are_posted_to_disk_and_merged() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("lc_oplog_{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("show.json");
+
+        // client A has unsaved edits; posting must not write them
+        let mut a = Show::new("Show");
+        a.save_json_file(&path)?;
+        a.name = "Unsaved".into();
+        let note = LogEntry {
+            time: 100,
+            author: Some("jane".into()),
+            text: "replaced lamp in 14".into(),
+        };
+        let on_disk = post(&path, note.clone())?;
+        assert_eq!(on_disk.name, "Show");
+        assert_eq!(Show::load_json_file(&path)?.log, vec![note.clone()]);
+
+        // client B sees one new entry, then nothing new after merging
+        let b = Show::new("Show");
+        assert_eq!(new_entries(&b.log, &on_disk.log), vec![&note]);
+        let earlier = LogEntry {
+            time: 50,
+            author: None,
+            text: "doors".into(),
+        };
+        let merged = merge_log(&[earlier.clone(), note.clone()], &on_disk.log);
+        assert_eq!(merged, vec![earlier, note]);
+        assert!(new_entries(&merged, &on_disk.log).is_empty());
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,573 @@
+//! Output thread: sends rendered frames to a driver at a fixed rate, with a
+//! watchdog for a stalled/panicked engine and a clean final state on shutdown.
+//! A driver whose device or network goes away is reattached automatically;
+//! frontends poll `OutputThread::events` to tell the operator. Universes can
+//! be held back a few frames to line up with slower links (`set_delays`), and
+//! the whole output held on one frame while programming goes on blind
+//! (`freeze`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::{Clock, FrameLog, LiveState, MonotonicClock};
+
+/// What the outputs show once the engine is gone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShutdownLook {
+    /// Keep sending the last frame.
+    #[default]
+    HoldLast,
+    /// Zero every address that was in use.
+    Blackout,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputConfig {
+    /// Frame interval (DMX refresh).
+    pub refresh_ms: u32,
+    /// No frame submitted for this long = engine stalled; switch to the shutdown look.
+    pub watchdog_ms: u32,
+    pub shutdown_look: ShutdownLook,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            refresh_ms: 25,
+            watchdog_ms: 1000,
+            shutdown_look: ShutdownLook::default(),
+        }
+    }
+}
+
+/// How often a failing driver is asked to reattach.
+pub const RECONNECT_MS: u64 = 1000;
+
+/// Connection changes reported by an output thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputEvent {
+    /// Sending failed (device unplugged, network down...); output is paused.
+    Disconnected { driver: String, error: String },
+    /// Frames are going out again.
+    Reconnected { driver: String },
+}
+
+/// A protocol/device sink (Art-Net, sACN, USB...).
+pub trait OutputDriver: Send {
+    fn send(&mut self, frame: &LiveState) -> anyhow::Result<()>;
+
+    /// Name for status messages, e.g. "Art-Net 2.255.255.255".
+    fn name(&self) -> String {
+        "output".to_string()
+    }
+
+    /// Reopen the device/socket after a failed send (hot-plug, changed
+    /// network interface). Called every `RECONNECT_MS` while sends fail.
+    fn reconnect(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Called once after the final frame, e.g. to send sACN stream-termination packets.
+    fn terminate(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct Latest {
+    frame: LiveState,
+    submitted_ms: u64,
+    freeze: Option<Freeze>,
+}
+
+impl Latest {
+    /// What goes out at `now_ms`: the submitted frame unless frozen.
+    fn output(&self, now_ms: u64) -> LiveState {
+        match &self.freeze {
+            None => self.frame.clone(),
+            Some(f) => match f.release {
+                None => f.frame.clone(),
+                Some((start, fade)) => {
+                    let t = now_ms.saturating_sub(start).min(fade as u64) as u32;
+                    crossfade(&f.frame, &self.frame, t, fade)
+                }
+            },
+        }
+    }
+}
+
+/// Held output frame; `release` is Some((started_ms, fade_ms)) while
+/// crossfading back to the submitted frames.
+#[derive(Debug)]
+struct Freeze {
+    frame: LiveState,
+    release: Option<(u64, u32)>,
+}
+
+/// Runs a driver on its own thread. Dropping it (also while unwinding from a
+/// panic) sends the shutdown look and terminates the driver.
+#[derive(Debug)]
+pub struct OutputThread {
+    latest: Arc<Mutex<Latest>>,
+    stop: Arc<AtomicBool>,
+    clock: Box<dyn Clock>,
+    events: Receiver<OutputEvent>,
+    frame_log: FrameLog,
+    delays: Arc<Mutex<BTreeMap<u16, u32>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl OutputThread {
+    pub fn spawn(driver: Box<dyn OutputDriver>, config: OutputConfig) -> Self {
+        Self::spawn_with_clock(driver, config, MonotonicClock::new())
+    }
+
+    /// Like `spawn`, with the watchdog timed by `clock` (e.g. `SimClock` in tests).
+    pub fn spawn_with_clock<C: Clock + Clone + 'static>(
+        mut driver: Box<dyn OutputDriver>,
+        config: OutputConfig,
+        clock: C,
+    ) -> Self {
+        let latest = Arc::new(Mutex::new(Latest {
+            frame: LiveState::new(),
+            submitted_ms: clock.now_ms(),
+            freeze: None,
+        }));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (events_tx, events) = channel();
+        let frame_log = FrameLog::default();
+        let delays = Arc::new(Mutex::new(BTreeMap::new()));
+
+        let handle = {
+            let latest = latest.clone();
+            let stop = stop.clone();
+            let clock = clock.clone();
+            let frame_log = frame_log.clone();
+            let delays = delays.clone();
+            std::thread::spawn(move || {
+                let mut link = Link::default();
+                let mut delay_line = DelayLine::default();
+                while !stop.load(Ordering::SeqCst) {
+                    let (frame, age) = {
+                        // a panicking engine may poison the lock; the frame is still usable
+                        let l = latest.lock().unwrap_or_else(|e| e.into_inner());
+                        let now = clock.now_ms();
+                        (l.output(now), now.saturating_sub(l.submitted_ms))
+                    };
+
+                    let frame = if age > config.watchdog_ms as u64 {
+                        final_frame(&frame, config.shutdown_look)
+                    } else {
+                        frame
+                    };
+                    delay_line.delays = delays.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                    let frame = delay_line.apply(clock.now_ms(), frame);
+                    // a failing driver must not kill the loop; keep trying
+                    let sent = driver.send(&frame);
+                    if sent.is_ok() {
+                        frame_log.record(clock.now_ms(), &frame);
+                    }
+                    link.update(driver.as_mut(), sent, clock.now_ms(), &events_tx);
+
+                    std::thread::sleep(Duration::from_millis(config.refresh_ms.max(1) as u64));
+                }
+
+                let last = latest
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .output(clock.now_ms());
+                let _ = driver.send(&final_frame(&last, config.shutdown_look));
+                let _ = driver.terminate();
+            })
+        };
+
+        Self {
+            latest,
+            stop,
+            clock: Box::new(clock),
+            events,
+            frame_log,
+            delays,
+            handle: Some(handle),
+        }
+    }
+
+    /// Hold universes back by the given milliseconds, e.g. to land fades on
+    /// wired universes together with a media server or wireless link.
+    pub fn set_delays(&self, delays: &BTreeMap<u16, u32>) {
+        *self.delays.lock().unwrap_or_else(|e| e.into_inner()) = delays.clone();
+    }
+
+    /// Log of the frames this thread sends; off until enabled.
+    pub fn frame_log(&self) -> &FrameLog {
+        &self.frame_log
+    }
+
+    /// Connection events since the last call.
+    pub fn events(&self) -> Vec<OutputEvent> {
+        self.events.try_iter().collect()
+    }
+
+    /// Post the newest rendered frame; also feeds the watchdog.
+    pub fn submit(&self, frame: LiveState) {
+        let mut l = self.latest.lock().unwrap_or_else(|e| e.into_inner());
+        l.frame = frame;
+        l.submitted_ms = self.clock.now_ms();
+    }
+
+    /// Hold the outputs on the frame going out now. Frames submitted
+    /// meanwhile (programming continues blind) come back with `unfreeze`.
+    pub fn freeze(&self) {
+        let now = self.clock.now_ms();
+        let mut l = self.latest.lock().unwrap_or_else(|e| e.into_inner());
+        let frame = l.output(now);
+        l.freeze = Some(Freeze {
+            frame,
+            release: None,
+        });
+    }
+
+    /// Crossfade from the held frame back to the submitted ones.
+    pub fn unfreeze(&self, fade_ms: u32) {
+        let now = self.clock.now_ms();
+        let mut l = self.latest.lock().unwrap_or_else(|e| e.into_inner());
+        match &mut l.freeze {
+            Some(f) if fade_ms > 0 => f.release = Some((now, fade_ms)),
+            _ => l.freeze = None,
+        }
+    }
+
+    /// True while the outputs are held (including the fade back to live).
+    pub fn is_frozen(&self) -> bool {
+        let now = self.clock.now_ms();
+        let mut l = self.latest.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(Freeze {
+            release: Some((start, fade)),
+            ..
+        }) = l.freeze
+            && now >= start + fade as u64
+        {
+            l.freeze = None;
+        }
+        l.freeze.is_some()
+    }
+
+    /// Stop the thread, sending the shutdown look and terminating the driver.
+    pub fn shutdown(mut self) {
+        self.stop_and_join();
+    }
+
+    fn stop_and_join(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(h) = self.handle.take() {
+            let _ = h.join();
+        }
+    }
+}
+
+impl Drop for OutputThread {
+    fn drop(&mut self) {
+        self.stop_and_join();
+    }
+}
+
+/// Driver connection state inside the output thread.
+#[derive(Debug, Default)]
+struct Link {
+    /// Some(last reconnect attempt) while sends fail.
+    down_since_retry: Option<u64>,
+}
+
+impl Link {
+    fn update(
+        &mut self,
+        driver: &mut dyn OutputDriver,
+        sent: anyhow::Result<()>,
+        now_ms: u64,
+        events: &Sender<OutputEvent>,
+    ) {
+        match (sent, self.down_since_retry) {
+            (Ok(()), None) => {}
+            (Ok(()), Some(_)) => {
+                self.down_since_retry = None;
+                let _ = events.send(OutputEvent::Reconnected {
+                    driver: driver.name(),
+                });
+            }
+            (Err(e), None) => {
+                self.down_since_retry = Some(now_ms);
+                let _ = events.send(OutputEvent::Disconnected {
+                    driver: driver.name(),
+                    error: format!("{e:#}"),
+                });
+            }
+            (Err(_), Some(last)) if now_ms.saturating_sub(last) >= RECONNECT_MS => {
+                self.down_since_retry = Some(now_ms);
+                let _ = driver.reconnect();
+            }
+            (Err(_), Some(_)) => {}
+        }
+    }
+}
+
+/// Recent frames, so delayed universes can be sent as they were a while ago.
+#[derive(Debug, Default)]
+struct DelayLine {
+    delays: BTreeMap<u16, u32>,
+    history: VecDeque<(u64, LiveState)>,
+}
+
+impl DelayLine {
+    fn apply(&mut self, now_ms: u64, frame: LiveState) -> LiveState {
+        let Some(&longest) = self.delays.values().max() else {
+            self.history.clear();
+            return frame;
+        };
+        self.history.push_back((now_ms, frame.clone()));
+        // keep the newest frame that is at least `longest` old
+        while self
+            .history
+            .get(1)
+            .is_some_and(|(at, _)| at + longest as u64 <= now_ms)
+        {
+            self.history.pop_front();
+        }
+        let mut out = frame;
+        for (&universe, &delay) in &self.delays {
+            let due = now_ms.saturating_sub(delay as u64);
+            let (_, past) = self
+                .history
+                .iter()
+                .rev()
+                .find(|(at, _)| *at <= due)
+                .unwrap_or(&self.history[0]);
+            match past.universes.get(&universe) {
+                Some(addrs) => out.universes.insert(universe, addrs.clone()),
+                None => out.universes.remove(&universe),
+            };
+        }
+        out
+    }
+}
+
+fn crossfade(from: &LiveState, to: &LiveState, t: u32, dur: u32) -> LiveState {
+    let mut out = LiveState::new();
+    let t = t.min(dur) as i64;
+    let dur = dur.max(1) as i64;
+
+    let value = |s: &LiveState, u: u16, a: u16| -> i64 {
+        s.universes
+            .get(&u)
+            .and_then(|m| m.get(&a))
+            .copied()
+            .unwrap_or(0) as i64
+    };
+
+    for s in [from, to] {
+        for (&u, addrs) in &s.universes {
+            for &a in addrs.keys() {
+                let (f, to_v) = (value(from, u, a), value(to, u, a));
+                out.set(u, a, (f + (to_v - f) * t / dur) as u8);
+            }
+        }
+    }
+    out
+}
+
+fn final_frame(last: &LiveState, look: ShutdownLook) -> LiveState {
+    match look {
+        ShutdownLook::HoldLast => last.clone(),
+        ShutdownLook::Blackout => {
+            let mut out = last.clone();
+            for addrs in out.universes.values_mut() {
+                addrs.values_mut().for_each(|v| *v = 0);
+            }
+            out
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimClock;
+    use std::time::Instant;
+
+    #[derive(Clone, Default)]
+    struct Recorder {
+        frames: Arc<Mutex<Vec<LiveState>>>,
+        terminated: Arc<AtomicBool>,
+    }
+
+    impl OutputDriver for Recorder {
+        fn send(&mut self, frame: &LiveState) -> anyhow::Result<()> {
+            self.frames.lock().unwrap().push(frame.clone());
+            Ok(())
+        }
+
+        fn terminate(&mut self) -> anyhow::Result<()> {
+            self.terminated.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    impl Recorder {
+        fn wait_for(&self, want: impl Fn(&LiveState) -> bool) -> bool {
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_secs(5) {
+                if self.frames.lock().unwrap().last().is_some_and(&want) {
+                    return true;
+                }
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            false
+        }
+    }
+
+    #[test]
+    fn watchdog_blacks_out_stalled_engine_and_drop_terminates() {
+        let rec = Recorder::default();
+        let clock = SimClock::new();
+        let config = OutputConfig {
+            refresh_ms: 1,
+            watchdog_ms: 100,
+            shutdown_look: ShutdownLook::Blackout,
+        };
+        let out = OutputThread::spawn_with_clock(Box::new(rec.clone()), config, clock.clone());
+
+        let mut frame = LiveState::new();
+        frame.set(1, 1, 255);
+        out.submit(frame);
+        assert!(rec.wait_for(|f| f.nonzero() == vec![(1, 1, 255)]));
+
+        // engine stops submitting
+        clock.advance(500);
+        assert!(rec.wait_for(|f| f.nonzero().is_empty() && !f.universes.is_empty()));
+
+        drop(out);
+        assert!(rec.terminated.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn freeze_holds_output_and_fades_back() {
+        let rec = Recorder::default();
+        let clock = SimClock::new();
+        let config = OutputConfig {
+            refresh_ms: 1,
+            watchdog_ms: 60_000,
+            shutdown_look: ShutdownLook::HoldLast,
+        };
+        let out = OutputThread::spawn_with_clock(Box::new(rec.clone()), config, clock.clone());
+
+        let mut held = LiveState::new();
+        held.set(1, 10, 200);
+        out.submit(held);
+        assert!(rec.wait_for(|f| f.nonzero() == vec![(1, 10, 200)]));
+        out.freeze();
+
+        // programming continues blind
+        let mut live = LiveState::new();
+        live.set(1, 10, 0);
+        live.set(1, 11, 100);
+        out.submit(live);
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(
+            rec.frames.lock().unwrap().last().unwrap().nonzero(),
+            vec![(1, 10, 200)]
+        );
+
+        out.unfreeze(1000);
+        clock.advance(500);
+        assert!(rec.wait_for(|f| f.nonzero() == vec![(1, 10, 100), (1, 11, 50)]));
+        assert!(out.is_frozen());
+
+        clock.advance(500);
+        assert!(rec.wait_for(|f| f.nonzero() == vec![(1, 11, 100)]));
+        assert!(!out.is_frozen());
+    }
+
+    #[test]
+    fn delayed_universes_lag_behind() {
+        let mut line = DelayLine {
+            delays: BTreeMap::from([(2, 100)]),
+            ..Default::default()
+        };
+        let frame = |v| {
+            let mut f = LiveState::new();
+            f.set(1, 1, v);
+            f.set(2, 1, v);
+            f
+        };
+        let mut sent = Vec::new();
+        for (t, v) in [(0, 10), (50, 20), (100, 30), (150, 40), (225, 50)] {
+            let out = line.apply(t, frame(v));
+            sent.push((out.get(1, 1), out.get(2, 1)));
+        }
+        assert_eq!(sent, [(10, 10), (20, 10), (30, 10), (40, 20), (50, 30)]);
+        assert_eq!(line.history.len(), 3);
+
+        line.delays.clear();
+        assert_eq!(line.apply(250, frame(60)).get(2, 1), 60);
+        assert!(line.history.is_empty());
+    }
+
+    /// Fails while `unplugged`; `reconnect` plugs it back in.
+    #[derive(Clone, Default)]
+    struct Flaky {
+        unplugged: Arc<AtomicBool>,
+    }
+
+    impl OutputDriver for Flaky {
+        fn send(&mut self, _frame: &LiveState) -> anyhow::Result<()> {
+            anyhow::ensure!(!self.unplugged.load(Ordering::SeqCst), "device gone");
+            Ok(())
+        }
+
+        fn name(&self) -> String {
+            "flaky".into()
+        }
+
+        fn reconnect(&mut self) -> anyhow::Result<()> {
+            self.unplugged.store(false, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn failing_driver_is_reattached_with_events() {
+        let flaky = Flaky::default();
+        let clock = SimClock::new();
+        let config = OutputConfig {
+            refresh_ms: 1,
+            ..Default::default()
+        };
+        let out = OutputThread::spawn_with_clock(Box::new(flaky.clone()), config, clock.clone());
+
+        flaky.unplugged.store(true, Ordering::SeqCst);
+        let mut events = Vec::new();
+        let start = Instant::now();
+        while events.len() < 2 && start.elapsed() < Duration::from_secs(5) {
+            clock.advance(100);
+            events.extend(out.events());
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(
+            events,
+            vec![
+                OutputEvent::Disconnected {
+                    driver: "flaky".into(),
+                    error: "device gone".into()
+                },
+                OutputEvent::Reconnected {
+                    driver: "flaky".into()
+                },
+            ]
+        );
+    }
+}
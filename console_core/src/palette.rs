@@ -1,12 +1,15 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::{ChannelKind, FixtureType, FixtureValues, Meta, Show};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PaletteKind {
     Intensity,
     Color,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PaletteValues {
     pub intensity: Option<u8>,
     pub r: Option<u8>,
@@ -14,14 +17,171 @@ pub struct PaletteValues {
     pub b: Option<u8>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Palette {
     pub kind: PaletteKind,
     pub values: PaletteValues,
+
+    #[serde(default, skip_serializing_if = "Meta::is_empty")]
+    pub meta: Meta,
 }
 
 impl Palette {
     pub fn new(kind: PaletteKind, values: PaletteValues) -> Self {
-        Self { kind, values }
+        Self {
+            kind,
+            values,
+            meta: Meta::default(),
+        }
+    }
+
+    /// A color palette from a swatch.
+    pub fn from_rgb([r, g, b]: [u8; 3]) -> Self {
+        Self::new(
+            PaletteKind::Color,
+            PaletteValues {
+                r: Some(r),
+                g: Some(g),
+                b: Some(b),
+                ..Default::default()
+            },
+        )
+    }
+}
+
+/// Built-in palettes every show has. Their values come from each fixture's
+/// type, so they work before anything is recorded; they can't be recorded
+/// over or deleted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemPalette {
+    /// Intensity at full.
+    Full,
+    /// Intensity out.
+    Out,
+    /// Every color channel at full.
+    White,
+    /// Intensity, color and position back to the type's defaults.
+    Home,
+}
+
+impl SystemPalette {
+    pub const ALL: [Self; 4] = [Self::Full, Self::Out, Self::White, Self::Home];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Out => "out",
+            Self::White => "white",
+            Self::Home => "home",
+        }
+    }
+
+    /// Case-insensitive; `open white` is accepted for `white`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        let name = if name == "open white" { "white" } else { &name };
+        Self::ALL.into_iter().find(|p| p.name() == name)
+    }
+
+    /// What the palette sets on a fixture of type `ft`; only the parameters
+    /// the type has. None if it has none of them.
+    pub fn values_for(self, ft: &FixtureType) -> Option<FixtureValues> {
+        let channel = |kind: ChannelKind| ft.channels.iter().find(|c| c.kind == kind);
+        let set = |kind: ChannelKind, level: u8| channel(kind).map(|_| level);
+        let home = |kind: ChannelKind, level: u8| channel(kind).map(|c| c.default.unwrap_or(level));
+        let values = match self {
+            Self::Full => FixtureValues {
+                intensity: set(ChannelKind::Intensity, 255),
+                ..Default::default()
+            },
+            Self::Out => FixtureValues {
+                intensity: set(ChannelKind::Intensity, 0),
+                ..Default::default()
+            },
+            Self::White => FixtureValues {
+                intensity: None,
+                r: set(ChannelKind::ColorR, 255),
+                g: set(ChannelKind::ColorG, 255),
+                b: set(ChannelKind::ColorB, 255),
+                ..Default::default()
+            },
+            Self::Home => FixtureValues {
+                intensity: home(ChannelKind::Intensity, 0),
+                r: home(ChannelKind::ColorR, 255),
+                g: home(ChannelKind::ColorG, 255),
+                b: home(ChannelKind::ColorB, 255),
+                pan: home(ChannelKind::Pan, 128),
+                tilt: home(ChannelKind::Tilt, 128),
+            },
+        };
+        (!values.is_all_none()).then_some(values)
+    }
+}
+
+impl std::fmt::Display for SystemPalette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Show {
+    /// Palette names that would hide a system palette are refused.
+    pub fn check_palette_name(&self, name: &str) -> anyhow::Result<()> {
+        anyhow::ensure!(!name.trim().is_empty(), "Palette name can't be empty");
+        if let Some(sys) = SystemPalette::from_name(name) {
+            anyhow::bail!("'{name}' is the built-in '{sys}' palette and can't be changed");
+        }
+        Ok(())
+    }
+
+    /// (cue list, cue) of every cue referencing palette `name`.
+    pub fn palette_users(&self, name: &str) -> Vec<(String, u32)> {
+        self.cue_lists
+            .iter()
+            .flat_map(|(list, cl)| {
+                cl.cues
+                    .values()
+                    .filter(|c| {
+                        c.palette_refs
+                            .values()
+                            .any(|r| r.names().any(|n| n == name))
+                    })
+                    .map(move |c| (list.clone(), c.number))
+            })
+            .collect()
+    }
+}
+
+/// Palettes a cue uses on one fixture, by name. They are resolved when the
+/// cue is played, so editing a palette updates every cue referencing it; the
+/// values recorded with the cue are used if the palette is deleted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaletteRefs {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intensity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+impl PaletteRefs {
+    pub fn is_empty(&self) -> bool {
+        self.intensity.is_none() && self.color.is_none()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.intensity.iter().chain(&self.color).map(String::as_str)
+    }
+
+    /// Overwrite `values` with the referenced palettes' current values.
+    pub fn resolve(&self, palettes: &BTreeMap<String, Palette>, values: &mut FixtureValues) {
+        let pal = |name: &Option<String>| name.as_ref().and_then(|n| palettes.get(n));
+        if let Some(p) = pal(&self.intensity) {
+            values.intensity = p.values.intensity.or(values.intensity);
+        }
+        if let Some(p) = pal(&self.color) {
+            values.r = p.values.r.or(values.r);
+            values.g = p.values.g.or(values.g);
+            values.b = p.values.b.or(values.b);
+        }
     }
 }
@@ -1,9 +1,11 @@
 use anyhow::Context;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use crate::{ChannelKind, FixtureValues, LiveState, Show};
+use crate::{ChannelKind, CueList, FixtureValues, LiveState, Show};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PlaybackMode {
     Tracking,
     CueOnly,
@@ -12,10 +14,192 @@ pub enum PlaybackMode {
 #[derive(Debug, Clone)]
 struct Transition {
     from: BTreeMap<u32, FixtureValues>, // fully-resolved: Some(...) for all fields
-    to: BTreeMap<u32, FixtureValues>,   // fully-resolved
+    /// Fine bytes of `from` when it was caught mid-fade.
+    from_fine: StateMap,
+    to: BTreeMap<u32, FixtureValues>, // fully-resolved
     elapsed_ms: u32,
     fade_ms: u32,
     delay_ms: u32,
+    /// Crossfader position in percent; Some = manual fade, time doesn't complete it.
+    manual_pos: Option<u8>,
+    /// Split crossfade: falling intensities follow this position instead.
+    manual_down: Option<u8>,
+}
+
+impl Transition {
+    /// How far the fade is, in percent, manual or timed.
+    fn progress(&self) -> u8 {
+        if let Some(pos) = self.manual_pos {
+            return pos;
+        }
+        if self.elapsed_ms < self.delay_ms {
+            return 0;
+        }
+        if self.fade_ms == 0 {
+            return 100;
+        }
+        let t = (self.elapsed_ms - self.delay_ms).min(self.fade_ms);
+        (t as u64 * 100 / self.fade_ms as u64) as u8
+    }
+}
+
+/// Which parameters a playback contributes to the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParamFilter {
+    pub intensity: bool,
+    pub color: bool,
+    /// Pan and tilt. Filters saved before it existed didn't pass it.
+    #[serde(default)]
+    pub position: bool,
+}
+
+impl Default for ParamFilter {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl ParamFilter {
+    pub const ALL: Self = Self {
+        intensity: true,
+        color: true,
+        position: true,
+    };
+    pub const INTENSITY: Self = Self {
+        intensity: true,
+        ..Self::NONE
+    };
+    pub const COLOR: Self = Self {
+        color: true,
+        ..Self::NONE
+    };
+    pub const POSITION: Self = Self {
+        position: true,
+        ..Self::NONE
+    };
+
+    pub const NONE: Self = Self {
+        intensity: false,
+        color: false,
+        position: false,
+    };
+
+    pub fn is_all(&self) -> bool {
+        *self == Self::ALL
+    }
+
+    pub fn is_none(&self) -> bool {
+        *self == Self::NONE
+    }
+
+    fn apply(&self, v: &mut FixtureValues) {
+        if !self.intensity {
+            v.intensity = None;
+        }
+        if !self.color {
+            v.r = None;
+            v.g = None;
+            v.b = None;
+        }
+        if !self.position {
+            v.pan = None;
+            v.tilt = None;
+        }
+    }
+
+    /// The opposite of `apply`: clear the parameters this filter passes.
+    fn remove(&self, v: &mut FixtureValues) {
+        Self {
+            intensity: !self.intensity,
+            color: !self.color,
+            position: !self.position,
+        }
+        .apply(v)
+    }
+
+    /// Set the parameters this filter passes to zero (released, faded from).
+    /// Position has no zero to fade from and stays where it is.
+    fn zero(&self, v: &mut FixtureValues) {
+        let zero = FixtureValues {
+            intensity: self.intensity.then_some(0),
+            r: self.color.then_some(0),
+            g: self.color.then_some(0),
+            b: self.color.then_some(0),
+            ..Default::default()
+        };
+        v.apply_delta(&zero);
+    }
+}
+
+impl std::str::FromStr for ParamFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "all" => Ok(Self::ALL),
+            "intensity" => Ok(Self::INTENSITY),
+            "color" => Ok(Self::COLOR),
+            "position" => Ok(Self::POSITION),
+            _ => anyhow::bail!("unknown parameters '{s}' (use all|intensity|color|position)"),
+        }
+    }
+}
+
+/// Per-playback settings saved in the show (keyed by playback name, e.g. "a").
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlaybackConfig {
+    #[serde(default, skip_serializing_if = "ParamFilter::is_all")]
+    pub filter: ParamFilter,
+    /// See `Playback::priority`.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub priority: u8,
+    /// Cue list the playback runs; None means "main".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cuelist: Option<String>,
+}
+
+/// Short description of a cue for status displays.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CueSummary {
+    pub number: u32,
+    pub label: String,
+    pub fade_ms: u32,
+    pub delay_ms: u32,
+    /// Go into it must be confirmed (see `Cue::confirm`).
+    pub confirm: bool,
+}
+
+/// What a playback is doing and what Go will do next.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlaybackStatus {
+    pub current: Option<CueSummary>,
+    /// Cue that `go` moves to; None at the end of the list.
+    pub next: Option<CueSummary>,
+    /// Time left in the running delay + fade.
+    pub remaining_ms: Option<u32>,
+}
+
+/// Protection against accidental double Go presses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GoGuard {
+    /// Ignore a Go pressed within this many ms of the previous one (0 = off).
+    pub debounce_ms: u32,
+    /// Ask for confirmation while the running fade is in its first 10%.
+    pub confirm_early_fade: bool,
+}
+
+/// Result of a guarded Go press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoOutcome {
+    /// The playback moved (or stayed at the last cue).
+    Went(Option<u32>),
+    /// Swallowed by the debounce window.
+    Ignored,
+    /// Frontend should confirm, then call `go` directly.
+    NeedsConfirm,
+    /// The next cue needs confirming and is now armed: Go again (or call
+    /// `go`) to fire it, `disarm` to cancel.
+    CueNeedsConfirm(u32),
 }
 
 #[derive(Debug, Clone)]
@@ -23,7 +207,44 @@ pub struct Playback {
     pub cuelist: String,
     pub current: Option<u32>,
     pub mode: PlaybackMode,
+    pub go_guard: GoGuard,
+    /// Parameters this playback contributes; others are left to other playbacks.
+    pub filter: ParamFilter,
+    /// While any playback is soloed, only soloed playbacks reach the output.
+    pub solo: bool,
+    /// Playback fader: scales this playback's intensities (255 = full).
+    pub master: u8,
+    /// Flash button held: intensities at full, ignoring `master`.
+    pub flash: bool,
+    /// Merge priority: parameters set by a higher priority playback win
+    /// over lower ones, intensities included.
+    pub priority: u8,
+    /// Stamp of the last cue change; between equal priorities the latest
+    /// wins LTP parameters.
+    last_action: u64,
+    /// Rate override: fade, delay and follow times run this many times
+    /// faster (1.0 = as recorded). Set with `set_rate`.
+    rate: f32,
+    /// Fraction of a scaled millisecond left over from the last tick.
+    rate_carry: f32,
     transition: Option<Transition>,
+    since_go_ms: Option<u32>,
+    /// Time spent on the current cue (not counting pauses); drives follows.
+    on_cue_ms: u32,
+    /// (cue, ms) measured by the last completed manual crossfade.
+    learned: Option<(u32, u32)>,
+    /// Running fade is held; the next Go/Back resumes.
+    paused: bool,
+    /// Look left on stage by a blind goto; replaced by the next cue change.
+    held: Option<(StateMap, StateMap)>,
+    /// Parameters taken out of this playback's output per fixture, until a
+    /// cue sets them again (see `release_fixtures`).
+    released: BTreeMap<u32, ParamFilter>,
+    /// Confirm cue armed by a first Go; cleared on any cue change.
+    armed: Option<u32>,
+    /// Links taken from each cue in the loop being run (see `Cue::link`);
+    /// a goto leaves every loop.
+    link_passes: BTreeMap<u32, u32>,
 }
 
 impl Playback {
@@ -32,8 +253,47 @@ impl Playback {
             cuelist: cuelist.into(),
             current: None,
             mode: PlaybackMode::Tracking,
+            go_guard: GoGuard::default(),
+            filter: ParamFilter::ALL,
+            solo: false,
+            master: 255,
+            flash: false,
+            priority: 0,
+            last_action: 0,
+            rate: 1.0,
+            rate_carry: 0.0,
             transition: None,
+            since_go_ms: None,
+            on_cue_ms: 0,
+            learned: None,
+            paused: false,
+            held: None,
+            released: BTreeMap::new(),
+            armed: None,
+            link_passes: BTreeMap::new(),
+        }
+    }
+
+    /// Retarget to another cue list. The playback starts over at the top
+    /// of the new list; retargeting to the current list does nothing.
+    pub fn use_list(&mut self, show: &Show, cuelist: &str) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            show.cue_lists.contains_key(cuelist),
+            "unknown cuelist '{cuelist}'"
+        );
+        if self.cuelist != cuelist {
+            self.cuelist = cuelist.to_string();
+            self.current = None;
+            self.transition = None;
+            self.since_go_ms = None;
+            self.learned = None;
+            self.paused = false;
+            self.held = None;
+            self.released.clear();
+            self.armed = None;
+            self.link_passes.clear();
         }
+        Ok(())
     }
 
     pub fn on_cue_deleted(&mut self, cue: u32) {
@@ -43,6 +303,16 @@ impl Playback {
         }
     }
 
+    /// Follow cues renumbered by `move cue` or `renumber` ((old, new) pairs), so the
+    /// playback stays on the cue it was on.
+    pub fn on_cues_moved(&mut self, moves: &[(u32, u32)]) {
+        let Some(&(_, to)) = moves.iter().find(|(from, _)| self.current == Some(*from)) else {
+            return;
+        };
+        self.current = Some(to);
+        self.armed = None;
+    }
+
     fn state_map_at(
         &self,
         show: &Show,
@@ -64,25 +334,7 @@ impl Playback {
             .get(&self.cuelist)
             .with_context(|| format!("unknown cuelist '{}'", self.cuelist))?;
 
-        let mut tracked: BTreeMap<u32, FixtureValues> = BTreeMap::new();
-
-        for (&num, cue) in &list.cues {
-            if num > cue_num {
-                break;
-            }
-
-            if cue.block {
-                for &fid in cue.changes.keys() {
-                    tracked.insert(fid, FixtureValues::default());
-                }
-            }
-
-            for (&fid, delta) in &cue.changes {
-                tracked.entry(fid).or_default().apply_delta(delta);
-            }
-        }
-
-        Ok(tracked)
+        Ok(list.resolved_state_at(cue_num, &show.palettes))
     }
 
     fn cue_only_state_at(
@@ -95,11 +347,14 @@ impl Playback {
             .get(&self.cuelist)
             .with_context(|| format!("unknown cuelist '{}'", self.cuelist))?;
 
-        Ok(list
-            .cues
-            .get(&cue_num)
-            .map(|c| c.changes.clone())
-            .unwrap_or_default())
+        let Some(cue) = list.cues.get(&cue_num) else {
+            return Ok(BTreeMap::new());
+        };
+        let mut state = cue.changes.clone();
+        for (&fid, refs) in &cue.palette_refs {
+            refs.resolve(&show.palettes, state.entry(fid).or_default());
+        }
+        Ok(state)
     }
 
     fn resolve_map(mut m: BTreeMap<u32, FixtureValues>) -> BTreeMap<u32, FixtureValues> {
@@ -120,28 +375,129 @@ impl Playback {
         m
     }
 
+    /// Visible output with `filter` applied: filtered-out parameters are None.
     pub fn output_state_map(&self, show: &Show) -> anyhow::Result<BTreeMap<u32, FixtureValues>> {
+        Ok(self.output_levels(show)?.0)
+    }
+
+    /// Like `output_state_map`, plus the fine (low) bytes of the 16-bit
+    /// values mid-fade. Parameters without a fine byte are at whole 8-bit steps.
+    pub fn output_levels(&self, show: &Show) -> anyhow::Result<(StateMap, StateMap)> {
+        let (mut out, mut fine) = self.unfiltered_output(show)?;
+        if !self.filter.is_all() {
+            out.values_mut().for_each(|v| self.filter.apply(v));
+            fine.values_mut().for_each(|v| self.filter.apply(v));
+        }
+        for (fid, params) in &self.released {
+            out.entry(*fid).and_modify(|v| params.remove(v));
+            fine.entry(*fid).and_modify(|v| params.remove(v));
+        }
+        out.retain(|_, v| !v.is_all_none());
+        Ok((out, fine))
+    }
+
+    fn unfiltered_output(&self, show: &Show) -> anyhow::Result<(StateMap, StateMap)> {
+        if let Some(held) = &self.held {
+            return Ok(held.clone());
+        }
         if let Some(tr) = &self.transition {
+            if let Some(pos) = tr.manual_pos {
+                let (mut out, mut fine) = interpolate_maps(tr, pos as u32, 100);
+                if let Some(down) = tr.manual_down.filter(|&d| d != pos) {
+                    let (d_out, d_fine) = interpolate_maps(tr, down as u32, 100);
+                    let level = |m: &StateMap, fid| m.get(fid).and_then(|v| v.intensity);
+                    for (fid, v) in out.iter_mut() {
+                        if level(&tr.from, fid).unwrap_or(0) > level(&tr.to, fid).unwrap_or(0) {
+                            v.intensity = level(&d_out, fid);
+                            fine.entry(*fid).or_default().intensity = level(&d_fine, fid);
+                        }
+                    }
+                }
+                if pos > 0 {
+                    snap_channels(show, &mut out, &mut fine, &tr.to);
+                }
+                return Ok((out, fine));
+            }
+
             // During delay: hold the start look
             if tr.elapsed_ms < tr.delay_ms {
-                return Ok(tr.from.clone());
+                return Ok((tr.from.clone(), tr.from_fine.clone()));
             }
 
             // After delay: fade from -> to
             if tr.fade_ms == 0 {
-                return Ok(tr.to.clone());
+                return Ok((tr.to.clone(), BTreeMap::new()));
             }
 
             let t = (tr.elapsed_ms - tr.delay_ms).min(tr.fade_ms);
-            return Ok(interpolate_maps(&tr.from, &tr.to, t, tr.fade_ms));
+            let (mut out, mut fine) = interpolate_maps(tr, t, tr.fade_ms);
+            snap_channels(show, &mut out, &mut fine, &tr.to);
+            return Ok((out, fine));
         }
 
         let Some(cur) = self.current else {
-            return Ok(BTreeMap::new());
+            return Ok((BTreeMap::new(), BTreeMap::new()));
         };
 
         let raw = self.state_map_at(show, cur)?;
-        Ok(Self::resolve_map(raw))
+        let mut out = Self::resolve_map(raw);
+        for (fid, mark) in self.marks_from(show, &out) {
+            out.insert(fid, mark);
+        }
+        Ok((out, BTreeMap::new()))
+    }
+
+    /// Move in black lookahead: fixtures dark at the current cue that come
+    /// up in the next one, with the values they're pre-positioned to (still
+    /// at zero intensity). Empty unless the list has `move_in_black` on and
+    /// the playback is resting on a cue.
+    pub fn marks(&self, show: &Show) -> anyhow::Result<StateMap> {
+        let Some(cur) = self.current else {
+            return Ok(StateMap::new());
+        };
+        if self.transition.is_some() || self.held.is_some() {
+            return Ok(StateMap::new());
+        }
+        let resting = Self::resolve_map(self.state_map_at(show, cur)?);
+        Ok(self.marks_from(show, &resting))
+    }
+
+    fn marks_from(&self, show: &Show, resting: &StateMap) -> StateMap {
+        let marking = show
+            .cue_lists
+            .get(&self.cuelist)
+            .is_some_and(|l| l.move_in_black);
+        let Some(next) = self.next_cue(show).filter(|_| marking) else {
+            return StateMap::new();
+        };
+        let Ok(next_state) = self.state_map_at(show, next) else {
+            return StateMap::new();
+        };
+        let mut marks = StateMap::new();
+        for (&fid, up) in &next_state {
+            if up.intensity.is_none_or(|i| i == 0) {
+                continue;
+            }
+            let now = resting.get(&fid).cloned().unwrap_or(FixtureValues {
+                intensity: Some(0),
+                r: Some(0),
+                g: Some(0),
+                b: Some(0),
+                ..Default::default()
+            });
+            if now.intensity != Some(0) {
+                continue;
+            }
+            let mut mark = now.clone();
+            mark.apply_delta(&FixtureValues {
+                intensity: None,
+                ..up.clone()
+            });
+            if mark != now {
+                marks.insert(fid, mark);
+            }
+        }
+        marks
     }
 
     pub fn state_map(&self, show: &Show) -> anyhow::Result<BTreeMap<u32, FixtureValues>> {
@@ -162,33 +518,127 @@ impl Playback {
             .get(&self.cuelist)
             .with_context(|| format!("unknown cuelist '{}'", self.cuelist))?;
 
-        let mut tracked: BTreeMap<u32, FixtureValues> = BTreeMap::new();
+        Ok(list.tracked_state_at(cur))
+    }
 
-        for (&num, cue) in &list.cues {
-            if num > cur {
-                break;
-            }
+    pub fn goto(&mut self, show: &Show, cue: u32) -> anyhow::Result<()> {
+        self.goto_timed(show, cue, None)
+    }
 
-            // BLOCKING: reset fixtures touched by this cue so nothing tracks through
-            if cue.block {
-                for &fid in cue.changes.keys() {
-                    tracked.insert(fid, FixtureValues::default());
-                }
-            }
+    /// Crossfade from the visible state to any cue. `fade_ms` overrides the
+    /// timing (no delay). Without it, jumping backwards uses the out-time of
+    /// the cue being left (its fade) and forward jumps use the target's timing.
+    pub fn goto_timed(
+        &mut self,
+        show: &Show,
+        cue: u32,
+        fade_ms: Option<u32>,
+    ) -> anyhow::Result<()> {
+        let timing = match (fade_ms, self.current) {
+            (Some(fade), _) => Some((fade, 0)),
+            (None, Some(cur)) if cue < cur => Some((self.cue_timing(show, cur)?.0, 0)),
+            _ => None,
+        };
+        self.activate_with(show, cue, timing)?;
+        self.link_passes.clear();
+        Ok(())
+    }
+
+    /// Move the current-cue pointer to `cue` without touching the output:
+    /// the stage keeps its look until the next Go (or goto/back), which
+    /// fades from that look as usual.
+    pub fn goto_blind(&mut self, show: &Show, cue: u32) -> anyhow::Result<()> {
+        let list = show
+            .cue_lists
+            .get(&self.cuelist)
+            .with_context(|| format!("unknown cuelist '{}'", self.cuelist))?;
+        anyhow::ensure!(list.cues.contains_key(&cue), "cue {cue} not found");
+
+        self.held = Some(self.unfiltered_output(show)?);
+        self.transition = None;
+        self.paused = false;
+        self.armed = None;
+        self.link_passes.clear();
+        self.current = Some(cue);
+        Ok(())
+    }
+
+    /// True while the output is held by `goto_blind`.
+    pub fn is_blind(&self) -> bool {
+        self.held.is_some()
+    }
+
+    fn cue_timing(&self, show: &Show, cue: u32) -> anyhow::Result<(u32, u32)> {
+        let list = show
+            .cue_lists
+            .get(&self.cuelist)
+            .with_context(|| format!("unknown cuelist '{}'", self.cuelist))?;
+        Ok(list
+            .cues
+            .get(&cue)
+            .map_or((0, 0), |c| (c.fade_ms, c.delay_ms)))
+    }
+
+    /// Go as pressed by an operator: applies `go_guard` before moving.
+    pub fn press_go(&mut self, show: &Show) -> anyhow::Result<GoOutcome> {
+        let guard = self.go_guard;
+
+        if guard.debounce_ms > 0
+            && let Some(since) = self.since_go_ms
+            && since < guard.debounce_ms
+        {
+            return Ok(GoOutcome::Ignored);
+        }
 
-            for (&fid, delta) in &cue.changes {
-                tracked.entry(fid).or_default().apply_delta(delta);
+        if guard.confirm_early_fade
+            && let Some(tr) = &self.transition
+        {
+            let in_delay = tr.elapsed_ms < tr.delay_ms;
+            let faded = tr.elapsed_ms.saturating_sub(tr.delay_ms);
+            if in_delay || (tr.fade_ms > 0 && faded.saturating_mul(10) < tr.fade_ms) {
+                return Ok(GoOutcome::NeedsConfirm);
             }
         }
 
-        Ok(tracked)
+        self.go_or_arm(show)
     }
 
-    pub fn goto(&mut self, show: &Show, cue: u32) -> anyhow::Result<()> {
-        self.activate(show, cue)
+    /// Go without the go guard (`go!`, a confirmed early fade), arming a
+    /// confirm cue instead of failing.
+    pub fn go_or_arm(&mut self, show: &Show) -> anyhow::Result<GoOutcome> {
+        if let Some(cue) = self.pending_confirm(show) {
+            self.armed = Some(cue);
+            self.since_go_ms = Some(0);
+            return Ok(GoOutcome::CueNeedsConfirm(cue));
+        }
+        Ok(GoOutcome::Went(self.go(show)?))
+    }
+
+    /// The next cue, if it needs confirming and no Go has armed it yet.
+    pub fn pending_confirm(&self, show: &Show) -> Option<u32> {
+        let next = self.next_cue(show).filter(|&n| Some(n) != self.current)?;
+        let cue = show.cue_lists.get(&self.cuelist)?.cues.get(&next)?;
+        (cue.confirm && self.armed != Some(next)).then_some(next)
+    }
+
+    /// Confirm cue armed by a first Go, waiting for the second.
+    pub fn armed(&self) -> Option<u32> {
+        self.armed
+    }
+
+    pub fn disarm(&mut self) {
+        self.armed = None;
     }
 
+    /// Moves to the next cue. A confirm cue is never fired by a single call:
+    /// the first arms it and fails, asking for another Go.
     pub fn go(&mut self, show: &Show) -> anyhow::Result<Option<u32>> {
+        self.since_go_ms = Some(0);
+        if let Some(cue) = self.pending_confirm(show) {
+            self.armed = Some(cue);
+            anyhow::bail!("cue {cue} needs confirming: Go again to fire it");
+        }
+
         let list = show
             .cue_lists
             .get(&self.cuelist)
@@ -198,33 +648,154 @@ impl Playback {
         if nums.is_empty() {
             self.current = None;
             self.transition = None;
+            self.held = None;
             return Ok(None);
         }
 
         let next = match self.current {
             None => nums[0],
-            Some(cur) => nums.into_iter().find(|n| *n > cur).unwrap_or(cur),
+            Some(cur) => match self.link_from(list, cur) {
+                Some(link) => {
+                    *self.link_passes.entry(cur).or_default() += 1;
+                    link
+                }
+                None => {
+                    // a finished loop runs its full count again next time
+                    self.link_passes.remove(&cur);
+                    match nums.iter().copied().find(|n| *n > cur) {
+                        Some(n) => n,
+                        None if list.looping => nums[0],
+                        // auto-black: release with the last cue's fade as out-time
+                        None if list.auto_black => {
+                            let fade_ms = self.cue_timing(show, cur)?.0;
+                            self.release(show, fade_ms)?;
+                            return Ok(None);
+                        }
+                        None => cur,
+                    }
+                }
+            },
         };
 
         self.activate(show, next)?;
         Ok(self.current)
     }
 
+    /// Time left until the current cue follows on, 0 when due. None when
+    /// nothing will follow: no follow time, the last cue of a list that
+    /// neither loops nor auto-blacks, paused, blind, in a manual crossfade or
+    /// before a confirm cue nobody has armed.
+    pub fn follow_remaining_ms(&self, show: &Show) -> Option<u32> {
+        let cur = self.current?;
+        let manual = self
+            .transition
+            .as_ref()
+            .is_some_and(|t| t.manual_pos.is_some());
+        if self.paused || self.held.is_some() || manual || self.pending_confirm(show).is_some() {
+            return None;
+        }
+        let list = show.cue_lists.get(&self.cuelist)?;
+        let follow_ms = list.cues.get(&cur)?.follow_ms?;
+        let has_next = list.auto_black || self.next_cue(show).is_some();
+        has_next.then(|| follow_ms.saturating_sub(self.on_cue_ms))
+    }
+
+    /// Go on once the current cue's follow time is up. Returns the cue gone to.
+    pub fn follow(&mut self, show: &Show) -> anyhow::Result<Option<u32>> {
+        if self.follow_remaining_ms(show) != Some(0) {
+            return Ok(None);
+        }
+        self.go(show)
+    }
+
+    /// Release the playback: fade its intensities to zero, then drop out of
+    /// the output. The next Go starts from the first cue again.
+    pub fn release(&mut self, show: &Show, fade_ms: u32) -> anyhow::Result<()> {
+        let (mut from, mut from_fine) = self.unfiltered_output(show)?;
+        for (fid, params) in std::mem::take(&mut self.released) {
+            from.entry(fid).and_modify(|v| params.zero(v));
+            from_fine.entry(fid).and_modify(|v| params.remove(v));
+        }
+        self.held = None;
+        self.current = None;
+        self.paused = false;
+        self.armed = None;
+        self.last_action = next_action();
+        if fade_ms == 0 {
+            self.transition = None;
+            return Ok(());
+        }
+
+        let mut to = from.clone();
+        for v in to.values_mut() {
+            v.intensity = Some(0);
+        }
+        self.transition = Some(Transition {
+            from,
+            from_fine,
+            to,
+            elapsed_ms: 0,
+            fade_ms,
+            delay_ms: 0,
+            manual_pos: None,
+            manual_down: None,
+        });
+        Ok(())
+    }
+
+    /// Step back to the previous cue, fading with the out-time of the cue being left.
+    pub fn back(&mut self, show: &Show) -> anyhow::Result<Option<u32>> {
+        let Some(cur) = self.current else {
+            return Ok(None);
+        };
+        let list = show
+            .cue_lists
+            .get(&self.cuelist)
+            .with_context(|| format!("unknown cuelist '{}'", self.cuelist))?;
+
+        if let Some((&prev, _)) = list.cues.range(..cur).next_back() {
+            self.goto_timed(show, prev, None)?;
+        }
+        Ok(self.current)
+    }
+
+    /// Hold or resume the running timed fade. Returns true if now paused.
+    pub fn toggle_pause(&mut self) -> bool {
+        let running = self
+            .transition
+            .as_ref()
+            .is_some_and(|t| t.manual_pos.is_none());
+        self.paused = !self.paused && running;
+        self.paused
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
     fn activate(&mut self, show: &Show, target: u32) -> anyhow::Result<()> {
+        self.activate_with(show, target, None)
+    }
+
+    /// `timing` = (fade_ms, delay_ms) override; None uses the target cue's timing.
+    fn activate_with(
+        &mut self,
+        show: &Show,
+        target: u32,
+        timing: Option<(u32, u32)>,
+    ) -> anyhow::Result<()> {
+        self.paused = false;
+        self.on_cue_ms = 0;
+        self.armed = None;
+        self.last_action = next_action();
         // IMPORTANT: capture the CURRENT visible output, even if we're mid-fade
-        let from = self.output_state_map(show)?;
-
-        // determine timing from target cue (if present)
-        let (fade_ms, delay_ms) = {
-            let list = show
-                .cue_lists
-                .get(&self.cuelist)
-                .with_context(|| format!("unknown cuelist '{}'", self.cuelist))?;
-            if let Some(cue) = list.cues.get(&target) {
-                (cue.fade_ms, cue.delay_ms)
-            } else {
-                (0, 0)
-            }
+        let (mut from, mut from_fine) = self.unfiltered_output(show)?;
+        self.held = None;
+        self.restore_released(show, target, &mut from, &mut from_fine);
+
+        let (fade_ms, delay_ms) = match timing {
+            Some(t) => t,
+            None => self.cue_timing(show, target)?,
         };
 
         let to_raw = self.state_map_at(show, target)?;
@@ -239,18 +810,158 @@ impl Playback {
 
         self.transition = Some(Transition {
             from,
+            from_fine,
             to,
             elapsed_ms: 0,
             fade_ms,
             delay_ms,
+            manual_pos: None,
+            manual_down: None,
+        });
+
+        Ok(())
+    }
+
+    /// Start a manual crossfade to the next cue at fader position 0.
+    /// Returns the target cue, or None at the end of the list.
+    pub fn start_manual(&mut self, show: &Show) -> anyhow::Result<Option<u32>> {
+        let Some(target) = self.next_cue(show) else {
+            return Ok(None);
+        };
+        self.start_manual_to(show, target)?;
+        Ok(Some(target))
+    }
+
+    /// Start a manual crossfade to any cue (forwards or backwards).
+    pub fn start_manual_to(&mut self, show: &Show, target: u32) -> anyhow::Result<()> {
+        let (from, from_fine) = self.unfiltered_output(show)?;
+        let to = Self::resolve_map(self.state_map_at(show, target)?);
+        self.held = None;
+
+        self.current = Some(target);
+        self.armed = None;
+        self.learned = None;
+        self.paused = false;
+        self.last_action = next_action();
+        self.transition = Some(Transition {
+            from,
+            from_fine,
+            to,
+            elapsed_ms: 0,
+            fade_ms: 0,
+            delay_ms: 0,
+            manual_pos: Some(0),
+            manual_down: None,
         });
+        Ok(())
+    }
+
+    /// Move the crossfader (0..=100). A running timed fade is taken over
+    /// where it is. Reaching 100 completes the fade and returns the
+    /// measured duration, which `learn_fade_time` can store.
+    pub fn set_manual(&mut self, percent: u8) -> anyhow::Result<Option<u32>> {
+        self.set_manual_split(percent, percent)
+    }
+
+    /// Split crossfade: rising intensities and colors follow `up`, falling
+    /// intensities follow `down`. The fade completes once both reach 100.
+    pub fn set_manual_split(&mut self, up: u8, down: u8) -> anyhow::Result<Option<u32>> {
+        let Some(tr) = &mut self.transition else {
+            anyhow::bail!("no crossfade running");
+        };
+        let (up, down) = (up.min(100), down.min(100));
+        tr.manual_pos = Some(up);
+        tr.manual_down = (down != up).then_some(down);
+        self.paused = false;
+        if up < 100 || down < 100 {
+            return Ok(None);
+        }
+
+        let ms = tr.elapsed_ms;
+        self.transition = None;
+        self.learned = self.current.map(|cue| (cue, ms));
+        Ok(Some(ms))
+    }
+
+    /// (up, down) crossfader positions of the running fade, manual or
+    /// timed; equal unless split.
+    pub fn crossfade_position(&self) -> Option<(u8, u8)> {
+        let tr = self.transition.as_ref()?;
+        let up = tr.progress();
+        Some((up, tr.manual_down.unwrap_or(up)))
+    }
+
+    /// (elapsed ms, position %) of the running manual crossfade.
+    pub fn manual_info(&self) -> Option<(u32, u8)> {
+        let tr = self.transition.as_ref()?;
+        Some((tr.elapsed_ms, tr.manual_pos?))
+    }
+
+    /// Cue and duration measured by the last completed manual crossfade.
+    pub fn learned_time(&self) -> Option<(u32, u32)> {
+        self.learned
+    }
+
+    /// Store the learned duration as the cue's fade time.
+    /// Returns (cue, fade_ms).
+    pub fn learn_fade_time(&mut self, show: &mut Show) -> anyhow::Result<(u32, u32)> {
+        let (cue, ms) = self
+            .learned
+            .context("nothing to learn; run a manual crossfade first")?;
+        let list = show
+            .cue_lists
+            .get_mut(&self.cuelist)
+            .filter(|l| l.cues.contains_key(&cue))
+            .with_context(|| format!("cue {cue} no longer exists"))?;
+        list.check_unlocked(cue, cue)?;
+
+        list.cues.get_mut(&cue).expect("checked above").fade_ms = ms;
+        self.learned = None;
+        Ok((cue, ms))
+    }
+
+    /// See `last_action`: later cue changes have higher stamps.
+    pub(crate) fn last_action(&self) -> u64 {
+        self.last_action
+    }
+
+    pub fn rate(&self) -> f32 {
+        self.rate
+    }
 
+    /// Speed up (above 1.0) or slow down the running and coming fades,
+    /// delays and follows without touching the cue times.
+    pub fn set_rate(&mut self, rate: f32) -> anyhow::Result<()> {
+        check_rate(rate)?;
+        self.rate = rate;
         Ok(())
     }
 
     pub fn tick(&mut self, dt_ms: u32) {
+        self.tick_at_speed(dt_ms, 1.0);
+    }
+
+    /// `tick` with cue timing further scaled by `speed` (the runtime's
+    /// speed master) on top of the playback's own rate.
+    pub fn tick_at_speed(&mut self, dt_ms: u32, speed: f32) {
+        if let Some(since) = &mut self.since_go_ms {
+            *since = since.saturating_add(dt_ms);
+        }
+        if self.paused {
+            return;
+        }
+        let exact = dt_ms as f32 * self.rate * speed + self.rate_carry;
+        let cue_ms = exact.floor();
+        self.rate_carry = exact - cue_ms;
+        let cue_ms = cue_ms as u32;
+        self.on_cue_ms = self.on_cue_ms.saturating_add(cue_ms);
         if let Some(tr) = &mut self.transition {
-            tr.elapsed_ms = tr.elapsed_ms.saturating_add(dt_ms);
+            if tr.manual_pos.is_some() {
+                // a hand on the fader: measured in real time for `learn`
+                tr.elapsed_ms = tr.elapsed_ms.saturating_add(dt_ms);
+                return;
+            }
+            tr.elapsed_ms = tr.elapsed_ms.saturating_add(cue_ms);
             let done_at = tr.delay_ms.saturating_add(tr.fade_ms);
             if tr.elapsed_ms >= done_at {
                 self.transition = None; // transition complete
@@ -277,43 +988,197 @@ impl Playback {
         Ok(cue.changes.clone())
     }
 
-    pub fn transition_info(&self) -> Option<(u32, u32, u32)> {
-        self.transition
-            .as_ref()
-            .map(|t| (t.elapsed_ms, t.delay_ms, t.fade_ms))
+    /// Cue number `go` would move to, or None at the end of the list.
+    pub fn next_cue(&self, show: &Show) -> Option<u32> {
+        let list = show.cue_lists.get(&self.cuelist)?;
+        let first = list.cues.keys().next().copied();
+        match self.current {
+            None => first,
+            Some(cur) => self.link_from(list, cur).or_else(|| {
+                list.cues
+                    .range(cur + 1..)
+                    .next()
+                    .map(|(&n, _)| n)
+                    .or(first.filter(|_| list.looping))
+            }),
+        }
     }
 
-    /// Render the tracked output of the cuelist at the current cue.
-    pub fn render(&self, show: &Show) -> anyhow::Result<LiveState> {
-        let state = self.output_state_map(show)?;
-        let mut live = LiveState::new();
+    /// Cue `cur` links to, while its loop count isn't used up.
+    fn link_from(&self, list: &CueList, cur: u32) -> Option<u32> {
+        let cue = list.cues.get(&cur)?;
+        let link = cue.link.filter(|l| list.cues.contains_key(l))?;
+        let passes = self.link_passes.get(&cur).copied().unwrap_or(0);
+        cue.link_loops.is_none_or(|n| passes < n).then_some(link)
+    }
 
-        for (fid, vals) in state {
-            render_fixture_values(show, fid, &vals, &mut live)?;
+    /// The look after the next Go has finished fading (fully resolved), without
+    /// touching the playback. None at the end of the list.
+    pub fn preview_next(
+        &self,
+        show: &Show,
+    ) -> anyhow::Result<Option<BTreeMap<u32, FixtureValues>>> {
+        let Some(next) = self.next_cue(show) else {
+            return Ok(None);
+        };
+        Ok(Some(Self::resolve_map(self.state_map_at(show, next)?)))
+    }
+
+    pub fn status(&self, show: &Show) -> PlaybackStatus {
+        let summary = |num: u32| {
+            let cue = show.cue_lists.get(&self.cuelist)?.cues.get(&num)?;
+            Some(CueSummary {
+                number: num,
+                label: cue.label.clone(),
+                fade_ms: cue.fade_ms,
+                delay_ms: cue.delay_ms,
+                confirm: cue.confirm,
+            })
+        };
+
+        PlaybackStatus {
+            current: self.current.and_then(summary),
+            next: self.next_cue(show).and_then(summary),
+            remaining_ms: self.transition.as_ref().map(|t| {
+                t.delay_ms
+                    .saturating_add(t.fade_ms)
+                    .saturating_sub(t.elapsed_ms)
+            }),
         }
+    }
 
-        Ok(live)
+    /// Take `params` of `fixtures` out of this playback's output without
+    /// touching the cue list. They stay out until a cue sets them again.
+    pub fn release_fixtures(
+        &mut self,
+        fixtures: impl IntoIterator<Item = u32>,
+        params: ParamFilter,
+    ) {
+        for fid in fixtures {
+            let p = self.released.entry(fid).or_insert(ParamFilter::NONE);
+            p.intensity |= params.intensity;
+            p.color |= params.color;
+            p.position |= params.position;
+        }
     }
-}
 
-fn lerp_u8(a: u8, b: u8, t: u32, dur: u32) -> u8 {
-    if dur == 0 {
-        return b;
+    /// Fixtures with released parameters.
+    pub fn released(&self) -> &BTreeMap<u32, ParamFilter> {
+        &self.released
     }
-    let a = a as i32;
-    let b = b as i32;
-    let delta = b - a;
-    let v = a + (delta * t as i32) / dur as i32;
-    v.clamp(0, 255) as u8
-}
 
-fn interpolate_maps(
-    from: &BTreeMap<u32, crate::FixtureValues>,
-    to: &BTreeMap<u32, crate::FixtureValues>,
-    t: u32,
-    dur: u32,
-) -> BTreeMap<u32, crate::FixtureValues> {
-    let mut out = BTreeMap::new();
+    /// Put every released parameter back.
+    pub fn clear_released(&mut self) {
+        self.released.clear();
+    }
+
+    /// Going to `target`: parameters it sets come back, fading up from zero.
+    fn restore_released(
+        &mut self,
+        show: &Show,
+        target: u32,
+        from: &mut StateMap,
+        from_fine: &mut StateMap,
+    ) {
+        let Some(cue) = show
+            .cue_lists
+            .get(&self.cuelist)
+            .and_then(|l| l.cues.get(&target))
+        else {
+            return;
+        };
+        for (fid, params) in &mut self.released {
+            let Some(change) = cue.changes.get(fid) else {
+                continue;
+            };
+            let back = ParamFilter {
+                intensity: params.intensity && change.intensity.is_some(),
+                color: params.color
+                    && (change.r.is_some() || change.g.is_some() || change.b.is_some()),
+                position: params.position && (change.pan.is_some() || change.tilt.is_some()),
+            };
+            if let Some(v) = from.get_mut(fid) {
+                back.zero(v);
+            }
+            if let Some(v) = from_fine.get_mut(fid) {
+                back.remove(v);
+            }
+            params.intensity &= !back.intensity;
+            params.color &= !back.color;
+            params.position &= !back.position;
+        }
+        self.released.retain(|_, p| !p.is_none());
+    }
+
+    pub fn transition_info(&self) -> Option<(u32, u32, u32)> {
+        self.transition
+            .as_ref()
+            .map(|t| (t.elapsed_ms, t.delay_ms, t.fade_ms))
+    }
+
+    /// Render the tracked output of the cuelist at the current cue.
+    pub fn render(&self, show: &Show) -> anyhow::Result<LiveState> {
+        let (state, fine) = self.output_levels(show)?;
+        let mut live = LiveState::new();
+
+        for (fid, vals) in state {
+            render_fixture_values(show, fid, &vals, fine.get(&fid), &mut live)?;
+        }
+
+        Ok(live)
+    }
+}
+
+/// Fixture values by fixture id.
+pub type StateMap = BTreeMap<u32, FixtureValues>;
+
+/// Slowest and fastest a playback rate or the speed master may run cue timing.
+pub const MIN_RATE: f32 = 0.1;
+pub const MAX_RATE: f32 = 10.0;
+
+pub(crate) fn check_rate(rate: f32) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        (MIN_RATE..=MAX_RATE).contains(&rate),
+        "rate {rate} isn't between {MIN_RATE} and {MAX_RATE}"
+    );
+    Ok(())
+}
+
+/// Stamps cue changes across every playback, in order.
+static ACTIONS: AtomicU64 = AtomicU64::new(1);
+
+fn next_action() -> u64 {
+    ACTIONS.fetch_add(1, Ordering::Relaxed)
+}
+
+fn is_zero(n: &u8) -> bool {
+    *n == 0
+}
+
+/// A value in 16-bit space. This is the one coarse-to-16-bit rule: an
+/// 8-bit value v without a fine byte is v * 257 (255 is full), so a fine
+/// channel at rest repeats its coarse byte.
+pub(crate) fn widen(coarse: u8, fine: Option<u8>) -> u16 {
+    match fine {
+        Some(f) => (coarse as u16) << 8 | f as u16,
+        None => coarse as u16 * 257,
+    }
+}
+
+/// Fade in 16-bit space so slow fades on 16-bit channels don't step.
+fn lerp_u16(a: u16, b: u16, t: u32, dur: u32) -> u16 {
+    let (a, b) = (a as i64, b as i64);
+    if dur == 0 {
+        return b as u16;
+    }
+    (a + (b - a) * t as i64 / dur as i64).clamp(0, 65535) as u16
+}
+
+/// The transition's values at `t` of `dur`, as (coarse, fine) bytes.
+fn interpolate_maps(tr: &Transition, t: u32, dur: u32) -> (StateMap, StateMap) {
+    let (from, to) = (&tr.from, &tr.to);
+    let mut out = BTreeMap::new();
+    let mut fine = BTreeMap::new();
 
     let keys = from
         .keys()
@@ -325,41 +1190,84 @@ fn interpolate_maps(
         let fa = from.get(&fid);
         let ta = to.get(&fid);
 
-        let f = fa.unwrap_or(&crate::FixtureValues {
+        let zero = crate::FixtureValues {
             intensity: Some(0),
             r: Some(0),
             g: Some(0),
             b: Some(0),
-        });
-        let tt = ta.unwrap_or(&crate::FixtureValues {
-            intensity: Some(0),
-            r: Some(0),
-            g: Some(0),
-            b: Some(0),
-        });
-
-        let fv = crate::FixtureValues {
-            intensity: Some(lerp_u8(
-                f.intensity.unwrap_or(0),
-                tt.intensity.unwrap_or(0),
-                t,
-                dur,
-            )),
-            r: Some(lerp_u8(f.r.unwrap_or(0), tt.r.unwrap_or(0), t, dur)),
-            g: Some(lerp_u8(f.g.unwrap_or(0), tt.g.unwrap_or(0), t, dur)),
-            b: Some(lerp_u8(f.b.unwrap_or(0), tt.b.unwrap_or(0), t, dur)),
+            ..Default::default()
+        };
+        let f = fa.unwrap_or(&zero);
+        let tt = ta.unwrap_or(&zero);
+        let ff = tr.from_fine.get(&fid).cloned().unwrap_or_default();
+
+        // `a` carries its fine byte when the fade started mid-fade
+        let lerp = |a: Option<u8>, a_fine: Option<u8>, b: Option<u8>| {
+            let b = widen(b.unwrap_or(0), None);
+            lerp_u16(widen(a.unwrap_or(0), a_fine), b, t, dur)
+        };
+        let v = [
+            lerp(f.intensity, ff.intensity, tt.intensity),
+            lerp(f.r, ff.r, tt.r),
+            lerp(f.g, ff.g, tt.g),
+            lerp(f.b, ff.b, tt.b),
+        ];
+        // position is left alone unless one side sets it: no fixture has
+        // a pan of 0 to fade from
+        let position = |a: Option<u8>, a_fine: Option<u8>, b: Option<u8>| {
+            let a_fine = a_fine.filter(|_| a.is_some());
+            (a.is_some() || b.is_some()).then(|| lerp(a.or(b), a_fine, b.or(a)))
+        };
+        let pan = position(f.pan, ff.pan, tt.pan);
+        let tilt = position(f.tilt, ff.tilt, tt.tilt);
+        let bytes = |byte: fn(u16) -> u8| crate::FixtureValues {
+            intensity: Some(byte(v[0])),
+            r: Some(byte(v[1])),
+            g: Some(byte(v[2])),
+            b: Some(byte(v[3])),
+            pan: pan.map(byte),
+            tilt: tilt.map(byte),
         };
 
-        out.insert(fid, fv);
+        out.insert(fid, bytes(|v| (v >> 8) as u8));
+        fine.insert(fid, bytes(|v| v as u8));
     }
 
-    out
+    (out, fine)
+}
+
+/// Jump snap-only channels straight to their target values.
+fn snap_channels(show: &Show, out: &mut StateMap, fine: &mut StateMap, to: &StateMap) {
+    for (fid, vals) in out.iter_mut() {
+        let Some(target) = to.get(fid) else {
+            continue;
+        };
+        // a snapped parameter sits on a whole 8-bit step
+        let fine = fine.entry(*fid).or_default();
+        for kind in show.patch.snap_kinds(*fid) {
+            match kind {
+                ChannelKind::Intensity => {
+                    (vals.intensity, fine.intensity) = (target.intensity, None)
+                }
+                ChannelKind::ColorR => (vals.r, fine.r) = (target.r, None),
+                ChannelKind::ColorG => (vals.g, fine.g) = (target.g, None),
+                ChannelKind::ColorB => (vals.b, fine.b) = (target.b, None),
+                ChannelKind::Pan => (vals.pan, fine.pan) = (target.pan, None),
+                ChannelKind::Tilt => (vals.tilt, fine.tilt) = (target.tilt, None),
+                _ => {}
+            }
+        }
+    }
 }
 
+/// Write a fixture's values to its channels. `fine` holds the low bytes of
+/// 16-bit values for fine channels; without one a fine channel repeats the
+/// coarse byte (v * 257, so 255 is full).
 pub(crate) fn render_fixture_values(
     show: &Show,
     fixture_id: u32,
     vals: &FixtureValues,
+    fine: Option<&FixtureValues>,
     live: &mut LiveState,
 ) -> anyhow::Result<()> {
     let f = show
@@ -392,6 +1300,14 @@ pub(crate) fn render_fixture_values(
             ChannelKind::ColorR => vals.r,
             ChannelKind::ColorG => vals.g,
             ChannelKind::ColorB => vals.b,
+            ChannelKind::Pan => vals.pan,
+            ChannelKind::Tilt => vals.tilt,
+            // without a fine byte the value is v * 257 (see `lerp_u16`)
+            ChannelKind::IntensityFine => vals
+                .intensity
+                .map(|v| fine.and_then(|f| f.intensity).unwrap_or(v)),
+            ChannelKind::PanFine => vals.pan.map(|v| fine.and_then(|f| f.pan).unwrap_or(v)),
+            ChannelKind::TiltFine => vals.tilt.map(|v| fine.and_then(|f| f.tilt).unwrap_or(v)),
             _ => None,
         };
 
@@ -427,7 +1343,14 @@ mod tests {
             Cue {
                 number: 1,
                 label: "Base".into(),
+                meta: Default::default(),
                 block: false,
+                follow_ms: None,
+                confirm: false,
+                link: None,
+                link_loops: None,
+                warn: None,
+                palette_refs: Default::default(),
                 fade_ms: 0,
                 delay_ms: 0,
                 changes: [(
@@ -448,7 +1371,14 @@ mod tests {
             Cue {
                 number: 2,
                 label: "Fade to Red".into(),
+                meta: Default::default(),
                 block: false,
+                follow_ms: None,
+                confirm: false,
+                link: None,
+                link_loops: None,
+                warn: None,
+                palette_refs: Default::default(),
                 fade_ms: 1000,
                 delay_ms: 0,
                 changes: [(
@@ -483,6 +1413,155 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn sixteen_bit_fade_steps_through_fine_byte() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        for ft in default_fixture_types() {
+            show.patch.add_fixture_type(ft);
+        }
+        show.patch
+            .add_fixture(FixtureInstance::new(1, "DIM 1", "dimmer_16bit_2ch", 1, 1))?;
+        let cue = |number, intensity, fade_ms| Cue {
+            number,
+            label: String::new(),
+            meta: Default::default(),
+            block: false,
+            follow_ms: None,
+            confirm: false,
+            link: None,
+            link_loops: None,
+            warn: None,
+            palette_refs: Default::default(),
+            fade_ms,
+            delay_ms: 0,
+            changes: [(
+                1u32,
+                FixtureValues {
+                    intensity: Some(intensity),
+                    ..Default::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+        };
+        let list = show.cue_lists.entry("main".into()).or_default();
+        list.cues.insert(1, cue(1, 0, 0));
+        list.cues.insert(2, cue(2, 1, 1000));
+
+        let mut pb = Playback::new("main");
+        pb.goto(&show, 1)?;
+        pb.goto(&show, 2)?;
+        // one 8-bit step spread over the fade: 0x0000 -> 0x0101
+        pb.tick(500);
+        assert_eq!(pb.render(&show)?.nonzero(), vec![(1, 2, 128)]);
+        pb.tick(250);
+        assert_eq!(pb.render(&show)?.nonzero(), vec![(1, 2, 192)]);
+        pb.tick(250);
+        assert_eq!(pb.render(&show)?.nonzero(), vec![(1, 1, 1), (1, 2, 1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn go_mid_fade_starts_from_the_16_bit_value() -> anyhow::Result<()> {
+        let show = crate::ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "DIM 1", "dimmer_16bit_2ch", 1, 1)
+            .cue(crate::CueBuilder::new(1).intensity(1, 0).fade_ms(0))
+            .cue(crate::CueBuilder::new(2).intensity(1, 1).fade_ms(1000))
+            .cue(crate::CueBuilder::new(3).intensity(1, 0).fade_ms(1000))
+            .build()?;
+
+        let mut pb = Playback::new("main");
+        pb.goto(&show, 1)?;
+        pb.go(&show)?;
+        pb.tick(500);
+        assert_eq!(pb.render(&show)?.nonzero(), vec![(1, 2, 128)]);
+        // the next fade picks up the fine byte instead of dropping to 0
+        pb.go(&show)?;
+        assert_eq!(pb.render(&show)?.nonzero(), vec![(1, 2, 128)]);
+        pb.tick(500);
+        assert_eq!(pb.render(&show)?.nonzero(), vec![(1, 2, 64)]);
+        Ok(())
+    }
+
+    #[test]
+    fn pan_tilt_fade_steps_through_fine_channels() -> anyhow::Result<()> {
+        let show = crate::ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "MH 1", "moving_head_5ch", 1, 1)
+            .cue(crate::CueBuilder::new(1).position(1, 0, 200).fade_ms(0))
+            .cue(crate::CueBuilder::new(2).position(1, 1, 200).fade_ms(1000))
+            .build()?;
+
+        let mut pb = Playback::new("main");
+        pb.goto(&show, 1)?;
+        pb.goto(&show, 2)?;
+        pb.tick(500);
+        let out = pb.render(&show)?;
+        // pan coarse holds while the fine byte carries the step
+        assert_eq!(out.get(1, 1), 0);
+        assert_eq!(out.get(1, 3), 128);
+        assert_eq!((out.get(1, 2), out.get(1, 4)), (200, 200));
+        pb.tick(500);
+        let out = pb.render(&show)?;
+        assert_eq!((out.get(1, 1), out.get(1, 3)), (1, 1));
+        Ok(())
+    }
+    #[test]
+    fn snap_channels_never_interpolate() -> anyhow::Result<()> {
+        use crate::ChannelDef;
+
+        let mut show = Show::new("Test");
+        for ft in default_fixture_types() {
+            show.patch.add_fixture_type(ft);
+        }
+        let mut ft = show.patch.fixture_types["rgb_par_3ch"].clone();
+        ft.type_id = "snap_par".into();
+        ft.channels[0] = ChannelDef {
+            snap: true,
+            ..ft.channels[0].clone()
+        };
+        show.patch.add_fixture_type(ft);
+        show.patch
+            .add_fixture(FixtureInstance::new(1, "Snap red", "snap_par", 1, 1))?;
+        let mut no_fade = FixtureInstance::new(2, "No fade", "rgb_par_3ch", 1, 4);
+        no_fade.snap = true;
+        show.patch.add_fixture(no_fade)?;
+
+        let rgb = FixtureValues {
+            r: Some(200),
+            g: Some(200),
+            ..Default::default()
+        };
+        show.cue_lists.get_mut("main").unwrap().cues.insert(
+            1,
+            Cue {
+                number: 1,
+                label: "Fade".into(),
+                meta: Default::default(),
+                block: false,
+                follow_ms: None,
+                confirm: false,
+                link: None,
+                link_loops: None,
+                warn: None,
+                palette_refs: Default::default(),
+                fade_ms: 1000,
+                delay_ms: 0,
+                changes: [(1, rgb.clone()), (2, rgb)].into_iter().collect(),
+            },
+        );
+
+        let mut pb = Playback::new("main");
+        pb.go(&show)?;
+        pb.tick(500);
+        let st = pb.output_state_map(&show)?;
+        assert_eq!((st[&1].r, st[&1].g), (Some(200), Some(100)));
+        assert_eq!((st[&2].r, st[&2].g), (Some(200), Some(200)));
+        Ok(())
+    }
+
     #[test]
     fn tracking_works_across_cues() -> anyhow::Result<()> {
         let mut show = Show::new("Test");
@@ -499,9 +1578,16 @@ mod tests {
             Cue {
                 number: 1,
                 label: "Red".into(),
+                meta: Default::default(),
                 fade_ms: 0,
                 delay_ms: 0,
                 block: false,
+                follow_ms: None,
+                confirm: false,
+                link: None,
+                link_loops: None,
+                warn: None,
+                palette_refs: Default::default(),
                 changes: [(
                     1u32,
                     FixtureValues {
@@ -520,9 +1606,16 @@ mod tests {
             Cue {
                 number: 2,
                 label: "Blue add".into(),
+                meta: Default::default(),
                 fade_ms: 0,
                 delay_ms: 0,
                 block: false,
+                follow_ms: None,
+                confirm: false,
+                link: None,
+                link_loops: None,
+                warn: None,
+                palette_refs: Default::default(),
                 changes: [(
                     1u32,
                     FixtureValues {
@@ -548,4 +1641,726 @@ mod tests {
         assert!(nz.contains(&(1, 3, 255)));
         Ok(())
     }
+
+    #[test]
+    fn status_reports_current_next_and_remaining() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        let cl = show.cue_lists.get_mut("main").unwrap();
+        for (num, label, fade_ms) in [(1, "Pre", 0), (5, "Open", 2000)] {
+            cl.cues.insert(
+                num,
+                Cue {
+                    number: num,
+                    label: label.into(),
+                    meta: Default::default(),
+                    block: false,
+                    follow_ms: None,
+                    confirm: false,
+                    link: None,
+                    link_loops: None,
+                    warn: None,
+                    palette_refs: Default::default(),
+                    fade_ms,
+                    delay_ms: 0,
+                    changes: BTreeMap::new(),
+                },
+            );
+        }
+
+        let mut pb = Playback::new("main");
+        assert_eq!(pb.status(&show).next.map(|c| c.number), Some(1));
+
+        pb.go(&show)?;
+        pb.go(&show)?;
+        pb.tick(500);
+        let st = pb.status(&show);
+        assert_eq!(st.current.map(|c| c.label), Some("Open".to_string()));
+        assert_eq!(st.next, None);
+        assert_eq!(st.remaining_ms, Some(1500));
+        Ok(())
+    }
+
+    #[test]
+    fn press_go_debounces_and_asks_to_confirm_early_fades() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        let cl = show.cue_lists.get_mut("main").unwrap();
+        for num in 1..=3 {
+            cl.cues.insert(
+                num,
+                Cue {
+                    number: num,
+                    label: format!("Cue {num}"),
+                    meta: Default::default(),
+                    block: false,
+                    follow_ms: None,
+                    confirm: false,
+                    link: None,
+                    link_loops: None,
+                    warn: None,
+                    palette_refs: Default::default(),
+                    fade_ms: 1000,
+                    delay_ms: 0,
+                    changes: BTreeMap::new(),
+                },
+            );
+        }
+
+        let mut pb = Playback::new("main");
+        pb.go_guard = GoGuard {
+            debounce_ms: 300,
+            confirm_early_fade: true,
+        };
+
+        assert_eq!(pb.press_go(&show)?, GoOutcome::Went(Some(1)));
+        pb.tick(50);
+        assert_eq!(pb.press_go(&show)?, GoOutcome::Ignored);
+        pb.tick(300);
+        assert_eq!(pb.press_go(&show)?, GoOutcome::Went(Some(2)));
+        pb.tick(350); // past debounce, 35% into the fade
+        assert_eq!(pb.press_go(&show)?, GoOutcome::Went(Some(3)));
+
+        pb.go_guard.debounce_ms = 0;
+        pb.tick(50); // 5% into cue 3's fade
+        assert_eq!(pb.press_go(&show)?, GoOutcome::NeedsConfirm);
+        assert_eq!(pb.current, Some(3));
+        Ok(())
+    }
+
+    #[test]
+    fn palette_references_resolve_when_played() -> anyhow::Result<()> {
+        let mut show = crate::ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "Par", "rgb_par_3ch", 1, 1)
+            .build()?;
+        show.palettes
+            .insert("warm".into(), crate::Palette::from_rgb([255, 100, 0]));
+
+        let mut prog = crate::Programmer::new();
+        prog.select_one(1);
+        prog.apply_named_palette("warm", &show.palettes["warm"]);
+        let refs = prog.palette_refs(&show.palettes);
+        assert_eq!(refs[&1].color.as_deref(), Some("warm"));
+        let mut cue = crate::CueBuilder::new(1).changes(prog.contents()).build();
+        cue.palette_refs = refs;
+        show.cue_lists.get_mut("main").unwrap().cues.insert(1, cue);
+
+        // a manual color is no longer the palette
+        prog.set_rgb(1, 2, 3);
+        assert!(prog.palette_refs(&show.palettes).is_empty());
+
+        show.palettes
+            .insert("warm".into(), crate::Palette::from_rgb([200, 50, 10]));
+        let mut pb = Playback::new("main");
+        pb.go(&show)?;
+        assert_eq!(
+            pb.render(&show)?.nonzero(),
+            vec![(1, 1, 200), (1, 2, 50), (1, 3, 10)]
+        );
+        assert_eq!(show.palette_users("warm"), [("main".to_string(), 1)]);
+
+        // deleted: the recorded values come back
+        show.palettes.remove("warm");
+        assert_eq!(pb.render(&show)?.nonzero()[0], (1, 1, 255));
+        Ok(())
+    }
+
+    #[test]
+    fn confirm_cue_needs_a_second_go() -> anyhow::Result<()> {
+        let show = crate::ShowBuilder::new("Test")
+            .cue(crate::CueBuilder::new(1).follow_ms(500))
+            .cue(crate::CueBuilder::new(2).confirm())
+            .cue(crate::CueBuilder::new(3))
+            .build()?;
+        let mut pb = Playback::new("main");
+        pb.go(&show)?;
+
+        // follows never fire it
+        pb.tick(600);
+        assert_eq!(pb.follow_remaining_ms(&show), None);
+        assert_eq!(pb.follow(&show)?, None);
+        assert!(pb.status(&show).next.is_some_and(|n| n.confirm));
+
+        assert_eq!(pb.press_go(&show)?, GoOutcome::CueNeedsConfirm(2));
+        assert_eq!((pb.current, pb.armed()), (Some(1), Some(2)));
+        assert_eq!(pb.press_go(&show)?, GoOutcome::Went(Some(2)));
+        assert_eq!(pb.armed(), None);
+        assert_eq!(pb.go(&show)?, Some(3));
+
+        // plain go arms and fails; disarm cancels
+        pb.goto(&show, 1)?;
+        assert!(pb.go(&show).is_err());
+        assert_eq!(pb.current, Some(1));
+        pb.disarm();
+        assert_eq!(pb.go_or_arm(&show)?, GoOutcome::CueNeedsConfirm(2));
+        assert_eq!(pb.go(&show)?, Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn preview_next_shows_look_without_going() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        let cl = show.cue_lists.get_mut("main").unwrap();
+        for (num, r) in [(1, 100), (2, 200)] {
+            cl.cues.insert(
+                num,
+                Cue {
+                    number: num,
+                    label: format!("Cue {num}"),
+                    meta: Default::default(),
+                    block: false,
+                    follow_ms: None,
+                    confirm: false,
+                    link: None,
+                    link_loops: None,
+                    warn: None,
+                    palette_refs: Default::default(),
+                    fade_ms: 1000,
+                    delay_ms: 0,
+                    changes: [(
+                        1,
+                        FixtureValues {
+                            r: Some(r),
+                            ..Default::default()
+                        },
+                    )]
+                    .into_iter()
+                    .collect(),
+                },
+            );
+        }
+
+        let mut pb = Playback::new("main");
+        pb.goto(&show, 1)?;
+        pb.tick(1000);
+
+        let next = pb.preview_next(&show)?.expect("cue 2 is next");
+        assert_eq!(next[&1].r, Some(200));
+        assert_eq!(pb.output_state_map(&show)?[&1].r, Some(100));
+        assert_eq!(pb.current, Some(1));
+
+        pb.go(&show)?;
+        assert!(pb.preview_next(&show)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn goto_with_time_override_and_backwards_out_time() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        let cl = show.cue_lists.get_mut("main").unwrap();
+        for (num, fade) in [(1, 1000), (5, 3000), (12, 2000)] {
+            cl.cues.insert(
+                num,
+                Cue {
+                    number: num,
+                    label: format!("Cue {num}"),
+                    meta: Default::default(),
+                    block: false,
+                    follow_ms: None,
+                    confirm: false,
+                    link: None,
+                    link_loops: None,
+                    warn: None,
+                    palette_refs: Default::default(),
+                    fade_ms: fade,
+                    delay_ms: 500,
+                    changes: BTreeMap::new(),
+                },
+            );
+        }
+
+        let mut pb = Playback::new("main");
+        pb.goto_timed(&show, 12, Some(8000))?;
+        assert_eq!(pb.transition_info(), Some((0, 0, 8000)));
+
+        pb.goto(&show, 5)?; // backwards: out-time of cue 12
+        assert_eq!(pb.transition_info(), Some((0, 0, 2000)));
+
+        pb.goto(&show, 12)?; // forwards: cue 12's own timing
+        assert_eq!(pb.transition_info(), Some((0, 500, 2000)));
+        Ok(())
+    }
+
+    #[test]
+    fn blind_goto_moves_pointer_but_keeps_output() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        let cl = show.cue_lists.get_mut("main").unwrap();
+        for (num, level) in [(1, 100), (2, 150), (3, 250)] {
+            cl.cues.insert(
+                num,
+                Cue {
+                    number: num,
+                    label: format!("Cue {num}"),
+                    meta: Default::default(),
+                    block: false,
+                    follow_ms: None,
+                    confirm: false,
+                    link: None,
+                    link_loops: None,
+                    warn: None,
+                    palette_refs: Default::default(),
+                    fade_ms: 1000,
+                    delay_ms: 0,
+                    changes: BTreeMap::from([(
+                        1,
+                        FixtureValues {
+                            intensity: Some(level),
+                            ..Default::default()
+                        },
+                    )]),
+                },
+            );
+        }
+
+        let mut pb = Playback::new("main");
+        pb.goto_timed(&show, 1, Some(0))?;
+        pb.goto_blind(&show, 2)?;
+        assert!(pb.is_blind());
+        assert_eq!(pb.current, Some(2));
+        assert_eq!(pb.output_state_map(&show)?[&1].intensity, Some(100));
+        assert!(pb.goto_blind(&show, 9).is_err());
+
+        // Go continues from the pointer, fading from the held look
+        assert_eq!(pb.go(&show)?, Some(3));
+        assert!(!pb.is_blind());
+        pb.tick(500);
+        assert_eq!(pb.output_state_map(&show)?[&1].intensity, Some(175));
+        Ok(())
+    }
+
+    #[test]
+    fn looping_list_follows_around_forever() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        let cl = show.cue_lists.get_mut("main").unwrap();
+        for n in 1..=3 {
+            cl.cues.insert(
+                n,
+                Cue {
+                    number: n,
+                    label: format!("Step {n}"),
+                    meta: Default::default(),
+                    block: false,
+                    follow_ms: Some(1000),
+                    confirm: false,
+                    link: None,
+                    link_loops: None,
+                    warn: None,
+                    palette_refs: Default::default(),
+                    fade_ms: 500,
+                    delay_ms: 0,
+                    changes: BTreeMap::new(),
+                },
+            );
+        }
+
+        let mut pb = Playback::new("main");
+        assert_eq!(pb.go(&show)?, Some(1));
+        pb.tick(999);
+        assert_eq!(pb.follow(&show)?, None);
+        pb.tick(1);
+        assert_eq!(pb.follow(&show)?, Some(2));
+        pb.tick(1000);
+        assert_eq!(pb.follow(&show)?, Some(3));
+
+        // without loop mode the last cue holds
+        pb.tick(1000);
+        assert_eq!(pb.follow(&show)?, None);
+        assert_eq!(pb.current, Some(3));
+
+        show.cue_lists.get_mut("main").unwrap().looping = true;
+        assert_eq!(pb.follow(&show)?, Some(1));
+        assert_eq!(pb.go(&show)?, Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn linked_cues_loop_their_count_then_carry_on() -> anyhow::Result<()> {
+        let show = crate::ShowBuilder::new("Test")
+            .cue(crate::CueBuilder::new(1))
+            .cue(crate::CueBuilder::new(2))
+            .cue(crate::CueBuilder::new(3).link(2, Some(2)))
+            .cue(crate::CueBuilder::new(4).link(1, None))
+            .build()?;
+        let mut pb = Playback::new("main");
+        let mut gone = Vec::new();
+        for _ in 0..9 {
+            gone.push(pb.go(&show)?.unwrap());
+        }
+        assert_eq!(gone, [1, 2, 3, 2, 3, 2, 3, 4, 1]);
+        // the inner loop counts afresh on the next pass of the outer one
+        assert_eq!(pb.next_cue(&show), Some(2));
+        pb.go(&show)?;
+        pb.go(&show)?;
+        assert_eq!(pb.next_cue(&show), Some(2));
+
+        pb.go(&show)?;
+        pb.go(&show)?;
+        pb.go(&show)?;
+        pb.go(&show)?;
+        assert_eq!(pb.next_cue(&show), Some(4));
+        // a goto leaves the loop, so its count starts over
+        pb.goto(&show, 3)?;
+        assert_eq!(pb.next_cue(&show), Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn filters_and_release_keep_position_apart() -> anyhow::Result<()> {
+        let show = crate::ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "MH 1", "moving_head_5ch", 1, 1)
+            .cue(
+                crate::CueBuilder::new(1)
+                    .intensity(1, 200)
+                    .position(1, 10, 20)
+                    .fade_ms(0),
+            )
+            .cue(crate::CueBuilder::new(2).position(1, 30, 40).fade_ms(0))
+            .build()?;
+        let mut pb = Playback::new("main");
+        pb.goto(&show, 1)?;
+
+        pb.filter = ParamFilter::INTENSITY;
+        let v = &pb.output_state_map(&show)?[&1];
+        assert_eq!((v.intensity, v.pan, v.tilt), (Some(200), None, None));
+        pb.filter = ParamFilter::POSITION;
+        let v = &pb.output_state_map(&show)?[&1];
+        assert_eq!((v.intensity, v.pan, v.tilt), (None, Some(10), Some(20)));
+        assert_eq!("position".parse::<ParamFilter>()?, ParamFilter::POSITION);
+
+        pb.filter = ParamFilter::ALL;
+        pb.release_fixtures([1], ParamFilter::POSITION);
+        let v = &pb.output_state_map(&show)?[&1];
+        assert_eq!((v.intensity, v.pan), (Some(200), None));
+        // cue 2 moves it again
+        pb.go(&show)?;
+        assert_eq!(pb.output_state_map(&show)?[&1].pan, Some(30));
+        assert!(pb.released().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn selective_release_holds_until_a_cue_sets_the_fixture() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        let cl = show.cue_lists.get_mut("main").unwrap();
+        let full = |fids: &[u32]| -> BTreeMap<u32, FixtureValues> {
+            fids.iter()
+                .map(|&f| {
+                    let v = FixtureValues {
+                        intensity: Some(255),
+                        r: Some(200),
+                        ..Default::default()
+                    };
+                    (f, v)
+                })
+                .collect()
+        };
+        for (n, fids) in [(1, vec![1, 2]), (2, vec![2]), (3, vec![1])] {
+            cl.cues.insert(
+                n,
+                Cue {
+                    number: n,
+                    label: String::new(),
+                    meta: Default::default(),
+                    block: false,
+                    follow_ms: None,
+                    confirm: false,
+                    link: None,
+                    link_loops: None,
+                    warn: None,
+                    palette_refs: Default::default(),
+                    fade_ms: 0,
+                    delay_ms: 0,
+                    changes: full(&fids),
+                },
+            );
+        }
+
+        let mut pb = Playback::new("main");
+        pb.goto(&show, 1)?;
+        pb.release_fixtures([1], ParamFilter::INTENSITY);
+        let out = pb.output_state_map(&show)?;
+        assert_eq!((out[&1].intensity, out[&1].r), (None, Some(200)));
+        assert_eq!(out[&2].intensity, Some(255));
+
+        // cue 2 doesn't touch fixture 1: it stays out, tracking or not
+        pb.go(&show)?;
+        assert_eq!(pb.output_state_map(&show)?[&1].intensity, None);
+        assert!(show.cue_lists["main"].cues[&1].changes.contains_key(&1));
+
+        // cue 3 sets it again
+        pb.go(&show)?;
+        assert_eq!(pb.output_state_map(&show)?[&1].intensity, Some(255));
+        assert!(pb.released().is_empty());
+
+        pb.release_fixtures([2], ParamFilter::ALL);
+        assert!(!pb.output_state_map(&show)?.contains_key(&2));
+        pb.clear_released();
+        assert!(pb.output_state_map(&show)?.contains_key(&2));
+        Ok(())
+    }
+
+    #[test]
+    fn move_in_black_presets_color_while_dark() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        let cl = show.cue_lists.get_mut("main").unwrap();
+        let values = |i, b| FixtureValues {
+            intensity: Some(i),
+            b: Some(b),
+            ..Default::default()
+        };
+        for (n, changes) in [
+            (1, vec![(1, values(255, 0)), (2, values(0, 0))]),
+            (2, vec![(2, values(255, 255))]),
+        ] {
+            cl.cues.insert(
+                n,
+                Cue {
+                    number: n,
+                    label: String::new(),
+                    meta: Default::default(),
+                    block: false,
+                    follow_ms: None,
+                    confirm: false,
+                    link: None,
+                    link_loops: None,
+                    warn: None,
+                    palette_refs: Default::default(),
+                    fade_ms: 1000,
+                    delay_ms: 0,
+                    changes: changes.into_iter().collect(),
+                },
+            );
+        }
+
+        let mut pb = Playback::new("main");
+        pb.goto_timed(&show, 1, Some(0))?;
+        assert!(pb.marks(&show)?.is_empty(), "off by default");
+        show.cue_lists.get_mut("main").unwrap().move_in_black = true;
+        let marks = pb.marks(&show)?;
+        assert_eq!(marks.keys().collect::<Vec<_>>(), [&2]);
+        let out = pb.output_state_map(&show)?;
+        assert_eq!((out[&2].intensity, out[&2].b), (Some(0), Some(255)));
+
+        // halfway through the fade only intensity moves
+        pb.go(&show)?;
+        pb.tick(500);
+        let out = pb.output_state_map(&show)?;
+        assert_eq!(out[&2].b, Some(255));
+        assert!(out[&2].intensity.is_some_and(|i| i > 0 && i < 255));
+        Ok(())
+    }
+
+    #[test]
+    fn auto_black_releases_past_last_cue() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        let cl = show.cue_lists.get_mut("main").unwrap();
+        cl.auto_black = true;
+        let mut changes = BTreeMap::new();
+        changes.insert(
+            1,
+            FixtureValues {
+                intensity: Some(200),
+                ..Default::default()
+            },
+        );
+        cl.cues.insert(
+            1,
+            Cue {
+                number: 1,
+                label: "Only".into(),
+                meta: Default::default(),
+                block: false,
+                follow_ms: None,
+                confirm: false,
+                link: None,
+                link_loops: None,
+                warn: None,
+                palette_refs: Default::default(),
+                fade_ms: 1000,
+                delay_ms: 0,
+                changes,
+            },
+        );
+
+        let mut pb = Playback::new("main");
+        pb.goto_timed(&show, 1, Some(0))?;
+        assert_eq!(pb.go(&show)?, None);
+
+        pb.tick(500);
+        assert_eq!(pb.output_state_map(&show)?[&1].intensity, Some(100));
+        pb.tick(500);
+        assert!(pb.output_state_map(&show)?.is_empty());
+
+        assert_eq!(pb.go(&show)?, Some(1)); // starts over
+        Ok(())
+    }
+
+    #[test]
+    fn pause_holds_fade_and_back_steps_back() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        let cl = show.cue_lists.get_mut("main").unwrap();
+        for num in 1..=2 {
+            cl.cues.insert(
+                num,
+                Cue {
+                    number: num,
+                    label: format!("Cue {num}"),
+                    meta: Default::default(),
+                    block: false,
+                    follow_ms: None,
+                    confirm: false,
+                    link: None,
+                    link_loops: None,
+                    warn: None,
+                    palette_refs: Default::default(),
+                    fade_ms: 1000,
+                    delay_ms: 0,
+                    changes: BTreeMap::new(),
+                },
+            );
+        }
+
+        let mut pb = Playback::new("main");
+        pb.go(&show)?;
+        pb.tick(200);
+        assert!(pb.toggle_pause());
+        pb.tick(5000);
+        assert_eq!(pb.transition_info(), Some((200, 0, 1000)));
+        assert!(!pb.toggle_pause());
+        pb.tick(800);
+        assert!(pb.transition_info().is_none());
+        assert!(!pb.toggle_pause()); // nothing running
+
+        pb.go(&show)?;
+        assert_eq!(pb.back(&show)?, Some(1));
+        assert_eq!(pb.back(&show)?, Some(1)); // first cue stays
+        Ok(())
+    }
+
+    #[test]
+    fn manual_crossfade_learns_fade_time() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        let cl = show.cue_lists.get_mut("main").unwrap();
+        cl.cues.insert(
+            1,
+            Cue {
+                number: 1,
+                label: "Red".to_string(),
+                meta: Default::default(),
+                block: false,
+                follow_ms: None,
+                confirm: false,
+                link: None,
+                link_loops: None,
+                warn: None,
+                palette_refs: Default::default(),
+                fade_ms: 0,
+                delay_ms: 0,
+                changes: [(
+                    1,
+                    FixtureValues {
+                        r: Some(200),
+                        ..Default::default()
+                    },
+                )]
+                .into_iter()
+                .collect(),
+            },
+        );
+
+        let mut pb = Playback::new("main");
+        assert_eq!(pb.start_manual(&show)?, Some(1));
+
+        pb.tick(1500);
+        assert_eq!(pb.set_manual(50)?, None);
+        assert_eq!(pb.output_state_map(&show)?[&1].r, Some(100));
+        assert_eq!(pb.manual_info(), Some((1500, 50)));
+
+        pb.tick(1000);
+        assert_eq!(pb.set_manual(100)?, Some(2500));
+        assert!(pb.transition_info().is_none());
+
+        assert_eq!(pb.learn_fade_time(&mut show)?, (1, 2500));
+        assert_eq!(show.cue_lists["main"].cues[&1].fade_ms, 2500);
+        assert!(pb.learn_fade_time(&mut show).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn crossfader_takes_over_timed_fades_and_splits() -> anyhow::Result<()> {
+        let show = crate::ShowBuilder::new("Test")
+            .cue(
+                crate::CueBuilder::new(1)
+                    .intensity(1, 200)
+                    .intensity(2, 0)
+                    .fade_ms(0),
+            )
+            .cue(
+                crate::CueBuilder::new(2)
+                    .intensity(1, 0)
+                    .intensity(2, 200)
+                    .fade_ms(1000),
+            )
+            .build()?;
+        let mut pb = Playback::new("main");
+        pb.go(&show)?;
+        pb.go(&show)?;
+        pb.tick(250);
+        assert_eq!(pb.crossfade_position(), Some((25, 25)));
+
+        // the fader picks the fade up; time no longer moves it
+        assert_eq!(pb.set_manual(40)?, None);
+        pb.tick(5000);
+        let out = pb.output_state_map(&show)?;
+        assert_eq!(
+            (out[&1].intensity, out[&2].intensity),
+            (Some(120), Some(80))
+        );
+
+        // split: the outgoing fixture stays up while the other comes in
+        assert_eq!(pb.set_manual_split(100, 0)?, None);
+        let out = pb.output_state_map(&show)?;
+        assert_eq!(
+            (out[&1].intensity, out[&2].intensity),
+            (Some(200), Some(200))
+        );
+        assert_eq!(pb.crossfade_position(), Some((100, 0)));
+        assert!(pb.set_manual_split(100, 100)?.is_some());
+        assert_eq!(pb.crossfade_position(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn rate_and_speed_master_scale_fades_and_follows() -> anyhow::Result<()> {
+        let show = crate::ShowBuilder::new("Test")
+            .cue(crate::CueBuilder::new(1).intensity(1, 0).fade_ms(0))
+            .cue(
+                crate::CueBuilder::new(2)
+                    .intensity(1, 200)
+                    .fade_ms(1000)
+                    .follow_ms(2000),
+            )
+            .cue(crate::CueBuilder::new(3).intensity(1, 0).fade_ms(0))
+            .build()?;
+        let mut pb = Playback::new("main");
+        pb.go(&show)?;
+        pb.go(&show)?;
+        pb.set_rate(2.0)?;
+        pb.tick(250);
+        assert_eq!(pb.output_state_map(&show)?[&1].intensity, Some(100));
+
+        // speed master on top: 2 x 0.25 is half speed
+        for _ in 0..3 {
+            pb.tick_at_speed(167, 0.25);
+        }
+        assert_eq!(pb.output_state_map(&show)?[&1].intensity, Some(150));
+        pb.tick(624);
+        assert_eq!(pb.follow(&show)?, None);
+        pb.tick(1);
+        assert_eq!(pb.follow(&show)?, Some(3));
+
+        assert!(pb.set_rate(0.0).is_err());
+        assert_eq!(pb.rate(), 2.0);
+        Ok(())
+    }
 }
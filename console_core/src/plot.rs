@@ -0,0 +1,304 @@
+//! Stage plot and cue thumbnails.
+//!
+//! Fixtures are drawn where `FixtureInstance::position` puts them; unplaced
+//! fixtures fill a grid underneath in id order. A cue thumbnail is the
+//! cue's tracked look drawn onto the plot, saved as a PNG next to the show.
+
+use anyhow::Context;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::{FixtureValues, Patch, Show};
+
+pub const THUMB_WIDTH: u32 = 160;
+pub const THUMB_HEIGHT: u32 = 90;
+
+const BACKGROUND: [u8; 3] = [24, 24, 28];
+const OUTLINE: [u8; 3] = [80, 80, 88];
+
+/// 8-bit RGB pixels, row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub rgb: Vec<u8>,
+}
+
+impl Image {
+    fn new(width: u32, height: u32, fill: [u8; 3]) -> Self {
+        Self {
+            width,
+            height,
+            rgb: fill.repeat((width * height) as usize),
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        let i = ((y * self.width + x) * 3) as usize;
+        [self.rgb[i], self.rgb[i + 1], self.rgb[i + 2]]
+    }
+
+    fn disc(&mut self, cx: f32, cy: f32, radius: f32, color: [u8; 3]) {
+        let (w, h) = (self.width as i32, self.height as i32);
+        let r = radius.ceil() as i32;
+        for y in (cy as i32 - r).max(0)..=(cy as i32 + r).min(h - 1) {
+            for x in (cx as i32 - r).max(0)..=(cx as i32 + r).min(w - 1) {
+                let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+                if dx * dx + dy * dy <= radius * radius {
+                    let i = ((y * w + x) * 3) as usize;
+                    self.rgb[i..i + 3].copy_from_slice(&color);
+                }
+            }
+        }
+    }
+
+    /// Encode as PNG (uncompressed deflate; thumbnails are small).
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(((self.width * 3 + 1) * self.height) as usize);
+        for row in self.rgb.chunks((self.width * 3) as usize) {
+            raw.push(0); // filter: none
+            raw.extend_from_slice(row);
+        }
+
+        let mut zlib = vec![0x78, 0x01];
+        let mut blocks = raw.chunks(0xffff).peekable();
+        if blocks.peek().is_none() {
+            zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+        }
+        while let Some(block) = blocks.next() {
+            let len = block.len() as u16;
+            zlib.push(u8::from(blocks.peek().is_none()));
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&self.width.to_be_bytes());
+        ihdr.extend_from_slice(&self.height.to_be_bytes());
+        ihdr.extend_from_slice(&[8, 2, 0, 0, 0]); // 8-bit RGB
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        for (kind, data) in [(b"IHDR", &ihdr), (b"IDAT", &zlib), (b"IEND", &Vec::new())] {
+            png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            let start = png.len();
+            png.extend_from_slice(kind);
+            png.extend_from_slice(data);
+            let crc = crc32(&png[start..]);
+            png.extend_from_slice(&crc.to_be_bytes());
+        }
+        png
+    }
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Plot position of every fixture, scaled to 0..1 in both directions.
+/// Placed fixtures use the top of the plot when some fixtures are unplaced.
+pub fn plot_positions(patch: &Patch) -> BTreeMap<u32, [f32; 2]> {
+    let placed: Vec<(u32, [i32; 2])> = patch
+        .fixtures
+        .values()
+        .filter_map(|f| Some((f.fixture_id, f.position?)))
+        .collect();
+    let unplaced: Vec<u32> = patch
+        .fixtures
+        .values()
+        .filter(|f| f.position.is_none())
+        .map(|f| f.fixture_id)
+        .collect();
+
+    let split = match (placed.is_empty(), unplaced.is_empty()) {
+        (true, _) => 0.0,
+        (false, true) => 1.0,
+        (false, false) => 0.7,
+    };
+    let mut out = BTreeMap::new();
+
+    if let Some(min_x) = placed.iter().map(|(_, p)| p[0]).min() {
+        let max_x = placed.iter().map(|(_, p)| p[0]).max().unwrap_or(min_x);
+        let min_y = placed.iter().map(|(_, p)| p[1]).min().unwrap_or(0);
+        let max_y = placed.iter().map(|(_, p)| p[1]).max().unwrap_or(min_y);
+        let scale = |v: i32, lo: i32, hi: i32| {
+            if hi == lo {
+                0.5
+            } else {
+                0.1 + 0.8 * (v - lo) as f32 / (hi - lo) as f32
+            }
+        };
+        for (fid, [x, y]) in placed {
+            out.insert(
+                fid,
+                [scale(x, min_x, max_x), scale(y, min_y, max_y) * split],
+            );
+        }
+    }
+
+    if !unplaced.is_empty() {
+        let cols = ((unplaced.len() as f32 * 16.0 / 9.0).sqrt().ceil() as usize).max(1);
+        let rows = unplaced.len().div_ceil(cols);
+        for (i, fid) in unplaced.into_iter().enumerate() {
+            let (col, row) = (i % cols, i / cols);
+            let x = (col as f32 + 0.5) / cols as f32;
+            let y = split + (1.0 - split) * (row as f32 + 0.5) / rows as f32;
+            out.insert(fid, [x, y]);
+        }
+    }
+    out
+}
+
+/// Draw `look` onto the plot. Fixtures without color values light white;
+/// fixtures that are out show as an outline.
+pub fn render_look(
+    show: &Show,
+    look: &BTreeMap<u32, FixtureValues>,
+    width: u32,
+    height: u32,
+) -> Image {
+    let mut img = Image::new(width, height, BACKGROUND);
+    let positions = plot_positions(&show.patch);
+    let radius =
+        (width.min(height) as f32 / (2.0 * (positions.len() as f32).sqrt() + 2.0)).clamp(2.0, 12.0);
+
+    for (fid, [x, y]) in positions {
+        let (cx, cy) = (x * width as f32, y * height as f32);
+        img.disc(cx, cy, radius, OUTLINE);
+        let Some(v) = look.get(&fid) else {
+            continue;
+        };
+        let level = v.intensity.unwrap_or(0) as u32;
+        if level == 0 {
+            continue;
+        }
+        let scale = |c: Option<u8>| (c.unwrap_or(255) as u32 * level / 255) as u8;
+        img.disc(cx, cy, radius - 1.0, [scale(v.r), scale(v.g), scale(v.b)]);
+    }
+    img
+}
+
+/// Thumbnail of a cue's tracked look.
+pub fn cue_thumbnail(show: &Show, cuelist: &str, cue: u32) -> anyhow::Result<Image> {
+    let list = show
+        .cue_lists
+        .get(cuelist)
+        .with_context(|| format!("unknown cuelist '{cuelist}'"))?;
+    anyhow::ensure!(list.cues.contains_key(&cue), "cue {cue} not found");
+    Ok(render_look(
+        show,
+        &list.tracked_state_at(cue),
+        THUMB_WIDTH,
+        THUMB_HEIGHT,
+    ))
+}
+
+/// `<show>.thumbs/<cuelist>_<cue>.png` next to the show file.
+pub fn thumbnail_path(show_path: &Path, cuelist: &str, cue: u32) -> PathBuf {
+    let mut dir = show_path.as_os_str().to_owned();
+    dir.push(".thumbs");
+    PathBuf::from(dir).join(format!("{cuelist}_{cue}.png"))
+}
+
+/// Write a thumbnail for every cue of `cuelist`. Returns how many were written.
+pub fn export_thumbnails(show: &Show, show_path: &Path, cuelist: &str) -> anyhow::Result<usize> {
+    let list = show
+        .cue_lists
+        .get(cuelist)
+        .with_context(|| format!("unknown cuelist '{cuelist}'"))?;
+    for &cue in list.cues.keys() {
+        let path = thumbnail_path(show_path, cuelist, cue);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+        }
+        let png = cue_thumbnail(show, cuelist, cue)?.to_png();
+        std::fs::write(&path, png).with_context(|| format!("write {}", path.display()))?;
+    }
+    Ok(list.cues.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cue, FixtureInstance, default_fixture_types};
+
+    #[test]
+    fn cue_thumbnail_draws_placed_fixtures() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        for ft in default_fixture_types() {
+            show.patch.add_fixture_type(ft);
+        }
+        let mut left = FixtureInstance::new(1, "Left", "rgb_par_3ch", 1, 1);
+        left.position = Some([-300, 0]);
+        let mut right = FixtureInstance::new(2, "Right", "rgb_par_3ch", 1, 4);
+        right.position = Some([300, 0]);
+        show.patch.add_fixture(left)?;
+        show.patch.add_fixture(right)?;
+        show.patch
+            .add_fixture(FixtureInstance::new(3, "Spare", "dimmer_1ch", 1, 7))?;
+
+        let pos = plot_positions(&show.patch);
+        assert_eq!(pos[&1], [0.1, 0.35]);
+        assert!((pos[&2][0] - 0.9).abs() < 1e-6);
+        assert!(pos[&3][1] > 0.7);
+
+        show.cue_lists.get_mut("main").unwrap().cues.insert(
+            1,
+            Cue {
+                number: 1,
+                label: "Red left".into(),
+                meta: Default::default(),
+                changes: BTreeMap::from([(
+                    1,
+                    FixtureValues {
+                        intensity: Some(255),
+                        r: Some(255),
+                        g: Some(0),
+                        b: Some(0),
+                        ..Default::default()
+                    },
+                )]),
+                fade_ms: 0,
+                delay_ms: 0,
+                block: false,
+                follow_ms: None,
+                confirm: false,
+                link: None,
+                link_loops: None,
+                warn: None,
+                palette_refs: Default::default(),
+            },
+        );
+        let img = cue_thumbnail(&show, "main", 1)?;
+        let at = |[x, y]: [f32; 2]| img.pixel((x * 160.0) as u32, (y * 90.0) as u32);
+        assert_eq!(at(pos[&1]), [255, 0, 0]);
+        assert_eq!(at(pos[&2]), OUTLINE);
+
+        let png = img.to_png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert_eq!(&png[16..24], &[0, 0, 0, 160, 0, 0, 0, 90]);
+        assert!(png.ends_with(&[0xae, 0x42, 0x60, 0x82])); // IEND crc
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+//! Pre-show channel check: imports an RDM discovery dump and compares it
+//! with the patch.
+//!
+//! The console sends no RDM traffic itself; the Art-Net and sACN outputs
+//! are transmit-only. Responders come from a JSON list written by an RDM
+//! gateway or a sensor rig. Each patched fixture is matched to the responder
+//! at its universe/address and its footprint and model are compared with
+//! the patch.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::Patch;
+
+/// What a responder reported: where it listens and its current personality.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RdmResponder {
+    pub uid: String,
+    pub universe: u16,
+    pub address: u16,
+    /// DMX footprint of the active personality.
+    pub footprint: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub personality: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    /// No responder starts at the patched address.
+    NoResponder,
+    FootprintMismatch {
+        expected: u16,
+        measured: u16,
+    },
+    ModelMismatch {
+        expected: String,
+        measured: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixtureCheck {
+    pub fixture_id: u32,
+    pub name: String,
+    pub uid: Option<String>,
+    pub status: CheckStatus,
+}
+
+impl FixtureCheck {
+    pub fn passed(&self) -> bool {
+        self.status == CheckStatus::Pass
+    }
+}
+
+impl std::fmt::Display for FixtureCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let verdict = if self.passed() { "PASS" } else { "FAIL" };
+        write!(f, "{verdict} {} {}", self.fixture_id, self.name)?;
+        match &self.status {
+            CheckStatus::Pass => Ok(()),
+            CheckStatus::NoResponder => write!(f, ": no responder at patched address"),
+            CheckStatus::FootprintMismatch { expected, measured } => {
+                write!(f, ": footprint {measured}, patch expects {expected}")
+            }
+            CheckStatus::ModelMismatch { expected, measured } => {
+                write!(f, ": responder is '{measured}', patch expects '{expected}'")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChannelCheckReport {
+    /// One entry per patched fixture, in fixture id order.
+    pub fixtures: Vec<FixtureCheck>,
+    /// Responders that no patched fixture starts at.
+    pub unpatched: Vec<RdmResponder>,
+}
+
+impl ChannelCheckReport {
+    pub fn failures(&self) -> usize {
+        self.fixtures.iter().filter(|c| !c.passed()).count()
+    }
+}
+
+/// Read a discovery dump: a JSON array of responders.
+pub fn load_responders(path: &Path) -> anyhow::Result<Vec<RdmResponder>> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    serde_json::from_str(&text).with_context(|| format!("parse {}", path.display()))
+}
+
+impl Patch {
+    /// Compare the patch with what the responders report.
+    pub fn channel_check(&self, responders: &[RdmResponder]) -> ChannelCheckReport {
+        let mut matched = BTreeSet::new();
+        let mut fixtures = Vec::new();
+
+        for f in self.fixtures.values() {
+            let found = responders
+                .iter()
+                .position(|r| r.universe == f.universe && r.address == f.address);
+            let Some(i) = found else {
+                fixtures.push(FixtureCheck {
+                    fixture_id: f.fixture_id,
+                    name: f.name.clone(),
+                    uid: None,
+                    status: CheckStatus::NoResponder,
+                });
+                continue;
+            };
+            matched.insert(i);
+            let r = &responders[i];
+            let ft = self.fixture_types.get(&f.fixture_type);
+            let expected = ft.map(|t| t.channels.len() as u16).unwrap_or(0);
+            let model = ft.map(|t| t.model.as_str()).unwrap_or_default();
+
+            let status = match &r.model {
+                Some(m)
+                    if !model.is_empty() && !m.to_lowercase().contains(&model.to_lowercase()) =>
+                {
+                    CheckStatus::ModelMismatch {
+                        expected: model.to_string(),
+                        measured: m.clone(),
+                    }
+                }
+                _ if r.footprint != expected => CheckStatus::FootprintMismatch {
+                    expected,
+                    measured: r.footprint,
+                },
+                _ => CheckStatus::Pass,
+            };
+            fixtures.push(FixtureCheck {
+                fixture_id: f.fixture_id,
+                name: f.name.clone(),
+                uid: Some(r.uid.clone()),
+                status,
+            });
+        }
+
+        let unpatched = responders
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !matched.contains(i))
+            .map(|(_, r)| r.clone())
+            .collect();
+        ChannelCheckReport {
+            fixtures,
+            unpatched,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FixtureInstance, default_fixture_types};
+
+    #[test]
+    fn channel_check_compares_responders_with_patch() -> anyhow::Result<()> {
+        let mut patch = Patch::default();
+        for ft in default_fixture_types() {
+            patch.add_fixture_type(ft);
+        }
+        patch.add_fixture(FixtureInstance::new(1, "Par 1", "rgb_par_3ch", 1, 1))?;
+        patch.add_fixture(FixtureInstance::new(2, "Par 2", "rgb_par_3ch", 1, 4))?;
+        patch.add_fixture(FixtureInstance::new(3, "Dim", "dimmer_1ch", 1, 7))?;
+
+        let responder = |uid: &str, address, footprint| RdmResponder {
+            uid: uid.into(),
+            universe: 1,
+            address,
+            footprint,
+            personality: None,
+            model: None,
+        };
+        let responders = vec![
+            responder("0001:00000001", 1, 3),
+            responder("0001:00000002", 4, 7),
+            responder("0001:00000003", 20, 1),
+        ];
+        let report = patch.channel_check(&responders);
+
+        let status: Vec<_> = report.fixtures.iter().map(|c| c.status.clone()).collect();
+        assert_eq!(
+            status,
+            vec![
+                CheckStatus::Pass,
+                CheckStatus::FootprintMismatch {
+                    expected: 3,
+                    measured: 7
+                },
+                CheckStatus::NoResponder,
+            ]
+        );
+        assert_eq!(report.failures(), 2);
+        assert_eq!(report.unpatched.len(), 1);
+        assert_eq!(report.unpatched[0].address, 20);
+        Ok(())
+    }
+}
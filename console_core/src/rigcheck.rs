@@ -0,0 +1,142 @@
+//! Power-up rig check: a generated cue list that brings up every fixture in
+//! turn, universe by universe, so a load-in can be checked without any
+//! programming.
+
+use std::collections::BTreeMap;
+
+use crate::{Cue, CueList, FixtureValues, Show};
+
+/// How long each fixture stays up by default.
+pub const DEFAULT_STEP_MS: u32 = 2000;
+
+const FULL: FixtureValues = FixtureValues {
+    intensity: Some(255),
+    r: Some(255),
+    g: Some(255),
+    b: Some(255),
+    pan: None,
+    tilt: None,
+};
+const OUT: FixtureValues = FixtureValues {
+    intensity: Some(0),
+    r: Some(0),
+    g: Some(0),
+    b: Some(0),
+    pan: None,
+    tilt: None,
+};
+
+impl Show {
+    /// Build the rig check: one cue per fixture at full (white where it has
+    /// color), ordered by universe and address, each following on after
+    /// `step_ms`, ending with everything out.
+    pub fn rig_check_list(&self, step_ms: u32) -> CueList {
+        let mut fixtures: Vec<_> = self.patch.fixtures.values().collect();
+        fixtures.sort_by_key(|f| (f.universe, f.address, f.fixture_id));
+
+        let mut list = CueList::ensure();
+        let mut prev = None;
+        for (f, number) in fixtures.iter().zip(1..) {
+            let mut changes = BTreeMap::from([(f.fixture_id, FULL)]);
+            if let Some(p) = prev {
+                changes.insert(p, OUT);
+            }
+            list.cues.insert(
+                number,
+                Cue {
+                    number,
+                    label: format!("U{} {:03} {}", f.universe, f.address, f.name),
+                    block: false,
+                    fade_ms: 0,
+                    delay_ms: 0,
+                    follow_ms: Some(step_ms),
+                    confirm: false,
+                    link: None,
+                    link_loops: None,
+                    warn: None,
+                    palette_refs: Default::default(),
+                    changes,
+                    meta: Default::default(),
+                },
+            );
+            prev = Some(f.fixture_id);
+        }
+        if let Some(p) = prev {
+            let number = fixtures.len() as u32 + 1;
+            list.cues.insert(
+                number,
+                Cue {
+                    number,
+                    label: "Rig check done".into(),
+                    block: false,
+                    fade_ms: 0,
+                    delay_ms: 0,
+                    follow_ms: None,
+                    confirm: false,
+                    link: None,
+                    link_loops: None,
+                    warn: None,
+                    palette_refs: Default::default(),
+                    changes: BTreeMap::from([(p, OUT)]),
+                    meta: Default::default(),
+                },
+            );
+        }
+        list
+    }
+
+    /// Save the rig check as a new cue list. Returns the number of fixtures.
+    pub fn add_rig_check(&mut self, name: &str, step_ms: u32) -> anyhow::Result<usize> {
+        anyhow::ensure!(!self.patch.fixtures.is_empty(), "nothing patched");
+        self.add_cue_list(name)?;
+        let list = self.rig_check_list(step_ms);
+        let count = list.cues.len() - 1;
+        self.cue_lists.insert(name.to_string(), list);
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FixtureInstance, default_fixture_types};
+
+    #[test]
+    fn rig_check_steps_through_fixtures_by_universe() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        for ft in default_fixture_types() {
+            show.patch.add_fixture_type(ft);
+        }
+        show.patch
+            .add_fixture(FixtureInstance::new(1, "Back", "dimmer_1ch", 2, 1))?;
+        show.patch
+            .add_fixture(FixtureInstance::new(2, "Front", "rgb_par_3ch", 1, 10))?;
+        show.patch
+            .add_fixture(FixtureInstance::new(3, "Side", "dimmer_1ch", 1, 1))?;
+
+        assert_eq!(show.add_rig_check("rigcheck", 1500)?, 3);
+        assert!(show.add_rig_check("rigcheck", 1500).is_err());
+
+        let list = &show.cue_lists["rigcheck"];
+        let order: Vec<&str> = list.cues.values().map(|c| c.label.as_str()).collect();
+        assert_eq!(
+            order,
+            [
+                "U1 001 Side",
+                "U1 010 Front",
+                "U2 001 Back",
+                "Rig check done"
+            ]
+        );
+        assert_eq!(list.cues[&1].follow_ms, Some(1500));
+        let look = list.tracked_state_at(2);
+        assert_eq!(look[&2].intensity, Some(255));
+        assert_eq!(look[&3].intensity, Some(0));
+        assert!(
+            list.tracked_state_at(4)
+                .values()
+                .all(|v| v.intensity == Some(0))
+        );
+        Ok(())
+    }
+}
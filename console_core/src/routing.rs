@@ -0,0 +1,286 @@
+//! Output routing: each destination (an Art-Net node, sACN multicast, a USB
+//! widget...)
+//! receives any set of internal universes, renumbered as it needs them.
+//! The matrix is saved with the show; `RoutedOutput` drives it on an
+//! `OutputThread`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use crate::{
+    ArtNetConfig, ArtNetOutput, LiveState, OutputDriver, SacnConfig, SacnOutput, UsbDmxOutput,
+    UsbWidget,
+};
+
+/// Protocol and address of a destination.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DestinationKind {
+    ArtNet {
+        target: SocketAddr,
+    },
+    /// Multicast per universe, or everything to one receiver.
+    Sacn {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        unicast: Option<SocketAddr>,
+    },
+    /// A USB widget on a serial device; it outputs the lowest universe
+    /// mapped to it.
+    UsbDmx {
+        device: PathBuf,
+        widget: UsbWidget,
+    },
+}
+
+impl std::fmt::Display for DestinationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ArtNet { target } => write!(f, "Art-Net {target}"),
+            Self::Sacn { unicast: None } => write!(f, "sACN multicast"),
+            Self::Sacn {
+                unicast: Some(addr),
+            } => write!(f, "sACN {addr}"),
+            Self::UsbDmx { device, widget } => write!(f, "{widget} {}", device.display()),
+        }
+    }
+}
+
+fn default_priority() -> u8 {
+    100
+}
+
+fn is_default_priority(p: &u8) -> bool {
+    *p == default_priority()
+}
+
+fn yes() -> bool {
+    true
+}
+
+fn is_true(b: &bool) -> bool {
+    *b
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Destination {
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: DestinationKind,
+    #[serde(default = "yes", skip_serializing_if = "is_true")]
+    pub enabled: bool,
+    /// Source priority for protocols that carry one (0-200, default 100).
+    #[serde(
+        default = "default_priority",
+        skip_serializing_if = "is_default_priority"
+    )]
+    pub priority: u8,
+    /// Internal universe -> universe on this destination.
+    #[serde(default)]
+    pub universes: BTreeMap<u16, u16>,
+}
+
+impl Destination {
+    pub fn new(name: impl Into<String>, kind: DestinationKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            enabled: true,
+            priority: default_priority(),
+            universes: BTreeMap::new(),
+        }
+    }
+
+    /// The part of `frame` this destination receives, renumbered.
+    pub fn route(&self, frame: &LiveState) -> LiveState {
+        let mut out = LiveState::new();
+        for (internal, external) in &self.universes {
+            if let Some(addrs) = frame.universes.get(internal) {
+                out.universes.insert(*external, addrs.clone());
+            }
+        }
+        out
+    }
+
+    /// sACN destinations use the show's source name and per-universe
+    /// priorities, with this destination's priority as the default.
+    fn open(&self, sacn: &SacnConfig) -> anyhow::Result<Box<dyn OutputDriver>> {
+        Ok(match &self.kind {
+            DestinationKind::ArtNet { target } => Box::new(ArtNetOutput::new(ArtNetConfig {
+                target: *target,
+                unicast: BTreeMap::new(),
+            })?),
+            DestinationKind::Sacn { unicast } => {
+                let config = SacnConfig {
+                    priority: self.priority,
+                    ..sacn.clone()
+                };
+                Box::new(SacnOutput::new(config, *unicast)?)
+            }
+            DestinationKind::UsbDmx { device, widget } => {
+                Box::new(UsbDmxOutput::new(device, *widget)?)
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputRouting {
+    #[serde(default)]
+    pub destinations: Vec<Destination>,
+    /// Output delay in ms per internal universe, see `OutputThread::set_delays`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub delays: BTreeMap<u16, u32>,
+}
+
+impl OutputRouting {
+    /// No destinations configured.
+    pub fn is_empty(&self) -> bool {
+        self.destinations.is_empty()
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Destination> {
+        self.destinations.iter_mut().find(|d| d.name == name)
+    }
+
+    /// Add a destination; names are unique.
+    pub fn add(&mut self, dest: Destination) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !self.destinations.iter().any(|d| d.name == dest.name),
+            "output '{}' already exists",
+            dest.name
+        );
+        self.destinations.push(dest);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> anyhow::Result<()> {
+        let before = self.destinations.len();
+        self.destinations.retain(|d| d.name != name);
+        anyhow::ensure!(self.destinations.len() < before, "no output '{name}'");
+        Ok(())
+    }
+
+    /// Destinations each internal universe is sent to, as (destination, universe).
+    pub fn matrix(&self) -> BTreeMap<u16, Vec<(&str, u16)>> {
+        let mut out: BTreeMap<u16, Vec<(&str, u16)>> = BTreeMap::new();
+        for d in self.destinations.iter().filter(|d| d.enabled) {
+            for (&internal, &external) in &d.universes {
+                out.entry(internal).or_default().push((&d.name, external));
+            }
+        }
+        out
+    }
+}
+
+/// Sends every frame to all enabled destinations of a routing.
+pub struct RoutedOutput {
+    routes: Vec<(Destination, Box<dyn OutputDriver>)>,
+}
+
+impl RoutedOutput {
+    /// Open a driver for each enabled destination.
+    pub fn open(routing: &OutputRouting, sacn: &SacnConfig) -> anyhow::Result<Self> {
+        Ok(Self::with_drivers(
+            routing
+                .destinations
+                .iter()
+                .filter(|d| d.enabled)
+                .map(|d| Ok((d.clone(), d.open(sacn)?)))
+                .collect::<anyhow::Result<_>>()?,
+        ))
+    }
+
+    /// Use the given drivers (e.g. recorders in tests).
+    pub fn with_drivers(routes: Vec<(Destination, Box<dyn OutputDriver>)>) -> Self {
+        Self { routes }
+    }
+}
+
+impl OutputDriver for RoutedOutput {
+    fn name(&self) -> String {
+        let names: Vec<_> = self.routes.iter().map(|(d, _)| d.name.as_str()).collect();
+        format!("outputs ({})", names.join(", "))
+    }
+
+    /// Every destination gets its frame even if an earlier one fails.
+    fn send(&mut self, frame: &LiveState) -> anyhow::Result<()> {
+        let mut failed = Vec::new();
+        for (dest, driver) in &mut self.routes {
+            if let Err(e) = driver.send(&dest.route(frame)) {
+                failed.push(format!("{}: {e:#}", dest.name));
+            }
+        }
+        anyhow::ensure!(failed.is_empty(), "{}", failed.join("; "));
+        Ok(())
+    }
+
+    fn reconnect(&mut self) -> anyhow::Result<()> {
+        for (_, driver) in &mut self.routes {
+            driver.reconnect()?;
+        }
+        Ok(())
+    }
+
+    fn terminate(&mut self) -> anyhow::Result<()> {
+        for (_, driver) in &mut self.routes {
+            driver.terminate()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<LiveState>>>);
+
+    impl OutputDriver for Recorder {
+        fn send(&mut self, frame: &LiveState) -> anyhow::Result<()> {
+            self.0.lock().unwrap().push(frame.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn universes_fan_out_to_destinations() -> anyhow::Result<()> {
+        let node = |port| DestinationKind::ArtNet {
+            target: SocketAddr::from(([10, 0, 0, 1], port)),
+        };
+        let mut routing = OutputRouting::default();
+        let mut a = Destination::new("A", node(1));
+        a.universes = BTreeMap::from([(1, 1), (2, 2)]);
+        let mut b = Destination::new("B", node(2));
+        b.universes = BTreeMap::from([(1, 5)]);
+        routing.add(a.clone())?;
+        routing.add(b.clone())?;
+        assert!(routing.add(Destination::new("A", node(3))).is_err());
+        assert_eq!(routing.matrix()[&1], vec![("A", 1), ("B", 5)]);
+
+        let json = serde_json::to_string(&routing)?;
+        assert_eq!(serde_json::from_str::<OutputRouting>(&json)?, routing);
+
+        let (ra, rb) = (Recorder::default(), Recorder::default());
+        let mut out =
+            RoutedOutput::with_drivers(vec![(a, Box::new(ra.clone())), (b, Box::new(rb.clone()))]);
+        let mut frame = LiveState::new();
+        frame.set(1, 1, 200);
+        frame.set(2, 3, 50);
+        out.send(&frame)?;
+
+        assert_eq!(
+            ra.0.lock().unwrap()[0].nonzero(),
+            vec![(1, 1, 200), (2, 3, 50)]
+        );
+        assert_eq!(rb.0.lock().unwrap()[0].nonzero(), vec![(5, 1, 200)]);
+        Ok(())
+    }
+}
@@ -1,105 +1,801 @@
-use crate::{FixtureValues, LiveState, Playback, Programmer, Show};
-use std::collections::BTreeMap;
+use crate::{
+    Clock, DmxInput, FixtureValues, Focus, LiveState, Modifiers, MonotonicClock, Playback,
+    Programmer, Show, ShowStore, WingTarget,
+};
+use anyhow::Context;
+use std::collections::{BTreeMap, BTreeSet};
 
+use crate::limits::apply_limits;
+use crate::masters::{apply_blackout, apply_masters, apply_panic, flash_levels, scale_levels};
 // Import the internal renderer from playback.rs
-use crate::playback::render_fixture_values;
+use crate::playback::{StateMap, render_fixture_values, widen};
 
+/// Every playback by name. "a" and "b" always exist; indexing with a name
+/// that isn't there panics, so look others up with `get`.
+#[derive(Debug, Default)]
+pub struct Playbacks(BTreeMap<String, Playback>);
+
+impl std::ops::Deref for Playbacks {
+    type Target = BTreeMap<String, Playback>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for Playbacks {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl std::ops::Index<&str> for Playbacks {
+    type Output = Playback;
+
+    fn index(&self, name: &str) -> &Playback {
+        self.0
+            .get(name)
+            .unwrap_or_else(|| panic!("unknown playback '{name}'"))
+    }
+}
+
+impl std::ops::IndexMut<&str> for Playbacks {
+    fn index_mut(&mut self, name: &str) -> &mut Playback {
+        self.0
+            .get_mut(name)
+            .unwrap_or_else(|| panic!("unknown playback '{name}'"))
+    }
+}
+
+/// A timed grand master move. `blackout_after` is Some for a timed
+/// blackout: the level the GM goes back to once the blackout is latched.
+#[derive(Debug, Clone, Copy)]
+struct GmFade {
+    from: u8,
+    to: u8,
+    elapsed_ms: u32,
+    fade_ms: u32,
+    blackout_after: Option<u8>,
+}
+
+/// A running fixture control macro.
+#[derive(Debug, Clone)]
+struct HeldControl {
+    fixture_id: u32,
+    name: String,
+    universe: u16,
+    address: u16,
+    value: u8,
+    remaining_ms: u32,
+}
+
+/// The show document (behind a revisioned store) plus the volatile state
+/// that drives output: playbacks, programmer, masters and overrides.
 #[derive(Debug)]
 pub struct Runtime {
-    pub show: Show,
-    pub playback_a: Playback,
-    pub playback_b: Playback,
+    pub show: ShowStore,
+    /// A, B and one playback per other entry of `Show::playbacks`, merged
+    /// by priority.
+    pub playbacks: Playbacks,
     pub programmer: Programmer,
+    /// Focus session stepping through fixtures; sits on the programmer.
+    pub focus: Option<Focus>,
+    /// External code changing the merged playback values (see `OutputModifier`).
+    pub modifiers: Modifiers,
+    /// Raw DMX tester layer: bypasses the patch and sits on top of everything.
+    pub tester: LiveState,
+    /// Received sACN, merged per `Show::dmx_input`.
+    pub dmx_input: DmxInput,
+    /// Last value each wing fader applied, by (universe, slot).
+    wing_levels: BTreeMap<(u16, u16), u8>,
+    controls: Vec<HeldControl>,
+    /// Group master levels; groups not listed are at full.
+    masters: BTreeMap<String, u8>,
+    grand_master: u8,
+    /// Multiplies every playback's rate (see `Playback::set_rate`).
+    speed_master: f32,
+    gm_fade: Option<GmFade>,
+    /// Blackout switched on until switched off.
+    blackout_latched: bool,
+    /// Blackout held by a momentary (flash) button.
+    blackout_held: bool,
+    panic: bool,
+    clock: Box<dyn Clock>,
+    last_advance_ms: u64,
 }
 
 impl Runtime {
     pub fn new(show: Show) -> Self {
+        Self::with_clock(show, Box::new(MonotonicClock::new()))
+    }
+
+    /// Runtime driven by a custom clock (e.g. `SimClock` in tests).
+    pub fn with_clock(show: Show, clock: Box<dyn Clock>) -> Self {
+        let last_advance_ms = clock.now_ms();
+        let playback = |name: &str| {
+            let cfg = show.playbacks.get(name);
+            let list = cfg
+                .and_then(|c| c.cuelist.as_deref())
+                .filter(|l| show.cue_lists.contains_key(*l))
+                .unwrap_or("main");
+            let mut pb = Playback::new(list);
+            if let Some(cfg) = cfg {
+                pb.filter = cfg.filter;
+                pb.priority = cfg.priority;
+            }
+            pb
+        };
+        let playbacks = ["a", "b"]
+            .into_iter()
+            .chain(show.playbacks.keys().map(String::as_str))
+            .map(|name| (name.to_string(), playback(name)))
+            .collect();
+        let mut programmer = Programmer::new();
+        programmer.policy = show.selection_policy;
         Self {
-            show,
-            playback_a: Playback::new("main"),
-            playback_b: Playback::new("main"),
-            programmer: Programmer::new(),
+            playbacks: Playbacks(playbacks),
+            show: ShowStore::new(show),
+            programmer,
+            focus: None,
+            modifiers: Modifiers::default(),
+            tester: LiveState::new(),
+            dmx_input: DmxInput::default(),
+            wing_levels: BTreeMap::new(),
+            controls: Vec::new(),
+            masters: BTreeMap::new(),
+            grand_master: 255,
+            speed_master: 1.0,
+            gm_fade: None,
+            blackout_latched: false,
+            blackout_held: false,
+            panic: false,
+            clock,
+            last_advance_ms,
+        }
+    }
+
+    pub fn now_ms(&self) -> u64 {
+        self.clock.now_ms()
+    }
+
+    /// Take in received sACN packets.
+    pub fn feed_dmx_input(&mut self, packets: &[crate::dmx_input::E131Data]) {
+        let now = self.clock.now_ms();
+        for p in packets {
+            self.dmx_input.feed(p, now);
+        }
+        self.apply_wing(now);
+    }
+
+    /// Wing faders whose input moved take over their target, like a fader
+    /// on the desk; a fader that hasn't moved leaves later edits alone.
+    fn apply_wing(&mut self, now_ms: u64) {
+        let mut moved = Vec::new();
+        for w in &self.show.dmx_wing {
+            let Some((_, data)) = self.dmx_input.universe(w.universe, now_ms) else {
+                continue;
+            };
+            let Some(&value) = data.get((w.address as usize).wrapping_sub(1)) else {
+                continue;
+            };
+            let key = (w.universe, w.address);
+            if self.wing_levels.insert(key, value) != Some(value) {
+                moved.push((w.target.clone(), value));
+            }
+        }
+        for (target, value) in moved {
+            match target {
+                // a group taken off the master page just stops following
+                WingTarget::Master(group) => {
+                    let _ = self.set_master(&group, value);
+                }
+                WingTarget::Fixtures(ids) => {
+                    for fid in ids {
+                        self.programmer.set_fixture_intensity(fid, value);
+                    }
+                }
+                WingTarget::Selection => {
+                    for fid in self.programmer.selected.clone() {
+                        self.programmer.set_fixture_intensity(fid, value);
+                    }
+                }
+            }
         }
     }
 
     pub fn tick(&mut self, dt_ms: u32) {
-        self.playback_a.tick(dt_ms);
-        self.playback_b.tick(dt_ms);
+        let show = &self.show;
+        for pb in self.playbacks.values_mut() {
+            pb.tick_at_speed(dt_ms, self.speed_master);
+            // A follow into a list that vanished under it simply doesn't fire.
+            let _ = pb.follow(show);
+        }
+        if let Some(focus) = &mut self.focus {
+            focus.tick(dt_ms);
+        }
+        self.modifiers.tick(dt_ms);
+
+        if let Some(f) = &mut self.gm_fade {
+            f.elapsed_ms = f.elapsed_ms.saturating_add(dt_ms);
+            if f.elapsed_ms >= f.fade_ms {
+                self.grand_master = f.to;
+                if let Some(level) = f.blackout_after {
+                    self.blackout_latched = true;
+                    self.grand_master = level;
+                }
+                self.gm_fade = None;
+            } else {
+                let t = f.elapsed_ms as f32 / f.fade_ms as f32;
+                self.grand_master =
+                    (f.from as f32 + (f.to as f32 - f.from as f32) * t).round() as u8;
+            }
+        }
+
+        for c in &mut self.controls {
+            c.remaining_ms = c.remaining_ms.saturating_sub(dt_ms);
+        }
+        self.controls.retain(|c| c.remaining_ms > 0);
+    }
+
+    /// Forget time passed since the last advance (e.g. when resuming run mode).
+    pub fn sync_clock(&mut self) {
+        self.last_advance_ms = self.clock.now_ms();
+    }
+
+    /// Tick playbacks by the clock time passed since the last advance.
+    /// `max_step_ms` caps one step so long pauses don't jump fades forward.
+    /// Returns the milliseconds actually ticked.
+    pub fn advance(&mut self, max_step_ms: u32) -> u32 {
+        let now = self.clock.now_ms();
+        let dt = now.saturating_sub(self.last_advance_ms);
+        self.last_advance_ms = now;
+
+        let dt = dt.min(max_step_ms as u64) as u32;
+        if dt > 0 {
+            self.tick(dt);
+        }
+        dt
+    }
+
+    /// Run a control macro of the fixture's type (e.g. "lamp_on"): the value
+    /// is held on its channel for the macro's hold time, then released.
+    pub fn fixture_control(&mut self, fixture_id: u32, name: &str) -> anyhow::Result<()> {
+        let f = self
+            .show
+            .patch
+            .fixtures
+            .get(&fixture_id)
+            .with_context(|| format!("unknown fixture id {fixture_id}"))?;
+        let ft = self
+            .show
+            .patch
+            .fixture_types
+            .get(&f.fixture_type)
+            .with_context(|| format!("unknown fixture type '{}'", f.fixture_type))?;
+        let ctl = ft
+            .controls
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+            .with_context(|| format!("fixture type '{}' has no control '{name}'", ft.type_id))?;
+        let idx = ft
+            .channels
+            .iter()
+            .position(|ch| ch.name.eq_ignore_ascii_case(&ctl.channel))
+            .with_context(|| {
+                format!(
+                    "control '{}' uses unknown channel '{}'",
+                    ctl.name, ctl.channel
+                )
+            })?;
+
+        let held = HeldControl {
+            fixture_id,
+            name: ctl.name.clone(),
+            universe: f.universe,
+            address: f.address + idx as u16,
+            value: ctl.value,
+            remaining_ms: ctl.hold_ms.max(1),
+        };
+        // a new control on the same channel replaces the old one
+        self.controls
+            .retain(|c| (c.universe, c.address) != (held.universe, held.address));
+        self.controls.push(held);
+        Ok(())
+    }
+
+    /// Running control macros as (fixture_id, name, remaining_ms).
+    pub fn active_controls(&self) -> Vec<(u32, &str, u32)> {
+        self.controls
+            .iter()
+            .map(|c| (c.fixture_id, c.name.as_str(), c.remaining_ms))
+            .collect()
+    }
+
+    /// Drive a raw DMX address from the tester layer.
+    pub fn set_dmx(&mut self, universe: u16, address: u16, value: u8) -> anyhow::Result<()> {
+        anyhow::ensure!(universe >= 1, "universe must be >= 1");
+        anyhow::ensure!(
+            (1..=512).contains(&address),
+            "DMX address must be 1..=512, got {address}"
+        );
+        self.tester.set(universe, address, value);
+        Ok(())
+    }
+
+    pub fn clear_dmx(&mut self) {
+        self.tester = LiveState::new();
+    }
+
+    /// Level wheel: nudge the selected fixtures relative to the current output.
+    pub fn nudge_intensity(&mut self, delta: i16) -> anyhow::Result<()> {
+        let output = self.render()?;
+        self.programmer.nudge_intensity(&self.show, &output, delta);
+        Ok(())
+    }
+
+    /// Set the master of a group on the master page.
+    pub fn set_master(&mut self, group: &str, level: u8) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.show.masters.groups.iter().any(|g| g == group),
+            "group '{group}' has no master on the master page"
+        );
+        self.masters.insert(group.to_string(), level);
+        Ok(())
+    }
+
+    pub fn master_level(&self, group: &str) -> u8 {
+        self.masters.get(group).copied().unwrap_or(255)
+    }
+
+    /// Move every master on the page to `level` (the "all inhibitors" action).
+    pub fn set_all_masters(&mut self, level: u8) {
+        for g in &self.show.masters.groups {
+            self.masters.insert(g.clone(), level);
+        }
+    }
+
+    /// Grand master: scales every intensity except the DMX tester and held controls.
+    /// Takes over from a timed GM move or blackout.
+    pub fn set_grand_master(&mut self, level: u8) {
+        self.gm_fade = None;
+        self.grand_master = level;
+    }
+
+    /// Move the grand master to `level` over `fade_ms`, advanced by `tick`.
+    pub fn fade_grand_master(&mut self, level: u8, fade_ms: u32) {
+        self.cancel_gm_fade();
+        if fade_ms == 0 {
+            self.grand_master = level;
+            return;
+        }
+        self.gm_fade = Some(GmFade {
+            from: self.grand_master,
+            to: level,
+            elapsed_ms: 0,
+            fade_ms,
+            blackout_after: None,
+        });
     }
 
-    /// Render final DMX:
-    /// 1) merge playback A + B at the *fixture-values* level (HTP/LTP)
+    /// Fade the grand master out over `fade_ms`, then latch the blackout and
+    /// put the GM back, so `blackout off` brings the stage back as it was.
+    pub fn timed_blackout(&mut self, fade_ms: u32) {
+        self.cancel_gm_fade();
+        if fade_ms == 0 {
+            self.blackout_latched = true;
+            return;
+        }
+        self.gm_fade = Some(GmFade {
+            from: self.grand_master,
+            to: 0,
+            elapsed_ms: 0,
+            fade_ms,
+            blackout_after: Some(self.grand_master),
+        });
+    }
+
+    /// Time left in a timed GM move or blackout, and whether it's a blackout.
+    pub fn gm_fade_remaining(&self) -> Option<(u32, bool)> {
+        self.gm_fade.map(|f| {
+            (
+                f.fade_ms.saturating_sub(f.elapsed_ms),
+                f.blackout_after.is_some(),
+            )
+        })
+    }
+
+    fn cancel_gm_fade(&mut self) {
+        if let Some(level) = self.gm_fade.take().and_then(|f| f.blackout_after) {
+            self.grand_master = level;
+        }
+    }
+
+    pub fn grand_master(&self) -> u8 {
+        self.grand_master
+    }
+
+    /// Speed master: runs the fades, delays and follows of both playbacks
+    /// `speed` times as fast, on top of their own rates.
+    pub fn set_speed_master(&mut self, speed: f32) -> anyhow::Result<()> {
+        crate::playback::check_rate(speed)?;
+        self.speed_master = speed;
+        Ok(())
+    }
+
+    pub fn speed_master(&self) -> f32 {
+        self.speed_master
+    }
+
+    /// Latching blackout: all intensities out until switched off. Programmer
+    /// and playbacks keep running underneath.
+    pub fn set_blackout(&mut self, on: bool) {
+        if self.gm_fade.is_some_and(|f| f.blackout_after.is_some()) {
+            self.cancel_gm_fade();
+        }
+        self.blackout_latched = on;
+    }
+
+    /// Momentary blackout: out only while held. Releasing it doesn't clear
+    /// a latched blackout.
+    pub fn hold_blackout(&mut self, held: bool) {
+        self.blackout_held = held;
+    }
+
+    pub fn is_blackout(&self) -> bool {
+        self.blackout_latched || self.blackout_held
+    }
+
+    pub fn is_blackout_latched(&self) -> bool {
+        self.blackout_latched
+    }
+
+    /// Emergency look: house group at full, every other intensity out.
+    /// Overrides everything, including the DMX tester, until released.
+    pub fn set_panic(&mut self, on: bool) {
+        self.panic = on;
+    }
+
+    pub fn is_panic(&self) -> bool {
+        self.panic
+    }
+
+    /// Playback by name: "a", "b" or one added with `add_playback`.
+    pub fn playback(&self, name: &str) -> anyhow::Result<&Playback> {
+        self.playbacks
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("unknown playback '{name}'"))
+    }
+
+    pub fn playback_mut(&mut self, name: &str) -> anyhow::Result<&mut Playback> {
+        self.playbacks
+            .get_mut(name)
+            .ok_or_else(|| anyhow::anyhow!("unknown playback '{name}'"))
+    }
+
+    /// Every playback with its name, by name.
+    pub fn all_playbacks(&self) -> impl Iterator<Item = (&str, &Playback)> {
+        self.playbacks.iter().map(|(n, pb)| (n.as_str(), pb))
+    }
+
+    /// Add a playback running `cuelist`, saved in the show.
+    pub fn add_playback(&mut self, name: &str, cuelist: &str) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !name.is_empty() && !name.contains(char::is_whitespace),
+            "playback name '{name}' is empty or has spaces"
+        );
+        anyhow::ensure!(
+            self.playback(name).is_err(),
+            "playback '{name}' already exists"
+        );
+        let mut pb = Playback::new("main");
+        pb.use_list(&self.show, cuelist)?;
+        self.playbacks.insert(name.to_string(), pb);
+        let cfg = self.show.playbacks.entry(name.to_string()).or_default();
+        cfg.cuelist = (cuelist != "main").then(|| cuelist.to_string());
+        Ok(())
+    }
+
+    /// Remove a playback added with `add_playback`; A and B stay.
+    pub fn remove_playback(&mut self, name: &str) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !matches!(name, "a" | "b") && self.playbacks.remove(name).is_some(),
+            "no playback '{name}' to remove (A and B always exist)"
+        );
+        self.show.playbacks.remove(name);
+        Ok(())
+    }
+
+    /// Set a playback's merge priority and remember it in the show.
+    pub fn set_playback_priority(&mut self, name: &str, priority: u8) -> anyhow::Result<()> {
+        self.playback_mut(name)?.priority = priority;
+        self.show
+            .playbacks
+            .entry(name.to_string())
+            .or_default()
+            .priority = priority;
+        Ok(())
+    }
+
+    /// Point playback `name` at another cue list and remember the choice
+    /// in the show.
+    pub fn use_cue_list(&mut self, name: &str, cuelist: &str) -> anyhow::Result<()> {
+        match self.playbacks.get_mut(name) {
+            Some(pb) => pb.use_list(&self.show, cuelist)?,
+            None => anyhow::bail!("unknown playback '{name}'"),
+        }
+        let cfg = self.show.playbacks.entry(name.to_string()).or_default();
+        cfg.cuelist = (cuelist != "main").then(|| cuelist.to_string());
+        Ok(())
+    }
+
+    /// Rename a cue list; playbacks running it follow the new name.
+    pub fn rename_cue_list(&mut self, from: &str, to: &str) -> anyhow::Result<()> {
+        self.show.rename_cue_list(from, to)?;
+        for pb in self.playbacks.values_mut() {
+            if pb.cuelist == from {
+                pb.cuelist = to.to_string();
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete a cue list that no playback is running.
+    pub fn delete_cue_list(&mut self, name: &str) -> anyhow::Result<()> {
+        for (label, pb) in self.all_playbacks() {
+            anyhow::ensure!(
+                pb.cuelist != name,
+                "cuelist '{name}' is in use by playback {label}"
+            );
+        }
+        self.show.delete_cue_list(name)
+    }
+
+    /// Render live DMX:
+    /// 0) channel defaults from the fixture types (shutter open, pan centre...)
+    /// 1) merge the playbacks at the *fixture-values* level, each scaled
+    ///    by its playback master (at full while flashed): the highest
+    ///    priority setting a parameter owns it, then intensity is HTP and
+    ///    color LTP (latest cue change wins);
+    ///    while any playback is soloed only soloed ones take part;
+    ///    then the enabled output modifiers, in order
     /// 2) render merged fixtures to LiveState
-    /// 3) overlay programmer on top
+    /// 3) overlay programmer and focus session on top, then scale by the
+    ///    group and grand masters
+    /// 4) overlay held fixture controls
+    /// 5) overlay the raw DMX tester layer
+    /// 6) merge received sACN input (HTP or by priority)
+    /// 7) clamp pan/tilt to the fixtures' soft limits
+    /// 8) during blackout: every intensity out
+    /// 9) while panicking: house lights full, stage out
     pub fn render(&self) -> anyhow::Result<LiveState> {
-        let a = self.playback_a.output_state_map(&self.show)?;
-        let b = self.playback_b.output_state_map(&self.show)?;
+        let any_solo = self.all_playbacks().any(|(_, pb)| pb.solo);
+        let output = |pb: &Playback| -> anyhow::Result<(StateMap, StateMap)> {
+            if any_solo && !pb.solo {
+                Ok(Default::default())
+            } else {
+                let (mut coarse, mut fine) = pb.output_levels(&self.show)?;
+                if pb.flash {
+                    flash_levels(&mut coarse, &mut fine);
+                } else {
+                    scale_levels(&mut coarse, &mut fine, pb.master);
+                }
+                Ok((coarse, fine))
+            }
+        };
+        let mut outputs = Vec::new();
+        let mut layers = Vec::new();
+        for (_, pb) in self.all_playbacks() {
+            outputs.push(output(pb)?);
+            layers.push((pb.priority, pb.last_action()));
+        }
+        let layers: Vec<Layer> = outputs
+            .iter()
+            .zip(layers)
+            .map(|((values, fine), (priority, action))| Layer {
+                values,
+                fine,
+                priority,
+                action,
+            })
+            .collect();
 
-        let merged = merge_maps(&a, &b);
+        let (mut merged, winners) = merge_maps(&layers);
+        self.modifiers.apply(&self.show, &mut merged);
+        let fine = merge_fine(&merged, &winners, &layers);
 
-        let mut live = LiveState::new();
-        for (fid, vals) in merged {
-            // IMPORTANT: keep same argument order as your playback.rs signature
-            render_fixture_values(&self.show, fid, &vals, &mut live)?;
-        }
+        let mut live = default_frame(&self.show);
+        render_merged(&self.show, &merged, &fine, &mut live)?;
 
         let prog = self.programmer.render(&self.show)?;
         live.overlay(&prog);
+        if let Some(focus) = &self.focus {
+            let mut layer = LiveState::new();
+            for (fid, level) in focus.levels() {
+                let vals = FixtureValues {
+                    intensity: Some(level),
+                    ..Default::default()
+                };
+                render_fixture_values(&self.show, fid, &vals, None, &mut layer)?;
+            }
+            live.overlay(&layer);
+        }
+        apply_masters(&self.show, &self.masters, self.grand_master, &mut live);
+        for c in &self.controls {
+            live.set(c.universe, c.address, c.value);
+        }
+        live.overlay(&self.tester);
+        self.dmx_input
+            .merge_into(&self.show, &mut live, self.clock.now_ms());
+        apply_limits(&self.show, &mut live);
+        if self.is_blackout() {
+            apply_blackout(&self.show, &mut live);
+        }
+        if self.panic {
+            apply_panic(&self.show, &mut live);
+        }
 
         Ok(live)
     }
 }
 
-fn ltp(a: Option<u8>, b: Option<u8>) -> Option<u8> {
-    match b {
-        Some(_) => b, // playback B wins for LTP params
-        None => a,
+/// Render merged playback values into `live`, one fixture at a time.
+#[cfg(not(feature = "parallel"))]
+fn render_merged(
+    show: &Show,
+    merged: &StateMap,
+    fine: &StateMap,
+    live: &mut LiveState,
+) -> anyhow::Result<()> {
+    for (&fid, vals) in merged {
+        render_fixture_values(show, fid, vals, fine.get(&fid), live)?;
     }
+    Ok(())
 }
 
-fn htp(a: Option<u8>, b: Option<u8>) -> Option<u8> {
-    if a.is_none() && b.is_none() {
-        None
-    } else {
-        Some(a.unwrap_or(0).max(b.unwrap_or(0)))
+/// Parallel version: each rayon worker renders a run of fixtures into its
+/// own per-universe buffers, which are overlaid in fixture order at the end
+/// so overlapping patches resolve exactly as in the serial loop.
+#[cfg(feature = "parallel")]
+fn render_merged(
+    show: &Show,
+    merged: &StateMap,
+    fine: &StateMap,
+    live: &mut LiveState,
+) -> anyhow::Result<()> {
+    use rayon::prelude::*;
+
+    let rendered = merged
+        .par_iter()
+        .try_fold(LiveState::new, |mut part, (&fid, vals)| {
+            render_fixture_values(show, fid, vals, fine.get(&fid), &mut part)?;
+            anyhow::Ok(part)
+        })
+        .try_reduce(LiveState::new, |mut acc, part| {
+            acc.overlay(&part);
+            Ok(acc)
+        })?;
+    live.overlay(&rendered);
+    Ok(())
+}
+
+fn default_frame(show: &Show) -> LiveState {
+    let mut live = LiveState::new();
+    for f in show.patch.fixtures.values() {
+        let Some(ft) = show.patch.fixture_types.get(&f.fixture_type) else {
+            continue;
+        };
+        for (i, ch) in ft.channels.iter().enumerate() {
+            let addr = f.address + i as u16;
+            if let Some(v) = ch.default
+                && (1..=512).contains(&addr)
+            {
+                live.set(f.universe, addr, v);
+            }
+        }
     }
+    live
 }
 
-fn merge_fixture(a: Option<&FixtureValues>, b: Option<&FixtureValues>) -> FixtureValues {
-    let a = a.cloned().unwrap_or_default();
-    let b = b.cloned().unwrap_or_default();
+/// One playback's output in the merge.
+struct Layer<'a> {
+    values: &'a StateMap,
+    fine: &'a StateMap,
+    priority: u8,
+    /// `Playback::last_action`.
+    action: u64,
+}
 
-    FixtureValues {
-        // Intensity: HTP
-        intensity: htp(a.intensity, b.intensity),
-        // Color: LTP (B wins)
-        r: ltp(a.r, b.r),
-        g: ltp(a.g, b.g),
-        b: ltp(a.b, b.b),
+/// Per parameter, only the highest priority layers setting it count; among
+/// those intensity is HTP and color LTP: the latest cue change wins, then
+/// the later layer (B over A). HTP compares 16-bit values, fine bytes
+/// included. Also returns, per fixture, the index of the layer that won
+/// each parameter (in `FixtureValues` field order).
+fn merge_maps(layers: &[Layer]) -> (StateMap, BTreeMap<u32, [Option<usize>; 6]>) {
+    let fids: BTreeSet<u32> = layers
+        .iter()
+        .flat_map(|l| l.values.keys().copied())
+        .collect();
+    let mut out = BTreeMap::new();
+    let mut winners = BTreeMap::new();
+    for fid in fids {
+        let merge = |param: fn(&FixtureValues) -> Option<u8>, htp: bool| {
+            let set: Vec<(u8, u64, usize, u8, u16)> = layers
+                .iter()
+                .enumerate()
+                .filter_map(|(i, l)| {
+                    let v = param(l.values.get(&fid)?)?;
+                    let fine = l.fine.get(&fid).and_then(param);
+                    Some((l.priority, l.action, i, v, widen(v, fine)))
+                })
+                .collect();
+            let top = set.iter().map(|s| s.0).max()?;
+            let owners = set.iter().filter(|s| s.0 == top);
+            if htp {
+                owners.max_by_key(|s| (s.4, s.2)).map(|s| (s.2, s.3))
+            } else {
+                owners.max_by_key(|s| (s.1, s.2)).map(|s| (s.2, s.3))
+            }
+        };
+        let won = [
+            merge(|v| v.intensity, true),
+            merge(|v| v.r, false),
+            merge(|v| v.g, false),
+            merge(|v| v.b, false),
+            merge(|v| v.pan, false),
+            merge(|v| v.tilt, false),
+        ];
+        let values = FixtureValues {
+            intensity: won[0].map(|w| w.1),
+            r: won[1].map(|w| w.1),
+            g: won[2].map(|w| w.1),
+            b: won[3].map(|w| w.1),
+            pan: won[4].map(|w| w.1),
+            tilt: won[5].map(|w| w.1),
+        };
+        out.insert(fid, values);
+        winners.insert(fid, won.map(|w| w.map(|w| w.0)));
     }
+    (out, winners)
 }
 
-fn merge_maps(
-    a: &BTreeMap<u32, FixtureValues>,
-    b: &BTreeMap<u32, FixtureValues>,
-) -> BTreeMap<u32, FixtureValues> {
+/// Fine bytes for the merged values: taken from the layer that won each
+/// parameter, as long as modifiers left its coarse byte alone.
+fn merge_fine(
+    merged: &StateMap,
+    winners: &BTreeMap<u32, [Option<usize>; 6]>,
+    layers: &[Layer],
+) -> StateMap {
     let mut out = BTreeMap::new();
-
-    for fid in a.keys().chain(b.keys()) {
-        let fid = *fid;
-        if out.contains_key(&fid) {
+    for (fid, m) in merged {
+        let Some(won) = winners.get(fid) else {
             continue;
+        };
+        let pick = |k: usize, param: fn(&FixtureValues) -> Option<u8>| {
+            let l = &layers[won[k]?];
+            let same = l.values.get(fid).and_then(param) == param(m);
+            same.then(|| l.fine.get(fid).and_then(param)).flatten()
+        };
+        let f = FixtureValues {
+            intensity: pick(0, |v| v.intensity),
+            r: pick(1, |v| v.r),
+            g: pick(2, |v| v.g),
+            b: pick(3, |v| v.b),
+            pan: pick(4, |v| v.pan),
+            tilt: pick(5, |v| v.tilt),
+        };
+        if !f.is_all_none() {
+            out.insert(*fid, f);
         }
-        out.insert(fid, merge_fixture(a.get(&fid), b.get(&fid)));
     }
-
     out
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        Cue, CueList, FixtureInstance, FixtureValues, PlaybackMode, Show, default_fixture_types,
+        ControlMacro, Cue, FixtureInstance, FixtureValues, PlaybackMode, Show, SimClock,
+        default_fixture_types,
     };
     use std::collections::BTreeMap;
 
@@ -116,9 +812,7 @@ mod tests {
         show.patch.add_fixture(f)?;
 
         // ensure main cuelist exists
-        show.cue_lists
-            .entry("main".to_string())
-            .or_insert_with(CueList::default);
+        show.cue_lists.entry("main".to_string()).or_default();
 
         Ok(show)
     }
@@ -142,10 +836,17 @@ mod tests {
         let cue1 = Cue {
             number: 1,
             label: "PB Red".to_string(),
+            meta: Default::default(),
             changes,
             fade_ms: 0,
             delay_ms: 0,
             block: false,
+            follow_ms: None,
+            confirm: false,
+            link: None,
+            link_loops: None,
+            warn: None,
+            palette_refs: Default::default(),
         };
 
         show.cue_lists.get_mut("main").unwrap().cues.insert(1, cue1);
@@ -154,11 +855,11 @@ mod tests {
         let mut rt = Runtime::new(show);
 
         // playback A -> cue 1
-        rt.playback_a.mode = PlaybackMode::CueOnly;
-        rt.playback_a.goto(&rt.show, 1)?;
+        rt.playbacks["a"].mode = PlaybackMode::CueOnly;
+        rt.playbacks["a"].goto(&rt.show, 1)?;
 
         // verify playback alone is red=200 at U1:001
-        let pb_live = rt.playback_a.render(&rt.show)?;
+        let pb_live = rt.playbacks["a"].render(&rt.show)?;
         assert!(
             pb_live
                 .nonzero()
@@ -193,4 +894,560 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn advance_follows_injected_clock() -> anyhow::Result<()> {
+        let mut show = make_test_show()?;
+        show.cue_lists.get_mut("main").unwrap().cues.insert(
+            1,
+            Cue {
+                number: 1,
+                label: "Fade".to_string(),
+                meta: Default::default(),
+                changes: [(
+                    1,
+                    FixtureValues {
+                        r: Some(200),
+                        ..Default::default()
+                    },
+                )]
+                .into_iter()
+                .collect(),
+                fade_ms: 1000,
+                delay_ms: 0,
+                block: false,
+                follow_ms: None,
+                confirm: false,
+                link: None,
+                link_loops: None,
+                warn: None,
+                palette_refs: Default::default(),
+            },
+        );
+
+        let clock = SimClock::new();
+        let mut rt = Runtime::with_clock(show, Box::new(clock.clone()));
+        rt.playbacks["a"].goto(&rt.show, 1)?;
+
+        clock.advance(500);
+        assert_eq!(rt.advance(u32::MAX), 500);
+        assert_eq!(rt.playbacks["a"].transition_info(), Some((500, 0, 1000)));
+
+        // long pause is capped
+        clock.advance(10_000);
+        assert_eq!(rt.advance(100), 100);
+        assert_eq!(rt.advance(100), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn fixture_control_holds_then_releases() -> anyhow::Result<()> {
+        let mut show = make_test_show()?;
+        show.patch
+            .fixture_types
+            .get_mut("rgb_par_3ch")
+            .unwrap()
+            .controls
+            .push(ControlMacro {
+                name: "reset".into(),
+                channel: "Blue".into(),
+                value: 250,
+                hold_ms: 3000,
+            });
+        let mut rt = Runtime::new(show);
+
+        assert!(rt.fixture_control(1, "lamp_on").is_err());
+        rt.fixture_control(1, "reset")?;
+        assert!(rt.render()?.nonzero().contains(&(1, 3, 250)));
+
+        rt.tick(2000);
+        assert_eq!(rt.active_controls(), vec![(1, "reset", 1000)]);
+        rt.tick(1000);
+        assert!(rt.active_controls().is_empty());
+        assert!(rt.render()?.nonzero().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn channel_defaults_form_the_base_layer() -> anyhow::Result<()> {
+        let mut show = make_test_show()?;
+        show.patch
+            .add_fixture(FixtureInstance::new(2, "MH 1", "moving_head_5ch", 1, 101))?;
+        let rt = Runtime::new(show);
+
+        let out = rt.render()?.nonzero();
+        // pan, tilt centred
+        assert_eq!(out, vec![(1, 101, 128), (1, 102, 128)]);
+        Ok(())
+    }
+
+    #[test]
+    fn playback_filters_layer_intensity_and_color() -> anyhow::Result<()> {
+        let mut show = make_test_show()?;
+        show.patch
+            .add_fixture(FixtureInstance::new(2, "Dim", "dimmer_1ch", 1, 10))?;
+        show.cue_lists.get_mut("main").unwrap().cues.insert(
+            1,
+            Cue {
+                number: 1,
+                label: "All".to_string(),
+                meta: Default::default(),
+                changes: [
+                    (
+                        1,
+                        FixtureValues {
+                            r: Some(200),
+                            ..Default::default()
+                        },
+                    ),
+                    (
+                        2,
+                        FixtureValues {
+                            intensity: Some(100),
+                            ..Default::default()
+                        },
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+                fade_ms: 0,
+                delay_ms: 0,
+                block: false,
+                follow_ms: None,
+                confirm: false,
+                link: None,
+                link_loops: None,
+                warn: None,
+                palette_refs: Default::default(),
+            },
+        );
+        show.playbacks.insert(
+            "a".into(),
+            crate::PlaybackConfig {
+                filter: crate::ParamFilter::INTENSITY,
+                ..Default::default()
+            },
+        );
+
+        let mut rt = Runtime::new(show);
+        rt.playbacks["a"].goto(&rt.show, 1)?;
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 10, 100)]);
+
+        rt.playbacks["a"].filter = crate::ParamFilter::COLOR;
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 1, 200)]);
+        Ok(())
+    }
+
+    #[test]
+    fn solo_mutes_other_playbacks_but_not_programmer() -> anyhow::Result<()> {
+        let mut show = make_test_show()?;
+        show.patch
+            .add_fixture(FixtureInstance::new(2, "Dim", "dimmer_1ch", 1, 10))?;
+        let cues = &mut show.cue_lists.get_mut("main").unwrap().cues;
+        for (num, fid, vals) in [
+            (
+                1,
+                1,
+                FixtureValues {
+                    r: Some(200),
+                    ..Default::default()
+                },
+            ),
+            (
+                2,
+                2,
+                FixtureValues {
+                    intensity: Some(100),
+                    ..Default::default()
+                },
+            ),
+        ] {
+            cues.insert(
+                num,
+                Cue {
+                    number: num,
+                    label: String::new(),
+                    meta: Default::default(),
+                    changes: [(fid, vals)].into_iter().collect(),
+                    fade_ms: 0,
+                    delay_ms: 0,
+                    block: true,
+                    follow_ms: None,
+                    confirm: false,
+                    link: None,
+                    link_loops: None,
+                    warn: None,
+                    palette_refs: Default::default(),
+                },
+            );
+        }
+
+        let mut rt = Runtime::new(show);
+        rt.playbacks["a"].mode = PlaybackMode::CueOnly;
+        rt.playbacks["b"].mode = PlaybackMode::CueOnly;
+        rt.playbacks["a"].goto(&rt.show, 1)?;
+        rt.playbacks["b"].goto(&rt.show, 2)?;
+        rt.programmer.selected.insert(1);
+        rt.programmer.g = Some(50);
+
+        rt.playbacks["b"].solo = true;
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 2, 50), (1, 10, 100)]);
+
+        rt.playbacks["b"].solo = false;
+        assert_eq!(rt.render()?.nonzero().len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn flash_and_solo_only_touch_their_playbacks() -> anyhow::Result<()> {
+        let show = crate::ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "D1", "dimmer_1ch", 1, 1)
+            .fixture(2, "D2", "dimmer_1ch", 1, 2)
+            .fixture(3, "D3", "dimmer_1ch", 1, 3)
+            .cue(crate::CueBuilder::new(1).intensity(1, 100).fade_ms(0))
+            .cue(crate::CueBuilder::new(2).intensity(2, 80).fade_ms(0))
+            .build()?;
+        let mut rt = Runtime::new(show);
+        rt.playbacks["a"].mode = PlaybackMode::CueOnly;
+        rt.playbacks["b"].mode = PlaybackMode::CueOnly;
+        rt.playbacks["a"].goto(&rt.show, 1)?;
+        rt.playbacks["b"].goto(&rt.show, 2)?;
+        rt.playbacks["a"].master = 128;
+        rt.programmer.select_one(3);
+        rt.programmer.intensity = Some(60);
+        let normal = vec![(1, 1, 50), (1, 2, 80), (1, 3, 60)];
+        assert_eq!(rt.render()?.nonzero(), normal);
+
+        // flash: A's fixture to full over its fader, only while held
+        rt.playbacks["a"].flash = true;
+        assert_eq!(
+            rt.render()?.nonzero(),
+            vec![(1, 1, 255), (1, 2, 80), (1, 3, 60)]
+        );
+        rt.playbacks["a"].flash = false;
+        assert_eq!(rt.render()?.nonzero(), normal);
+
+        // solo: B's fixture out, A and the programmer untouched
+        rt.playbacks["a"].solo = true;
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 1, 50), (1, 3, 60)]);
+        rt.playbacks["a"].solo = false;
+        assert_eq!(rt.render()?.nonzero(), normal);
+        Ok(())
+    }
+
+    #[test]
+    fn priority_then_latest_action_decide_between_playbacks() -> anyhow::Result<()> {
+        let show = crate::ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "PAR", "rgb_par_3ch", 1, 1)
+            .fixture(2, "Dim", "dimmer_1ch", 1, 10)
+            .cue(
+                crate::CueBuilder::new(1)
+                    .rgb(1, 100, 0, 0)
+                    .intensity(2, 200)
+                    .fade_ms(0),
+            )
+            .cue_in(
+                "fx",
+                crate::CueBuilder::new(1)
+                    .rgb(1, 50, 0, 0)
+                    .intensity(2, 80)
+                    .fade_ms(0),
+            )
+            .build()?;
+        let mut rt = Runtime::new(show);
+        rt.add_playback("c", "fx")?;
+        assert!(rt.add_playback("b", "fx").is_err());
+
+        // equal priority: the last cue change wins color, intensity is HTP
+        rt.playbacks["b"].goto(&rt.show, 1)?;
+        rt.playbacks.get_mut("c").unwrap().goto(&rt.show, 1)?;
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 1, 50), (1, 10, 200)]);
+        rt.playbacks["a"].goto(&rt.show, 1)?;
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 1, 100), (1, 10, 200)]);
+
+        // a higher priority owns what it sets, intensity included
+        rt.set_playback_priority("c", 10)?;
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 1, 50), (1, 10, 80)]);
+        assert_eq!(rt.show.playbacks["c"].priority, 10);
+        let rt2 = Runtime::new(rt.show.clone());
+        assert_eq!(
+            (
+                rt2.playbacks["c"].priority,
+                rt2.playbacks["c"].cuelist.as_str()
+            ),
+            (10, "fx")
+        );
+
+        rt.remove_playback("c")?;
+        assert!(rt.remove_playback("a").is_err());
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 1, 100), (1, 10, 200)]);
+        Ok(())
+    }
+
+    #[test]
+    fn dmx_tester_overrides_and_clears() -> anyhow::Result<()> {
+        let mut rt = Runtime::new(make_test_show()?);
+        rt.programmer.selected.insert(1);
+        rt.programmer.r = Some(10);
+
+        rt.set_dmx(1, 1, 255)?;
+        rt.set_dmx(2, 512, 7)?; // unpatched address
+        assert!(rt.set_dmx(1, 513, 1).is_err());
+
+        let live = rt.render()?;
+        assert!(live.nonzero().contains(&(1, 1, 255)));
+        assert!(live.nonzero().contains(&(2, 512, 7)));
+
+        rt.clear_dmx();
+        let live = rt.render()?;
+        assert!(live.nonzero().contains(&(1, 1, 10)));
+        assert!(!live.nonzero().contains(&(2, 512, 7)));
+        Ok(())
+    }
+
+    #[test]
+    fn grand_and_playback_masters_scale_intensity_only() -> anyhow::Result<()> {
+        let mut show = make_test_show()?;
+        show.patch
+            .add_fixture(FixtureInstance::new(2, "Dim", "dimmer_1ch", 1, 10))?;
+        show.cue_lists.get_mut("main").unwrap().cues.insert(
+            1,
+            Cue {
+                number: 1,
+                label: "Look".into(),
+                meta: Default::default(),
+                changes: BTreeMap::from([
+                    (
+                        1,
+                        FixtureValues {
+                            r: Some(200),
+                            ..Default::default()
+                        },
+                    ),
+                    (
+                        2,
+                        FixtureValues {
+                            intensity: Some(200),
+                            ..Default::default()
+                        },
+                    ),
+                ]),
+                fade_ms: 0,
+                delay_ms: 0,
+                block: false,
+                follow_ms: None,
+                confirm: false,
+                link: None,
+                link_loops: None,
+                warn: None,
+                palette_refs: Default::default(),
+            },
+        );
+
+        let mut rt = Runtime::new(show);
+        rt.playbacks["a"].goto(&rt.show, 1)?;
+        rt.playbacks["a"].master = 128;
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 1, 200), (1, 10, 100)]);
+
+        rt.set_grand_master(128);
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 1, 200), (1, 10, 50)]);
+        rt.set_grand_master(0);
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 1, 200)]);
+        Ok(())
+    }
+
+    #[test]
+    fn timed_gm_fade_and_blackout() -> anyhow::Result<()> {
+        let mut rt = Runtime::new(make_test_show()?);
+        rt.fade_grand_master(55, 2000);
+        rt.tick(1000);
+        assert_eq!(rt.grand_master(), 155);
+        assert_eq!(rt.gm_fade_remaining(), Some((1000, false)));
+        rt.tick(1000);
+        assert_eq!((rt.grand_master(), rt.gm_fade_remaining()), (55, None));
+
+        rt.timed_blackout(1000);
+        rt.tick(500);
+        assert_eq!(rt.grand_master(), 28);
+        assert!(!rt.is_blackout());
+        rt.tick(500);
+        assert!(rt.is_blackout_latched());
+        assert_eq!(rt.grand_master(), 55);
+
+        // switching blackout off mid-fade cancels it and restores the GM
+        rt.set_blackout(false);
+        rt.timed_blackout(1000);
+        rt.tick(500);
+        rt.set_blackout(false);
+        assert_eq!((rt.grand_master(), rt.gm_fade_remaining()), (55, None));
+        rt.tick(1000);
+        assert!(!rt.is_blackout());
+        Ok(())
+    }
+
+    #[test]
+    fn group_masters_scale_and_panic_overrides() -> anyhow::Result<()> {
+        let mut show = make_test_show()?;
+        show.patch
+            .add_fixture(FixtureInstance::new(2, "Stage", "dimmer_1ch", 1, 10))?;
+        show.patch
+            .add_fixture(FixtureInstance::new(3, "House", "dimmer_1ch", 1, 11))?;
+        show.groups
+            .insert("stage".into(), [2].into_iter().collect());
+        show.groups
+            .insert("house".into(), [3].into_iter().collect());
+        show.masters.groups = vec!["stage".into()];
+        show.masters.house_group = Some("house".into());
+
+        let mut rt = Runtime::new(show);
+        rt.programmer.selected.extend([2, 3]);
+        rt.programmer.intensity = Some(200);
+        assert!(rt.set_master("house", 0).is_err()); // not on the page
+
+        rt.set_master("stage", 128)?;
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 10, 100), (1, 11, 200)]);
+        rt.set_all_masters(0);
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 11, 200)]);
+        rt.set_all_masters(255);
+
+        rt.set_panic(true);
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 11, 255)]);
+        rt.set_panic(false);
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 10, 200), (1, 11, 200)]);
+        Ok(())
+    }
+
+    #[test]
+    fn blackout_latch_and_momentary() -> anyhow::Result<()> {
+        let show = crate::ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "D1", "dimmer_1ch", 1, 1)
+            .fixture(2, "D2", "dimmer_1ch", 1, 2)
+            .cue(crate::CueBuilder::new(1).intensity(1, 150).fade_ms(0))
+            .build()?;
+        let mut rt = Runtime::new(show);
+        rt.playbacks["a"].go(&rt.show)?;
+        rt.programmer.select_one(2);
+        rt.programmer.intensity = Some(200);
+        let lit = vec![(1, 1, 150), (1, 2, 200)];
+        assert_eq!(rt.render()?.nonzero(), lit);
+
+        rt.hold_blackout(true);
+        assert!(rt.render()?.nonzero().is_empty());
+        rt.hold_blackout(false);
+        assert_eq!(rt.render()?.nonzero(), lit);
+
+        rt.set_blackout(true);
+        rt.hold_blackout(true);
+        rt.hold_blackout(false); // releasing the momentary keeps the latch
+        assert!(rt.is_blackout_latched());
+        assert!(rt.render()?.nonzero().is_empty());
+
+        // the programmer and playback carry on underneath
+        assert_eq!(rt.playbacks["a"].current, Some(1));
+        assert_eq!(rt.programmer.intensity, Some(200));
+        rt.set_blackout(false);
+        assert_eq!(rt.render()?.nonzero(), lit);
+        Ok(())
+    }
+
+    #[test]
+    fn playbacks_retarget_to_named_cue_lists() -> anyhow::Result<()> {
+        let mut show = make_test_show()?;
+        show.add_cue_list("act2")?;
+        assert!(show.add_cue_list("act2").is_err());
+        let cue = |i| Cue {
+            number: 1,
+            label: String::new(),
+            meta: Default::default(),
+            changes: BTreeMap::from([(
+                1,
+                FixtureValues {
+                    r: Some(i),
+                    ..Default::default()
+                },
+            )]),
+            fade_ms: 0,
+            delay_ms: 0,
+            block: false,
+            follow_ms: None,
+            confirm: false,
+            link: None,
+            link_loops: None,
+            warn: None,
+            palette_refs: Default::default(),
+        };
+        show.cue_lists
+            .get_mut("main")
+            .unwrap()
+            .cues
+            .insert(1, cue(10));
+        show.cue_lists
+            .get_mut("act2")
+            .unwrap()
+            .cues
+            .insert(1, cue(20));
+
+        let mut rt = Runtime::new(show);
+        rt.playbacks["a"].goto(&rt.show, 1)?;
+        rt.use_cue_list("b", "act2")?;
+        rt.playbacks["b"].goto(&rt.show, 1)?;
+        assert_eq!(rt.render()?.nonzero(), vec![(1, 1, 20)]);
+        assert!(rt.use_cue_list("b", "nope").is_err());
+
+        rt.rename_cue_list("act2", "finale")?;
+        assert_eq!(rt.playbacks["b"].cuelist, "finale");
+        assert_eq!(rt.show.playbacks["b"].cuelist.as_deref(), Some("finale"));
+        assert!(rt.delete_cue_list("finale").is_err());
+        assert!(rt.delete_cue_list("main").is_err());
+
+        // the choice is saved with the show
+        let rt2 = Runtime::new(rt.show.clone());
+        assert_eq!(rt2.playbacks["b"].cuelist, "finale");
+
+        rt.use_cue_list("b", "main")?;
+        assert_eq!(rt.playbacks["b"].current, None);
+        rt.delete_cue_list("finale")?;
+        assert!(!rt.show.cue_lists.contains_key("finale"));
+        Ok(())
+    }
+
+    #[test]
+    fn fine_bytes_come_from_the_layer_that_won() {
+        let vals = |intensity, pan| {
+            BTreeMap::from([(
+                1,
+                FixtureValues {
+                    intensity: Some(intensity),
+                    pan: Some(pan),
+                    ..Default::default()
+                },
+            )])
+        };
+        let (a, a_fine) = (vals(100, 128), vals(10, 10));
+        let (b, b_fine) = (vals(100, 128), vals(200, 200));
+        // B moved last, so it owns pan, and is higher on the same coarse intensity
+        let layers = [
+            Layer {
+                values: &a,
+                fine: &a_fine,
+                priority: 0,
+                action: 1,
+            },
+            Layer {
+                values: &b,
+                fine: &b_fine,
+                priority: 0,
+                action: 2,
+            },
+        ];
+        let (merged, winners) = merge_maps(&layers);
+        assert_eq!(winners[&1][0], Some(1));
+        assert_eq!(winners[&1][4], Some(1));
+        let fine = merge_fine(&merged, &winners, &layers);
+        assert_eq!(fine[&1].intensity, Some(200));
+        assert_eq!(fine[&1].pan, Some(200));
+    }
 }
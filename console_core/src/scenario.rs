@@ -0,0 +1,284 @@
+//! Data-driven test scenarios: a show plus a script of steps and expected DMX.
+//!
+//! A scenario file is JSON, e.g.
+//!
+//! ```json
+//! {
+//!   "name": "red tracks into cue 2",
+//!   "show_file": "show.json",
+//!   "steps": [
+//!     { "goto": { "cue": 2 } },
+//!     { "expect": [ { "universe": 1, "address": 1, "value": 255 } ] }
+//!   ]
+//! }
+//! ```
+//!
+//! `show_file` is resolved relative to the scenario file.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{PlaybackMode, Runtime, Show, SimClock};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+
+    #[serde(default)]
+    pub show_file: Option<PathBuf>,
+
+    pub steps: Vec<Step>,
+}
+
+/// Which of the runtime playbacks a step talks to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaybackSel {
+    #[default]
+    A,
+    B,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DmxExpect {
+    pub universe: u16,
+    pub address: u16,
+    pub value: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    /// Add fixtures to the programmer selection.
+    Select(Vec<u32>),
+    /// Programmer intensity in percent.
+    At(u8),
+    Rgb([u8; 3]),
+    /// Clear programmer selection and values.
+    Clear,
+    Mode {
+        #[serde(default)]
+        pb: PlaybackSel,
+        mode: PlaybackMode,
+    },
+    Goto {
+        #[serde(default)]
+        pb: PlaybackSel,
+        cue: u32,
+    },
+    Go {
+        #[serde(default)]
+        pb: PlaybackSel,
+    },
+    /// Advance time in milliseconds.
+    Tick(u32),
+    /// Check rendered output. Addresses not in the output count as 0.
+    Expect(Vec<DmxExpect>),
+}
+
+/// One failed DMX expectation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub step: usize,
+    pub universe: u16,
+    pub address: u16,
+    pub expected: u8,
+    pub actual: u8,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScenarioReport {
+    pub checks: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl ScenarioReport {
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl Scenario {
+    pub fn load_json_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let text = fs::read_to_string(path.as_ref()).context("read scenario json file")?;
+        let scenario = serde_json::from_str::<Scenario>(&text).context("parse scenario json")?;
+        Ok(scenario)
+    }
+
+    /// Load a scenario file and the show it points to, then run it.
+    pub fn run_file(path: impl AsRef<Path>) -> anyhow::Result<(Scenario, ScenarioReport)> {
+        let path = path.as_ref();
+        let scenario = Self::load_json_file(path)?;
+        let show_file = scenario
+            .show_file
+            .as_ref()
+            .context("scenario has no show_file")?;
+        let show_path = path
+            .parent()
+            .map(|dir| dir.join(show_file))
+            .unwrap_or_else(|| show_file.clone());
+        let show = Show::load_json_file(&show_path)
+            .with_context(|| format!("load show '{}'", show_path.display()))?;
+
+        let report = scenario.run(show)?;
+        Ok((scenario, report))
+    }
+
+    /// Run every step against a fresh runtime for `show`.
+    /// Errors are reserved for steps that cannot execute; DMX mismatches end up in the report.
+    pub fn run(&self, show: Show) -> anyhow::Result<ScenarioReport> {
+        let clock = SimClock::new();
+        let mut rt = Runtime::with_clock(show, Box::new(clock.clone()));
+        let mut report = ScenarioReport::default();
+
+        for (i, step) in self.steps.iter().enumerate() {
+            let step_no = i + 1;
+            match step {
+                Step::Select(ids) => {
+                    for &id in ids {
+                        rt.programmer.select_one(id);
+                    }
+                }
+                Step::At(pct) => rt.programmer.set_intensity_percent(*pct),
+                Step::Rgb([r, g, b]) => rt.programmer.set_rgb(*r, *g, *b),
+                Step::Clear => rt.programmer.clear_all(),
+                Step::Mode { pb, mode } => pb_mut(&mut rt, *pb).mode = *mode,
+                Step::Goto { pb, cue } => {
+                    let show = &rt.show;
+                    let playback = match pb {
+                        PlaybackSel::A => &mut rt.playbacks["a"],
+                        PlaybackSel::B => &mut rt.playbacks["b"],
+                    };
+                    playback
+                        .goto(show, *cue)
+                        .with_context(|| format!("step {step_no}: goto {cue}"))?;
+                }
+                Step::Go { pb } => {
+                    let show = &rt.show;
+                    let playback = match pb {
+                        PlaybackSel::A => &mut rt.playbacks["a"],
+                        PlaybackSel::B => &mut rt.playbacks["b"],
+                    };
+                    playback
+                        .go(show)
+                        .with_context(|| format!("step {step_no}: go"))?;
+                }
+                Step::Tick(ms) => {
+                    clock.advance(*ms as u64);
+                    rt.advance(u32::MAX);
+                }
+                Step::Expect(expects) => {
+                    let live = rt
+                        .render()
+                        .with_context(|| format!("step {step_no}: render"))?;
+                    for e in expects {
+                        let actual = live
+                            .universes
+                            .get(&e.universe)
+                            .and_then(|u| u.get(&e.address))
+                            .copied()
+                            .unwrap_or(0);
+                        report.checks += 1;
+                        if actual != e.value {
+                            report.mismatches.push(Mismatch {
+                                step: step_no,
+                                universe: e.universe,
+                                address: e.address,
+                                expected: e.value,
+                                actual,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+fn pb_mut(rt: &mut Runtime, pb: PlaybackSel) -> &mut crate::Playback {
+    match pb {
+        PlaybackSel::A => &mut rt.playbacks["a"],
+        PlaybackSel::B => &mut rt.playbacks["b"],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cue, FixtureInstance, FixtureValues, default_fixture_types};
+
+    fn make_show() -> anyhow::Result<Show> {
+        let mut show = Show::new("Test");
+        for ft in default_fixture_types() {
+            show.patch.add_fixture_type(ft);
+        }
+        show.patch
+            .add_fixture(FixtureInstance::new(1, "PAR 1", "rgb_par_3ch", 1, 1))?;
+
+        let main = show.cue_lists.get_mut("main").unwrap();
+        main.cues.insert(
+            1,
+            Cue {
+                number: 1,
+                label: "Red".into(),
+                meta: Default::default(),
+                block: false,
+                follow_ms: None,
+                confirm: false,
+                link: None,
+                link_loops: None,
+                warn: None,
+                palette_refs: Default::default(),
+                fade_ms: 1000,
+                delay_ms: 0,
+                changes: [(
+                    1u32,
+                    FixtureValues {
+                        r: Some(255),
+                        ..Default::default()
+                    },
+                )]
+                .into_iter()
+                .collect(),
+            },
+        );
+        Ok(show)
+    }
+
+    #[test]
+    fn scenario_steps_time_and_reports_mismatches() -> anyhow::Result<()> {
+        let scenario: Scenario = serde_json::from_str(
+            r#"{
+                "name": "fade to red",
+                "steps": [
+                    { "go": {} },
+                    { "tick": 500 },
+                    { "expect": [ { "universe": 1, "address": 1, "value": 127 } ] },
+                    { "tick": 500 },
+                    { "expect": [
+                        { "universe": 1, "address": 1, "value": 255 },
+                        { "universe": 1, "address": 2, "value": 10 }
+                    ] }
+                ]
+            }"#,
+        )?;
+
+        let report = scenario.run(make_show()?)?;
+        assert_eq!(report.checks, 3);
+        assert_eq!(
+            report.mismatches,
+            vec![Mismatch {
+                step: 5,
+                universe: 1,
+                address: 2,
+                expected: 10,
+                actual: 0,
+            }]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Meta, Show};
+
+/// Points at one object in a show.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ObjectRef {
+    Fixture(u32),
+    Cue { list: String, number: u32 },
+    Group(String),
+    Palette(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit {
+    pub object: ObjectRef,
+    /// Display text, e.g. "Cue 5 (main): Opening".
+    pub title: String,
+}
+
+impl Show {
+    /// Case-insensitive search over names, labels and notes.
+    /// Results are ordered fixtures, cues, groups, palettes.
+    pub fn search(&self, text: &str) -> Vec<SearchHit> {
+        let needle = text.trim().to_lowercase();
+        if needle.is_empty() {
+            return Vec::new();
+        }
+        let matches = |s: &str| s.to_lowercase().contains(&needle);
+        let meta_matches = |m: &Meta| matches(&m.notes);
+
+        let mut hits = Vec::new();
+
+        for f in self.patch.fixtures.values() {
+            if matches(&f.name) || matches(&f.fixture_type) || f.fixture_id.to_string() == needle {
+                hits.push(SearchHit {
+                    object: ObjectRef::Fixture(f.fixture_id),
+                    title: format!("Fixture {}: {}", f.fixture_id, f.name),
+                });
+            }
+        }
+
+        for (list_name, list) in &self.cue_lists {
+            for (&num, cue) in &list.cues {
+                if matches(&cue.label) || meta_matches(&cue.meta) || num.to_string() == needle {
+                    hits.push(SearchHit {
+                        object: ObjectRef::Cue {
+                            list: list_name.clone(),
+                            number: num,
+                        },
+                        title: format!("Cue {num} ({list_name}): {}", cue.label),
+                    });
+                }
+            }
+        }
+
+        for name in self.groups.keys() {
+            let notes = self.group_meta.get(name).is_some_and(meta_matches);
+            if matches(name) || notes {
+                hits.push(SearchHit {
+                    object: ObjectRef::Group(name.clone()),
+                    title: format!("Group {name}"),
+                });
+            }
+        }
+
+        for (name, pal) in &self.palettes {
+            if matches(name) || meta_matches(&pal.meta) {
+                hits.push(SearchHit {
+                    object: ObjectRef::Palette(name.clone()),
+                    title: format!("Palette {name}"),
+                });
+            }
+        }
+
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FixtureInstance, default_fixture_types};
+
+    #[test]
+    fn search_matches_names_and_notes() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        for ft in default_fixture_types() {
+            show.patch.add_fixture_type(ft);
+        }
+        show.patch
+            .add_fixture(FixtureInstance::new(1, "Front PAR", "rgb_par_3ch", 1, 1))?;
+        show.groups
+            .insert("front".into(), [1].into_iter().collect());
+        show.groups.insert("back".into(), [2].into_iter().collect());
+        show.group_meta.entry("back".into()).or_default().notes =
+            "upstage of the front truss".into();
+
+        let hits: Vec<ObjectRef> = show.search("FRONT").into_iter().map(|h| h.object).collect();
+        assert_eq!(
+            hits,
+            vec![
+                ObjectRef::Fixture(1),
+                ObjectRef::Group("back".into()),
+                ObjectRef::Group("front".into()),
+            ]
+        );
+        assert!(show.search("  ").is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+//! Fixture locations, the automatic groups they produce, and selection
+//! expressions such as `1 thru 5 + area:FOH` or `group front - 3`.
+//!
+//! Automatic groups are derived from the patch every time they're asked
+//! for, so they always match it: `area:<area>`, `bar:<bar>` and
+//! `type:<fixture type>`. Saved groups win on a name clash.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Patch, Show};
+
+/// Where a fixture hangs: "FOH", bar "LX2", unit 4.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub area: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bar: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<u32>,
+}
+
+impl Location {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.area)?;
+        if let Some(bar) = &self.bar {
+            write!(f, " / {bar}")?;
+        }
+        if let Some(unit) = self.unit {
+            write!(f, " #{unit}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Patch {
+    /// Groups by area, bar and fixture type, named `area:..`, `bar:..`, `type:..`.
+    pub fn auto_groups(&self) -> BTreeMap<String, BTreeSet<u32>> {
+        let mut out: BTreeMap<String, BTreeSet<u32>> = BTreeMap::new();
+        for f in self.fixtures.values() {
+            let loc = &f.location;
+            let mut names = vec![format!("type:{}", f.fixture_type)];
+            if !loc.area.is_empty() {
+                names.push(format!("area:{}", loc.area));
+            }
+            if let Some(bar) = &loc.bar {
+                names.push(format!("bar:{bar}"));
+            }
+            for name in names {
+                out.entry(name).or_default().insert(f.fixture_id);
+            }
+        }
+        out
+    }
+}
+
+impl Show {
+    /// Fixtures of a saved group or an automatic one (case-insensitive prefix).
+    pub fn group_ids(&self, name: &str) -> Option<BTreeSet<u32>> {
+        if let Some(ids) = self.groups.get(name) {
+            return Some(ids.clone());
+        }
+        let (kind, rest) = name.split_once(':')?;
+        let key = format!("{}:{rest}", kind.to_lowercase());
+        self.patch.auto_groups().remove(&key)
+    }
+
+    /// Fixtures named by a selection expression: ids, `a thru b`, group
+    /// names and `group <name>` (or `g <name>`, for groups named like ids),
+    /// added with `+` and taken away with `-`. None if any part doesn't parse.
+    pub fn parse_selection(&self, parts: &[&str]) -> Option<BTreeSet<u32>> {
+        let mut ids = BTreeSet::new();
+        let mut i = 0;
+        let mut remove = false;
+        while i < parts.len() {
+            let (term, used) = self.selection_term(&parts[i..])?;
+            if remove {
+                ids.retain(|id| !term.contains(id));
+            } else {
+                ids.extend(term);
+            }
+            i += used;
+            match parts.get(i) {
+                None => break,
+                Some(&"+") => remove = false,
+                Some(&"-") => remove = true,
+                Some(_) => return None,
+            }
+            i += 1;
+        }
+        (!ids.is_empty()).then_some(ids)
+    }
+
+    /// The fixtures of the term at the start of `parts`, and how many parts it took.
+    fn selection_term(&self, parts: &[&str]) -> Option<(BTreeSet<u32>, usize)> {
+        let first = *parts.first()?;
+        if first.eq_ignore_ascii_case("group") || first.eq_ignore_ascii_case("g") {
+            return Some((self.group_ids(parts.get(1)?)?, 2));
+        }
+        match first.parse::<u32>() {
+            Ok(a) if parts.get(1).is_some_and(|p| p.eq_ignore_ascii_case("thru")) => {
+                let b: u32 = parts.get(2)?.parse().ok()?;
+                Some(((a.min(b)..=a.max(b)).collect(), 3))
+            }
+            Ok(a) => Some((BTreeSet::from([a]), 1)),
+            Err(_) => Some((self.group_ids(first)?, 1)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FixtureInstance, default_fixture_types};
+
+    #[test]
+    fn auto_groups_follow_the_patch() -> anyhow::Result<()> {
+        let mut show = Show::new("Test");
+        for ft in default_fixture_types() {
+            show.patch.add_fixture_type(ft);
+        }
+        for (id, area, bar) in [
+            (1, "FOH", None),
+            (2, "Stage", Some("LX2")),
+            (3, "Stage", None),
+        ] {
+            let mut f = FixtureInstance::new(id, format!("F{id}"), "dimmer_1ch", 1, id as u16);
+            f.location = Location {
+                area: area.into(),
+                bar: bar.map(str::to_string),
+                unit: None,
+            };
+            show.patch.add_fixture(f)?;
+        }
+        show.groups.insert("front".into(), BTreeSet::from([1]));
+
+        assert_eq!(show.group_ids("AREA:Stage"), Some(BTreeSet::from([2, 3])));
+        assert_eq!(show.group_ids("bar:LX2"), Some(BTreeSet::from([2])));
+        assert_eq!(show.group_ids("type:dimmer_1ch").map(|g| g.len()), Some(3));
+        assert_eq!(show.group_ids("area:Truss"), None);
+
+        show.patch.fixtures.get_mut(&3).unwrap().location.area = "FOH".into();
+        assert_eq!(show.group_ids("area:FOH"), Some(BTreeSet::from([1, 3])));
+
+        assert_eq!(
+            show.parse_selection(&["front", "+", "bar:LX2", "+", "5", "thru", "6"]),
+            Some(BTreeSet::from([1, 2, 5, 6]))
+        );
+        assert_eq!(show.parse_selection(&["front", "nope"]), None);
+
+        show.groups.insert("1".into(), BTreeSet::from([1, 2, 3]));
+        assert_eq!(
+            show.parse_selection(&["group", "1", "-", "2"]),
+            Some(BTreeSet::from([1, 3]))
+        );
+        assert_eq!(
+            show.parse_selection(&["1", "thru", "4", "-", "G", "front"]),
+            Some(BTreeSet::from([2, 3, 4]))
+        );
+        assert_eq!(show.parse_selection(&["group", "2"]), None);
+
+        // the CLI's `group 1 - 3`: terms after the group are fixture ids
+        // unless marked as groups
+        show.groups.insert("3".into(), BTreeSet::from([1]));
+        let cli = |line: &str| show.parse_selection(&line.split_whitespace().collect::<Vec<_>>());
+        assert_eq!(cli("group 1 - 3"), Some(BTreeSet::from([1, 2])));
+        assert_eq!(cli("group 1 - g 3"), Some(BTreeSet::from([2, 3])));
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+//! Client roles: what a connected client (GUI, remote, backup) may do.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Everything, including show edits.
+    #[default]
+    Full,
+    /// Run playbacks, no programming or show edits (e.g. a phone remote).
+    PlaybackOnly,
+    /// Look, don't touch.
+    ViewOnly,
+}
+
+/// What a command does, for permission checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandClass {
+    View,
+    Playback,
+    Programming,
+    ShowEdit,
+}
+
+/// Classify a command line by its first word (and, for `pb`, its
+/// subcommand). Unknown commands count as show edits.
+pub fn command_class(line: &str) -> CommandClass {
+    let words: Vec<String> = line.split_whitespace().map(str::to_lowercase).collect();
+    let word = |i: usize| words.get(i).map(String::as_str).unwrap_or_default();
+    match word(0) {
+        "help" | "quit" | "exit" | "list" | "show" | "out" | "state" | "trans" | "cues"
+        | "palettes" | "groups" | "info" | "search" | "at-address" | "about" | "version"
+        | "lint" | "preview" | "footprint" | "say" | "log" | "rdmcheck" | "agenda" | "framelog"
+        | "dumpframes" | "stats" => CommandClass::View,
+        // listing, picking and running playbacks; add/del/use/priority are saved
+        "pb" if matches!(word(2), "" | "go" | "release" | "goto") => CommandClass::Playback,
+        "go" | "go!" | "goto" | "back" | "pause" | "goguard" | "xfade" | "pbmode" | "run"
+        | "stop" | "tick" | "freeze" | "solo" | "master" | "panic" | "gm" | "fader"
+        | "blackout" | "disarm" | "rate" | "speed" | "flash" => CommandClass::Playback,
+        "select" | "at" | "rgb" | "color" | "r" | "g" | "b" | "clear" | "clearvals"
+        | "clearprog" | "clearall" | "group" | "apply" | "time" | "dmx" | "release" | "nudge"
+        | "colors" | "@" | "fan" | "focus" => CommandClass::Programming,
+        // everything else changes the saved show (`filter` too), writes files
+        // (`export`, `thumbs`) or sends lamp and reset commands to the rig
+        // (`fixture`)
+        _ => CommandClass::ShowEdit,
+    }
+}
+
+impl Role {
+    pub fn allows(self, class: CommandClass) -> bool {
+        match self {
+            Role::Full => true,
+            Role::PlaybackOnly => matches!(class, CommandClass::View | CommandClass::Playback),
+            Role::ViewOnly => class == CommandClass::View,
+        }
+    }
+
+    /// Error if this role may not run command line `line`.
+    pub fn check(self, line: &str) -> anyhow::Result<()> {
+        let class = command_class(line);
+        let command = line.split_whitespace().next().unwrap_or_default();
+        anyhow::ensure!(
+            self.allows(class),
+            "'{command}' is not allowed for role {self:?} ({class:?} command)"
+        );
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Role {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().replace(['-', '_'], "").as_str() {
+            "full" | "programmer" => Ok(Role::Full),
+            "playback" | "playbackonly" => Ok(Role::PlaybackOnly),
+            "view" | "viewonly" => Ok(Role::ViewOnly),
+            _ => anyhow::bail!("unknown role '{s}' (use full|playback|view)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_gate_command_classes() -> anyhow::Result<()> {
+        let remote: Role = "playback-only".parse()?;
+        assert!(remote.check("go").is_ok());
+        assert!(remote.check("cues").is_ok());
+        assert!(remote.check("delete").is_err());
+        assert!(remote.check("select").is_err());
+        assert!(remote.check("pb").is_ok());
+        assert!(remote.check("pb c go").is_ok());
+        assert!(remote.check("pb x del").is_err());
+        assert!(remote.check("pb c priority 10").is_err());
+        assert!(remote.check("filter color").is_err());
+
+        let view = Role::ViewOnly;
+        assert!(view.check("out").is_ok());
+        assert!(view.check("go").is_err());
+        assert!(view.check("export").is_err());
+        assert!(view.check("thumbs").is_err());
+        assert!(remote.check("fixture").is_err());
+
+        assert!(Role::Full.check("delete").is_ok());
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+//! Show statistics for pre-production sanity checks: what's patched where,
+//! how big the cue lists are, which palettes nothing uses and which cues
+//! carry the most data.
+
+use std::collections::BTreeMap;
+
+use crate::Show;
+
+/// How many of the largest cues `Show::stats` lists.
+pub const LARGEST_CUES: usize = 5;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShowStats {
+    pub fixtures: usize,
+    pub fixtures_by_type: BTreeMap<String, usize>,
+    pub fixtures_by_universe: BTreeMap<u16, usize>,
+    pub groups: usize,
+    pub cues_by_list: BTreeMap<String, usize>,
+    /// Mean fade time over all cues; None without cues.
+    pub average_fade_ms: Option<u32>,
+    /// Cues referencing each palette; unused palettes count 0.
+    pub palette_usage: BTreeMap<String, usize>,
+    /// Cues changing the most fixtures, largest first: (list, cue, fixtures).
+    pub largest_cues: Vec<(String, u32, usize)>,
+}
+
+impl Show {
+    pub fn stats(&self) -> ShowStats {
+        let mut stats = ShowStats {
+            fixtures: self.patch.fixtures.len(),
+            groups: self.groups.len(),
+            ..Default::default()
+        };
+        for f in self.patch.fixtures.values() {
+            *stats
+                .fixtures_by_type
+                .entry(f.fixture_type.clone())
+                .or_default() += 1;
+            *stats.fixtures_by_universe.entry(f.universe).or_default() += 1;
+        }
+
+        let mut fade_total = 0u64;
+        let mut cues = Vec::new();
+        for (name, list) in &self.cue_lists {
+            stats.cues_by_list.insert(name.clone(), list.cues.len());
+            for cue in list.cues.values() {
+                fade_total += cue.fade_ms as u64;
+                cues.push((name.clone(), cue.number, cue.changes.len()));
+            }
+        }
+        if !cues.is_empty() {
+            stats.average_fade_ms = Some((fade_total / cues.len() as u64) as u32);
+        }
+        // stable sort keeps list/cue order among equals
+        cues.sort_by_key(|c| std::cmp::Reverse(c.2));
+        cues.truncate(LARGEST_CUES);
+        stats.largest_cues = cues;
+
+        for name in self.palettes.keys() {
+            stats
+                .palette_usage
+                .insert(name.clone(), self.palette_users(name).len());
+        }
+        stats
+    }
+}
+
+impl ShowStats {
+    /// Plain-text report, one fact per line.
+    pub fn lines(&self) -> Vec<String> {
+        let mut out = vec![format!(
+            "Fixtures: {} ({} groups)",
+            self.fixtures, self.groups
+        )];
+        for (ty, n) in &self.fixtures_by_type {
+            out.push(format!("  {ty}: {n}"));
+        }
+        for (u, n) in &self.fixtures_by_universe {
+            out.push(format!("  universe {u}: {n}"));
+        }
+        let cues: usize = self.cues_by_list.values().sum();
+        out.push(format!("Cues: {cues}"));
+        for (list, n) in &self.cues_by_list {
+            out.push(format!("  {list}: {n}"));
+        }
+        if let Some(ms) = self.average_fade_ms {
+            out.push(format!("Average fade: {:.1}s", ms as f64 / 1000.0));
+        }
+        if !self.palette_usage.is_empty() {
+            out.push("Palettes (cues using them):".into());
+            for (name, n) in &self.palette_usage {
+                let unused = if *n == 0 { "  (unused)" } else { "" };
+                out.push(format!("  {name}: {n}{unused}"));
+            }
+        }
+        if !self.largest_cues.is_empty() {
+            out.push("Largest cues (fixtures changed):".into());
+            for (list, cue, n) in &self.largest_cues {
+                out.push(format!("  {list}/{cue}: {n}"));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CueBuilder, Palette, PaletteKind, PaletteRefs, PaletteValues, ShowBuilder};
+
+    #[test]
+    fn stats_summarize_patch_cues_and_palettes() -> anyhow::Result<()> {
+        let mut show = ShowBuilder::new("Test")
+            .default_fixture_types()
+            .fixture(1, "D1", "dimmer_1ch", 1, 1)
+            .fixture(2, "D2", "dimmer_1ch", 2, 1)
+            .fixture(3, "Par", "rgb_par_3ch", 2, 10)
+            .group("all", [1, 2, 3])
+            .cue(CueBuilder::new(1).intensity(1, 255).fade_ms(1000))
+            .cue(
+                CueBuilder::new(2)
+                    .intensity(1, 0)
+                    .intensity(2, 255)
+                    .fade_ms(3000),
+            )
+            .cue_in("fx", CueBuilder::new(1))
+            .build()?;
+        for name in ["full", "spare"] {
+            show.palettes.insert(
+                name.into(),
+                Palette::new(PaletteKind::Intensity, PaletteValues::default()),
+            );
+        }
+        let refs = PaletteRefs {
+            intensity: Some("full".into()),
+            color: None,
+        };
+        let main = show.cue_lists.get_mut("main").unwrap();
+        main.cues.get_mut(&1).unwrap().palette_refs.insert(1, refs);
+
+        let stats = show.stats();
+        assert_eq!(stats.fixtures, 3);
+        assert_eq!(stats.fixtures_by_type["dimmer_1ch"], 2);
+        assert_eq!(stats.fixtures_by_universe[&2], 2);
+        assert_eq!(stats.cues_by_list["main"], 2);
+        assert_eq!(stats.average_fade_ms, Some(1333));
+        assert_eq!(stats.palette_usage["full"], 1);
+        assert_eq!(stats.palette_usage["spare"], 0);
+        assert_eq!(stats.largest_cues[0], ("main".into(), 2, 2));
+        assert!(stats.lines().contains(&"  spare: 0  (unused)".to_string()));
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+//! Show document store: the saved show, separate from volatile runtime state.
+//!
+//! Every mutable access bumps the revision (conservatively: a `&mut` that
+//! changes nothing still counts). Snapshots share the document copy-on-write,
+//! so an autosave or sync thread can hold one while the engine keeps editing;
+//! the first edit after a snapshot clones the document once. The same sharing
+//! makes undo cheap: a checkpoint is a snapshot kept on a stack.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+use crate::Show;
+
+/// Checkpoints kept for undo.
+pub const UNDO_DEPTH: usize = 32;
+
+#[derive(Debug)]
+pub struct ShowStore {
+    doc: Arc<Show>,
+    revision: u64,
+    /// (what the edit was, the document before it), oldest first.
+    undo: Vec<(String, Arc<Show>)>,
+}
+
+/// A consistent, read-only view of the show at one revision.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub revision: u64,
+    pub show: Arc<Show>,
+}
+
+impl ShowStore {
+    pub fn new(show: Show) -> Self {
+        Self {
+            doc: Arc::new(show),
+            revision: 0,
+            undo: Vec::new(),
+        }
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            revision: self.revision,
+            show: Arc::clone(&self.doc),
+        }
+    }
+
+    /// Swap in a whole document (load, reload, restore).
+    pub fn replace(&mut self, show: Show) {
+        self.doc = Arc::new(show);
+        self.revision += 1;
+    }
+
+    /// Remember the document before an undoable edit named `label`.
+    pub fn checkpoint(&mut self, label: impl Into<String>) {
+        if self.undo.len() == UNDO_DEPTH {
+            self.undo.remove(0);
+        }
+        self.undo.push((label.into(), Arc::clone(&self.doc)));
+    }
+
+    /// The edit `undo` would revert.
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo.last().map(|(label, _)| label.as_str())
+    }
+
+    /// Go back to the last checkpoint; returns what was undone.
+    pub fn undo(&mut self) -> Option<String> {
+        let (label, doc) = self.undo.pop()?;
+        self.doc = doc;
+        self.revision += 1;
+        Some(label)
+    }
+}
+
+impl Deref for ShowStore {
+    type Target = Show;
+
+    fn deref(&self) -> &Show {
+        &self.doc
+    }
+}
+
+impl DerefMut for ShowStore {
+    fn deref_mut(&mut self) -> &mut Show {
+        self.revision += 1;
+        Arc::make_mut(&mut self.doc)
+    }
+}
+
+impl Deref for Snapshot {
+    type Target = Show;
+
+    fn deref(&self) -> &Show {
+        &self.show
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_keep_their_revision_while_editing() {
+        let mut store = ShowStore::new(Show::new("Test"));
+        let before = store.snapshot();
+        assert_eq!(before.revision, 0);
+
+        store.groups.insert("front".into(), [1, 2].into());
+        assert_eq!(store.revision(), 1);
+        assert!(before.groups.is_empty());
+        assert_eq!(store.snapshot().groups.len(), 1);
+
+        store.replace(Show::new("Other"));
+        assert_eq!(store.revision(), 2);
+        assert_eq!(before.revision, 0);
+
+        store.checkpoint("rename");
+        store.name = "Renamed".into();
+        assert_eq!(store.undo_label(), Some("rename"));
+        assert_eq!(store.undo().as_deref(), Some("rename"));
+        assert_eq!(store.name, "Other");
+        assert_eq!(store.undo(), None);
+    }
+}
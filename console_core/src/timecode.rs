@@ -0,0 +1,176 @@
+//! Timecode positions and the timecoded event list (commands fired at frames,
+//! independent of cue lists).
+
+use serde::{Deserialize, Serialize};
+
+/// Timecode as a frame count at `fps`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Timecode {
+    pub frame: u32,
+    pub fps: u8,
+}
+
+impl Timecode {
+    pub fn from_frame(frame: u32, fps: u8) -> Self {
+        Self { frame, fps }
+    }
+
+    /// Parse `HH:MM:SS:FF` (or `HH:MM:SS.FF`).
+    pub fn parse(s: &str, fps: u8) -> anyhow::Result<Self> {
+        let fields: Vec<&str> = s.trim().split([':', '.', ';']).collect();
+        let [h, m, sec, f] = fields.as_slice() else {
+            anyhow::bail!("timecode must be HH:MM:SS:FF, got '{s}'");
+        };
+        let num = |v: &str| -> anyhow::Result<u32> {
+            v.parse()
+                .map_err(|_| anyhow::anyhow!("invalid timecode '{s}'"))
+        };
+        let (h, m, sec, f) = (num(h)?, num(m)?, num(sec)?, num(f)?);
+        anyhow::ensure!(m < 60 && sec < 60, "invalid timecode '{s}'");
+        anyhow::ensure!(f < fps as u32, "frame {f} out of range at {fps} fps");
+
+        Ok(Self {
+            frame: ((h * 60 + m) * 60 + sec) * fps as u32 + f,
+            fps,
+        })
+    }
+
+    pub fn to_ms(self) -> u64 {
+        self.frame as u64 * 1000 / self.fps.max(1) as u64
+    }
+}
+
+impl std::fmt::Display for Timecode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fps = self.fps.max(1) as u32;
+        let secs = self.frame / fps;
+        write!(
+            f,
+            "{:02}:{:02}:{:02}:{:02}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60,
+            self.frame % fps
+        )
+    }
+}
+
+/// One event: a command line run when timecode passes `frame`.
+/// Commands are what the operator would type (`go`, `goto 5`, a macro...).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimedEvent {
+    pub frame: u32,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub label: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventList {
+    pub fps: u8,
+    /// Sorted by frame; events on the same frame keep insertion order.
+    pub events: Vec<TimedEvent>,
+}
+
+impl Default for EventList {
+    fn default() -> Self {
+        Self {
+            fps: 25,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl EventList {
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn timecode(&self, frame: u32) -> Timecode {
+        Timecode::from_frame(frame, self.fps)
+    }
+
+    /// Insert keeping frame order; returns the event's index.
+    pub fn add(&mut self, event: TimedEvent) -> usize {
+        let idx = self.events.partition_point(|e| e.frame <= event.frame);
+        self.events.insert(idx, event);
+        idx
+    }
+
+    pub fn remove(&mut self, idx: usize) -> anyhow::Result<TimedEvent> {
+        anyhow::ensure!(idx < self.events.len(), "no event #{idx}");
+        Ok(self.events.remove(idx))
+    }
+
+    /// Move an event to a new frame; returns its new index.
+    pub fn retime(&mut self, idx: usize, frame: u32) -> anyhow::Result<usize> {
+        let mut ev = self.remove(idx)?;
+        ev.frame = frame;
+        Ok(self.add(ev))
+    }
+
+    /// Point `goto <cue>` events at renumbered cues ((old, new) pairs).
+    /// Returns how many events changed.
+    pub fn renumber_gotos(&mut self, moves: &[(u32, u32)]) -> usize {
+        let mut changed = 0;
+        for ev in &mut self.events {
+            let mut words: Vec<String> = ev.command.split_whitespace().map(String::from).collect();
+            let [goto, cue, ..] = words.as_mut_slice() else {
+                continue;
+            };
+            let Ok(n) = cue.parse::<u32>() else {
+                continue;
+            };
+            if !goto.eq_ignore_ascii_case("goto") {
+                continue;
+            }
+            if let Some(&(_, to)) = moves.iter().find(|&&(from, _)| from == n) {
+                *cue = to.to_string();
+                ev.command = words.join(" ");
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Events crossed when timecode rolls forward from `from` (exclusive) to
+    /// `to` (inclusive). Jumping backwards fires nothing.
+    pub fn due(&self, from: u32, to: u32) -> impl Iterator<Item = &TimedEvent> {
+        self.events
+            .iter()
+            .filter(move |e| to > from && e.frame > from && e.frame <= to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timecode_parses_and_events_fire_in_order() -> anyhow::Result<()> {
+        let tc = Timecode::parse("01:00:02:10", 25)?;
+        assert_eq!(tc.frame, (3600 + 2) * 25 + 10);
+        assert_eq!(tc.to_string(), "01:00:02:10");
+        assert!(Timecode::parse("00:00:00:25", 25).is_err());
+
+        let mut list = EventList::default();
+        for (frame, cmd) in [(50, "goto 2"), (10, "go"), (50, "go")] {
+            list.add(TimedEvent {
+                frame,
+                command: cmd.into(),
+                label: String::new(),
+            });
+        }
+
+        let fired: Vec<&str> = list.due(0, 50).map(|e| e.command.as_str()).collect();
+        assert_eq!(fired, ["go", "goto 2", "go"]);
+        assert_eq!(list.due(10, 49).count(), 0);
+        assert_eq!(list.due(60, 0).count(), 0);
+
+        assert_eq!(list.retime(0, 100)?, 2);
+        assert_eq!(list.events[2].command, "go");
+        assert_eq!(list.renumber_gotos(&[(2, 20)]), 1);
+        assert_eq!(list.events[0].command, "goto 20");
+        Ok(())
+    }
+}
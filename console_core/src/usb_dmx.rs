@@ -0,0 +1,276 @@
+//! USB DMX widgets on a serial device (`/dev/ttyUSB0`). A widget drives
+//! one universe. The DMX USB Pro frames each universe in its own serial
+//! protocol; an Open DMX is a bare FTDI chip, so the console sets the line
+//! to 250 kbaud 8N2 and sends the DMX break itself (Linux only).
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::dmx_output::slots;
+use crate::{LiveState, OutputDriver};
+
+/// Directory with stable names for USB serial devices.
+pub const SERIAL_BY_ID: &str = "/dev/serial/by-id";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsbWidget {
+    /// Enttec Open DMX and other plain FTDI interfaces.
+    OpenDmx,
+    /// Enttec DMX USB Pro (and compatible widgets).
+    UsbPro,
+}
+
+impl std::fmt::Display for UsbWidget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OpenDmx => f.write_str("Open DMX"),
+            Self::UsbPro => f.write_str("DMX USB Pro"),
+        }
+    }
+}
+
+impl std::str::FromStr for UsbWidget {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "open" | "opendmx" | "open_dmx" => Ok(Self::OpenDmx),
+            "pro" | "usbpro" | "usb_pro" => Ok(Self::UsbPro),
+            _ => anyhow::bail!("unknown USB widget '{s}' (use open|pro)"),
+        }
+    }
+}
+
+impl UsbWidget {
+    /// Widget a USB serial name suggests, from the product string in it
+    /// (`usb-ENTTEC_DMX_USB_PRO_EN123456-if00-port0`). None for non-FTDI
+    /// adapters, which are unlikely to be DMX interfaces.
+    pub fn guess(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        if name.contains("dmx_usb_pro") || name.contains("usb_pro") {
+            Some(Self::UsbPro)
+        } else if name.contains("enttec") || name.contains("ftdi") || name.contains("open_dmx") {
+            Some(Self::OpenDmx)
+        } else {
+            None
+        }
+    }
+}
+
+/// A serial device that may be a DMX widget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsbDmxDevice {
+    pub path: PathBuf,
+    /// What the device name suggests it is.
+    pub widget: Option<UsbWidget>,
+}
+
+/// USB serial devices, by stable name where udev provides one, else the
+/// plain `/dev/ttyUSB*` nodes.
+pub fn usb_dmx_devices() -> Vec<UsbDmxDevice> {
+    let by_id = devices_in(Path::new(SERIAL_BY_ID), |_| true);
+    if !by_id.is_empty() {
+        return by_id;
+    }
+    devices_in(Path::new("/dev"), |name| name.starts_with("ttyUSB"))
+}
+
+fn devices_in(dir: &Path, keep: impl Fn(&str) -> bool) -> Vec<UsbDmxDevice> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut devices: Vec<UsbDmxDevice> = entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            keep(&name).then(|| UsbDmxDevice {
+                widget: UsbWidget::guess(&name),
+                path: e.path(),
+            })
+        })
+        .collect();
+    devices.sort_by(|a, b| a.path.cmp(&b.path));
+    devices
+}
+
+/// DMX USB Pro message label for "output only send DMX packet".
+pub const USB_PRO_SEND_DMX: u8 = 6;
+
+/// A DMX USB Pro "send DMX" message: start code 0 and the 512 slots.
+pub fn usb_pro_packet(data: &[u8; 512]) -> Vec<u8> {
+    let len = (data.len() + 1) as u16;
+    let mut p = Vec::with_capacity(data.len() + 6);
+    p.push(0x7e);
+    p.push(USB_PRO_SEND_DMX);
+    p.extend_from_slice(&len.to_le_bytes());
+    p.push(0); // start code
+    p.extend_from_slice(data);
+    p.push(0xe7);
+    p
+}
+
+#[derive(Debug)]
+pub struct UsbDmxOutput {
+    device: PathBuf,
+    widget: UsbWidget,
+    port: std::fs::File,
+}
+
+impl UsbDmxOutput {
+    pub fn new(device: impl Into<PathBuf>, widget: UsbWidget) -> anyhow::Result<Self> {
+        let device = device.into();
+        let port = std::fs::OpenOptions::new()
+            .write(true)
+            .open(&device)
+            .with_context(|| format!("open {}", device.display()))?;
+        if widget == UsbWidget::OpenDmx {
+            serial::set_dmx_line(&port)
+                .with_context(|| format!("set up {} for DMX", device.display()))?;
+        }
+        Ok(Self {
+            device,
+            widget,
+            port,
+        })
+    }
+}
+
+impl OutputDriver for UsbDmxOutput {
+    fn name(&self) -> String {
+        format!("{} {}", self.widget, self.device.display())
+    }
+
+    /// The widget was unplugged and plugged back in.
+    fn reconnect(&mut self) -> anyhow::Result<()> {
+        *self = Self::new(self.device.clone(), self.widget)?;
+        Ok(())
+    }
+
+    /// Sends the lowest universe routed here; a widget has one output.
+    fn send(&mut self, frame: &LiveState) -> anyhow::Result<()> {
+        let Some(addrs) = frame.universes.values().next() else {
+            return Ok(());
+        };
+        let data = slots(addrs);
+        let write = |port: &mut std::fs::File, bytes: &[u8]| {
+            port.write_all(bytes)
+                .with_context(|| format!("write to {}", self.device.display()))
+        };
+        match self.widget {
+            UsbWidget::UsbPro => write(&mut self.port, &usb_pro_packet(&data)),
+            UsbWidget::OpenDmx => {
+                serial::send_break(&self.port)
+                    .with_context(|| format!("send break on {}", self.device.display()))?;
+                let mut packet = Vec::with_capacity(513);
+                packet.push(0); // start code
+                packet.extend_from_slice(&data);
+                write(&mut self.port, &packet)
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod serial {
+    use std::fs::File;
+    use std::io;
+    use std::os::fd::AsRawFd;
+    use std::time::Duration;
+
+    /// DMX line speed.
+    const BAUD: u32 = 250_000;
+
+    fn check(ret: libc::c_int) -> io::Result<()> {
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Raw 250 kbaud, 8 data bits, 2 stop bits, no parity.
+    pub fn set_dmx_line(port: &File) -> io::Result<()> {
+        let fd = port.as_raw_fd();
+        // SAFETY: fd is open for the lifetime of `port`; termios2 is plain data
+        unsafe {
+            let mut tio: libc::termios2 = std::mem::zeroed();
+            check(libc::ioctl(fd, libc::TCGETS2, &mut tio))?;
+            tio.c_iflag = 0;
+            tio.c_oflag = 0;
+            tio.c_lflag = 0;
+            tio.c_cflag &= !(libc::CBAUD | libc::CSIZE | libc::PARENB);
+            tio.c_cflag |= libc::BOTHER | libc::CS8 | libc::CSTOPB | libc::CLOCAL;
+            tio.c_ispeed = BAUD;
+            tio.c_ospeed = BAUD;
+            check(libc::ioctl(fd, libc::TCSETS2, &tio))
+        }
+    }
+
+    /// Wait for the last frame to leave, then hold the line low for the
+    /// break (at least 88 µs) and release it for the mark after break.
+    pub fn send_break(port: &File) -> io::Result<()> {
+        let fd = port.as_raw_fd();
+        // SAFETY: fd is open for the lifetime of `port`
+        unsafe {
+            check(libc::tcdrain(fd))?;
+            check(libc::ioctl(fd, libc::TIOCSBRK))?;
+            std::thread::sleep(Duration::from_micros(110));
+            check(libc::ioctl(fd, libc::TIOCCBRK))?;
+        }
+        std::thread::sleep(Duration::from_micros(12));
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod serial {
+    use std::fs::File;
+    use std::io;
+
+    fn unsupported() -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Open DMX output needs Linux; use a DMX USB Pro",
+        ))
+    }
+
+    pub fn set_dmx_line(_port: &File) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn send_break(_port: &File) -> io::Result<()> {
+        unsupported()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usb_pro_frames_and_widget_guesses() -> anyhow::Result<()> {
+        let mut data = [0u8; 512];
+        data[0] = 255;
+        data[511] = 7;
+        let p = usb_pro_packet(&data);
+        assert_eq!(p.len(), 518);
+        assert_eq!(p[..6], [0x7e, 6, 0x01, 0x02, 0, 255]);
+        assert_eq!(p[p.len() - 2..], [7, 0xe7]);
+
+        let guess = UsbWidget::guess;
+        assert_eq!(
+            guess("usb-ENTTEC_DMX_USB_PRO_EN123456-if00-port0"),
+            Some(UsbWidget::UsbPro)
+        );
+        assert_eq!(
+            guess("usb-FTDI_FT232R_USB_UART_A6008isP-if00-port0"),
+            Some(UsbWidget::OpenDmx)
+        );
+        assert_eq!(guess("usb-Prolific_USB-Serial_Controller-if00"), None);
+        assert_eq!("pro".parse::<UsbWidget>()?, UsbWidget::UsbPro);
+        assert!("midi".parse::<UsbWidget>().is_err());
+        Ok(())
+    }
+}
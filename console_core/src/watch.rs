@@ -0,0 +1,403 @@
+//! Detect external edits to a show file and merge them into the in-memory show.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::{CueList, Show};
+
+/// Polls a show file's modification time and size.
+/// Cheap enough to call once per REPL command or GUI frame.
+#[derive(Debug, Clone)]
+pub struct ShowWatcher {
+    path: PathBuf,
+    seen: Option<(SystemTime, u64)>,
+}
+
+impl ShowWatcher {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let seen = stamp(&path);
+        Self { path, seen }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// True once per change on disk since the last `poll`/`mark_seen`.
+    pub fn poll(&mut self) -> bool {
+        let now = stamp(&self.path);
+        if now.is_some() && now != self.seen {
+            self.seen = now;
+            return true;
+        }
+        false
+    }
+
+    /// Accept the current file state as known (e.g. right after we saved it ourselves).
+    pub fn mark_seen(&mut self) {
+        self.seen = stamp(&self.path);
+    }
+}
+
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// Result of merging an externally edited show into the in-memory one.
+#[derive(Debug, Clone)]
+pub struct MergeOutcome {
+    pub show: Show,
+    /// Objects edited both in memory and on disk; the in-memory version was kept.
+    pub conflicts: Vec<String>,
+}
+
+/// Three-way merge: `base` is the show as last loaded/saved, `local` the in-memory
+/// edits since then and `external` the file now on disk.
+/// External changes are taken unless the same object was also edited locally.
+pub fn merge_reload(local: &Show, base: &Show, external: &Show) -> MergeOutcome {
+    let mut conflicts = Vec::new();
+
+    let name = pick(
+        Some(&local.name),
+        Some(&base.name),
+        Some(&external.name),
+        "show name",
+        &mut conflicts,
+    )
+    .cloned()
+    .unwrap_or_default();
+
+    let address_format = *pick(
+        Some(&local.address_format),
+        Some(&base.address_format),
+        Some(&external.address_format),
+        "address format",
+        &mut conflicts,
+    )
+    .unwrap_or(&local.address_format);
+
+    let events = pick(
+        Some(&local.events),
+        Some(&base.events),
+        Some(&external.events),
+        "timecode events",
+        &mut conflicts,
+    )
+    .unwrap_or(&local.events)
+    .clone();
+
+    let masters = pick(
+        Some(&local.masters),
+        Some(&base.masters),
+        Some(&external.masters),
+        "master page",
+        &mut conflicts,
+    )
+    .unwrap_or(&local.masters)
+    .clone();
+
+    let outputs = pick(
+        Some(&local.outputs),
+        Some(&base.outputs),
+        Some(&external.outputs),
+        "output routing",
+        &mut conflicts,
+    )
+    .unwrap_or(&local.outputs)
+    .clone();
+
+    let sacn = pick(
+        Some(&local.sacn),
+        Some(&base.sacn),
+        Some(&external.sacn),
+        "sACN settings",
+        &mut conflicts,
+    )
+    .unwrap_or(&local.sacn)
+    .clone();
+
+    let dmx_wing = pick(
+        Some(&local.dmx_wing),
+        Some(&base.dmx_wing),
+        Some(&external.dmx_wing),
+        "DMX wing",
+        &mut conflicts,
+    )
+    .unwrap_or(&local.dmx_wing)
+    .clone();
+
+    let selection_policy = *pick(
+        Some(&local.selection_policy),
+        Some(&base.selection_policy),
+        Some(&external.selection_policy),
+        "selection policy",
+        &mut conflicts,
+    )
+    .unwrap_or(&local.selection_policy);
+
+    let save_policy = *pick(
+        Some(&local.save_policy),
+        Some(&base.save_policy),
+        Some(&external.save_policy),
+        "save policy",
+        &mut conflicts,
+    )
+    .unwrap_or(&local.save_policy);
+
+    let mut show = Show {
+        name,
+        masters,
+        outputs,
+        sacn,
+        dmx_input: merge_map(
+            &local.dmx_input,
+            &base.dmx_input,
+            &external.dmx_input,
+            "DMX input for universe",
+            &mut conflicts,
+        ),
+        dmx_wing,
+        // append-only: never a conflict
+        log: crate::oplog::merge_log(&local.log, &external.log),
+        selection_policy,
+        save_policy,
+        address_format,
+        events,
+        aliases: merge_map(
+            &local.aliases,
+            &base.aliases,
+            &external.aliases,
+            "alias",
+            &mut conflicts,
+        ),
+        patch: local.patch.clone(),
+        palettes: merge_map(
+            &local.palettes,
+            &base.palettes,
+            &external.palettes,
+            "palette",
+            &mut conflicts,
+        ),
+        cue_lists: merge_cue_lists(
+            &local.cue_lists,
+            &base.cue_lists,
+            &external.cue_lists,
+            &mut conflicts,
+        ),
+        groups: merge_map(
+            &local.groups,
+            &base.groups,
+            &external.groups,
+            "group",
+            &mut conflicts,
+        ),
+        playbacks: merge_map(
+            &local.playbacks,
+            &base.playbacks,
+            &external.playbacks,
+            "playback settings",
+            &mut conflicts,
+        ),
+        group_meta: merge_map(
+            &local.group_meta,
+            &base.group_meta,
+            &external.group_meta,
+            "group metadata",
+            &mut conflicts,
+        ),
+    };
+
+    show.patch.fixture_types = merge_map(
+        &local.patch.fixture_types,
+        &base.patch.fixture_types,
+        &external.patch.fixture_types,
+        "fixture type",
+        &mut conflicts,
+    );
+    show.patch.fixtures = merge_map(
+        &local.patch.fixtures,
+        &base.patch.fixtures,
+        &external.patch.fixtures,
+        "fixture",
+        &mut conflicts,
+    );
+
+    MergeOutcome { show, conflicts }
+}
+
+fn pick<'a, T: PartialEq>(
+    local: Option<&'a T>,
+    base: Option<&'a T>,
+    external: Option<&'a T>,
+    what: &str,
+    conflicts: &mut Vec<String>,
+) -> Option<&'a T> {
+    if local == base {
+        external
+    } else if external == base || external == local {
+        local
+    } else {
+        conflicts.push(what.to_string());
+        local
+    }
+}
+
+fn merge_map<K, V>(
+    local: &BTreeMap<K, V>,
+    base: &BTreeMap<K, V>,
+    external: &BTreeMap<K, V>,
+    what: &str,
+    conflicts: &mut Vec<String>,
+) -> BTreeMap<K, V>
+where
+    K: Ord + Clone + std::fmt::Display,
+    V: PartialEq + Clone,
+{
+    let keys: BTreeSet<&K> = local
+        .keys()
+        .chain(base.keys())
+        .chain(external.keys())
+        .collect();
+    let mut out = BTreeMap::new();
+
+    for k in keys {
+        let label = format!("{what} {k}");
+        if let Some(v) = pick(
+            local.get(k),
+            base.get(k),
+            external.get(k),
+            &label,
+            conflicts,
+        ) {
+            out.insert(k.clone(), v.clone());
+        }
+    }
+
+    out
+}
+
+fn merge_cue_lists(
+    local: &BTreeMap<String, CueList>,
+    base: &BTreeMap<String, CueList>,
+    external: &BTreeMap<String, CueList>,
+    conflicts: &mut Vec<String>,
+) -> BTreeMap<String, CueList> {
+    let keys: BTreeSet<&String> = local
+        .keys()
+        .chain(base.keys())
+        .chain(external.keys())
+        .collect();
+    let mut out = BTreeMap::new();
+    let empty = CueList::default();
+
+    for k in keys {
+        let (l, b, e) = (local.get(k), base.get(k), external.get(k));
+        // Both sides kept the list but edited it: merge cue by cue.
+        if let (Some(l), Some(e)) = (l, e)
+            && l != b.unwrap_or(&empty)
+            && e != b.unwrap_or(&empty)
+            && l != e
+        {
+            let b = b.unwrap_or(&empty);
+            let cues = merge_map(
+                &l.cues,
+                &b.cues,
+                &e.cues,
+                &format!("cuelist {k} cue"),
+                conflicts,
+            );
+            let auto_black = *pick(
+                Some(&l.auto_black),
+                Some(&b.auto_black),
+                Some(&e.auto_black),
+                &format!("cuelist {k} auto-black"),
+                conflicts,
+            )
+            .unwrap_or(&l.auto_black);
+            let looping = *pick(
+                Some(&l.looping),
+                Some(&b.looping),
+                Some(&e.looping),
+                &format!("cuelist {k} loop"),
+                conflicts,
+            )
+            .unwrap_or(&l.looping);
+            let move_in_black = *pick(
+                Some(&l.move_in_black),
+                Some(&b.move_in_black),
+                Some(&e.move_in_black),
+                &format!("cuelist {k} move in black"),
+                conflicts,
+            )
+            .unwrap_or(&l.move_in_black);
+            let locks = pick(
+                Some(&l.locks),
+                Some(&b.locks),
+                Some(&e.locks),
+                &format!("cuelist {k} locks"),
+                conflicts,
+            )
+            .unwrap_or(&l.locks)
+            .clone();
+            out.insert(
+                k.clone(),
+                CueList {
+                    cues,
+                    auto_black,
+                    looping,
+                    move_in_black,
+                    locks,
+                },
+            );
+            continue;
+        }
+
+        if let Some(v) = pick(l, b, e, &format!("cuelist {k}"), conflicts) {
+            out.insert(k.clone(), v.clone());
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Palette, PaletteKind, PaletteValues};
+
+    fn pal(i: u8) -> Palette {
+        Palette::new(
+            PaletteKind::Intensity,
+            PaletteValues {
+                intensity: Some(i),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn merge_takes_external_edits_and_keeps_local_ones() {
+        let mut base = Show::new("Test");
+        base.palettes.insert("full".into(), pal(255));
+        base.palettes.insert("half".into(), pal(127));
+
+        let mut local = base.clone();
+        local.palettes.insert("half".into(), pal(100));
+        local.palettes.insert("low".into(), pal(20));
+
+        let mut external = base.clone();
+        external.palettes.insert("full".into(), pal(250));
+        external.palettes.insert("half".into(), pal(90));
+
+        let out = merge_reload(&local, &base, &external);
+
+        assert_eq!(out.show.palettes["full"], pal(250));
+        assert_eq!(out.show.palettes["half"], pal(100));
+        assert_eq!(out.show.palettes["low"], pal(20));
+        assert_eq!(out.conflicts, vec!["palette half".to_string()]);
+    }
+}
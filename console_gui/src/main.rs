@@ -14,6 +14,11 @@ const HANDLE_RADIUS: f32 = 7.0;
 const MIN_W: i32 = 2;
 const MIN_H: i32 = 1;
 
+/// Unsaved layout edits are written at most this long after they happen;
+/// the show follows its own save policy.
+const AUTOSAVE_SECS: f64 = 30.0;
+const TITLE: &str = "LightConsole - Grid Zone";
+
 fn main() -> eframe::Result<()> {
     let args: Vec<String> = std::env::args().collect();
     if args.len() != 2 {
@@ -28,16 +33,12 @@ fn main() -> eframe::Result<()> {
 
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_title("LightConsole - Grid Zone")
+            .with_title(TITLE)
             .with_inner_size([1280.0, 720.0]),
         ..Default::default()
     };
 
-    eframe::run_native(
-        "LightConsole - Grid Zone",
-        native_options,
-        Box::new(|_cc| Ok(Box::new(app))),
-    )
+    eframe::run_native(TITLE, native_options, Box::new(|_cc| Ok(Box::new(app))))
 }
 
 fn layout_path_for_show(show_path: &Path) -> PathBuf {
@@ -54,21 +55,93 @@ enum ContainerKind {
     Cues,
     Groups,
     Palettes,
+    Macros,
+    Playbacks,
 }
 
 impl ContainerKind {
+    /// Cue and playback cells act on playback A, so the header shows its state.
+    fn shows_playback(self) -> bool {
+        matches!(self, ContainerKind::Cues | ContainerKind::Playbacks)
+    }
+
     fn title(self) -> &'static str {
         match self {
             ContainerKind::Cues => "Cues",
             ContainerKind::Groups => "Groups",
             ContainerKind::Palettes => "Palettes",
+            ContainerKind::Macros => "Macros",
+            ContainerKind::Playbacks => "Playbacks",
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 enum CellItem {
-    Placeholder { label: String },
+    Placeholder {
+        label: String,
+    },
+    /// Bound to a show group by name.
+    Group {
+        name: String,
+    },
+    /// Runs a command line when pressed.
+    Macro {
+        command: String,
+    },
+    /// Playback A control button.
+    Playback {
+        action: PlaybackAction,
+    },
+    /// Bound to a show palette by name; applies it when pressed.
+    Palette {
+        name: String,
+    },
+    /// Bound to a cue of playback A's list; goes to it when pressed.
+    Cue {
+        number: u32,
+        label: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+enum PlaybackAction {
+    Go,
+    Pause,
+    Back,
+}
+
+impl PlaybackAction {
+    const CLUSTER: [PlaybackAction; 3] = [
+        PlaybackAction::Go,
+        PlaybackAction::Pause,
+        PlaybackAction::Back,
+    ];
+
+    fn command(self) -> &'static str {
+        match self {
+            PlaybackAction::Go => "go",
+            PlaybackAction::Pause => "pause",
+            PlaybackAction::Back => "back",
+        }
+    }
+}
+
+impl CellItem {
+    fn label(&self) -> &str {
+        match self {
+            CellItem::Placeholder { label } => label,
+            CellItem::Group { name } => name,
+            CellItem::Macro { command } => command,
+            CellItem::Palette { name } => name,
+            CellItem::Cue { label, .. } => label,
+            CellItem::Playback { action } => match action {
+                PlaybackAction::Go => "GO",
+                PlaybackAction::Pause => "PAUSE",
+                PlaybackAction::Back => "BACK",
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,6 +158,10 @@ struct Container {
 
     #[serde(default)]
     cells: Vec<Option<CellItem>>,
+
+    /// Header color; None draws the default grey.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<[u8; 3]>,
 }
 
 impl Container {
@@ -152,6 +229,9 @@ struct Layout {
     rows: i32,
     next_id: u32,
     containers: Vec<Container>,
+    /// Keyboard shortcuts: egui key name (e.g. "F1") -> command or alias.
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    keys: std::collections::BTreeMap<String, String>,
 }
 
 impl Default for Layout {
@@ -161,6 +241,7 @@ impl Default for Layout {
             rows: GRID_ROWS,
             next_id: 1,
             containers: vec![],
+            keys: Default::default(),
         }
     }
 }
@@ -177,18 +258,50 @@ enum DragState {
     },
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum EncoderBank {
-    Color,     // R G B
-    Intensity, // I (single)
+/// Clicking an empty Cues or Palettes cell with programmer content offers
+/// to record into that slot.
+#[derive(Debug, Clone)]
+struct QuickRecord {
+    container: u32,
+    cx: i32,
+    cy: i32,
+    record: console_core::QuickRecord,
+    /// What the cell binds to once recorded.
+    item: CellItem,
 }
 
-impl Default for EncoderBank {
-    fn default() -> Self {
-        EncoderBank::Color
+/// Shift-clicked containers (by header) and cells, for bulk operations.
+#[derive(Debug, Default)]
+struct MultiSelection {
+    containers: std::collections::BTreeSet<u32>,
+    cells: std::collections::BTreeSet<(u32, i32, i32)>,
+    /// "Delete" was pressed once and waits for confirmation.
+    confirm_delete: bool,
+}
+
+impl MultiSelection {
+    fn is_empty(&self) -> bool {
+        self.containers.is_empty() && self.cells.is_empty()
+    }
+
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn toggle<T: Ord>(set: &mut std::collections::BTreeSet<T>, item: T) {
+        if !set.remove(&item) {
+            set.insert(item);
+        }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum EncoderBank {
+    #[default]
+    Color, // R G B
+    Intensity, // I (single)
+}
+
 #[derive(Debug, Default)]
 struct ProgrammerUi {
     // command console
@@ -201,6 +314,8 @@ struct ProgrammerUi {
     g: u8,
     b: u8,
     intensity: u8,
+    /// Name for recording a color swatch as a palette (blank = "Color N").
+    swatch_name: String,
 }
 
 impl ProgrammerUi {
@@ -232,17 +347,20 @@ impl ProgrammerUi {
         self.line.clear();
     }
 
-    fn submit(&mut self) {
+    /// Log and return the entered command line (None if blank).
+    fn submit(&mut self) -> Option<String> {
         let cmd = self.line.trim().to_string();
-        if !cmd.is_empty() {
-            self.log.push(format!("> {}", cmd));
-        }
         self.line.clear();
+        if cmd.is_empty() {
+            return None;
+        }
+        self.log.push(format!("> {}", cmd));
+        Some(cmd)
     }
 }
 
 /// Minimal rotary knob (drag up/down to change).
-fn knob_u8(ui: &mut egui::Ui, id: egui::Id, value: &mut u8, enabled: bool) {
+fn knob_u8(ui: &mut egui::Ui, id: egui::Id, value: &mut u8, enabled: bool) -> egui::Response {
     let size = egui::vec2(56.0, 56.0);
     let (rect, resp) = ui.allocate_exact_size(size, egui::Sense::drag());
 
@@ -284,13 +402,10 @@ fn knob_u8(ui: &mut egui::Ui, id: egui::Id, value: &mut u8, enabled: bool) {
         egui::Stroke::new(2.0, egui::Color32::from_rgb(220, 220, 220)),
     );
 
-    // tooltip
-    if resp.hovered() {
-        resp.on_hover_text(format!("{}", *value));
-    }
-
     // keep id "used" (prevents warnings if you later expand)
     let _ = id;
+
+    resp.on_hover_text(format!("{}", *value))
 }
 
 struct GridApp {
@@ -299,15 +414,192 @@ struct GridApp {
     layout: Layout,
     selected_id: Option<u32>,
     drag: DragState,
-    dirty: bool,
+    layout_dirty: bool,
+    /// Show edited since the last frame; handed to `show_saver`.
+    show_dirty: bool,
+    /// Writes the show off the UI thread, per `Show::save_policy`.
+    show_saver: console_core::Autosave,
+    /// Time (s) of the last layout autosave check that wrote something.
+    last_autosave: f64,
+    /// Window title last sent, to send it only when it changes.
+    title: String,
 
     selected_cell: Option<(u32, i32, i32)>, // (container_id, cx, cy)
+    multi: MultiSelection,
+    quick_record: Option<QuickRecord>,
 
     next_cue: u32,
     next_group: u32,
     next_palette: u32,
 
     programmer_ui: ProgrammerUi,
+
+    rt: console_core::Runtime,
+    /// A guarded Go is waiting for confirmation.
+    go_confirm: bool,
+    /// Top-bar crossfader shows separate up and down faders.
+    xfade_split: bool,
+    /// Countdowns to warned cues, flashed in the top bar.
+    cue_warner: console_core::CueWarner,
+    quick_open: Option<QuickOpen>,
+    /// Last command-line entry; an empty Macros cell binds to it when clicked.
+    last_command: Option<String>,
+    /// Permission role from LIGHTCONSOLE_ROLE (full by default).
+    role: console_core::Role,
+    timeline: Timeline,
+    /// Show file came from a newer core; shown as a banner until dismissed.
+    compat_warning: Option<String>,
+    show_about: bool,
+    /// Show statistics; Some = show info window open.
+    show_info: Option<console_core::ShowStats>,
+    /// Lint findings; Some = problems window open.
+    problems: Option<Vec<console_core::Finding>>,
+    /// RDM channel check; Some = window open.
+    channel_check: Option<ChannelCheck>,
+    /// sACN input, started when the show merges any (configured from the CLI).
+    dmx_in: Option<console_core::SacnReceiver>,
+    /// MIDI control surface; the map is saved next to the show.
+    midi_map: console_core::MidiMap,
+    midi: Option<console_core::MidiInput>,
+    /// Some = MIDI window open.
+    midi_ui: Option<MidiUi>,
+    /// USB HID jog wheel or keypad, mapped like the MIDI surface.
+    hid_map: console_core::HidMap,
+    hid: Option<console_core::HidInput>,
+    hid_decoder: console_core::HidDecoder,
+    /// Some = HID window open.
+    hid_ui: Option<HidUi>,
+    show_fixture_sheet: bool,
+    /// Fixture whose pan/tilt limits are being edited.
+    limits_edit: Option<u32>,
+    show_masters: bool,
+    /// Time (s) for the timed GM moves in the masters window.
+    gm_fade_secs: f32,
+    show_agenda: bool,
+    show_programmer: bool,
+    /// Panic button armed; a second click fires it.
+    panic_armed: bool,
+    /// Deselected fixtures awaiting keep/release (prompt selection policy).
+    pending_release: Vec<u32>,
+    /// Some = hookup import wizard open.
+    hookup: Option<HookupWizard>,
+    /// Some = patch window open, sorted this way.
+    patch_view: Option<PatchSort>,
+    /// Some = outputs window open.
+    outputs_ui: Option<OutputsUi>,
+    /// Some = cue sheet open, with its thumbnails.
+    cue_sheet: Option<CueSheet>,
+    /// DMX output over the show's routing, when running.
+    output: Option<console_core::OutputThread>,
+    /// History kept by `framelog on`, reapplied when the output restarts.
+    frame_log_keep: Option<u64>,
+    /// Connect/disconnect notices with the time (s) they expire.
+    toasts: Vec<(String, f64)>,
+    /// Notices new operator-log notes posted by other clients.
+    watcher: console_core::ShowWatcher,
+    /// Some(draft) = operator log window open.
+    log_draft: Option<String>,
+}
+
+/// Outputs window: the "add destination" row.
+#[derive(Debug, Default)]
+struct OutputsUi {
+    name: String,
+    /// Add an sACN destination instead of Art-Net.
+    sacn: bool,
+    target: String,
+}
+
+/// Cue sheet window: playback A's cues with plot thumbnails.
+struct CueSheet {
+    cuelist: String,
+    rows: Vec<(u32, String, egui::TextureHandle)>,
+}
+
+/// Timeline view state.
+#[derive(Debug)]
+struct Timeline {
+    /// Playhead position in frames.
+    frame: u32,
+    /// (runtime ms, frame) when play was pressed; None = stopped.
+    playing: Option<(u64, u32)>,
+    px_per_sec: f32,
+    /// Frame at the left edge.
+    scroll: f32,
+    /// Event being dragged to a new time.
+    dragging: Option<usize>,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            frame: 0,
+            playing: None,
+            px_per_sec: 40.0,
+            scroll: 0.0,
+            dragging: None,
+        }
+    }
+}
+
+/// Sort order of the patch window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum PatchSort {
+    #[default]
+    Id,
+    Address,
+    Type,
+}
+
+#[derive(Debug, Default)]
+struct ChannelCheck {
+    /// Discovery dump (JSON list of responders).
+    path: String,
+    report: Option<console_core::ChannelCheckReport>,
+}
+
+/// MIDI window state.
+#[derive(Debug, Default)]
+struct MidiUi {
+    device: String,
+    action: String,
+    /// Bound to the next control touched.
+    learning: Option<console_core::MidiAction>,
+}
+
+/// HID window state.
+#[derive(Debug, Default)]
+struct HidUi {
+    device: String,
+    keyboard: bool,
+    action: String,
+    /// Bound to the next key pressed or wheel turned.
+    learning: Option<console_core::MidiAction>,
+}
+
+/// CSV hookup import wizard state.
+struct HookupWizard {
+    path: String,
+    rows: Vec<Vec<String>>,
+    has_header: bool,
+    map: console_core::hookup::ColumnMap,
+}
+
+impl Default for HookupWizard {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            rows: Vec::new(),
+            has_header: true,
+            map: Default::default(),
+        }
+    }
+}
+
+/// Ctrl+K search popup.
+#[derive(Debug, Default)]
+struct QuickOpen {
+    query: String,
 }
 
 impl GridApp {
@@ -316,89 +608,3176 @@ impl GridApp {
         for c in &mut layout.containers {
             c.ensure_cells_len();
         }
+
+        let mut programmer_ui = ProgrammerUi {
+            bank: EncoderBank::Color,
+            ..Default::default()
+        };
+        let (show, compat_warning) = match console_core::Show::load_json_file_stamped(&show_path) {
+            Ok((s, stamp)) => (s, stamp.and_then(|s| s.compatibility_warning())),
+            Err(e) => {
+                programmer_ui
+                    .log
+                    .push(format!("Failed to load show: {e:#}"));
+                (console_core::Show::new("(no show)"), None)
+            }
+        };
+
+        let role = match std::env::var("LIGHTCONSOLE_ROLE") {
+            Ok(r) => r.parse().unwrap_or_else(|e| {
+                programmer_ui.log.push(format!("{e:#}; using view-only"));
+                console_core::Role::ViewOnly
+            }),
+            Err(_) => console_core::Role::Full,
+        };
+
+        let watcher = console_core::ShowWatcher::new(&show_path);
+        let show_saver = console_core::Autosave::new(&show_path);
+        let mut rt = console_core::Runtime::new(show);
+        rt.playbacks["a"].go_guard = console_core::GoGuard {
+            debounce_ms: 500,
+            confirm_early_fade: true,
+        };
+        let sources = rt.show.dmx_input_universes();
+        let dmx_in = if sources.is_empty() {
+            None
+        } else {
+            console_core::SacnReceiver::new(None, &sources)
+                .map_err(|e| programmer_ui.log.push(format!("sACN input: {e:#}")))
+                .ok()
+        };
+        let midi_map = console_core::MidiMap::load(&console_core::MidiMap::path_for(&show_path))
+            .unwrap_or_else(|e| {
+                programmer_ui.log.push(format!("{e:#}"));
+                Default::default()
+            });
+        let midi = midi_map.device.as_ref().and_then(|dev| {
+            console_core::MidiInput::open(dev)
+                .map_err(|e| programmer_ui.log.push(format!("MIDI: {e:#}")))
+                .ok()
+        });
+        let hid_map = console_core::HidMap::load(&console_core::HidMap::path_for(&show_path))
+            .unwrap_or_else(|e| {
+                programmer_ui.log.push(format!("{e:#}"));
+                Default::default()
+            });
+        let hid = hid_map.device.as_ref().and_then(|dev| {
+            console_core::HidInput::open(dev)
+                .map_err(|e| programmer_ui.log.push(format!("HID: {e:#}")))
+                .ok()
+        });
+
         Self {
             show_path,
             layout_path,
             layout,
             selected_id: None,
             drag: DragState::None,
-            dirty: false,
+            layout_dirty: false,
+            show_dirty: false,
+            show_saver,
+            last_autosave: 0.0,
+            title: TITLE.to_string(),
             selected_cell: None,
+            multi: MultiSelection::default(),
+            quick_record: None,
             next_cue: 1,
             next_group: 1,
             next_palette: 1,
-            programmer_ui: ProgrammerUi {
-                bank: EncoderBank::Color,
-                ..Default::default()
-            },
+            programmer_ui,
+            rt,
+            go_confirm: false,
+            xfade_split: false,
+            cue_warner: console_core::CueWarner::default(),
+            quick_open: None,
+            last_command: None,
+            role,
+            timeline: Timeline::default(),
+            compat_warning,
+            show_about: false,
+            show_info: None,
+            problems: None,
+            channel_check: None,
+            dmx_in,
+            midi_map,
+            midi,
+            midi_ui: None,
+            hid_map,
+            hid,
+            hid_decoder: Default::default(),
+            hid_ui: None,
+            show_fixture_sheet: false,
+            limits_edit: None,
+            show_masters: false,
+            gm_fade_secs: 5.0,
+            show_agenda: false,
+            show_programmer: false,
+            panic_armed: false,
+            pending_release: Vec::new(),
+            hookup: None,
+            patch_view: None,
+            outputs_ui: None,
+            cue_sheet: None,
+            output: None,
+            frame_log_keep: None,
+            toasts: Vec::new(),
+            watcher,
+            log_draft: None,
         }
     }
 
-    fn save_layout(&mut self) {
-        if let Err(e) = save_layout(&self.layout_path, &self.layout) {
-            eprintln!("Failed to save layout: {e}");
+    /// Execute a command-line entry. Handles selection syntax:
+    /// `<id>`, `<a> thru <b>` (ids may be joined with `+`), `group <cell#|name>`, `clear`.
+    fn run_command(&mut self, cmd: &str) {
+        // macro cells store what was typed, so they follow alias edits
+        let typed = cmd.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !typed.is_empty() {
+            self.last_command = Some(typed);
+        }
+        let cmd = match console_core::alias::expand(&self.rt.show.aliases, cmd) {
+            Ok(cmd) => cmd,
+            Err(e) => {
+                self.programmer_ui.log.push(format!("{e:#}"));
+                return;
+            }
+        };
+        let cmd = cmd.as_str();
+        let parts: Vec<&str> = cmd.split_whitespace().collect();
+        // bare selections like `1 thru 5` or `area:FOH` are programming
+        let word = parts.first().copied().unwrap_or_default();
+        let class_line = if word.parse::<u32>().is_ok() || word.contains(':') {
+            "select"
+        } else if word.starts_with('@') {
+            "@"
         } else {
-            self.dirty = false;
+            cmd
+        };
+        if let Err(e) = self.role.check(class_line) {
+            self.programmer_ui.log.push(format!("{e:#}"));
+            return;
+        }
+        if let Some(parsed) = console_core::Command::parse(cmd) {
+            let ctx = console_core::CommandContext::default();
+            match parsed.and_then(|c| console_core::commands::execute(c, &mut self.rt, &ctx)) {
+                Ok(res) => {
+                    self.programmer_ui.log.extend(res.message);
+                    self.show_dirty |= res.show_changed;
+                }
+                Err(e) => self.programmer_ui.log.push(format!("{e:#}")),
+            }
+            return;
+        }
+        match parts.as_slice() {
+            ["say", ..] if parts.len() > 1 => self.post_note(&parts[1..].join(" ")),
+            ["apply", "palette", name @ ..] if !name.is_empty() => {
+                self.apply_palette(&name.join(" "))
+            }
+            ["autosave"] => self
+                .programmer_ui
+                .log
+                .push(format!("Autosave: {}", self.rt.show.save_policy)),
+            ["autosave", policy @ ..] => match policy.join(" ").parse() {
+                Ok(policy) => {
+                    self.rt.show.save_policy = policy;
+                    // the setting itself is written whatever it says
+                    self.save_show();
+                    self.programmer_ui.log.push(format!("Autosave: {policy}"));
+                }
+                Err(e) => self.programmer_ui.log.push(format!("{e:#}")),
+            },
+            ["bind", key, command @ ..] if !command.is_empty() => {
+                let Some(key) = egui::Key::from_name(key) else {
+                    self.programmer_ui.log.push(format!("Unknown key '{key}'"));
+                    return;
+                };
+                let command = command.join(" ");
+                self.programmer_ui
+                    .log
+                    .push(format!("{} runs '{command}'", key.name()));
+                self.layout.keys.insert(key.name().to_string(), command);
+                self.layout_dirty = true;
+            }
+            ["unbind", key] => {
+                let name = egui::Key::from_name(key).map_or(*key, |k| k.name());
+                if self.layout.keys.remove(name).is_some() {
+                    self.layout_dirty = true;
+                    self.programmer_ui.log.push(format!("{name} unbound"));
+                } else {
+                    self.programmer_ui.log.push(format!("{name} isn't bound"));
+                }
+            }
+            ["go"] => self.press_go(),
+            ["balance", rest @ ..] => self.balance(cmd, rest),
+            ["release", "clear", "a"] => {
+                self.rt.playbacks["a"].clear_released();
+                self.programmer_ui
+                    .log
+                    .push("Released fixtures restored".into());
+            }
+            ["release", args @ .., "from", "a"] if !args.is_empty() => {
+                let (sel, params) = match args.last().map(|p| p.parse()) {
+                    Some(Ok(params)) => (&args[..args.len() - 1], params),
+                    _ => (args, console_core::ParamFilter::ALL),
+                };
+                // `group 3` is the group at cell 3, as when selecting
+                let ids = match sel {
+                    ["group", which] => self
+                        .group_by_cell_or_name(which)
+                        .and_then(|name| self.rt.show.group_ids(&name)),
+                    _ => self.rt.show.parse_selection(sel),
+                };
+                match ids {
+                    Some(ids) => {
+                        self.programmer_ui.log.push(format!(
+                            "Released {} fixture(s) from playback A until a cue sets them",
+                            ids.len()
+                        ));
+                        self.rt.playbacks["a"].release_fixtures(ids, params);
+                    }
+                    None => self
+                        .programmer_ui
+                        .log
+                        .push(format!("No fixtures in '{}'", sel.join(" "))),
+                }
+            }
+            ["framelog", "on"] => {
+                self.frame_log_keep = Some(console_core::framelog::DEFAULT_KEEP_MS);
+                self.programmer_ui.log.push("Frame log on".into());
+            }
+            ["framelog", "off"] => {
+                self.frame_log_keep = None;
+                if let Some(out) = &self.output {
+                    out.frame_log().disable();
+                }
+                self.programmer_ui.log.push("Frame log off".into());
+            }
+            ["dumpframes", rest @ ..] if rest.len() <= 1 => {
+                let window = match rest.first() {
+                    None => Some(5.0),
+                    Some(s) => s.strip_suffix('s').unwrap_or(s).parse::<f64>().ok(),
+                };
+                let Some(window) = window.filter(|w| *w >= 0.0) else {
+                    self.programmer_ui
+                        .log
+                        .push("Usage: dumpframes [<n>s]".into());
+                    return;
+                };
+                let Some(out) = self.output.as_ref().filter(|o| o.frame_log().is_enabled()) else {
+                    self.programmer_ui
+                        .log
+                        .push("Frame log is off (framelog on, with output running)".into());
+                    return;
+                };
+                let frames = out.frame_log().dump((window * 1000.0) as u64);
+                if frames.is_empty() {
+                    self.programmer_ui.log.push("No output changes".into());
+                }
+                let lines =
+                    console_core::framelog::dump_lines(&frames, self.rt.show.address_format);
+                self.programmer_ui.log.extend(lines);
+            }
+            ["confirm", num, on @ ("on" | "off")] => {
+                let Ok(num) = num.parse::<u32>() else {
+                    self.programmer_ui
+                        .log
+                        .push("Usage: confirm <cue_number> on|off".into());
+                    return;
+                };
+                let list = self.rt.playbacks["a"].cuelist.clone();
+                let Some(cl) = self.rt.show.cue_lists.get_mut(&list) else {
+                    return;
+                };
+                if let Err(e) = cl.check_unlocked(num, num) {
+                    self.programmer_ui.log.push(format!("{e:#}"));
+                    return;
+                }
+                let Some(cue) = cl.cues.get_mut(&num) else {
+                    self.programmer_ui.log.push(format!("Cue {num} not found"));
+                    return;
+                };
+                cue.confirm = *on == "on";
+                self.show_dirty = true;
+                self.programmer_ui
+                    .log
+                    .push(format!("Cue {num} confirm {on}"));
+            }
+            ["link", num, rest @ ..] => {
+                let usage = "Usage: link <cue_number> <target> [<times>]|off";
+                let link = match rest {
+                    ["off"] => Some(None),
+                    [target] => target.parse::<u32>().ok().map(|t| Some((t, None))),
+                    [target, times] => match (target.parse::<u32>(), times.parse::<u32>()) {
+                        (Ok(t), Ok(n)) => Some(Some((t, Some(n)))),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                let (Ok(num), Some(link)) = (num.parse::<u32>(), link) else {
+                    self.programmer_ui.log.push(usage.into());
+                    return;
+                };
+                let list = self.rt.playbacks["a"].cuelist.clone();
+                let Some(cl) = self.rt.show.cue_lists.get_mut(&list) else {
+                    return;
+                };
+                if let Err(e) = cl.check_unlocked(num, num) {
+                    self.programmer_ui.log.push(format!("{e:#}"));
+                    return;
+                }
+                let Some(cue) = cl.cues.get_mut(&num) else {
+                    self.programmer_ui.log.push(format!("Cue {num} not found"));
+                    return;
+                };
+                cue.link = link.map(|(t, _)| t);
+                cue.link_loops = link.and_then(|(_, n)| n);
+                self.show_dirty = true;
+                let msg = match link {
+                    Some((t, Some(n))) => format!("Cue {num} links to cue {t}, {n} time(s)"),
+                    Some((t, None)) => format!("Cue {num} links to cue {t} until a goto"),
+                    None => format!("Cue {num} link off"),
+                };
+                self.programmer_ui.log.push(msg);
+            }
+            ["warn", num, rest @ ..] => {
+                let usage = "Usage: warn <cue_number> <seconds> [beep]|off";
+                let secs = |s: &str| {
+                    s.parse::<f32>()
+                        .ok()
+                        .filter(|s| *s >= 0.0)
+                        .map(|s| (s * 1000.0).round() as u32)
+                };
+                // outer None: doesn't parse; inner None: warning off
+                let warn = match rest {
+                    ["off"] => Some(None),
+                    [s] => secs(s).map(|ms| Some((ms, false))),
+                    [s, "beep"] => secs(s).map(|ms| Some((ms, true))),
+                    _ => None,
+                };
+                let (Ok(num), Some(warn)) = (num.parse::<u32>(), warn) else {
+                    self.programmer_ui.log.push(usage.into());
+                    return;
+                };
+                let list = self.rt.playbacks["a"].cuelist.clone();
+                let Some(cl) = self.rt.show.cue_lists.get_mut(&list) else {
+                    return;
+                };
+                if let Err(e) = cl.check_unlocked(num, num) {
+                    self.programmer_ui.log.push(format!("{e:#}"));
+                    return;
+                }
+                let Some(cue) = cl.cues.get_mut(&num) else {
+                    self.programmer_ui.log.push(format!("Cue {num} not found"));
+                    return;
+                };
+                cue.warn = warn.map(|(lead_ms, beep)| console_core::CueWarning { lead_ms, beep });
+                self.show_dirty = true;
+                let msg = match warn {
+                    Some((ms, beep)) => format!(
+                        "Cue {num} warns {} before it fires{}",
+                        fmt_secs(ms),
+                        if beep { ", with a beep" } else { "" }
+                    ),
+                    None => format!("Cue {num} warning off"),
+                };
+                self.programmer_ui.log.push(msg);
+            }
+            ["disarm"] => self.rt.playbacks["a"].disarm(),
+            ["undo"] => match self.rt.show.undo() {
+                Some(label) => {
+                    self.show_dirty = true;
+                    self.programmer_ui.log.push(format!("Undid '{label}'"));
+                }
+                None => self.programmer_ui.log.push("Nothing to undo".into()),
+            },
+            ["back"] => match self.rt.playbacks["a"].back(&self.rt.show) {
+                Ok(cur) => self
+                    .programmer_ui
+                    .log
+                    .push(format!("Playback A back to cue {cur:?}")),
+                Err(e) => self.programmer_ui.log.push(format!("{e:#}")),
+            },
+            ["pause"] => {
+                let msg = if self.rt.playbacks["a"].toggle_pause() {
+                    "Playback A paused"
+                } else {
+                    "Playback A running"
+                };
+                self.programmer_ui.log.push(msg.into());
+            }
+            ["goto", num, rest @ ..] => {
+                let usage = "Usage: goto <cue> [time <seconds>|manual|blind]";
+                let Ok(num) = num.parse::<u32>() else {
+                    self.programmer_ui.log.push(usage.into());
+                    return;
+                };
+                let show = &self.rt.show;
+                let pb = &mut self.rt.playbacks["a"];
+                let res = match rest {
+                    [] => pb.goto(show, num),
+                    ["time", secs] => match secs.parse::<f32>() {
+                        Ok(secs) => pb.goto_timed(show, num, Some((secs.max(0.0) * 1000.0) as u32)),
+                        Err(_) => {
+                            self.programmer_ui.log.push(usage.into());
+                            return;
+                        }
+                    },
+                    ["manual"] => pb.start_manual_to(show, num),
+                    ["blind"] => pb.goto_blind(show, num),
+                    _ => {
+                        self.programmer_ui.log.push(usage.into());
+                        return;
+                    }
+                };
+                match res {
+                    Ok(()) => self
+                        .programmer_ui
+                        .log
+                        .push(format!("Playback A at cue {num}")),
+                    Err(e) => self.programmer_ui.log.push(format!("{e:#}")),
+                }
+            }
+            ["dmx", "clear"] => {
+                self.rt.clear_dmx();
+                self.programmer_ui.log.push("DMX tester cleared".into());
+            }
+            ["dmx", rest @ ..] => {
+                let args = rest.concat();
+                let parsed = args.split_once('@').and_then(|(target, value)| {
+                    let (u, addr) = console_core::parse_address(target).ok()?;
+                    Some((u, addr, value.parse().ok()?))
+                });
+                let Some((u, addr, v)) = parsed else {
+                    self.programmer_ui
+                        .log
+                        .push("Usage: dmx <universe>/<address> @ <value>".into());
+                    return;
+                };
+                match self.rt.set_dmx(u, addr, v) {
+                    Ok(()) => self.programmer_ui.log.push(format!(
+                        "DMX tester: {} = {v}",
+                        self.rt.show.address_format.format(u, addr)
+                    )),
+                    Err(e) => self.programmer_ui.log.push(format!("{e:#}")),
+                }
+            }
+            ["at-address", addr, rest @ ..] => {
+                let (u, a) = match console_core::parse_address(addr) {
+                    Ok(ua) => ua,
+                    Err(e) => {
+                        self.programmer_ui.log.push(format!("{e:#}"));
+                        return;
+                    }
+                };
+                let shown = self.rt.show.address_format.format(u, a);
+                let Some((f, idx, ch)) = self.rt.show.patch.fixture_at(u, a) else {
+                    self.programmer_ui
+                        .log
+                        .push(format!("{shown}: nothing patched"));
+                    return;
+                };
+                let id = f.fixture_id;
+                let line = format!(
+                    "{shown}: fixture #{id} '{}', channel {} '{}'",
+                    f.name,
+                    idx + 1,
+                    ch.name
+                );
+                self.programmer_ui.log.push(line);
+                if rest == ["select"] {
+                    self.change_selection([id].into_iter().collect());
+                }
+            }
+            ["group", which] => match self.group_by_cell_or_name(which) {
+                Some(name) => self.select_group(&name),
+                None => self.programmer_ui.log.push(format!("No group '{which}'")),
+            },
+            _ => match self.rt.show.parse_programmer_line(cmd) {
+                Ok(console_core::LineStatus::Complete(line)) => self.apply_programmer_line(line),
+                Ok(console_core::LineStatus::Incomplete(next)) => self
+                    .programmer_ui
+                    .log
+                    .push(format!("Incomplete: needs {next}")),
+                Err(e) if word == "select" || word == "@" => {
+                    self.programmer_ui.log.push(format!("{e:#}"))
+                }
+                Err(_) => self
+                    .programmer_ui
+                    .log
+                    .push(format!("Unknown command '{cmd}'")),
+            },
         }
     }
 
-    fn add_container_fill_row(&mut self, kind: ContainerKind) {
-        // default size
-        let w = self.layout.cols;
-        let h = MIN_H;
+    /// `1 thru 5 @ 50`: select, then set the level.
+    fn apply_programmer_line(&mut self, line: console_core::ProgrammerLine) {
+        if let Some(ids) = line.selection {
+            self.change_selection(ids);
+        }
+        if let Some(pct) = line.level {
+            self.rt.programmer.set_intensity_percent(pct);
+            self.programmer_ui.log.push(format!("@ {pct}%"));
+        }
+    }
 
-        // find first y where a full-row container of height h fits without overlap
-        let mut placed: Option<i32> = None;
-        'outer: for y in 0..=(self.layout.rows - h) {
-            let candidate = (0, y, w, h);
-            for c in &self.layout.containers {
-                if rects_intersect(candidate, c.rect_cells()) {
-                    continue 'outer;
+    /// Advance the playhead while the timeline plays, firing crossed events.
+    fn tick_timeline(&mut self) {
+        let Some((start_ms, start_frame)) = self.timeline.playing else {
+            return;
+        };
+        let fps = self.rt.show.events.fps as u64;
+        let elapsed = self.rt.now_ms().saturating_sub(start_ms);
+        let to = start_frame + (elapsed * fps / 1000) as u32;
+
+        let due: Vec<String> = self
+            .rt
+            .show
+            .events
+            .due(self.timeline.frame, to)
+            .map(|e| e.command.clone())
+            .collect();
+        self.timeline.frame = to;
+        for cmd in due {
+            self.programmer_ui.log.push(format!("[tc] {cmd}"));
+            self.run_command(&cmd);
+        }
+    }
+
+    /// Marker text: cue label for `goto N`, otherwise the event label/command.
+    fn event_title(&self, e: &console_core::TimedEvent) -> String {
+        if !e.label.is_empty() {
+            return e.label.clone();
+        }
+        if let Some(num) = e.command.strip_prefix("goto ")
+            && let Ok(num) = num.trim().parse::<u32>()
+            && let Some(cue) = self
+                .rt
+                .show
+                .cue_lists
+                .get(&self.rt.playbacks["a"].cuelist)
+                .and_then(|l| l.cues.get(&num))
+        {
+            return format!("Q{num} {}", cue.label);
+        }
+        e.command.clone()
+    }
+
+    /// Timecode timeline: events against timecode with a playhead.
+    /// Drag a marker to retime it, drag the background to scroll, click to
+    /// locate, Ctrl+wheel to zoom.
+    fn timeline_panel(&mut self, ui: &mut egui::Ui) {
+        let fps = self.rt.show.events.fps as u32;
+        ui.horizontal(|ui| {
+            let playing = self.timeline.playing.is_some();
+            if ui.button(if playing { "⏸" } else { "▶" }).clicked() {
+                self.timeline.playing = if playing {
+                    None
+                } else {
+                    Some((self.rt.now_ms(), self.timeline.frame))
+                };
+            }
+            if ui.button("⏮").clicked() {
+                self.timeline.frame = 0;
+                self.timeline.scroll = 0.0;
+                if self.timeline.playing.is_some() {
+                    self.timeline.playing = Some((self.rt.now_ms(), 0));
                 }
             }
-            placed = Some(y);
-            break;
+            ui.monospace(
+                self.rt
+                    .show
+                    .events
+                    .timecode(self.timeline.frame)
+                    .to_string(),
+            );
+            ui.label(format!("{fps} fps"));
+            ui.separator();
+            ui.add(
+                egui::Slider::new(&mut self.timeline.px_per_sec, 5.0..=400.0)
+                    .logarithmic(true)
+                    .text("zoom"),
+            );
+        });
+
+        let (rect, resp) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), 44.0),
+            egui::Sense::click_and_drag(),
+        );
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 4.0, egui::Color32::from_rgb(28, 29, 32));
+
+        if resp.hovered() {
+            let zoom = ui.input(|i| i.zoom_delta());
+            if zoom != 1.0 {
+                self.timeline.px_per_sec = (self.timeline.px_per_sec * zoom).clamp(5.0, 400.0);
+            }
         }
 
-        let Some(y) = placed else {
-            // No space on this page
-            return;
+        let px_per_frame = self.timeline.px_per_sec / fps.max(1) as f32;
+        let scroll = self.timeline.scroll;
+        let x_of = |frame: f32| rect.left() + (frame - scroll) * px_per_frame;
+        let frame_at = |x: f32| ((x - rect.left()) / px_per_frame + scroll).round().max(0.0) as u32;
+
+        // second ticks
+        let first_sec = (scroll / fps.max(1) as f32) as u32;
+        let visible_secs = (rect.width() / self.timeline.px_per_sec) as u32 + 1;
+        let step = (40.0 / self.timeline.px_per_sec).ceil().max(1.0) as u32;
+        for sec in (first_sec..=first_sec + visible_secs).filter(|s| s % step == 0) {
+            let x = x_of((sec * fps) as f32);
+            painter.line_segment(
+                [
+                    egui::pos2(x, rect.bottom() - 6.0),
+                    egui::pos2(x, rect.bottom()),
+                ],
+                egui::Stroke::new(1.0, egui::Color32::from_gray(90)),
+            );
+        }
+
+        // start or continue a drag; moving events edits the show
+        if resp.drag_started()
+            && self.role.allows(console_core::CommandClass::ShowEdit)
+            && let Some(p) = resp.interact_pointer_pos()
+        {
+            self.timeline.dragging = self
+                .rt
+                .show
+                .events
+                .events
+                .iter()
+                .position(|e| (x_of(e.frame as f32) - p.x).abs() < 5.0);
+        }
+        if resp.dragged() && self.timeline.dragging.is_none() {
+            self.timeline.scroll =
+                (self.timeline.scroll - resp.drag_delta().x / px_per_frame).max(0.0);
+        }
+        let drag_frame = match (self.timeline.dragging, resp.interact_pointer_pos()) {
+            (Some(idx), Some(p)) => Some((idx, frame_at(p.x))),
+            _ => None,
+        };
+        if resp.drag_stopped()
+            && let Some((idx, frame)) = drag_frame
+        {
+            match self.rt.show.events.retime(idx, frame) {
+                Ok(_) => {
+                    let tc = self.rt.show.events.timecode(frame);
+                    self.programmer_ui.log.push(format!("Event moved to {tc}"));
+                    self.show_dirty = true;
+                }
+                Err(e) => self.programmer_ui.log.push(format!("{e:#}")),
+            }
+        } else if resp.clicked()
+            && let Some(p) = resp.interact_pointer_pos()
+        {
+            self.timeline.frame = frame_at(p.x);
+            if self.timeline.playing.is_some() {
+                self.timeline.playing = Some((self.rt.now_ms(), self.timeline.frame));
+            }
+        }
+
+        // indices changed on retime; draw the stored times from here on
+        let drag_frame = if resp.drag_stopped() {
+            self.timeline.dragging = None;
+            None
+        } else {
+            drag_frame
         };
 
-        let id = self.layout.next_id;
-        self.layout.next_id += 1;
+        let mut hovered = None;
+        for (i, e) in self.rt.show.events.events.iter().enumerate() {
+            let frame = match drag_frame {
+                Some((idx, f)) if idx == i => f,
+                _ => e.frame,
+            };
+            let x = x_of(frame as f32);
+            if !(rect.left() - 200.0..=rect.right()).contains(&x) {
+                continue;
+            }
+            painter.line_segment(
+                [
+                    egui::pos2(x, rect.top() + 4.0),
+                    egui::pos2(x, rect.bottom() - 8.0),
+                ],
+                egui::Stroke::new(2.0, egui::Color32::from_rgb(220, 190, 40)),
+            );
+            painter.text(
+                egui::pos2(x + 3.0, rect.top() + 4.0),
+                egui::Align2::LEFT_TOP,
+                self.event_title(e),
+                egui::FontId::proportional(11.0),
+                egui::Color32::from_rgb(220, 220, 220),
+            );
+            if resp.hover_pos().is_some_and(|p| (p.x - x).abs() < 5.0) {
+                let tc = self.rt.show.events.timecode(frame);
+                hovered = Some(format!("{tc} | {}", e.command));
+            }
+        }
 
-        let title = kind.title().to_string();
+        // playhead
+        let x = x_of(self.timeline.frame as f32);
+        if rect.x_range().contains(x) {
+            painter.line_segment(
+                [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+                egui::Stroke::new(2.0, egui::Color32::from_rgb(220, 60, 60)),
+            );
+        }
 
-        let mut c = Container {
-            id,
-            kind,
-            title,
-            x: 0,
-            y,
-            w,
-            h,
-            cells: vec![None; (w * h) as usize],
+        if let Some(text) = hovered {
+            resp.on_hover_text(text);
+        }
+    }
+
+    /// Guarded Go on playback A; a pending confirmation is handled in the top bar.
+    fn press_go(&mut self) {
+        match self.rt.playbacks["a"].press_go(&self.rt.show) {
+            Ok(console_core::GoOutcome::Went(cur)) => {
+                self.go_confirm = false;
+                self.programmer_ui
+                    .log
+                    .push(format!("Playback A at cue {cur:?}"));
+            }
+            Ok(console_core::GoOutcome::Ignored) => {}
+            Ok(console_core::GoOutcome::NeedsConfirm) => self.go_confirm = true,
+            Ok(console_core::GoOutcome::CueNeedsConfirm(n)) => self
+                .programmer_ui
+                .log
+                .push(format!("Cue {n} armed: Go again to fire it")),
+            Err(e) => self.programmer_ui.log.push(format!("{e:#}")),
+        }
+    }
+
+    /// `balance`: log how the fixtures sit across the cues, or scale them
+    /// with an undo checkpoint.
+    fn balance(&mut self, line: &str, parts: &[&str]) {
+        let log = &mut self.programmer_ui.log;
+        let cmd = match console_core::BalanceCommand::parse(&self.rt.show, parts) {
+            Ok(c) => c,
+            Err(e) => return log.push(format!("{e:#}")),
         };
-        c.ensure_cells_len();
-        self.layout.containers.push(c);
+        let list = self.rt.playbacks["a"].cuelist.clone();
+        let Some(cl) = self.rt.show.cue_lists.get(&list) else {
+            return log.push(format!("Unknown cuelist '{list}'"));
+        };
+        let pct = |v: u8| (v as u32 * 100 + 127) / 255;
+        let Some(factor) = cmd.scale else {
+            let report = cl.intensity_balance(&cmd.fixtures, cmd.first, cmd.last);
+            for &f in &report.fixtures {
+                log.push(match report.stats(f) {
+                    Some((min, max, avg)) => format!(
+                        "Fixture {f}: {}-{}%, avg {}% over {} cue(s)",
+                        pct(min),
+                        pct(max),
+                        pct(avg),
+                        report.cues.len()
+                    ),
+                    None => format!("Fixture {f}: no intensity"),
+                });
+            }
+            return;
+        };
+        if let Err(e) = cl.check_unlocked(cmd.first, cmd.last) {
+            return log.push(format!("{e:#}"));
+        }
+        self.rt.show.checkpoint(line.trim());
+        let author = console_core::meta::current_author();
+        let cl = self
+            .rt
+            .show
+            .cue_lists
+            .get_mut(&list)
+            .expect("checked above");
+        let touched = cl.scale_intensity(&cmd.fixtures, cmd.first, cmd.last, factor);
+        for num in &touched {
+            if let Some(c) = cl.cues.get_mut(num) {
+                c.meta.touch(author.as_deref());
+            }
+        }
+        if touched.is_empty() {
+            self.rt.show.undo();
+            log.push("Those fixtures have no intensity in that range".into());
+        } else {
+            self.show_dirty = true;
+            log.push(format!(
+                "Scaled {} fixture(s) in {} cue(s); 'undo' reverts",
+                cmd.fixtures.len(),
+                touched.len()
+            ));
+        }
+    }
+
+    /// Crossfader for playback A: follows a running fade, takes it over when
+    /// moved, and starts a manual fade to the next cue when nothing runs.
+    fn crossfader_ui(&mut self, ui: &mut egui::Ui) {
+        let pb = &mut self.rt.playbacks["a"];
+        let (mut up, mut down) = pb.crossfade_position().unwrap_or_default();
+        let before = (up, down);
+        let label = if self.xfade_split { "up" } else { "X-fade" };
+        ui.add(egui::Slider::new(&mut up, 0..=100).suffix("%").text(label));
+        if self.xfade_split {
+            ui.add(
+                egui::Slider::new(&mut down, 0..=100)
+                    .suffix("%")
+                    .text("down"),
+            );
+        } else {
+            down = up;
+        }
+        ui.checkbox(&mut self.xfade_split, "Split")
+            .on_hover_text("Separate faders for rising and falling intensities");
+        if (up, down) == before {
+            return;
+        }
+        if pb.crossfade_position().is_none() {
+            match pb.start_manual(&self.rt.show) {
+                Ok(Some(_)) => {}
+                Ok(None) => return,
+                Err(e) => {
+                    self.programmer_ui.log.push(format!("{e:#}"));
+                    return;
+                }
+            }
+        }
+        match pb.set_manual_split(up, down) {
+            Ok(Some(ms)) => self
+                .programmer_ui
+                .log
+                .push(format!("Crossfade done in {}", fmt_secs(ms))),
+            Ok(None) => {}
+            Err(e) => self.programmer_ui.log.push(format!("{e:#}")),
+        }
+    }
+
+    fn confirm_go(&mut self) {
+        self.go_confirm = false;
+        match self.rt.playbacks["a"].go_or_arm(&self.rt.show) {
+            Ok(console_core::GoOutcome::CueNeedsConfirm(n)) => self
+                .programmer_ui
+                .log
+                .push(format!("Cue {n} armed: Go again to fire it")),
+            Ok(_) => self.programmer_ui.log.push(format!(
+                "Playback A at cue {:?}",
+                self.rt.playbacks["a"].current
+            )),
+            Err(e) => self.programmer_ui.log.push(format!("{e:#}")),
+        }
+    }
+
+    /// Resolve `group 3` to the group bound at cell 3 of the active Groups container,
+    /// falling back to a group name.
+    fn group_by_cell_or_name(&self, which: &str) -> Option<String> {
+        if let Ok(n) = which.parse::<usize>() {
+            let active = self
+                .selected_id
+                .and_then(|id| self.layout.containers.iter().find(|c| c.id == id))
+                .filter(|c| c.kind == ContainerKind::Groups);
+            let container = active.or_else(|| {
+                self.layout
+                    .containers
+                    .iter()
+                    .find(|c| c.kind == ContainerKind::Groups)
+            })?;
+            return match container.cells.get(n)? {
+                Some(CellItem::Group { name }) => Some(name.clone()),
+                _ => None,
+            };
+        }
+        let show = &self.rt.show;
+        show.group_ids(which).map(|_| which.to_string())
+    }
+
+    fn select_group(&mut self, name: &str) {
+        let Some(ids) = self.rt.show.group_ids(name) else {
+            self.programmer_ui
+                .log
+                .push(format!("Unknown group '{name}'"));
+            return;
+        };
+        self.programmer_ui.log.push(format!("Group '{name}'"));
+        self.change_selection(ids);
+    }
+
+    /// What the keypad line will do, or what it still needs, while typing a
+    /// selection or level.
+    fn programmer_line_feedback(&self, ui: &mut egui::Ui) {
+        let line = self.programmer_ui.line.trim_start();
+        let keypad = line.starts_with(|c: char| c.is_ascii_digit() || c == '@')
+            || line.starts_with("group ");
+        if !keypad {
+            return;
+        }
+        match self.rt.show.parse_programmer_line(line) {
+            Ok(console_core::LineStatus::Complete(l)) => {
+                let sel = l.selection.map_or("selection".to_string(), |ids| {
+                    format!("{} fixture(s)", ids.len())
+                });
+                let level = l.level.map(|p| format!(" @ {p}%")).unwrap_or_default();
+                ui.weak(format!("→ {sel}{level}"));
+            }
+            Ok(console_core::LineStatus::Incomplete(next)) => {
+                ui.colored_label(egui::Color32::YELLOW, format!("… needs {next}"));
+            }
+            Err(e) => {
+                ui.colored_label(egui::Color32::LIGHT_RED, format!("{e:#}"));
+            }
+        }
+    }
+
+    /// Replace the selection through the programmer's selection policy.
+    fn change_selection(&mut self, ids: std::collections::BTreeSet<u32>) {
+        let pending = self.rt.programmer.set_selection(ids);
+        self.log_selection();
+        if !pending.is_empty() {
+            self.pending_release = pending;
+        }
+    }
+
+    /// Patch list (by id, address or type) with a footprint bar per universe:
+    /// one colour per fixture, red where fixtures collide.
+    fn patch_window(&mut self, ctx: &egui::Context) {
+        let Some(sort) = &mut self.patch_view else {
+            return;
+        };
+        let patch = &self.rt.show.patch;
+        let fmt = self.rt.show.address_format;
+        let mut open = true;
+
+        egui::Window::new("Patch")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                for (u, spans) in patch.footprints() {
+                    ui.label(format!("Universe {u}"));
+                    let (rect, resp) = ui.allocate_exact_size(
+                        egui::vec2(ui.available_width(), 18.0),
+                        egui::Sense::hover(),
+                    );
+                    let painter = ui.painter();
+                    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(35));
+                    let x = |addr: u16| rect.left() + rect.width() * (addr - 1) as f32 / 512.0;
+                    for (i, s) in spans.iter().enumerate() {
+                        let collides = spans
+                            .iter()
+                            .enumerate()
+                            .any(|(j, o)| i != j && o.first <= s.last && s.first <= o.last);
+                        let color = if collides {
+                            egui::Color32::from_rgb(210, 50, 50)
+                        } else {
+                            fixture_color(s.fixture_id)
+                        };
+                        let span = egui::Rect::from_x_y_ranges(
+                            x(s.first)..=x(s.last.min(512) + 1),
+                            rect.y_range(),
+                        );
+                        painter.rect_filled(span, 0.0, color);
+                    }
+                    if let Some(p) = resp.hover_pos() {
+                        let addr = 1 + ((p.x - rect.left()) / rect.width() * 512.0) as u16;
+                        let who: Vec<String> = spans
+                            .iter()
+                            .filter(|s| (s.first..=s.last).contains(&addr))
+                            .map(|s| format!("#{}", s.fixture_id))
+                            .collect();
+                        resp.on_hover_text(format!(
+                            "{} {}",
+                            fmt.format(u, addr.min(512)),
+                            if who.is_empty() {
+                                "free".to_string()
+                            } else {
+                                who.join(", ")
+                            }
+                        ));
+                    }
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Sort:");
+                    ui.selectable_value(sort, PatchSort::Id, "Id");
+                    ui.selectable_value(sort, PatchSort::Address, "Address");
+                    ui.selectable_value(sort, PatchSort::Type, "Type");
+                });
+                let fixtures = match sort {
+                    PatchSort::Id => patch.list_fixtures(),
+                    PatchSort::Address => patch.by_address(),
+                    PatchSort::Type => patch.by_type(),
+                };
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("patch_list").striped(true).show(ui, |ui| {
+                            ui.label("");
+                            ui.strong("Fixture");
+                            ui.strong("Type");
+                            ui.strong("Address");
+                            ui.end_row();
+                            for f in fixtures {
+                                let (rect, _) = ui.allocate_exact_size(
+                                    egui::vec2(10.0, 10.0),
+                                    egui::Sense::hover(),
+                                );
+                                ui.painter()
+                                    .rect_filled(rect, 2.0, fixture_color(f.fixture_id));
+                                ui.label(format!("{} {}", f.fixture_id, f.name));
+                                ui.label(&f.fixture_type);
+                                ui.label(fmt.format(f.universe, f.address));
+                                ui.end_row();
+                            }
+                        });
+                    });
+            });
+        if !open {
+            self.patch_view = None;
+        }
+    }
+
+    /// CSV hookup import: load a file, map its columns, check the preview,
+    /// then patch the rows that passed validation.
+    fn hookup_window(&mut self, ctx: &egui::Context) {
+        use console_core::hookup;
+
+        let Some(wiz) = &mut self.hookup else {
+            return;
+        };
+        let mut open = true;
+        let mut log = None;
+        let mut patch = false;
+        let preview = hookup::preview(&self.rt.show, &wiz.rows, &wiz.map, wiz.has_header);
+
+        egui::Window::new("Import hookup (CSV)")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("File:");
+                    ui.text_edit_singleline(&mut wiz.path);
+                    if ui.button("Load").clicked() {
+                        match std::fs::read_to_string(wiz.path.trim()) {
+                            Ok(text) => {
+                                wiz.rows = hookup::parse_csv(&text);
+                                if let Some(header) = wiz.rows.first() {
+                                    wiz.map = hookup::ColumnMap::guess(header);
+                                }
+                            }
+                            Err(e) => log = Some(format!("Can't read {}: {e}", wiz.path)),
+                        }
+                    }
+                });
+                if wiz.rows.is_empty() {
+                    ui.label("Columns: channel, fixture type, universe, address, position");
+                    return;
+                }
+
+                ui.checkbox(&mut wiz.has_header, "First row is a header");
+                let columns: Vec<String> = match wiz.rows.first() {
+                    Some(first) if wiz.has_header => first.clone(),
+                    Some(first) => (1..=first.len()).map(|i| format!("Column {i}")).collect(),
+                    None => Vec::new(),
+                };
+                let pick = |ui: &mut egui::Ui, label: &str, col: &mut Option<usize>| {
+                    let text = col
+                        .and_then(|c| columns.get(c).cloned())
+                        .unwrap_or_else(|| "(none)".into());
+                    egui::ComboBox::from_label(label)
+                        .selected_text(text)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(col, None, "(none)");
+                            for (i, name) in columns.iter().enumerate() {
+                                ui.selectable_value(col, Some(i), name);
+                            }
+                        });
+                };
+                egui::Grid::new("hookup_map").show(ui, |ui| {
+                    pick(ui, "Channel", &mut wiz.map.channel);
+                    pick(ui, "Fixture type", &mut wiz.map.fixture_type);
+                    ui.end_row();
+                    pick(ui, "Universe", &mut wiz.map.universe);
+                    pick(ui, "Address", &mut wiz.map.address);
+                    ui.end_row();
+                    pick(ui, "Position", &mut wiz.map.position);
+                    ui.end_row();
+                });
+
+                ui.separator();
+                let ok = preview.iter().filter(|r| r.is_ok()).count();
+                ui.label(format!("{ok} of {} rows can be patched", preview.len()));
+                egui::ScrollArea::vertical()
+                    .max_height(260.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("hookup_preview")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Line");
+                                ui.strong("Fixture");
+                                ui.strong("Status");
+                                ui.end_row();
+                                for row in &preview {
+                                    ui.label(row.line.to_string());
+                                    match &row.fixture {
+                                        Some(f) => ui.label(format!(
+                                            "#{} {} · {} @ {}.{}",
+                                            f.fixture_id,
+                                            f.name,
+                                            f.fixture_type,
+                                            f.universe,
+                                            f.address
+                                        )),
+                                        None => ui.label("–"),
+                                    };
+                                    if row.problems.is_empty() {
+                                        ui.label("ok");
+                                    } else {
+                                        ui.label(
+                                            egui::RichText::new(row.problems.join("; "))
+                                                .color(egui::Color32::from_rgb(240, 170, 40)),
+                                        );
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if ui
+                    .add_enabled(
+                        ok > 0 && self.role.allows(console_core::CommandClass::ShowEdit),
+                        egui::Button::new(format!("Patch {ok} fixture(s)")),
+                    )
+                    .clicked()
+                {
+                    patch = true;
+                }
+            });
+
+        if let Some(msg) = log {
+            self.programmer_ui.log.push(msg);
+        }
+        if patch {
+            match hookup::apply(&mut self.rt.show, &preview) {
+                Ok(n) => {
+                    self.programmer_ui
+                        .log
+                        .push(format!("Patched {n} fixture(s) from hookup"));
+                    self.show_dirty = true;
+                    open = false;
+                }
+                Err(e) => self.programmer_ui.log.push(format!("{e:#}")),
+            }
+        }
+        if !open {
+            self.hookup = None;
+        }
+    }
+
+    /// Prompt policy: ask whether deselected fixtures keep their values.
+    fn release_prompt_window(&mut self, ctx: &egui::Context) {
+        if self.pending_release.is_empty() {
+            return;
+        }
+        egui::Window::new("Deselected fixtures")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Fixtures {:?} left the selection with programmer values.",
+                    self.pending_release
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("Keep values").clicked() {
+                        self.pending_release.clear();
+                    }
+                    if ui.button("Release").clicked() {
+                        self.rt.programmer.release(&self.pending_release);
+                        self.pending_release.clear();
+                    }
+                });
+            });
+    }
+
+    /// Offer to record the programmer into an empty Cues or Palettes cell.
+    fn offer_quick_record(&mut self, container: &Container, cx: i32, cy: i32) {
+        let n = container.idx(cx, cy) as u32;
+        let pool = match container.kind {
+            ContainerKind::Cues => console_core::QuickPool::Cues,
+            ContainerKind::Palettes => console_core::QuickPool::Palettes,
+            _ => return,
+        };
+        let ctx = console_core::CommandContext::default();
+        let record = match console_core::QuickRecord::new(&self.rt, &ctx, pool, n) {
+            Ok(record) => record,
+            Err(e) => {
+                self.programmer_ui.log.push(format!("{e:#}"));
+                return;
+            }
+        };
+        let item = match pool {
+            console_core::QuickPool::Cues => CellItem::Cue {
+                number: n,
+                label: record.name.clone(),
+            },
+            console_core::QuickPool::Palettes => CellItem::Palette {
+                name: record.name.clone(),
+            },
+        };
+        self.quick_record = Some(QuickRecord {
+            container: container.id,
+            cx,
+            cy,
+            record,
+            item,
+        });
+    }
+
+    fn apply_palette(&mut self, name: &str) {
+        if let Some(sys) = console_core::SystemPalette::from_name(name) {
+            let n = self.rt.programmer.apply_system_palette(&self.rt.show, sys);
+            self.programmer_ui
+                .log
+                .push(format!("Applied '{sys}' to {n} fixture(s)"));
+            return;
+        }
+        let Some(pal) = self.rt.show.palettes.get(name) else {
+            self.programmer_ui
+                .log
+                .push(format!("Palette '{name}' no longer exists"));
+            return;
+        };
+        self.rt.programmer.apply_named_palette(name, pal);
+        self.programmer_ui
+            .log
+            .push(format!("Applied palette '{name}'"));
+    }
+
+    fn quick_record_window(&mut self, ctx: &egui::Context) {
+        let Some(q) = &self.quick_record else {
+            return;
+        };
+        let mut record = false;
+        let mut cancel = false;
+        egui::Window::new("Quick record")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Record the programmer into this cell: {}",
+                    q.record.line
+                ));
+                if q.record.replaces {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(230, 170, 60),
+                        format!("'{}' exists and will be overwritten.", q.record.name),
+                    );
+                }
+                ui.horizontal(|ui| {
+                    record = ui.button("Record").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+        if cancel {
+            self.quick_record = None;
+        }
+        if !record {
+            return;
+        }
+        let Some(q) = self.quick_record.take() else {
+            return;
+        };
+        let ctx = console_core::CommandContext::default();
+        // the window above was the confirmation
+        let res = self
+            .role
+            .check("record")
+            .and_then(|_| q.record.run(&mut self.rt, &ctx, true));
+        match res {
+            Ok(res) => {
+                self.programmer_ui.log.extend(res.message);
+                self.show_dirty |= res.show_changed;
+                if let Some(c) = self
+                    .layout
+                    .containers
+                    .iter_mut()
+                    .find(|c| c.id == q.container)
+                {
+                    c.ensure_cells_len();
+                    c.set_cell(q.cx, q.cy, Some(q.item));
+                    self.layout_dirty = true;
+                }
+            }
+            Err(e) => self.programmer_ui.log.push(format!("{e:#}")),
+        }
+    }
+
+    fn log_selection(&mut self) {
+        let ids = self
+            .rt
+            .programmer
+            .selected
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.programmer_ui.log.push(format!("Selected: {ids}"));
+    }
+
+    /// First show group not yet bound to a cell of this container.
+    fn next_unbound_group(&self, c: &Container) -> Option<String> {
+        self.rt
+            .show
+            .groups
+            .keys()
+            .find(|name| {
+                !c.cells
+                    .iter()
+                    .any(|cell| matches!(cell, Some(CellItem::Group { name: n }) if n == *name))
+            })
+            .cloned()
+    }
+
+    /// Select the first container showing this kind of object.
+    fn jump_to(&mut self, hit: &console_core::SearchHit) {
+        let kind = match hit.object {
+            console_core::ObjectRef::Cue { .. } => ContainerKind::Cues,
+            console_core::ObjectRef::Group(_) => ContainerKind::Groups,
+            console_core::ObjectRef::Palette(_) => ContainerKind::Palettes,
+            console_core::ObjectRef::Fixture(_) => {
+                self.programmer_ui.log.push(format!("→ {}", hit.title));
+                return;
+            }
+        };
+
+        match self.layout.containers.iter().find(|c| c.kind == kind) {
+            Some(c) => {
+                self.selected_id = Some(c.id);
+                self.selected_cell = None;
+                self.programmer_ui.log.push(format!("→ {}", hit.title));
+            }
+            None => self.programmer_ui.log.push(format!(
+                "→ {} (no {} container)",
+                hit.title,
+                kind.title()
+            )),
+        }
+    }
+
+    /// Fixture sheet: live look of playback A next to a tinted preview of the
+    /// look after the next Go.
+    fn fixture_sheet_window(&mut self, ctx: &egui::Context) {
+        let show = &self.rt.show;
+        let pb = &self.rt.playbacks["a"];
+        let (live, next) = match (pb.output_state_map(show), pb.preview_next(show)) {
+            (Ok(live), Ok(next)) => (live, next),
+            (Err(e), _) | (_, Err(e)) => {
+                self.programmer_ui.log.push(format!("{e:#}"));
+                self.show_fixture_sheet = false;
+                return;
+            }
+        };
+
+        let swatch = |ui: &mut egui::Ui, v: Option<&console_core::FixtureValues>, next: bool| {
+            let v = v.cloned().unwrap_or_default();
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(44.0, 16.0), egui::Sense::hover());
+            let color =
+                egui::Color32::from_rgb(v.r.unwrap_or(0), v.g.unwrap_or(0), v.b.unwrap_or(0));
+            ui.painter().rect_filled(rect, 2.0, color);
+            if next {
+                // ghost tint: marks the preview column
+                ui.painter().rect_filled(
+                    rect,
+                    2.0,
+                    egui::Color32::from_rgba_unmultiplied(90, 140, 255, 40),
+                );
+            }
+            let text = match v.intensity {
+                Some(i) => format!("{}%", i as u32 * 100 / 255),
+                None => String::new(),
+            };
+            ui.painter().text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                text,
+                egui::FontId::proportional(10.0),
+                egui::Color32::WHITE,
+            );
+        };
+
+        let mut edit_limits = None;
+        let window = egui::Window::new("Fixture sheet")
+            .open(&mut self.show_fixture_sheet)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                let next_label = match pb.next_cue(show) {
+                    Some(n) => format!("After Go (cue {n})"),
+                    None => "After Go (end)".to_string(),
+                };
+                egui::Grid::new("fixture_sheet")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("#");
+                        ui.strong("Name");
+                        ui.strong("Now");
+                        ui.strong(next_label);
+                        ui.strong("Limits");
+                        ui.end_row();
+                        for f in show.patch.fixtures.values() {
+                            ui.label(f.fixture_id.to_string());
+                            ui.label(&f.name);
+                            swatch(ui, live.get(&f.fixture_id), false);
+                            match &next {
+                                Some(next) => swatch(ui, next.get(&f.fixture_id), true),
+                                None => {
+                                    ui.label("–");
+                                }
+                            }
+                            let moves = [
+                                console_core::ChannelKind::Pan,
+                                console_core::ChannelKind::Tilt,
+                            ]
+                            .into_iter()
+                            .any(|k| !show.patch.channel_addresses(f.fixture_id, k).is_empty());
+                            if !moves {
+                                ui.label("");
+                            } else if ui
+                                .small_button(if f.limits.is_empty() {
+                                    "free"
+                                } else {
+                                    "limited"
+                                })
+                                .on_hover_text("Pan/tilt soft limits")
+                                .clicked()
+                            {
+                                edit_limits = Some(f.fixture_id);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                ui.weak("Mouse wheel here: level of the selected fixtures");
+            });
+
+        if edit_limits.is_some() {
+            self.limits_edit = edit_limits;
+        }
+
+        // level wheel over the sheet
+        let hovered = window.is_some_and(|w| w.response.contains_pointer());
+        let dy = ctx.input(|i| i.raw_scroll_delta.y);
+        if hovered && dy != 0.0 {
+            let delta = (dy / 10.0).round() as i16;
+            if let Err(e) = self.rt.nudge_intensity(delta) {
+                self.programmer_ui.log.push(format!("{e:#}"));
+            }
+        }
+    }
+
+    /// Pan/tilt soft limits of one fixture, in percent of the full range.
+    fn limits_window(&mut self, ctx: &egui::Context) {
+        let Some(fid) = self.limits_edit else {
+            return;
+        };
+        let Some(f) = self.rt.show.patch.fixtures.get(&fid) else {
+            self.limits_edit = None;
+            return;
+        };
+        let mut limits = f.limits;
+        let mut open = true;
+        let can_edit = self.role.allows(console_core::CommandClass::ShowEdit);
+        egui::Window::new(format!("Limits: {fid} {}", f.name))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if !can_edit {
+                    ui.disable();
+                }
+                for (axis, limit) in [("Pan", &mut limits.pan), ("Tilt", &mut limits.tilt)] {
+                    ui.horizontal(|ui| {
+                        let mut on = limit.is_some();
+                        if ui.checkbox(&mut on, axis).changed() {
+                            *limit = on.then(|| console_core::Limit::from_percent(0.0, 100.0));
+                        }
+                        if let Some(l) = limit {
+                            let (mut lo, mut hi) = l.percent();
+                            let a = ui
+                                .add(egui::DragValue::new(&mut lo).range(0.0..=100.0).suffix("%"));
+                            ui.label("–");
+                            let b = ui
+                                .add(egui::DragValue::new(&mut hi).range(0.0..=100.0).suffix("%"));
+                            if a.changed() || b.changed() {
+                                *l = console_core::Limit::from_percent(lo, hi);
+                            }
+                        }
+                    });
+                }
+                ui.weak("Enforced on output, whatever cues or the tester send.");
+            });
+        let changed = self.rt.show.patch.fixtures.get(&fid).map(|f| f.limits) != Some(limits);
+        if changed && let Some(f) = self.rt.show.patch.fixtures.get_mut(&fid) {
+            f.limits = limits;
+            self.show_dirty = true;
+        }
+        if !open {
+            self.limits_edit = None;
+        }
+    }
+
+    /// Lint findings; clicking one jumps to its object.
+    fn problems_window(&mut self, ctx: &egui::Context) {
+        let Some(findings) = &self.problems else {
+            return;
+        };
+        let mut open = true;
+        let mut picked = None;
+        let mut rerun = false;
+        egui::Window::new(format!("Problems ({})", findings.len()))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                if ui.button("Re-check").clicked() {
+                    rerun = true;
+                }
+                if findings.is_empty() {
+                    ui.label("No problems found");
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for f in findings {
+                            let color = match f.severity {
+                                console_core::Severity::Warning => {
+                                    egui::Color32::from_rgb(240, 170, 40)
+                                }
+                                console_core::Severity::Info => egui::Color32::from_gray(200),
+                            };
+                            if ui
+                                .selectable_label(
+                                    false,
+                                    egui::RichText::new(&f.message).color(color),
+                                )
+                                .clicked()
+                            {
+                                picked = Some(console_core::SearchHit {
+                                    object: f.object.clone(),
+                                    title: f.message.clone(),
+                                });
+                            }
+                        }
+                    });
+            });
+
+        if !open {
+            self.problems = None;
+        } else if rerun {
+            self.problems = Some(self.rt.show.lint());
+        }
+        if let Some(hit) = picked {
+            self.jump_to(&hit);
+        }
+    }
+
+    /// Run what the surface's controls are bound to, or bind the first one
+    /// touched while learning.
+    fn poll_midi(&mut self, ctx: &egui::Context) {
+        let Some(input) = &self.midi else {
+            return;
+        };
+        ctx.request_repaint_after(std::time::Duration::from_millis(25));
+        for msg in input.poll() {
+            let (control, value) = msg.control();
+            if let Some(action) = self.midi_ui.as_mut().and_then(|ui| ui.learning.take()) {
+                self.programmer_ui
+                    .log
+                    .push(format!("MIDI {control} -> {action}"));
+                self.midi_map.bind(control, action);
+                self.save_midi_map();
+                continue;
+            }
+            let Some(action) = self.midi_map.action_for(control).cloned() else {
+                continue;
+            };
+            // Go on A goes through the go guard's confirmation like the button
+            if action == console_core::MidiAction::Go("a".into()) {
+                if value > 0 {
+                    self.press_go();
+                }
+                continue;
+            }
+            match self.rt.midi_action(&action, value) {
+                Ok(Some(cmd)) => self.run_command(&cmd),
+                Ok(None) => {}
+                Err(e) => self.programmer_ui.log.push(format!("MIDI {action}: {e:#}")),
+            }
+        }
+    }
+
+    fn save_midi_map(&mut self) {
+        let path = console_core::MidiMap::path_for(&self.show_path);
+        if let Err(e) = self.midi_map.save(&path) {
+            self.programmer_ui.log.push(format!("{e:#}"));
+        }
+    }
+
+    fn midi_window(&mut self, ctx: &egui::Context) {
+        let Some(state) = &mut self.midi_ui else {
+            return;
+        };
+        let mut open = true;
+        let mut opened = None;
+        let mut unbind = None;
+        egui::Window::new("MIDI")
+            .open(&mut open)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Device:");
+                    ui.text_edit_singleline(&mut state.device);
+                    if ui.button("Open").clicked() {
+                        opened = Some(state.device.trim().to_string());
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Action:");
+                    ui.text_edit_singleline(&mut state.action).on_hover_text(
+                        "go a, back b, fader a, gm, master <group>, encoder red, cmd <command>",
+                    );
+                    if ui.button("Learn").clicked() {
+                        state.learning = state.action.parse().ok();
+                    }
+                });
+                if self.midi.is_none() {
+                    ui.weak("No device open.");
+                }
+                if let Some(action) = &state.learning {
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        format!("Touch a control for '{action}'..."),
+                    );
+                } else if !state.action.trim().is_empty()
+                    && let Err(e) = state.action.parse::<console_core::MidiAction>()
+                {
+                    ui.weak(e.to_string());
+                }
+                ui.separator();
+                egui::Grid::new("midi_bindings")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (i, b) in self.midi_map.bindings.iter().enumerate() {
+                            ui.label(b.control.to_string());
+                            ui.label(b.action.to_string());
+                            if ui.small_button("✖").clicked() {
+                                unbind = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        if let Some(dev) = opened {
+            let dev = std::path::PathBuf::from(dev);
+            match console_core::MidiInput::open(&dev) {
+                Ok(input) => {
+                    self.programmer_ui
+                        .log
+                        .push(format!("MIDI input open: {}", dev.display()));
+                    self.midi = Some(input);
+                    self.midi_map.device = Some(dev);
+                    self.save_midi_map();
+                }
+                Err(e) => self.programmer_ui.log.push(format!("{e:#}")),
+            }
+        }
+        if let Some(i) = unbind {
+            self.midi_map.bindings.remove(i);
+            self.save_midi_map();
+        }
+        if !open {
+            self.midi_ui = None;
+        }
+    }
+
+    /// Run what the HID device's keys and wheels are bound to, or bind the
+    /// first one pressed while learning.
+    fn poll_hid(&mut self, ctx: &egui::Context) {
+        let Some(input) = &self.hid else {
+            return;
+        };
+        ctx.request_repaint_after(std::time::Duration::from_millis(25));
+        for report in input.poll() {
+            for ev in self.hid_decoder.decode(&report, &self.hid_map) {
+                if ev.value != 0
+                    && let Some(action) = self.hid_ui.as_mut().and_then(|ui| ui.learning.take())
+                {
+                    self.hid_map.bind(ev.control, action);
+                    if let Some(b) = self.hid_map.bindings.last() {
+                        self.programmer_ui
+                            .log
+                            .push(format!("HID {} -> {}", b.control, b.action));
+                    }
+                    self.save_hid_map();
+                    continue;
+                }
+                let Some(action) = self.hid_map.action_for(ev.control).cloned() else {
+                    continue;
+                };
+                if action == console_core::MidiAction::Go("a".into()) {
+                    if ev.value > 0 {
+                        self.press_go();
+                    }
+                    continue;
+                }
+                match self.rt.midi_action(&action, ev.midi_value()) {
+                    Ok(Some(cmd)) => self.run_command(&cmd),
+                    Ok(None) => {}
+                    Err(e) => self.programmer_ui.log.push(format!("HID {action}: {e:#}")),
+                }
+            }
+        }
+    }
+
+    fn save_hid_map(&mut self) {
+        let path = console_core::HidMap::path_for(&self.show_path);
+        if let Err(e) = self.hid_map.save(&path) {
+            self.programmer_ui.log.push(format!("{e:#}"));
+        }
+    }
+
+    fn hid_window(&mut self, ctx: &egui::Context) {
+        let Some(state) = &mut self.hid_ui else {
+            return;
+        };
+        let mut open = true;
+        let mut opened = None;
+        let mut unbind = None;
+        egui::Window::new("HID")
+            .open(&mut open)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Device:");
+                    ui.text_edit_singleline(&mut state.device);
+                    ui.checkbox(&mut state.keyboard, "Keyboard")
+                        .on_hover_text("Keypad sends keyboard reports");
+                    if ui.button("Open").clicked() {
+                        opened = Some((state.device.trim().to_string(), state.keyboard));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Action:");
+                    ui.text_edit_singleline(&mut state.action).on_hover_text(
+                        "go a, back b, fader a, gm, master <group>, encoder red, cmd <command>",
+                    );
+                    if ui.button("Learn").clicked() {
+                        state.learning = state.action.parse().ok();
+                    }
+                });
+                if self.hid.is_none() {
+                    ui.weak("No device open.");
+                }
+                if let Some(action) = &state.learning {
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        format!("Press a key or turn a wheel for '{action}'..."),
+                    );
+                } else if !state.action.trim().is_empty()
+                    && let Err(e) = state.action.parse::<console_core::MidiAction>()
+                {
+                    ui.weak(e.to_string());
+                }
+                ui.separator();
+                egui::Grid::new("hid_bindings")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (i, b) in self.hid_map.bindings.iter().enumerate() {
+                            ui.label(b.control.to_string());
+                            ui.label(b.action.to_string());
+                            if ui.small_button("✖").clicked() {
+                                unbind = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        if let Some((dev, keyboard)) = opened {
+            let dev = std::path::PathBuf::from(dev);
+            match console_core::HidInput::open(&dev) {
+                Ok(input) => {
+                    self.programmer_ui
+                        .log
+                        .push(format!("HID input open: {}", dev.display()));
+                    self.hid = Some(input);
+                    self.hid_decoder = Default::default();
+                    self.hid_map.device = Some(dev);
+                    self.hid_map.format = if keyboard {
+                        console_core::HidFormat::Keyboard
+                    } else {
+                        console_core::HidFormat::Raw
+                    };
+                    self.save_hid_map();
+                }
+                Err(e) => self.programmer_ui.log.push(format!("{e:#}")),
+            }
+        }
+        if let Some(i) = unbind {
+            self.hid_map.bindings.remove(i);
+            self.save_hid_map();
+        }
+        if !open {
+            self.hid_ui = None;
+        }
+    }
+
+    /// Show statistics: patch per type and universe, cue lists, palette use.
+    fn show_info_window(&mut self, ctx: &egui::Context) {
+        let Some(stats) = &self.show_info else {
+            return;
+        };
+        let mut open = true;
+        let mut refresh = false;
+        egui::Window::new("Show info")
+            .open(&mut open)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                let count_grid = |ui: &mut egui::Ui, id: &str, rows: Vec<(String, usize)>| {
+                    egui::Grid::new(id).striped(true).show(ui, |ui| {
+                        for (name, n) in rows {
+                            ui.label(name);
+                            ui.label(n.to_string());
+                            ui.end_row();
+                        }
+                    });
+                };
+                ui.heading(format!(
+                    "{} fixtures, {} groups",
+                    stats.fixtures, stats.groups
+                ));
+                ui.columns(2, |cols| {
+                    cols[0].label("By type");
+                    let rows = stats.fixtures_by_type.iter();
+                    count_grid(
+                        &mut cols[0],
+                        "stats_types",
+                        rows.map(|(t, n)| (t.clone(), *n)).collect(),
+                    );
+                    cols[1].label("By universe");
+                    let rows = stats.fixtures_by_universe.iter();
+                    count_grid(
+                        &mut cols[1],
+                        "stats_universes",
+                        rows.map(|(u, n)| (format!("U{u}"), *n)).collect(),
+                    );
+                });
+                ui.separator();
+                ui.heading(format!(
+                    "{} cues",
+                    stats.cues_by_list.values().sum::<usize>()
+                ));
+                let rows = stats.cues_by_list.iter();
+                count_grid(
+                    ui,
+                    "stats_lists",
+                    rows.map(|(l, n)| (l.clone(), *n)).collect(),
+                );
+                if let Some(ms) = stats.average_fade_ms {
+                    ui.label(format!("Average fade {:.1}s", ms as f64 / 1000.0));
+                }
+                if !stats.largest_cues.is_empty() {
+                    ui.label("Largest cues (fixtures changed)");
+                    let rows = stats.largest_cues.iter();
+                    count_grid(
+                        ui,
+                        "stats_largest",
+                        rows.map(|(l, c, n)| (format!("{l}/{c}"), *n)).collect(),
+                    );
+                }
+                if !stats.palette_usage.is_empty() {
+                    ui.separator();
+                    ui.heading("Palettes (cues using them)");
+                    egui::Grid::new("stats_palettes")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (name, n) in &stats.palette_usage {
+                                ui.label(name);
+                                if *n == 0 {
+                                    ui.colored_label(egui::Color32::YELLOW, "unused");
+                                } else {
+                                    ui.label(n.to_string());
+                                }
+                                ui.end_row();
+                            }
+                        });
+                }
+                ui.separator();
+                refresh = ui.button("Refresh").clicked();
+            });
+        if !open {
+            self.show_info = None;
+        } else if refresh {
+            self.show_info = Some(self.rt.show.stats());
+        }
+    }
+
+    /// Pre-show check: an imported RDM discovery dump against the patch.
+    /// No RDM traffic is sent.
+    fn channel_check_window(&mut self, ctx: &egui::Context) {
+        let Some(check) = &mut self.channel_check else {
+            return;
+        };
+        let mut open = true;
+        let mut log = None;
+        egui::Window::new("Channel check (RDM)")
+            .open(&mut open)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Responders:");
+                    ui.text_edit_singleline(&mut check.path);
+                    if ui.button("Check").clicked() {
+                        let path = std::path::Path::new(check.path.trim());
+                        match console_core::rdm::load_responders(path) {
+                            Ok(responders) => {
+                                check.report = Some(self.rt.show.patch.channel_check(&responders));
+                            }
+                            Err(e) => log = Some(format!("{e:#}")),
+                        }
+                    }
+                });
+                let Some(report) = &check.report else {
+                    ui.label("JSON list of {uid, universe, address, footprint, model}");
+                    ui.label("Imported from an RDM gateway; the console sends no RDM.");
+                    return;
+                };
+                let failures = report.failures();
+                ui.label(format!(
+                    "{} of {} fixture(s) passed",
+                    report.fixtures.len() - failures,
+                    report.fixtures.len()
+                ));
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        for c in &report.fixtures {
+                            let color = if c.passed() {
+                                egui::Color32::from_rgb(90, 200, 90)
+                            } else {
+                                egui::Color32::from_rgb(230, 80, 60)
+                            };
+                            ui.label(egui::RichText::new(c.to_string()).color(color));
+                        }
+                        for r in &report.unpatched {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "EXTRA {} at {}.{} ({} ch) is not in the patch",
+                                    r.uid, r.universe, r.address, r.footprint
+                                ))
+                                .color(egui::Color32::from_rgb(240, 170, 40)),
+                            );
+                        }
+                    });
+            });
+        if let Some(msg) = log {
+            self.programmer_ui.log.push(msg);
+        }
+        if !open {
+            self.channel_check = None;
+        }
+    }
+
+    /// Master page: one fader per mastered group plus the all-inhibitors action.
+    /// Upcoming follows and timeline events with countdowns.
+    fn agenda_window(&mut self, ctx: &egui::Context) {
+        if !self.show_agenda {
+            return;
+        }
+        let timecode = Some((self.timeline.frame, self.timeline.playing.is_some()));
+        let items = self.rt.agenda(timecode, 12);
+        if items.iter().any(|i| i.running) {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+        let mut open = true;
+        egui::Window::new("Agenda")
+            .open(&mut open)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                if items.is_empty() {
+                    ui.weak("Nothing coming up");
+                }
+                egui::Grid::new("agenda").striped(true).show(ui, |ui| {
+                    for item in &items {
+                        let countdown = format!("{:.1}s", item.in_ms as f64 / 1000.0);
+                        if item.running {
+                            ui.monospace(countdown);
+                        } else {
+                            ui.weak(countdown).on_hover_text("Timeline stopped");
+                        }
+                        match &item.source {
+                            console_core::AgendaSource::Follow { playback, from, to } => {
+                                ui.label(format!("Follow {}", playback.to_uppercase()));
+                                ui.label(match to {
+                                    Some(to) => format!("{from} → {to}"),
+                                    None => format!("{from} → black"),
+                                });
+                            }
+                            console_core::AgendaSource::Timecode { at, .. } => {
+                                ui.label(at.to_string());
+                                ui.label(&item.command);
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        self.show_agenda = open;
+    }
+
+    fn masters_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_masters;
+        egui::Window::new("Masters")
+            .open(&mut open)
+            .default_width(260.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("All full").clicked() {
+                        self.rt.set_all_masters(255);
+                    }
+                    if ui.button("All zero").clicked() {
+                        self.rt.set_all_masters(0);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        let mut level = self.rt.grand_master();
+                        if ui
+                            .add(egui::Slider::new(&mut level, 0..=255).vertical())
+                            .changed()
+                        {
+                            self.rt.set_grand_master(level);
+                        }
+                        ui.strong("GM");
+                    });
+                    for (name, pb) in self.rt.playbacks.iter_mut() {
+                        ui.vertical(|ui| {
+                            ui.add(egui::Slider::new(&mut pb.master, 0..=255).vertical());
+                            ui.label(format!("PB {}", name.to_uppercase()));
+                        });
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut self.gm_fade_secs)
+                            .range(0.0..=600.0)
+                            .speed(0.1)
+                            .suffix(" s"),
+                    );
+                    let ms = (self.gm_fade_secs * 1000.0).round() as u32;
+                    if ui.button("GM out").clicked() {
+                        self.rt.fade_grand_master(0, ms);
+                    }
+                    if ui.button("GM full").clicked() {
+                        self.rt.fade_grand_master(255, ms);
+                    }
+                    if ui
+                        .button("Blackout")
+                        .on_hover_text("Fade the GM out, then latch the blackout")
+                        .clicked()
+                    {
+                        self.rt.timed_blackout(ms);
+                    }
+                });
+                if let Some((ms, blackout)) = self.rt.gm_fade_remaining() {
+                    let what = if blackout { "Blackout" } else { "GM fade" };
+                    ui.label(format!("{what} in {}", fmt_secs(ms)));
+                }
+                ui.separator();
+                if let Some(speed) = rate_slider(ui, "Speed", self.rt.speed_master()) {
+                    let _ = self.rt.set_speed_master(speed);
+                }
+                for (name, pb) in self.rt.playbacks.iter_mut() {
+                    let label = format!("Rate {}", name.to_uppercase());
+                    if let Some(rate) = rate_slider(ui, &label, pb.rate()) {
+                        let _ = pb.set_rate(rate);
+                    }
+                }
+                ui.separator();
+                if self.rt.show.masters.groups.is_empty() {
+                    ui.label("No masters (CLI: masters add <group>)");
+                }
+                ui.horizontal(|ui| {
+                    for group in self.rt.show.masters.groups.clone() {
+                        ui.vertical(|ui| {
+                            let mut level = self.rt.master_level(&group);
+                            if ui
+                                .add(egui::Slider::new(&mut level, 0..=255).vertical())
+                                .changed()
+                            {
+                                let _ = self.rt.set_master(&group, level);
+                            }
+                            ui.label(&group);
+                        });
+                    }
+                });
+            });
+        self.show_masters = open;
+    }
+
+    /// Render a thumbnail of every cue in playback A's list.
+    fn build_cue_sheet(&self, ctx: &egui::Context) -> CueSheet {
+        let show = &self.rt.show;
+        let cuelist = self.rt.playbacks["a"].cuelist.clone();
+        let mut rows = Vec::new();
+        if let Some(list) = show.cue_lists.get(&cuelist) {
+            for (&num, cue) in &list.cues {
+                let Ok(img) = console_core::plot::cue_thumbnail(show, &cuelist, num) else {
+                    continue;
+                };
+                let image =
+                    egui::ColorImage::from_rgb([img.width as usize, img.height as usize], &img.rgb);
+                let texture = ctx.load_texture(
+                    format!("cue_thumb_{cuelist}_{num}"),
+                    image,
+                    egui::TextureOptions::LINEAR,
+                );
+                rows.push((num, cue.label.clone(), texture));
+            }
+        }
+        CueSheet { cuelist, rows }
+    }
+
+    /// Cues with thumbnails of their look on the stage plot; click to go there.
+    fn cue_sheet_window(&mut self, ctx: &egui::Context) {
+        let Some(sheet) = &self.cue_sheet else {
+            return;
+        };
+        let mut open = true;
+        let mut refresh = false;
+        let mut export = false;
+        let mut goto = None;
+        let current = self.rt.playbacks["a"].current;
+        egui::Window::new(format!("Cue sheet: {}", sheet.cuelist))
+            .open(&mut open)
+            .default_width(300.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    refresh = ui.button("Refresh").clicked();
+                    export = ui
+                        .button("Export PNGs")
+                        .on_hover_text("Write the thumbnails next to the show file")
+                        .clicked();
+                });
+                if sheet.rows.is_empty() {
+                    ui.label("No cues");
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(420.0)
+                    .show(ui, |ui| {
+                        for (num, label, texture) in &sheet.rows {
+                            let resp = ui
+                                .horizontal(|ui| {
+                                    ui.add(egui::Image::new(texture).sense(egui::Sense::click()))
+                                        .clicked()
+                                        | ui.selectable_label(
+                                            current == Some(*num),
+                                            format!("{num}  {label}"),
+                                        )
+                                        .clicked()
+                                })
+                                .inner;
+                            if resp {
+                                goto = Some(*num);
+                            }
+                        }
+                    });
+            });
+
+        if !open {
+            self.cue_sheet = None;
+            return;
+        }
+        if refresh {
+            self.cue_sheet = Some(self.build_cue_sheet(ctx));
+        }
+        if export {
+            let list = self.rt.playbacks["a"].cuelist.clone();
+            let msg = match console_core::plot::export_thumbnails(
+                &self.rt.show,
+                &self.show_path,
+                &list,
+            ) {
+                Ok(n) => format!("Exported {n} cue thumbnail(s)"),
+                Err(e) => format!("{e:#}"),
+            };
+            self.programmer_ui.log.push(msg);
+        }
+        if let Some(num) = goto {
+            self.run_command(&format!("goto {num}"));
+        }
+    }
+
+    /// Programmer contents per fixture, i.e. what a tracking record stores.
+    /// Values are edited in place; ✖ knocks a fixture out of the programmer.
+    fn programmer_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_programmer;
+        let contents = self.rt.programmer.contents();
+        let mut edits = Vec::new();
+        let mut knockout = None;
+        let can_program = self.role.allows(console_core::CommandClass::Programming);
+        egui::Window::new("Programmer")
+            .open(&mut open)
+            .default_width(300.0)
+            .show(ctx, |ui| {
+                if contents.is_empty() {
+                    ui.label("Programmer is empty");
+                    return;
+                }
+                egui::Grid::new("programmer_contents")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for h in ["#", "Name", "I", "R", "G", "B", ""] {
+                            ui.strong(h);
+                        }
+                        ui.end_row();
+                        for (&fid, v) in &contents {
+                            let name = self
+                                .rt
+                                .show
+                                .patch
+                                .fixtures
+                                .get(&fid)
+                                .map_or("?", |f| f.name.as_str());
+                            let selected = self.rt.programmer.selected.contains(&fid);
+                            ui.label(fid.to_string());
+                            if selected {
+                                ui.label(name);
+                            } else {
+                                ui.weak(name).on_hover_text("Not selected (retained)");
+                            }
+                            let params = [v.intensity, v.r, v.g, v.b];
+                            for (i, value) in params.into_iter().enumerate() {
+                                let Some(mut value) = value else {
+                                    ui.weak("–");
+                                    continue;
+                                };
+                                let drag = egui::DragValue::new(&mut value);
+                                if ui.add_enabled(can_program, drag).changed() {
+                                    let mut edit = console_core::FixtureValues::default();
+                                    let field = match i {
+                                        0 => &mut edit.intensity,
+                                        1 => &mut edit.r,
+                                        2 => &mut edit.g,
+                                        _ => &mut edit.b,
+                                    };
+                                    *field = Some(value);
+                                    edits.push((fid, edit));
+                                }
+                            }
+                            let knock = egui::Button::new("✖").small();
+                            if ui
+                                .add_enabled(can_program, knock)
+                                .on_hover_text("Knock out")
+                                .clicked()
+                            {
+                                knockout = Some(fid);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                ui.weak("Record (track) stores exactly these values");
+            });
+        self.show_programmer = open;
+
+        for (fid, edit) in edits {
+            self.rt.programmer.edit_fixture(fid, &edit);
+        }
+        if let Some(fid) = knockout {
+            self.rt.programmer.knockout(fid);
+            self.programmer_ui
+                .log
+                .push(format!("Fixture {fid} knocked out of the programmer"));
+        }
+    }
+
+    /// Routing matrix: internal universes (rows) to destinations (columns).
+    fn outputs_window(&mut self, ctx: &egui::Context) {
+        let Some(mut ui_state) = self.outputs_ui.take() else {
+            return;
+        };
+        let mut open = true;
+        let mut edited = false;
+        let mut running = self.output.is_some();
+        let mut log = None;
+        let can_edit = self.role.allows(console_core::CommandClass::ShowEdit);
+
+        let mut universes: std::collections::BTreeSet<u16> =
+            self.rt.show.patch.footprints().into_keys().collect();
+        for d in &self.rt.show.outputs.destinations {
+            universes.extend(d.universes.keys());
+        }
+
+        egui::Window::new("Outputs")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                // routing is part of the show: look, don't touch
+                if !can_edit {
+                    ui.disable();
+                }
+                if ui.checkbox(&mut running, "Send DMX").changed() {
+                    edited = true;
+                }
+                ui.separator();
+
+                let routing = &mut self.rt.show.outputs;
+                let mut remove = None;
+                egui::Grid::new("outputs_matrix")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("");
+                        for (i, d) in routing.destinations.iter_mut().enumerate() {
+                            ui.vertical(|ui| {
+                                ui.horizontal(|ui| {
+                                    edited |= ui.checkbox(&mut d.enabled, &d.name).changed();
+                                    if ui.small_button("✕").clicked() {
+                                        remove = Some(i);
+                                    }
+                                });
+                                ui.small(d.kind.to_string());
+                                ui.horizontal(|ui| {
+                                    ui.small("prio");
+                                    edited |= ui
+                                        .add(egui::DragValue::new(&mut d.priority).range(0..=200))
+                                        .changed();
+                                });
+                            });
+                        }
+                        ui.label("Delay")
+                            .on_hover_text("Hold the universe back to line up with slower links");
+                        ui.end_row();
+
+                        for &u in &universes {
+                            ui.label(format!("Universe {u}"));
+                            for d in routing.destinations.iter_mut() {
+                                let mut routed = d.universes.contains_key(&u);
+                                ui.horizontal(|ui| {
+                                    if ui.checkbox(&mut routed, "").changed() {
+                                        if routed {
+                                            d.universes.insert(u, u);
+                                        } else {
+                                            d.universes.remove(&u);
+                                        }
+                                        edited = true;
+                                    }
+                                    if let Some(ext) = d.universes.get_mut(&u) {
+                                        edited |= ui
+                                            .add(
+                                                egui::DragValue::new(ext)
+                                                    .range(1..=32768)
+                                                    .prefix("→ "),
+                                            )
+                                            .changed();
+                                    }
+                                });
+                            }
+                            // the output thread picks delays up without a restart
+                            let mut ms = routing.delays.get(&u).copied().unwrap_or(0);
+                            let drag = egui::DragValue::new(&mut ms).range(0..=2000).suffix(" ms");
+                            if ui.add(drag).changed() {
+                                if ms == 0 {
+                                    routing.delays.remove(&u);
+                                } else {
+                                    routing.delays.insert(u, ms);
+                                }
+                                self.show_dirty = true;
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(i) = remove {
+                    routing.destinations.remove(i);
+                    edited = true;
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut ui_state.name)
+                            .hint_text("name")
+                            .desired_width(90.0),
+                    );
+                    ui.selectable_value(&mut ui_state.sacn, false, "Art-Net");
+                    ui.selectable_value(&mut ui_state.sacn, true, "sACN");
+                    let hint = if ui_state.sacn {
+                        "receiver (blank = multicast)"
+                    } else {
+                        "ip[:port]"
+                    };
+                    ui.add(
+                        egui::TextEdit::singleline(&mut ui_state.target)
+                            .hint_text(hint)
+                            .desired_width(150.0),
+                    );
+                    if ui.button("Add").clicked() {
+                        let port = if ui_state.sacn {
+                            console_core::dmx_output::SACN_PORT
+                        } else {
+                            console_core::dmx_output::ARTNET_PORT
+                        };
+                        let target = ui_state.target.trim();
+                        let addr = if target.contains(':') {
+                            target.parse()
+                        } else {
+                            format!("{target}:{port}").parse()
+                        };
+                        let kind = match (ui_state.sacn, addr) {
+                            (true, _) if target.is_empty() => {
+                                Some(console_core::DestinationKind::Sacn { unicast: None })
+                            }
+                            (true, Ok(addr)) => Some(console_core::DestinationKind::Sacn {
+                                unicast: Some(addr),
+                            }),
+                            (false, Ok(target)) => {
+                                Some(console_core::DestinationKind::ArtNet { target })
+                            }
+                            (_, Err(_)) => {
+                                log = Some(format!("Invalid address '{target}'"));
+                                None
+                            }
+                        };
+                        if let Some(kind) = kind {
+                            let mut dest =
+                                console_core::Destination::new(ui_state.name.trim(), kind);
+                            dest.universes = universes.iter().map(|&u| (u, u)).collect();
+                            match routing.add(dest) {
+                                Ok(()) => {
+                                    ui_state = OutputsUi::default();
+                                    edited = true;
+                                }
+                                Err(e) => log = Some(format!("{e:#}")),
+                            }
+                        }
+                    }
+                    // a widget carries one universe: the first patched one
+                    ui.menu_button("USB…", |ui| {
+                        let devices = console_core::usb_dmx_devices();
+                        if devices.is_empty() {
+                            ui.label("No USB serial devices found");
+                        }
+                        for dev in devices {
+                            let widget = dev.widget.unwrap_or(console_core::UsbWidget::UsbPro);
+                            let label = format!("{} ({widget})", dev.path.display());
+                            if !ui.button(label).clicked() {
+                                continue;
+                            }
+                            let name = match ui_state.name.trim() {
+                                "" => format!("USB {}", routing.destinations.len() + 1),
+                                name => name.to_string(),
+                            };
+                            let kind = console_core::DestinationKind::UsbDmx {
+                                device: dev.path,
+                                widget,
+                            };
+                            let mut dest = console_core::Destination::new(name, kind);
+                            dest.universes =
+                                universes.first().map(|&u| (u, u)).into_iter().collect();
+                            match routing.add(dest) {
+                                Ok(()) => {
+                                    ui_state = OutputsUi::default();
+                                    edited = true;
+                                }
+                                Err(e) => log = Some(format!("{e:#}")),
+                            }
+                            ui.close_menu();
+                        }
+                    });
+                });
+                ui.horizontal(|ui| {
+                    ui.label("sACN source name");
+                    let name = ui.text_edit_singleline(&mut self.rt.show.sacn.source_name);
+                    edited |= name.lost_focus();
+                });
+            });
+
+        if let Some(msg) = log {
+            self.programmer_ui.log.push(msg);
+        }
+        if edited {
+            self.show_dirty = true;
+            // restart so the thread picks up the new routing
+            if let Some(old) = self.output.take() {
+                old.shutdown();
+            }
+            if running {
+                match console_core::RoutedOutput::open(&self.rt.show.outputs, &self.rt.show.sacn) {
+                    Ok(driver) => {
+                        self.output = Some(console_core::OutputThread::spawn(
+                            Box::new(driver),
+                            console_core::OutputConfig::default(),
+                        ))
+                    }
+                    Err(e) => self.programmer_ui.log.push(format!("Output: {e:#}")),
+                }
+            }
+        }
+        if open {
+            self.outputs_ui = Some(ui_state);
+        }
+    }
+
+    /// Recently used colors: click to use, right-click to record as a palette.
+    fn color_swatches(&mut self, ui: &mut egui::Ui) {
+        let history = self.rt.programmer.color_history().clone();
+        if history.is_empty() {
+            return;
+        }
+        let mut apply = None;
+        let mut record = None;
+        ui.horizontal_wrapped(|ui| {
+            for rgb in history {
+                let [r, g, b] = rgb;
+                let swatch = ui
+                    .add(
+                        egui::Button::new("")
+                            .fill(egui::Color32::from_rgb(r, g, b))
+                            .min_size(egui::vec2(22.0, 22.0)),
+                    )
+                    .on_hover_text(format!("rgb({r},{g},{b})"));
+                if swatch.clicked() {
+                    apply = Some(rgb);
+                }
+                swatch.context_menu(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.programmer_ui.swatch_name)
+                            .hint_text("palette name"),
+                    );
+                    if ui.button("Record as palette").clicked() {
+                        record = Some(rgb);
+                        ui.close_menu();
+                    }
+                });
+            }
+        });
+
+        let programming = self.role.allows(console_core::CommandClass::Programming);
+        if let Some([r, g, b]) = apply
+            && programming
+        {
+            self.rt.programmer.set_rgb(r, g, b);
+            (
+                self.programmer_ui.r,
+                self.programmer_ui.g,
+                self.programmer_ui.b,
+            ) = (r, g, b);
+        }
+        if let Some(rgb) = record
+            && self.role.allows(console_core::CommandClass::ShowEdit)
+        {
+            let name = match self.programmer_ui.swatch_name.trim() {
+                "" => (1..)
+                    .map(|n| format!("Color {n}"))
+                    .find(|n| !self.rt.show.palettes.contains_key(n))
+                    .unwrap(),
+                name => name.to_string(),
+            };
+            if let Err(e) = self.rt.show.check_palette_name(&name) {
+                self.programmer_ui.log.push(format!("{e:#}"));
+                return;
+            }
+            if self.rt.show.palettes.contains_key(&name) {
+                self.programmer_ui
+                    .log
+                    .push(format!("Palette '{name}' already exists"));
+                return;
+            }
+            let author = console_core::meta::current_author();
+            let mut pal = console_core::Palette::from_rgb(rgb);
+            pal.meta = console_core::Meta::created_now(author.as_deref());
+            self.rt.show.palettes.insert(name.clone(), pal);
+            self.programmer_ui.swatch_name.clear();
+            self.programmer_ui
+                .log
+                .push(format!("Recorded color palette '{name}'"));
+            self.show_dirty = true;
+        }
+    }
+
+    /// Append a note to the show file's operator log (unsaved edits stay in memory).
+    fn post_note(&mut self, text: &str) {
+        let author = console_core::meta::current_author();
+        let entry = console_core::LogEntry::now(author.as_deref(), text);
+        match console_core::oplog::post(&self.show_path, entry) {
+            Ok(on_disk) => {
+                self.rt.show.log = console_core::oplog::merge_log(&self.rt.show.log, &on_disk.log);
+                self.watcher.mark_seen();
+            }
+            Err(e) => self
+                .programmer_ui
+                .log
+                .push(format!("Can't post to the show log: {e:#}")),
+        }
+    }
+
+    /// Pick up notes other clients posted, as toasts.
+    fn poll_show_log(&mut self, ctx: &egui::Context) {
+        if self.watcher.poll()
+            && let Ok(on_disk) = console_core::Show::load_json_file(&self.show_path)
+        {
+            let now = ctx.input(|i| i.time);
+            for entry in console_core::oplog::new_entries(&self.rt.show.log, &on_disk.log) {
+                self.toasts.push((entry.line(), now + 8.0));
+            }
+            self.rt.show.log = console_core::oplog::merge_log(&self.rt.show.log, &on_disk.log);
+        }
+        // other clients' notes arrive without input
+        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+    }
+
+    fn log_window(&mut self, ctx: &egui::Context) {
+        let Some(mut draft) = self.log_draft.take() else {
+            return;
+        };
+        let mut open = true;
+        let mut post = false;
+        egui::Window::new("Show log")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(240.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for entry in &self.rt.show.log {
+                            ui.label(entry.line());
+                        }
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let edit = ui.text_edit_singleline(&mut draft);
+                    let enter = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (ui.button("Post").clicked() || enter) && !draft.trim().is_empty() {
+                        post = true;
+                    }
+                });
+            });
+        if post {
+            self.post_note(draft.trim());
+            draft.clear();
+        }
+        if open {
+            self.log_draft = Some(draft);
+        }
+    }
+
+    /// Feed the output thread and turn its events into toasts.
+    fn drive_output(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|i| i.time);
+        if let Some(out) = &self.output {
+            if let Some(keep) = self.frame_log_keep
+                && !out.frame_log().is_enabled()
+            {
+                out.frame_log().enable(keep);
+            }
+            out.set_delays(&self.rt.show.outputs.delays);
+            match self.rt.render() {
+                Ok(frame) => out.submit(frame),
+                Err(e) => self.programmer_ui.log.push(format!("Render: {e:#}")),
+            }
+            for ev in out.events() {
+                let msg = match ev {
+                    console_core::OutputEvent::Disconnected { driver, error } => {
+                        format!("{driver} disconnected: {error}")
+                    }
+                    console_core::OutputEvent::Reconnected { driver } => {
+                        format!("{driver} reconnected")
+                    }
+                };
+                self.programmer_ui.log.push(msg.clone());
+                self.toasts.push((msg, now + 5.0));
+            }
+            // keep frames flowing; the output watchdog blacks out after 1 s
+            ctx.request_repaint_after(std::time::Duration::from_millis(40));
+        }
+
+        self.toasts.retain(|(_, until)| *until > now);
+        if self.toasts.is_empty() {
+            return;
+        }
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -12.0))
+            .show(ctx, |ui| {
+                for (msg, _) in &self.toasts {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.label(msg);
+                    });
+                }
+            });
+        ctx.request_repaint_after(std::time::Duration::from_millis(250));
+    }
+
+    fn quick_open_window(&mut self, ctx: &egui::Context) {
+        if ctx.input_mut(|i| {
+            i.consume_shortcut(&egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::K,
+            ))
+        }) {
+            self.quick_open = match self.quick_open {
+                Some(_) => None,
+                None => Some(QuickOpen::default()),
+            };
+        }
+
+        let Some(qo) = &mut self.quick_open else {
+            return;
+        };
+
+        let hits = self.rt.show.search(&qo.query);
+
+        let mut chosen = None;
+        let mut close = ctx.input(|i| i.key_pressed(egui::Key::Escape));
+
+        egui::Window::new("Quick open")
+            .collapsible(false)
+            .resizable(false)
+            .title_bar(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+            .show(ctx, |ui| {
+                let resp = ui.add(
+                    egui::TextEdit::singleline(&mut qo.query)
+                        .hint_text("search fixtures, cues, groups, palettes…")
+                        .desired_width(360.0),
+                );
+                resp.request_focus();
+
+                if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    chosen = hits.first().cloned();
+                }
+
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for hit in &hits {
+                            if ui.selectable_label(false, &hit.title).clicked() {
+                                chosen = Some(hit.clone());
+                            }
+                        }
+                        if hits.is_empty() && !qo.query.trim().is_empty() {
+                            ui.label("(no matches)");
+                        }
+                    });
+            });
+
+        if let Some(hit) = chosen {
+            self.jump_to(&hit);
+            close = true;
+        }
+        if close {
+            self.quick_open = None;
+        }
+    }
+
+    fn save_layout(&mut self) {
+        if let Err(e) = save_layout(&self.layout_path, &self.layout) {
+            eprintln!("Failed to save layout: {e}");
+        } else {
+            self.layout_dirty = false;
+        }
+    }
+
+    fn save_show(&mut self) {
+        self.show_dirty = false;
+        self.show_saver.save_now(&self.rt.show);
+    }
+
+    /// Hand show edits to the writer as the show's save policy says, and
+    /// write the layout once it has waited `AUTOSAVE_SECS` or right away
+    /// when the window is closing.
+    fn autosave(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|i| i.time);
+        let closing = ctx.input(|i| i.viewport().close_requested());
+        if std::mem::take(&mut self.show_dirty) {
+            self.show_saver.edited(&self.rt.show);
+        }
+        let mut done = self.show_saver.poll(&self.rt.show);
+        if closing {
+            done.extend(self.show_saver.finish(&self.rt.show));
+        }
+        for d in done {
+            match d.result {
+                Ok(()) => self.watcher.mark_seen(),
+                Err(e) => self.programmer_ui.log.push(format!("Save failed: {e:#}")),
+            }
+        }
+        if self.show_saver.is_unsaved() {
+            // timed saves and finished writes arrive without input
+            ctx.request_repaint_after(std::time::Duration::from_secs(1));
+        }
+
+        if !self.layout_dirty {
+            self.last_autosave = now;
+        } else if closing || now - self.last_autosave >= AUTOSAVE_SECS {
+            self.save_layout();
+            self.last_autosave = now;
+        } else {
+            let wait = AUTOSAVE_SECS - (now - self.last_autosave);
+            ctx.request_repaint_after(std::time::Duration::from_secs_f64(wait));
+        }
+
+        let show_unsaved = self.show_saver.is_unsaved();
+        let unsaved: Vec<&str> = [(show_unsaved, "show"), (self.layout_dirty, "layout")]
+            .into_iter()
+            .filter_map(|(dirty, what)| dirty.then_some(what))
+            .collect();
+        let title = if unsaved.is_empty() {
+            TITLE.to_string()
+        } else {
+            format!("{TITLE} (unsaved {})", unsaved.join(" + "))
+        };
+        if title != self.title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.title = title;
+        }
+    }
+
+    fn add_container_fill_row(&mut self, kind: ContainerKind) {
+        // default size
+        let w = self.layout.cols;
+        let h = MIN_H;
+
+        // find first y where a full-row container of height h fits without overlap
+        let mut placed: Option<i32> = None;
+        'outer: for y in 0..=(self.layout.rows - h) {
+            let candidate = (0, y, w, h);
+            for c in &self.layout.containers {
+                if rects_intersect(candidate, c.rect_cells()) {
+                    continue 'outer;
+                }
+            }
+            placed = Some(y);
+            break;
+        }
+
+        let Some(y) = placed else {
+            // No space on this page
+            return;
+        };
+
+        let id = self.layout.next_id;
+        self.layout.next_id += 1;
+
+        let title = kind.title().to_string();
+
+        let mut c = Container {
+            id,
+            kind,
+            title,
+            x: 0,
+            y,
+            w,
+            h,
+            cells: vec![None; (w * h) as usize],
+            color: None,
+        };
+        c.ensure_cells_len();
+        if kind == ContainerKind::Palettes {
+            // the built-in palettes come first, after the title cell
+            for (i, pal) in console_core::SystemPalette::ALL.iter().enumerate() {
+                let name = pal.name().to_string();
+                c.set_cell(i as i32 + 1, 0, Some(CellItem::Palette { name }));
+            }
+        }
+        self.layout.containers.push(c);
+
+        self.selected_id = Some(id);
+        self.layout_dirty = true;
+    }
+
+    /// Move every bulk-selected container by whole cells. Nothing moves if
+    /// any of them would leave the grid or land on another container.
+    fn move_block(&mut self, dx: i32, dy: i32) -> bool {
+        if dx == 0 && dy == 0 {
+            return true;
+        }
+        let (cols, rows) = (self.layout.cols, self.layout.rows);
+        let ids = &self.multi.containers;
+        let fits =
+            self.layout
+                .containers
+                .iter()
+                .filter(|c| ids.contains(&c.id))
+                .all(|c| {
+                    let (x, y) = (c.x + dx, c.y + dy);
+                    let candidate = (x, y, c.w, c.h);
+                    x >= 0
+                        && y >= 0
+                        && x + c.w <= cols
+                        && y + c.h <= rows
+                        && !self.layout.containers.iter().any(|o| {
+                            !ids.contains(&o.id) && rects_intersect(candidate, o.rect_cells())
+                        })
+                });
+        if !fits {
+            return false;
+        }
+        for c in &mut self.layout.containers {
+            if ids.contains(&c.id) {
+                c.x += dx;
+                c.y += dy;
+            }
+        }
+        self.layout_dirty = true;
+        true
+    }
+
+    /// Empty the shift-selected cells and every cell of the shift-selected
+    /// containers.
+    fn clear_marked_cells(&mut self) {
+        let mut cleared = 0;
+        for c in &mut self.layout.containers {
+            c.ensure_cells_len();
+            let whole = self.multi.containers.contains(&c.id);
+            for y in 0..c.h {
+                for x in 0..c.w {
+                    if (whole || self.multi.cells.contains(&(c.id, x, y)))
+                        && c.get_cell(x, y).is_some()
+                    {
+                        c.set_cell(x, y, None);
+                        cleared += 1;
+                    }
+                }
+            }
+        }
+        if cleared > 0 {
+            self.layout_dirty = true;
+        }
+        self.programmer_ui
+            .log
+            .push(format!("Cleared {cleared} cells"));
+    }
+
+    fn delete_marked_containers(&mut self) {
+        let ids = std::mem::take(&mut self.multi.containers);
+        self.layout.containers.retain(|c| !ids.contains(&c.id));
+        if self.selected_id.is_some_and(|id| ids.contains(&id)) {
+            self.selected_id = None;
+            self.selected_cell = None;
+        }
+        self.multi.clear();
+        self.layout_dirty = true;
+        self.programmer_ui
+            .log
+            .push(format!("Deleted {} containers", ids.len()));
+    }
+
+    /// Bulk operations on the shift-click selection.
+    fn selection_window(&mut self, ctx: &egui::Context) {
+        if self.multi.is_empty() {
+            return;
+        }
+        let mut open = true;
+        let mut clear = false;
+        let mut recolor: Option<Option<[u8; 3]>> = None;
+        let mut step = None;
+        let mut delete = false;
+        let containers: Vec<&Container> = self
+            .layout
+            .containers
+            .iter()
+            .filter(|c| self.multi.containers.contains(&c.id))
+            .collect();
+        egui::Window::new("Selection")
+            .open(&mut open)
+            .default_width(240.0)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} containers, {} cells (shift-click to add or remove)",
+                    containers.len(),
+                    self.multi.cells.len()
+                ));
+                if ui.button("Clear cells").clicked() {
+                    clear = true;
+                }
+                if containers.is_empty() {
+                    return;
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Color");
+                    let mut rgb = containers[0].color.unwrap_or([45, 46, 50]);
+                    if egui::color_picker::color_edit_button_srgb(ui, &mut rgb).changed() {
+                        recolor = Some(Some(rgb));
+                    }
+                    if ui.button("Default").clicked() {
+                        recolor = Some(None);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Move");
+                    for (label, d) in [("←", (-1, 0)), ("→", (1, 0)), ("↑", (0, -1)), ("↓", (0, 1))]
+                    {
+                        if ui.button(label).clicked() {
+                            step = Some(d);
+                        }
+                    }
+                });
+                ui.separator();
+                if self.multi.confirm_delete {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Delete {} containers?", containers.len()));
+                        if ui.button("Delete").clicked() {
+                            delete = true;
+                        }
+                        if ui.button("Keep").clicked() {
+                            step = Some((0, 0));
+                        }
+                    });
+                } else if ui.button("Delete containers").clicked() {
+                    delete = true;
+                }
+            });
+
+        if clear {
+            self.clear_marked_cells();
+        }
+        if let Some(color) = recolor {
+            for c in &mut self.layout.containers {
+                if self.multi.containers.contains(&c.id) {
+                    c.color = color;
+                }
+            }
+            self.layout_dirty = true;
+        }
+        if let Some((dx, dy)) = step {
+            self.multi.confirm_delete = false;
+            if !self.move_block(dx, dy) {
+                self.programmer_ui
+                    .log
+                    .push("The selection doesn't fit there".into());
+            }
+        }
+        if delete {
+            if self.multi.confirm_delete {
+                self.delete_marked_containers();
+            } else {
+                self.multi.confirm_delete = true;
+            }
+        }
+        if !open {
+            self.multi.clear();
+        }
+    }
+}
+
+impl eframe::App for GridApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if let Some(rx) = &mut self.dmx_in {
+            match rx.receive() {
+                Ok(packets) => self.rt.feed_dmx_input(&packets),
+                Err(e) => self.programmer_ui.log.push(format!("{e:#}")),
+            }
+            // keep merging while the other desk sends
+            ctx.request_repaint_after(std::time::Duration::from_millis(25));
+        }
+        self.poll_midi(ctx);
+        self.poll_hid(ctx);
+        self.rt.advance(u32::MAX);
+        self.tick_timeline();
+        let timecode = Some((self.timeline.frame, self.timeline.playing.is_some()));
+        for c in self.cue_warner.poll(&self.rt, timecode) {
+            self.programmer_ui
+                .log
+                .push(format!("Cue {} in {}", c.cue, fmt_secs(c.in_ms as u32)));
+            if c.beep {
+                beep();
+            }
+        }
+        if self.rt.playbacks["a"].transition_info().is_some()
+            || self.rt.focus.as_ref().is_some_and(|f| f.is_fading())
+            || self.rt.gm_fade_remaining().is_some()
+            || self.timeline.playing.is_some()
+            || !self.cue_warner.running().is_empty()
+        {
+            ctx.request_repaint();
+        }
+        let follows = self
+            .rt
+            .playbacks
+            .values()
+            .filter_map(|pb| pb.follow_remaining_ms(&self.rt.show))
+            .min();
+        if let Some(ms) = follows {
+            // often enough to start cue warnings on time
+            let ms = ms.min(250);
+            ctx.request_repaint_after(std::time::Duration::from_millis(ms as u64));
+        }
+
+        self.quick_open_window(ctx);
+
+        self.problems_window(ctx);
+        self.channel_check_window(ctx);
+        self.fixture_sheet_window(ctx);
+        self.limits_window(ctx);
+        self.midi_window(ctx);
+        self.show_info_window(ctx);
+        self.selection_window(ctx);
+        self.hid_window(ctx);
+        self.masters_window(ctx);
+        self.agenda_window(ctx);
+        self.programmer_window(ctx);
+        self.cue_sheet_window(ctx);
+        self.outputs_window(ctx);
+        self.poll_show_log(ctx);
+        self.log_window(ctx);
+        self.drive_output(ctx);
+        self.release_prompt_window(ctx);
+        self.quick_record_window(ctx);
+        self.hookup_window(ctx);
+        self.patch_window(ctx);
+        self.autosave(ctx);
+
+        // bound keys fire while no text field has focus
+        if !ctx.wants_keyboard_input() {
+            let fired: Vec<String> = self
+                .layout
+                .keys
+                .iter()
+                .filter(|(name, _)| {
+                    egui::Key::from_name(name).is_some_and(|key| {
+                        ctx.input(|i| i.modifiers.is_none() && i.key_pressed(key))
+                    })
+                })
+                .map(|(_, command)| command.clone())
+                .collect();
+            for command in fired {
+                self.run_command(&command);
+            }
+        }
 
-        self.selected_id = Some(id);
-        self.dirty = true;
-    }
-}
+        // Ctrl+Shift+P: panic on/off
+        if ctx.input_mut(|i| {
+            i.consume_shortcut(&egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+                egui::Key::P,
+            ))
+        }) {
+            self.rt.set_panic(!self.rt.is_panic());
+            self.panic_armed = false;
+        }
+
+        egui::Window::new("About")
+            .open(&mut self.show_about)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let info = console_core::build_info();
+                ui.heading("LightConsole");
+                ui.label(format!("Version {}", info.version));
+                ui.label(format!("Show format {}", info.show_format));
+                ui.label(format!("{} build, {}", info.profile, info.target_os));
+            });
 
-impl eframe::App for GridApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Top bar
         egui::TopBottomPanel::top("top_bar").show(ctx, |ui| {
             ui.horizontal(|ui| {
                 ui.label(format!(
-                    "Show: {}",
+                    "Show: {}{}",
                     self.show_path
                         .file_name()
                         .and_then(|s| s.to_str())
-                        .unwrap_or("?")
+                        .unwrap_or("?"),
+                    if self.show_saver.is_unsaved() {
+                        " (modified)"
+                    } else {
+                        ""
+                    }
                 ));
                 ui.label("•");
                 ui.label(format!(
@@ -407,7 +3786,7 @@ impl eframe::App for GridApp {
                         .file_name()
                         .and_then(|s| s.to_str())
                         .unwrap_or("layout.json"),
-                    if self.dirty { " (modified)" } else { "" }
+                    if self.layout_dirty { " (modified)" } else { "" }
                 ));
 
                 ui.separator();
@@ -421,12 +3800,385 @@ impl eframe::App for GridApp {
                 if ui.button("+ Palettes row").clicked() {
                     self.add_container_fill_row(ContainerKind::Palettes);
                 }
+                if ui.button("+ Macros row").clicked() {
+                    self.add_container_fill_row(ContainerKind::Macros);
+                }
+                if ui.button("+ Playbacks row").clicked() {
+                    self.add_container_fill_row(ContainerKind::Playbacks);
+                }
 
                 ui.separator();
 
+                if ui.button("Save Show").clicked() {
+                    self.save_show();
+                }
+
                 if ui.button("Save Layout").clicked() {
                     self.save_layout();
                 }
+
+                if ui.button("Masters").clicked() {
+                    self.show_masters = !self.show_masters;
+                }
+
+                if ui.button("Agenda").clicked() {
+                    self.show_agenda = !self.show_agenda;
+                }
+
+                if ui.button("Programmer").clicked() {
+                    self.show_programmer = !self.show_programmer;
+                }
+
+                if ui.button("Cue sheet").clicked() {
+                    self.cue_sheet = match self.cue_sheet {
+                        Some(_) => None,
+                        None => Some(self.build_cue_sheet(ctx)),
+                    };
+                }
+
+                if ui.button("Log").clicked() {
+                    self.log_draft = match self.log_draft {
+                        Some(_) => None,
+                        None => Some(String::new()),
+                    };
+                }
+
+                if ui.button("Outputs").clicked() {
+                    self.outputs_ui = match self.outputs_ui {
+                        Some(_) => None,
+                        None => Some(OutputsUi::default()),
+                    };
+                }
+
+                if ui.button("Patch").clicked() {
+                    self.patch_view = match self.patch_view {
+                        Some(_) => None,
+                        None => Some(PatchSort::default()),
+                    };
+                }
+
+                if ui.button("Import hookup…").clicked() {
+                    self.hookup = Some(HookupWizard::default());
+                }
+
+                if ui.button("Fixtures").clicked() {
+                    self.show_fixture_sheet = !self.show_fixture_sheet;
+                }
+
+                if ui.button("Problems").clicked() {
+                    self.problems = Some(self.rt.show.lint());
+                }
+
+                if ui.button("Channel check").clicked() {
+                    self.channel_check = Some(ChannelCheck::default());
+                }
+
+                if ui.button("MIDI").clicked() {
+                    self.midi_ui = Some(MidiUi {
+                        device: self
+                            .midi_map
+                            .device
+                            .as_ref()
+                            .map(|d| d.display().to_string())
+                            .unwrap_or_default(),
+                        ..Default::default()
+                    });
+                }
+
+                if ui.button("HID").clicked() {
+                    self.hid_ui = Some(HidUi {
+                        device: self
+                            .hid_map
+                            .device
+                            .as_ref()
+                            .map(|d| d.display().to_string())
+                            .unwrap_or_default(),
+                        keyboard: self.hid_map.format == console_core::HidFormat::Keyboard,
+                        ..Default::default()
+                    });
+                }
+
+                if ui.button("Show info").clicked() {
+                    self.show_info = Some(self.rt.show.stats());
+                }
+
+                if ui.button("About").clicked() {
+                    self.show_about = true;
+                }
+            });
+
+            if let Some(warning) = &self.compat_warning {
+                let mut dismiss = false;
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!("⚠ {warning}"))
+                            .color(egui::Color32::from_rgb(240, 170, 40)),
+                    );
+                    dismiss = ui.button("Dismiss").clicked();
+                });
+                if dismiss {
+                    self.compat_warning = None;
+                }
+            }
+
+            // Cue stack preview for playback A
+            let status = self.rt.playbacks["a"].status(&self.rt.show);
+            ui.horizontal(|ui| {
+                if self.go_confirm {
+                    let confirm = egui::Button::new(
+                        egui::RichText::new("CONFIRM GO").color(egui::Color32::WHITE),
+                    )
+                    .fill(egui::Color32::from_rgb(170, 40, 40));
+                    if ui.add_sized([110.0, 28.0], confirm).clicked() {
+                        self.confirm_go();
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.go_confirm = false;
+                    }
+                } else if let Some(n) = self.rt.playbacks["a"].armed() {
+                    let fire = egui::Button::new(
+                        egui::RichText::new(format!("FIRE CUE {n}")).color(egui::Color32::WHITE),
+                    )
+                    .fill(egui::Color32::from_rgb(170, 40, 40));
+                    if ui.add_sized([110.0, 28.0], fire).clicked() {
+                        self.press_go();
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.rt.playbacks["a"].disarm();
+                    }
+                } else {
+                    let go = egui::Button::new(egui::RichText::new("GO").strong())
+                        .fill(egui::Color32::from_rgb(40, 110, 60));
+                    if ui.add_sized([110.0, 28.0], go).clicked() {
+                        self.press_go();
+                    }
+                }
+
+                ui.separator();
+
+                let current = match &status.current {
+                    Some(c) => format!("Cue {} {}", c.number, c.label),
+                    None => "Cue -".to_string(),
+                };
+                ui.label(egui::RichText::new(current).strong());
+
+                if let Some(ms) = status.remaining_ms {
+                    ui.label(
+                        egui::RichText::new(format!("running {}", fmt_secs(ms)))
+                            .color(egui::Color32::from_rgb(220, 190, 40)),
+                    );
+                }
+
+                ui.separator();
+
+                match &status.next {
+                    Some(n) => {
+                        let mut next = format!(
+                            "Next: {} {} · fade {}",
+                            n.number,
+                            n.label,
+                            fmt_secs(n.fade_ms)
+                        );
+                        if n.delay_ms > 0 {
+                            next.push_str(&format!(" · delay {}", fmt_secs(n.delay_ms)));
+                        }
+                        ui.label(next);
+                        if n.confirm {
+                            ui.label(
+                                egui::RichText::new("needs confirm")
+                                    .color(egui::Color32::from_rgb(220, 80, 60)),
+                            );
+                        }
+                    }
+                    None if status.current.is_some()
+                        && self
+                            .rt
+                            .show
+                            .cue_lists
+                            .get(&self.rt.playbacks["a"].cuelist)
+                            .is_some_and(|l| l.auto_black) =>
+                    {
+                        ui.label("Next: release (auto-black)");
+                    }
+                    None => {
+                        ui.label("Next: (end of list)");
+                    }
+                }
+
+                ui.separator();
+                self.crossfader_ui(ui);
+
+                if let Some(c) = self.cue_warner.running().first() {
+                    ui.separator();
+                    let amber = egui::Color32::from_rgb(240, 170, 40);
+                    let text =
+                        egui::RichText::new(format!("Q{} in {}", c.cue, fmt_secs(c.in_ms as u32)))
+                            .strong();
+                    // flash at 2 Hz
+                    ui.label(if (self.rt.now_ms() / 250).is_multiple_of(2) {
+                        text.color(egui::Color32::BLACK).background_color(amber)
+                    } else {
+                        text.color(amber)
+                    });
+                }
+
+                if let Some(focus) = &self.rt.focus {
+                    let (at, count) = focus.position();
+                    ui.separator();
+                    ui.label(
+                        egui::RichText::new(format!("Focus {} ({at}/{count})", focus.current()))
+                            .color(egui::Color32::from_rgb(220, 190, 40)),
+                    );
+                    for (label, command) in [
+                        ("◀", "focus prev"),
+                        ("▶", "focus next"),
+                        ("End", "focus stop"),
+                    ] {
+                        if ui.small_button(label).clicked() {
+                            self.run_command(command);
+                        }
+                    }
+                }
+
+                let list = self.rt.playbacks["a"].cuelist.clone();
+                if let Some(mut mib) = self.rt.show.cue_lists.get(&list).map(|l| l.move_in_black) {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .checkbox(&mut mib, "Move in black")
+                            .on_hover_text("Preset the next cue's color on fixtures that are dark")
+                            .changed()
+                            && self.role.allows(console_core::CommandClass::ShowEdit)
+                            && let Some(l) = self.rt.show.cue_lists.get_mut(&list)
+                        {
+                            l.move_in_black = mib;
+                            self.show_dirty = true;
+                        }
+                        let marked = self.rt.playbacks["a"]
+                            .marks(&self.rt.show)
+                            .map_or(0, |m| m.len());
+                        if marked > 0 {
+                            ui.weak(format!("{marked} fixture(s) marked"));
+                        }
+                    });
+                }
+
+                ui.separator();
+                for (name, pb) in self.rt.playbacks.iter_mut() {
+                    let name = name.to_uppercase();
+                    let solo = pb.solo;
+                    let solo_btn = egui::Button::new(if solo {
+                        format!("SOLO {name}")
+                    } else {
+                        format!("Solo {name}")
+                    })
+                    .fill(if solo {
+                        egui::Color32::from_rgb(170, 120, 30)
+                    } else {
+                        egui::Color32::from_gray(60)
+                    });
+                    if ui.add(solo_btn).clicked() {
+                        pb.solo = !solo;
+                    }
+                    // momentary: full only while the button is held
+                    let label = egui::RichText::new(format!("Flash {name}"));
+                    let flash_btn = if pb.flash {
+                        egui::Button::new(label.color(egui::Color32::BLACK))
+                            .fill(egui::Color32::from_rgb(230, 230, 230))
+                    } else {
+                        egui::Button::new(label).fill(egui::Color32::from_gray(60))
+                    }
+                    .sense(egui::Sense::click_and_drag());
+                    pb.flash = ui.add(flash_btn).is_pointer_button_down_on();
+                }
+
+                // the output thread holds the frame; nothing to freeze without one
+                let frozen = self.output.as_ref().is_some_and(|o| o.is_frozen());
+                let freeze =
+                    egui::Button::new(if frozen { "FROZEN" } else { "Freeze" }).fill(if frozen {
+                        egui::Color32::from_rgb(40, 90, 170)
+                    } else {
+                        egui::Color32::from_gray(60)
+                    });
+                if ui.add_enabled(self.output.is_some(), freeze).clicked()
+                    && let Some(out) = &self.output
+                {
+                    if frozen {
+                        out.unfreeze(1000);
+                    } else {
+                        out.freeze();
+                    }
+                }
+
+                ui.separator();
+                let latched = self.rt.is_blackout_latched();
+                let dbo = egui::Button::new(if latched { "DBO ON" } else { "DBO" })
+                    .selected(latched)
+                    .fill(if latched {
+                        egui::Color32::from_rgb(170, 40, 40)
+                    } else {
+                        egui::Color32::from_gray(60)
+                    });
+                if ui
+                    .add(dbo)
+                    .on_hover_text("Dead blackout: all intensities out until clicked again")
+                    .clicked()
+                {
+                    self.rt.set_blackout(!latched);
+                }
+                if let Some((ms, true)) = self.rt.gm_fade_remaining() {
+                    ui.label(
+                        egui::RichText::new(format!("BO in {}", fmt_secs(ms)))
+                            .color(egui::Color32::from_rgb(220, 80, 60)),
+                    );
+                }
+                let flash = ui
+                    .button("BO (hold)")
+                    .on_hover_text("Blackout while the button is held");
+                self.rt.hold_blackout(flash.is_pointer_button_down_on());
+
+                ui.separator();
+                if self.rt.is_panic() {
+                    let release = egui::Button::new(
+                        egui::RichText::new("RELEASE PANIC").color(egui::Color32::WHITE),
+                    )
+                    .fill(egui::Color32::from_rgb(170, 40, 40));
+                    if ui.add(release).clicked() {
+                        self.rt.set_panic(false);
+                    }
+                } else if self.panic_armed {
+                    let fire = egui::Button::new(
+                        egui::RichText::new("PANIC")
+                            .strong()
+                            .color(egui::Color32::WHITE),
+                    )
+                    .fill(egui::Color32::from_rgb(170, 40, 40));
+                    if ui.add(fire).clicked() {
+                        self.rt.set_panic(true);
+                        self.panic_armed = false;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.panic_armed = false;
+                    }
+                } else if ui
+                    .button("Panic…")
+                    .on_hover_text("House lights on, stage out (Ctrl+Shift+P)")
+                    .clicked()
+                {
+                    self.panic_armed = true;
+                }
+
+                let tester = self.rt.tester.nonzero().len();
+                if tester > 0 {
+                    ui.separator();
+                    ui.label(
+                        egui::RichText::new(format!("DMX TESTER ({tester})"))
+                            .strong()
+                            .color(egui::Color32::from_rgb(230, 120, 40)),
+                    );
+                    if ui.button("Clear").clicked() {
+                        self.rt.clear_dmx();
+                    }
+                }
             });
         });
 
@@ -461,10 +4213,13 @@ impl eframe::App for GridApp {
                     let enter_pressed =
                         resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
 
-                    if ui.button("Enter").clicked() || enter_pressed {
-                        self.programmer_ui.submit();
+                    if (ui.button("Enter").clicked() || enter_pressed)
+                        && let Some(cmd) = self.programmer_ui.submit()
+                    {
+                        self.run_command(&cmd);
                     }
                 });
+                self.programmer_line_feedback(ui);
 
                 ui.separator();
 
@@ -488,6 +4243,12 @@ impl eframe::App for GridApp {
                     }
                 });
 
+                let rgb_before = [
+                    self.programmer_ui.r,
+                    self.programmer_ui.g,
+                    self.programmer_ui.b,
+                ];
+                let mut color_released = false;
                 ui.horizontal(|ui| {
                     let bank = self.programmer_ui.bank;
 
@@ -496,8 +4257,9 @@ impl eframe::App for GridApp {
                             EncoderBank::Color => self.programmer_ui.r,
                             _ => self.programmer_ui.intensity,
                         };
+                        let before = self.programmer_ui.intensity;
                         ui.label(format!("{v}"));
-                        knob_u8(
+                        let knob = knob_u8(
                             ui,
                             ui.id().with("knob1"),
                             match bank {
@@ -506,10 +4268,18 @@ impl eframe::App for GridApp {
                             },
                             true,
                         );
+                        color_released |= bank == EncoderBank::Color && knob.drag_stopped();
                         ui.label(match bank {
                             EncoderBank::Color => "R",
                             EncoderBank::Intensity => "I",
                         });
+                        // the intensity encoder drives the level wheel
+                        let delta = self.programmer_ui.intensity as i16 - before as i16;
+                        if delta != 0
+                            && let Err(e) = self.rt.nudge_intensity(delta)
+                        {
+                            self.programmer_ui.log.push(format!("{e:#}"));
+                        }
                     });
 
                     ui.add_space(8.0);
@@ -517,12 +4287,13 @@ impl eframe::App for GridApp {
                     ui.vertical_centered(|ui| {
                         let enabled = bank == EncoderBank::Color;
                         ui.label(format!("{}", self.programmer_ui.g));
-                        knob_u8(
+                        color_released |= knob_u8(
                             ui,
                             ui.id().with("knob2"),
                             &mut self.programmer_ui.g,
                             enabled,
-                        );
+                        )
+                        .drag_stopped();
                         ui.label("G");
                     });
 
@@ -531,16 +4302,35 @@ impl eframe::App for GridApp {
                     ui.vertical_centered(|ui| {
                         let enabled = bank == EncoderBank::Color;
                         ui.label(format!("{}", self.programmer_ui.b));
-                        knob_u8(
+                        color_released |= knob_u8(
                             ui,
                             ui.id().with("knob3"),
                             &mut self.programmer_ui.b,
                             enabled,
-                        );
+                        )
+                        .drag_stopped();
                         ui.label("B");
                     });
                 });
 
+                // color encoders drive the programmer; a released knob counts as "used"
+                let rgb = [
+                    self.programmer_ui.r,
+                    self.programmer_ui.g,
+                    self.programmer_ui.b,
+                ];
+                if rgb != rgb_before {
+                    let p = &mut self.rt.programmer;
+                    (p.r, p.g, p.b) = (Some(rgb[0]), Some(rgb[1]), Some(rgb[2]));
+                }
+                if color_released {
+                    self.rt.programmer.remember_color(rgb);
+                }
+
+                if self.programmer_ui.bank == EncoderBank::Color {
+                    self.color_swatches(ui);
+                }
+
                 ui.separator();
 
                 // ----- Keypad + shortcuts -----
@@ -622,8 +4412,9 @@ impl eframe::App for GridApp {
                             if ui
                                 .add_sized(egui::vec2(enter_w, key.y), egui::Button::new("Enter"))
                                 .clicked()
+                                && let Some(cmd) = self.programmer_ui.submit()
                             {
-                                self.programmer_ui.submit();
+                                self.run_command(&cmd);
                             }
                         });
                     });
@@ -643,6 +4434,9 @@ impl eframe::App for GridApp {
                         if ui.add_sized(b, egui::Button::new("Delete")).clicked() {
                             self.programmer_ui.push_token("delete");
                         }
+                        if ui.add_sized(b, egui::Button::new("Group")).clicked() {
+                            self.programmer_ui.push_token("group");
+                        }
 
                         ui.separator();
 
@@ -654,6 +4448,19 @@ impl eframe::App for GridApp {
                             self.programmer_ui.bank = EncoderBank::Intensity;
                             self.programmer_ui.push_token("intensity");
                         }
+                        ui.horizontal(|ui| {
+                            let third =
+                                egui::vec2((b.x - 2.0 * ui.spacing().item_spacing.x) / 3.0, 28.0);
+                            if ui.add_sized(third, egui::Button::new("Clear I")).clicked() {
+                                self.run_command("clear intensity");
+                            }
+                            if ui.add_sized(third, egui::Button::new("Clear C")).clicked() {
+                                self.run_command("clear color");
+                            }
+                            if ui.add_sized(third, egui::Button::new("Clear P")).clicked() {
+                                self.run_command("clear position");
+                            }
+                        });
 
                         ui.separator();
 
@@ -667,6 +4474,10 @@ impl eframe::App for GridApp {
                 });
             });
 
+        egui::TopBottomPanel::bottom("timeline").show(ctx, |ui| {
+            self.timeline_panel(ui);
+        });
+
         // Main canvas
         egui::CentralPanel::default().show(ctx, |ui| {
             // Scrollable canvas (so fixed CELL_PX works on smaller windows)
@@ -765,152 +4576,254 @@ impl eframe::App for GridApp {
                         }
                     }
 
-                    if left_pressed {
-                        if let Some(pos) = pointer_pos {
-                            if let Some(id) = hit_handle {
-                                self.selected_id = Some(id);
-                                self.selected_cell = None;
-                                self.drag = DragState::Resize { id };
-                            } else if let Some((id, cx, cy)) = hit_cell {
-                                self.selected_id = Some(id);
-                                self.selected_cell = Some((id, cx, cy));
-
-                                // header cell (0,0) -> move only
-                                if cx == 0 && cy == 0 {
-                                    let Some(c) =
-                                        self.layout.containers.iter().find(|c| c.id == id)
-                                    else {
-                                        return;
-                                    };
-                                    let r = container_rect_px(origin, c);
-                                    let grab_offset = pos - r.min;
-                                    self.drag = DragState::Move {
-                                        id,
-                                        grab_offset_px: grab_offset,
-                                    };
-                                } else {
-                                    // body cell -> place placeholder if empty
-                                    self.drag = DragState::None;
-
-                                    if let Some(idx) =
-                                        self.layout.containers.iter().position(|c| c.id == id)
-                                    {
-                                        let c = &mut self.layout.containers[idx];
-                                        c.ensure_cells_len();
-                                        if c.get_cell(cx, cy).is_none() {
-                                            let label = match c.kind {
-                                                ContainerKind::Cues => {
-                                                    let s = format!("Cue {}", self.next_cue);
-                                                    self.next_cue += 1;
-                                                    s
-                                                }
-                                                ContainerKind::Groups => {
-                                                    let s = format!("Grp {}", self.next_group);
-                                                    self.next_group += 1;
-                                                    s
-                                                }
-                                                ContainerKind::Palettes => {
-                                                    let s = format!("Pal {}", self.next_palette);
-                                                    self.next_palette += 1;
-                                                    s
-                                                }
-                                            };
-                                            c.set_cell(
-                                                cx,
-                                                cy,
-                                                Some(CellItem::Placeholder { label }),
-                                            );
-                                            self.dirty = true;
-                                        }
-                                    }
-                                }
-                            } else {
-                                self.selected_id = None;
-                                self.selected_cell = None;
-                                self.drag = DragState::None;
+                    let shift = ctx.input(|i| i.modifiers.shift);
+                    if left_pressed
+                        && shift
+                        && let Some((id, cx, cy)) = hit_cell
+                    {
+                        // shift-click builds the bulk selection, starting from the
+                        // single one; headers pick containers, body cells pick cells
+                        if cx == 0 && cy == 0 {
+                            if self.multi.containers.is_empty()
+                                && let Some(sel) = self.selected_id
+                            {
+                                self.multi.containers.insert(sel);
                             }
+                            MultiSelection::toggle(&mut self.multi.containers, id);
+                        } else {
+                            if self.multi.cells.is_empty()
+                                && let Some(cell) = self.selected_cell
+                                && (cell.1, cell.2) != (0, 0)
+                            {
+                                self.multi.cells.insert(cell);
+                            }
+                            MultiSelection::toggle(&mut self.multi.cells, (id, cx, cy));
                         }
-                    }
+                        self.multi.confirm_delete = false;
+                        self.selected_id = Some(id);
+                        self.selected_cell = Some((id, cx, cy));
+                        self.drag = DragState::None;
+                    } else if left_pressed && let Some(pos) = pointer_pos {
+                        // a plain click drops the bulk selection, except dragging
+                        // one of its headers, which moves the block
+                        let grabs_block = matches!(hit_cell, Some((id, 0, 0))
+                            if self.multi.containers.contains(&id));
+                        if !grabs_block {
+                            self.multi.clear();
+                        }
+                        if let Some(id) = hit_handle {
+                            self.selected_id = Some(id);
+                            self.selected_cell = None;
+                            self.drag = DragState::Resize { id };
+                        } else if let Some((id, cx, cy)) = hit_cell {
+                            self.selected_id = Some(id);
+                            self.selected_cell = Some((id, cx, cy));
+
+                            // header cell (0,0) -> move only
+                            if cx == 0 && cy == 0 {
+                                let Some(c) = self.layout.containers.iter().find(|c| c.id == id)
+                                else {
+                                    return;
+                                };
+                                let r = container_rect_px(origin, c);
+                                let grab_offset = pos - r.min;
+                                self.drag = DragState::Move {
+                                    id,
+                                    grab_offset_px: grab_offset,
+                                };
+                            } else {
+                                // body cell -> place placeholder if empty
+                                self.drag = DragState::None;
 
-                    if right_pressed {
-                        if let Some((id, cx, cy)) = hit_cell {
-                            if !(cx == 0 && cy == 0) {
                                 if let Some(idx) =
                                     self.layout.containers.iter().position(|c| c.id == id)
                                 {
+                                    self.layout.containers[idx].ensure_cells_len();
+                                    let unbound =
+                                        self.next_unbound_group(&self.layout.containers[idx]);
                                     let c = &mut self.layout.containers[idx];
-                                    c.ensure_cells_len();
-                                    c.set_cell(cx, cy, None);
-                                    self.selected_id = Some(id);
-                                    self.selected_cell = Some((id, cx, cy));
-                                    self.dirty = true;
+                                    // empty pool cell + programmer content: record there
+                                    let quick_record = matches!(
+                                        c.kind,
+                                        ContainerKind::Cues | ContainerKind::Palettes
+                                    ) && c.get_cell(cx, cy).is_none()
+                                        && !self.rt.programmer.contents().is_empty();
+                                    if let Some(CellItem::Group { name }) = c.get_cell(cx, cy) {
+                                        let name = name.clone();
+                                        self.select_group(&name);
+                                    } else if let Some(CellItem::Macro { command }) =
+                                        c.get_cell(cx, cy)
+                                    {
+                                        let command = command.clone();
+                                        self.run_command(&command);
+                                    } else if let Some(CellItem::Playback { action }) =
+                                        c.get_cell(cx, cy)
+                                    {
+                                        let action = *action;
+                                        self.run_command(action.command());
+                                    } else if let Some(CellItem::Palette { name }) =
+                                        c.get_cell(cx, cy)
+                                    {
+                                        let name = name.clone();
+                                        self.apply_palette(&name);
+                                    } else if let Some(CellItem::Cue { number, .. }) =
+                                        c.get_cell(cx, cy)
+                                    {
+                                        let number = *number;
+                                        self.run_command(&format!("goto {number}"));
+                                    } else if quick_record {
+                                        let container = c.clone();
+                                        self.offer_quick_record(&container, cx, cy);
+                                    } else if c.kind == ContainerKind::Macros
+                                        && c.get_cell(cx, cy).is_none()
+                                    {
+                                        match self.last_command.clone() {
+                                            Some(command) => {
+                                                c.set_cell(
+                                                    cx,
+                                                    cy,
+                                                    Some(CellItem::Macro { command }),
+                                                );
+                                                self.layout_dirty = true;
+                                            }
+                                            None => self.programmer_ui.log.push(
+                                                "Run a command first; an empty macro cell stores the last one".into(),
+                                            ),
+                                        }
+                                    } else if c.kind == ContainerKind::Playbacks
+                                        && c.get_cell(cx, cy).is_none()
+                                    {
+                                        // fill Go, Pause, Back in turn
+                                        let bound = c
+                                            .cells
+                                            .iter()
+                                            .filter(|cell| {
+                                                matches!(cell, Some(CellItem::Playback { .. }))
+                                            })
+                                            .count();
+                                        let action = PlaybackAction::CLUSTER[bound % 3];
+                                        c.set_cell(cx, cy, Some(CellItem::Playback { action }));
+                                        self.layout_dirty = true;
+                                    } else if c.kind == ContainerKind::Groups
+                                        && c.get_cell(cx, cy).is_none()
+                                        && let Some(name) = unbound
+                                    {
+                                        c.set_cell(cx, cy, Some(CellItem::Group { name }));
+                                        self.layout_dirty = true;
+                                    } else if c.get_cell(cx, cy).is_none() {
+                                        let label = match c.kind {
+                                            ContainerKind::Cues => {
+                                                let s = format!("Cue {}", self.next_cue);
+                                                self.next_cue += 1;
+                                                s
+                                            }
+                                            ContainerKind::Groups => {
+                                                let s = format!("Grp {}", self.next_group);
+                                                self.next_group += 1;
+                                                s
+                                            }
+                                            ContainerKind::Palettes => {
+                                                let s = format!("Pal {}", self.next_palette);
+                                                self.next_palette += 1;
+                                                s
+                                            }
+                                            ContainerKind::Macros | ContainerKind::Playbacks => {
+                                                unreachable!("handled above")
+                                            }
+                                        };
+                                        c.set_cell(cx, cy, Some(CellItem::Placeholder { label }));
+                                        self.layout_dirty = true;
+                                    }
                                 }
                             }
+                        } else {
+                            self.selected_id = None;
+                            self.selected_cell = None;
+                            self.drag = DragState::None;
                         }
                     }
 
-                    // Continue drag
-                    if pointer.primary_down() {
-                        if let Some(pos) = pointer_pos {
-                            match self.drag.clone() {
-                                DragState::Move { id, grab_offset_px } => {
-                                    let Some(idx) =
-                                        self.layout.containers.iter().position(|c| c.id == id)
-                                    else {
-                                        /* container deleted? */
-                                        return;
-                                    };
-
-                                    let mut c = self.layout.containers[idx].clone();
-
-                                    // compute new top-left px, then snap to cell
-                                    let tl = pos - grab_offset_px;
-                                    let (mut new_x, mut new_y) = px_to_cell(origin, tl);
-
-                                    // clamp to bounds
-                                    new_x = new_x.clamp(0, self.layout.cols - c.w);
-                                    new_y = new_y.clamp(0, self.layout.rows - c.h);
+                    if right_pressed
+                        && let Some((id, cx, cy)) = hit_cell
+                        && !(cx == 0 && cy == 0)
+                        && let Some(idx) = self.layout.containers.iter().position(|c| c.id == id)
+                    {
+                        let c = &mut self.layout.containers[idx];
+                        c.ensure_cells_len();
+                        c.set_cell(cx, cy, None);
+                        self.selected_id = Some(id);
+                        self.selected_cell = Some((id, cx, cy));
+                        self.layout_dirty = true;
+                    }
 
-                                    // prevent overlap
-                                    let candidate = (new_x, new_y, c.w, c.h);
-                                    if !would_overlap(&self.layout.containers, id, candidate) {
-                                        c.x = new_x;
-                                        c.y = new_y;
-                                        self.layout.containers[idx] = c;
-                                        self.dirty = true;
-                                    }
+                    // Continue drag
+                    if pointer.primary_down()
+                        && let Some(pos) = pointer_pos
+                    {
+                        match self.drag.clone() {
+                            DragState::Move { id, grab_offset_px } => {
+                                let Some(idx) =
+                                    self.layout.containers.iter().position(|c| c.id == id)
+                                else {
+                                    /* container deleted? */
+                                    return;
+                                };
+
+                                let mut c = self.layout.containers[idx].clone();
+
+                                // compute new top-left px, then snap to cell
+                                let tl = pos - grab_offset_px;
+                                let (mut new_x, mut new_y) = px_to_cell(origin, tl);
+                                let block = self.multi.containers.len() > 1
+                                    && self.multi.containers.contains(&id);
+
+                                // clamp to bounds
+                                new_x = new_x.clamp(0, self.layout.cols - c.w);
+                                new_y = new_y.clamp(0, self.layout.rows - c.h);
+
+                                // prevent overlap
+                                let candidate = (new_x, new_y, c.w, c.h);
+                                if block {
+                                    // blocked steps just don't move the block
+                                    self.move_block(new_x - c.x, new_y - c.y);
+                                } else if !would_overlap(&self.layout.containers, id, candidate)
+                                {
+                                    c.x = new_x;
+                                    c.y = new_y;
+                                    self.layout.containers[idx] = c;
+                                    self.layout_dirty = true;
                                 }
-                                DragState::Resize { id } => {
-                                    let Some(idx) =
-                                        self.layout.containers.iter().position(|c| c.id == id)
-                                    else {
-                                        return;
-                                    };
-
-                                    let mut c = self.layout.containers[idx].clone();
-                                    let r = container_rect_px(origin, &c);
-
-                                    // mouse position relative to container top-left
-                                    let rel = pos - r.min;
-
-                                    // desired size in cells (ceil so you can grow immediately)
-                                    let mut new_w = ((rel.x / CELL_PX).ceil() as i32).max(MIN_W);
-                                    let mut new_h = ((rel.y / CELL_PX).ceil() as i32).max(MIN_H);
-
-                                    // clamp to bounds
-                                    new_w = new_w.clamp(MIN_W, self.layout.cols - c.x);
-                                    new_h = new_h.clamp(MIN_H, self.layout.rows - c.y);
-
-                                    // prevent overlap
-                                    let candidate = (c.x, c.y, new_w, new_h);
-                                    if !would_overlap(&self.layout.containers, id, candidate) {
-                                        c.resize_preserve(new_w, new_h);
-                                        self.layout.containers[idx] = c;
-                                        self.dirty = true;
-                                    }
+                            }
+                            DragState::Resize { id } => {
+                                let Some(idx) =
+                                    self.layout.containers.iter().position(|c| c.id == id)
+                                else {
+                                    return;
+                                };
+
+                                let mut c = self.layout.containers[idx].clone();
+                                let r = container_rect_px(origin, &c);
+
+                                // mouse position relative to container top-left
+                                let rel = pos - r.min;
+
+                                // desired size in cells (ceil so you can grow immediately)
+                                let mut new_w = ((rel.x / CELL_PX).ceil() as i32).max(MIN_W);
+                                let mut new_h = ((rel.y / CELL_PX).ceil() as i32).max(MIN_H);
+
+                                // clamp to bounds
+                                new_w = new_w.clamp(MIN_W, self.layout.cols - c.x);
+                                new_h = new_h.clamp(MIN_H, self.layout.rows - c.y);
+
+                                // prevent overlap
+                                let candidate = (c.x, c.y, new_w, new_h);
+                                if !would_overlap(&self.layout.containers, id, candidate) {
+                                    c.resize_preserve(new_w, new_h);
+                                    self.layout.containers[idx] = c;
+                                    self.layout_dirty = true;
                                 }
-                                DragState::None => {}
                             }
+                            DragState::None => {}
                         }
                     }
 
@@ -921,23 +4834,88 @@ impl eframe::App for GridApp {
 
                     // --- Render containers ---
                     // draw in insertion order; selection gets higher-contrast border
+                    let header_status = HeaderStatus::of(&self.rt.playbacks["a"], &self.rt.show);
                     for c in &self.layout.containers {
                         let sel_cell = self.selected_cell.and_then(|(id, cx, cy)| {
                             if id == c.id { Some((cx, cy)) } else { None }
                         });
+                        let marked_cells: Vec<(i32, i32)> = self
+                            .multi
+                            .cells
+                            .iter()
+                            .filter(|(id, _, _)| *id == c.id)
+                            .map(|&(_, cx, cy)| (cx, cy))
+                            .collect();
                         draw_container(
                             &painter,
                             origin,
                             c,
                             self.selected_id == Some(c.id),
                             sel_cell,
+                            |item| {
+                                if let CellItem::Playback {
+                                    action: PlaybackAction::Pause,
+                                } = item
+                                    && self.rt.playbacks["a"].is_paused()
+                                {
+                                    return CellHighlight::Full;
+                                }
+                                if let CellItem::Cue { number, .. } = item {
+                                    return if header_status.cue == Some(*number) {
+                                        CellHighlight::Full
+                                    } else if header_status.next == Some(*number) {
+                                        CellHighlight::Partial
+                                    } else {
+                                        CellHighlight::None
+                                    };
+                                }
+                                let CellItem::Group { name } = item else {
+                                    return CellHighlight::None;
+                                };
+                                let Some(ids) = self.rt.show.group_ids(name) else {
+                                    return CellHighlight::None;
+                                };
+                                group_highlight(&ids, &self.rt.programmer.selected)
+                            },
                         );
+                        if c.kind.shows_playback() {
+                            draw_header_status(&painter, origin, c, &header_status);
+                        }
+                        let marked = self.multi.containers.contains(&c.id);
+                        draw_marks(&painter, origin, c, marked, &marked_cells);
                     }
                 });
         });
+    }
+}
+
+/// Milliseconds as seconds with one decimal, e.g. "2.5s".
+fn fmt_secs(ms: u32) -> String {
+    format!("{:.1}s", ms as f32 / 1000.0)
+}
+
+/// Terminal bell for cue warnings: the GUI has no audio output of its own.
+fn beep() {
+    use std::io::Write;
+    let mut out = std::io::stdout();
+    let _ = out.write_all(b"\x07");
+    let _ = out.flush();
+}
 
-        // Optional: autosave when closing later. For now, manual save is enough.
+/// Percent slider for a playback rate or the speed master; the new rate
+/// when moved. Double-click puts it back to 100%.
+fn rate_slider(ui: &mut egui::Ui, label: &str, rate: f32) -> Option<f32> {
+    let mut pct = rate * 100.0;
+    let range = console_core::MIN_RATE * 100.0..=console_core::MAX_RATE * 100.0;
+    let slider = egui::Slider::new(&mut pct, range)
+        .logarithmic(true)
+        .suffix("%")
+        .text(label);
+    let response = ui.add(slider);
+    if response.double_clicked() {
+        return Some(1.0);
     }
+    response.changed().then_some(pct / 100.0)
 }
 
 fn load_layout(path: &Path) -> anyhow::Result<Layout> {
@@ -1002,12 +4980,152 @@ fn would_overlap(
     false
 }
 
+/// How a group cell relates to the current fixture selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CellHighlight {
+    None,
+    Partial,
+    Full,
+}
+
+fn group_highlight(
+    group: &std::collections::BTreeSet<u32>,
+    selected: &std::collections::BTreeSet<u32>,
+) -> CellHighlight {
+    if group.is_empty() || selected.is_disjoint(group) {
+        CellHighlight::None
+    } else if group.is_subset(selected) {
+        CellHighlight::Full
+    } else {
+        CellHighlight::Partial
+    }
+}
+
+/// Parse `1`, `1 thru 5`, `1 + 3 + 7 thru 9` into fixture ids.
+/// Stable colour per fixture for footprint bars.
+fn fixture_color(fixture_id: u32) -> egui::Color32 {
+    let hue = (fixture_id.wrapping_mul(97) % 360) as f32 / 360.0;
+    egui::ecolor::Hsva::new(hue, 0.55, 0.75, 1.0).into()
+}
+
+/// Outline of shift-selected containers and cells.
+const MARK_COLOR: egui::Color32 = egui::Color32::from_rgb(70, 150, 230);
+
+/// Outline the bulk selection over an already drawn container.
+fn draw_marks(
+    painter: &egui::Painter,
+    origin: egui::Pos2,
+    c: &Container,
+    marked: bool,
+    cells: &[(i32, i32)],
+) {
+    let r = container_rect_px(origin, c);
+    for &(x, y) in cells {
+        let min = egui::pos2(r.min.x + x as f32 * CELL_PX, r.min.y + y as f32 * CELL_PX);
+        let cell = egui::Rect::from_min_size(min, egui::Vec2::new(CELL_PX, CELL_PX));
+        painter.rect_stroke(cell.shrink(3.0), 0.0, egui::Stroke::new(2.0, MARK_COLOR));
+    }
+    if marked {
+        painter.rect_stroke(r, 6.0, egui::Stroke::new(3.0, MARK_COLOR));
+    }
+}
+
+/// Live state of playback A for the headers of the containers driving it.
+struct HeaderStatus {
+    cue: Option<u32>,
+    next: Option<u32>,
+    /// Share of the running delay + fade done.
+    fade: Option<f32>,
+    paused: bool,
+    solo: bool,
+}
+
+impl HeaderStatus {
+    fn of(pb: &console_core::Playback, show: &console_core::Show) -> Self {
+        let status = pb.status(show);
+        Self {
+            cue: pb.current,
+            next: status.next.map(|c| c.number),
+            fade: pb.transition_info().map(|(elapsed, delay, fade)| {
+                (elapsed as f32 / (delay + fade).max(1) as f32).min(1.0)
+            }),
+            paused: pb.is_paused(),
+            solo: pb.solo,
+        }
+    }
+}
+
+/// Current cue, an LED (green fading, amber paused), a fade bar and SOLO
+/// in the header cell.
+fn draw_header_status(
+    painter: &egui::Painter,
+    origin: egui::Pos2,
+    c: &Container,
+    status: &HeaderStatus,
+) {
+    let r = container_rect_px(origin, c);
+    let header = egui::Rect::from_min_size(r.min, egui::Vec2::new(CELL_PX, CELL_PX));
+    let dim = egui::Color32::from_rgb(150, 150, 155);
+
+    let led = if status.paused {
+        egui::Color32::from_rgb(230, 170, 40)
+    } else if status.fade.is_some() {
+        egui::Color32::from_rgb(80, 220, 120)
+    } else if status.cue.is_some() {
+        egui::Color32::from_rgb(40, 110, 60)
+    } else {
+        egui::Color32::from_rgb(70, 70, 75)
+    };
+    painter.circle_filled(header.right_top() + egui::vec2(-9.0, 9.0), 4.0, led);
+
+    let cue = status
+        .cue
+        .map_or_else(|| "-".to_string(), |n| format!("Q{n}"));
+    painter.text(
+        header.left_bottom() + egui::vec2(6.0, -8.0),
+        egui::Align2::LEFT_BOTTOM,
+        cue,
+        egui::FontId::proportional(12.0),
+        egui::Color32::from_rgb(230, 230, 230),
+    );
+    if status.solo {
+        painter.text(
+            header.right_bottom() + egui::vec2(-6.0, -8.0),
+            egui::Align2::RIGHT_BOTTOM,
+            "SOLO",
+            egui::FontId::proportional(10.0),
+            egui::Color32::from_rgb(230, 170, 40),
+        );
+    } else if status.paused {
+        painter.text(
+            header.right_bottom() + egui::vec2(-6.0, -8.0),
+            egui::Align2::RIGHT_BOTTOM,
+            "PAUSED",
+            egui::FontId::proportional(10.0),
+            dim,
+        );
+    }
+    if let Some(done) = status.fade {
+        let bar = egui::Rect::from_min_size(
+            header.left_bottom() + egui::vec2(0.0, -4.0),
+            egui::Vec2::new(CELL_PX * done, 3.0),
+        );
+        painter.rect_filled(bar, 0.0, led);
+    }
+}
+
+fn header_color(c: &Container) -> egui::Color32 {
+    let [r, g, b] = c.color.unwrap_or([45, 46, 50]);
+    egui::Color32::from_rgb(r, g, b)
+}
+
 fn draw_container(
     painter: &egui::Painter,
     origin: egui::Pos2,
     c: &Container,
     selected: bool,
     selected_cell: Option<(i32, i32)>,
+    highlight: impl Fn(&CellItem) -> CellHighlight,
 ) {
     let r = container_rect_px(origin, c);
 
@@ -1032,12 +5150,12 @@ fn draw_container(
                         sw: 0.0,
                         se: 0.0,
                     },
-                    egui::Color32::from_rgb(45, 46, 50),
+                    header_color(c),
                 );
                 painter.text(
                     egui::pos2(cell.min.x + 6.0, cell.center().y),
                     egui::Align2::LEFT_CENTER,
-                    format!("{}", c.title),
+                    c.title.to_string(),
                     egui::FontId::proportional(13.0),
                     egui::Color32::from_rgb(230, 230, 230),
                 );
@@ -1050,27 +5168,47 @@ fn draw_container(
                 };
                 painter.rect_filled(cell, 0.0, bg);
 
-                if let Some(CellItem::Placeholder { label }) = c.get_cell(x, y) {
+                if let Some(item) = c.get_cell(x, y) {
+                    let hl = highlight(item);
+                    if hl != CellHighlight::None {
+                        let a = if hl == CellHighlight::Full { 90 } else { 40 };
+                        painter.rect_filled(
+                            cell.shrink(2.0),
+                            0.0,
+                            egui::Color32::from_rgba_unmultiplied(80, 200, 120, a),
+                        );
+                    }
                     painter.text(
                         cell.center(),
                         egui::Align2::CENTER_CENTER,
-                        label,
+                        item.label(),
                         egui::FontId::proportional(12.0),
                         egui::Color32::from_rgb(235, 235, 235),
                     );
+                    if matches!(item, CellItem::Group { .. }) {
+                        // cell number for `group <n>` on the keypad
+                        painter.text(
+                            cell.min + egui::vec2(4.0, 3.0),
+                            egui::Align2::LEFT_TOP,
+                            c.idx(x, y).to_string(),
+                            egui::FontId::proportional(10.0),
+                            egui::Color32::from_rgb(150, 150, 155),
+                        );
+                    }
                 }
             }
 
             painter.rect_stroke(cell, 0.0, line);
 
-            if let Some((sx, sy)) = selected_cell {
-                if sx == x && sy == y {
-                    painter.rect_stroke(
-                        cell.shrink(1.0),
-                        0.0,
-                        egui::Stroke::new(2.0, egui::Color32::from_rgb(220, 190, 40)),
-                    );
-                }
+            if let Some((sx, sy)) = selected_cell
+                && sx == x
+                && sy == y
+            {
+                painter.rect_stroke(
+                    cell.shrink(1.0),
+                    0.0,
+                    egui::Stroke::new(2.0, egui::Color32::from_rgb(220, 190, 40)),
+                );
             }
         }
     }